@@ -6,6 +6,7 @@ use na::{self, Isometry2, Matrix1, Matrix3, RealField, Vector3};
 
 /// The inertia of a rigid body grouping both its mass and its angular inertia.
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct Inertia2<N: RealField> {
     /// The linear part (mass) of the inertia.
     pub linear: N,
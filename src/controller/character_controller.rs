@@ -0,0 +1,689 @@
+use na::{RealField, Unit};
+use ncollide::pipeline::CollisionGroups;
+use ncollide::query::TOIStatus;
+use ncollide::shape::Shape;
+
+use crate::math::{ForceType, Isometry, Vector};
+use crate::object::{BodyHandle, BodySet, ColliderHandle, ColliderSet, RigidBody};
+use crate::world::{GeometricalWorld, ShapeCastHit};
+
+/// A controller moving a kinematic rigid body while resolving its collisions with the rest of the scene.
+///
+/// The controlled rigid body must have the `BodyStatus::Kinematic` status, and is typically
+/// given a capsule collider. The controller computes the motion of the body when `self.move_character`
+/// is called, and sets its velocity accordingly so that this motion is performed by the next call
+/// to `MechanicalWorld::step`.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct CharacterController<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle> {
+    body: Handle,
+    collider: CollHandle,
+    up: Unit<Vector<N>>,
+    offset: N,
+    max_slope_angle: N,
+    max_step_height: N,
+    min_step_width: N,
+    snap_distance: N,
+    push_force: N,
+    max_iterations: usize,
+    ground: Option<(CollHandle, Unit<Vector<N>>)>,
+}
+
+impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
+    CharacterController<N, Handle, CollHandle>
+{
+    /// Creates a controller for the given kinematic rigid body and its collider.
+    ///
+    /// The `up` direction is used to distinguish floors from walls, and to climb steps.
+    pub fn new(body: Handle, collider: CollHandle, up: Unit<Vector<N>>) -> Self {
+        CharacterController {
+            body,
+            collider,
+            up,
+            offset: na::convert(0.01),
+            max_slope_angle: na::convert(std::f64::consts::FRAC_PI_4),
+            max_step_height: na::convert(0.3),
+            min_step_width: na::convert(0.5),
+            snap_distance: na::convert(0.2),
+            push_force: na::convert(10.0),
+            max_iterations: 4,
+            ground: None,
+        }
+    }
+
+    /// The handle of the controlled rigid body.
+    pub fn body(&self) -> Handle {
+        self.body
+    }
+
+    /// The handle of the collider used to detect obstacles.
+    pub fn collider(&self) -> CollHandle {
+        self.collider
+    }
+
+    /// The up direction of the character.
+    pub fn up(&self) -> &Unit<Vector<N>> {
+        &self.up
+    }
+
+    /// Sets the up direction of the character.
+    pub fn set_up(&mut self, up: Unit<Vector<N>>) {
+        self.up = up
+    }
+
+    /// The gap kept between the character collider and the obstacles.
+    pub fn offset(&self) -> N {
+        self.offset
+    }
+
+    /// Sets the gap kept between the character collider and the obstacles.
+    pub fn set_offset(&mut self, offset: N) {
+        self.offset = offset
+    }
+
+    /// The maximum angle, in radians, between the up direction and the normal of a floor the character can walk on.
+    pub fn max_slope_angle(&self) -> N {
+        self.max_slope_angle
+    }
+
+    /// Sets the maximum angle, in radians, between the up direction and the normal of a floor the character can walk on.
+    pub fn set_max_slope_angle(&mut self, angle: N) {
+        self.max_slope_angle = angle
+    }
+
+    /// The maximum height of the steps the character automatically climbs.
+    pub fn max_step_height(&self) -> N {
+        self.max_step_height
+    }
+
+    /// Sets the maximum height of the steps the character automatically climbs.
+    ///
+    /// Set this to zero to disable step climbing.
+    pub fn set_max_step_height(&mut self, height: N) {
+        self.max_step_height = height
+    }
+
+    /// The minimum width of the top of the steps the character automatically climbs.
+    pub fn min_step_width(&self) -> N {
+        self.min_step_width
+    }
+
+    /// Sets the minimum width of the top of the steps the character automatically climbs.
+    pub fn set_min_step_width(&mut self, width: N) {
+        self.min_step_width = width
+    }
+
+    /// The maximum distance the character is moved down to keep it on the ground.
+    pub fn snap_distance(&self) -> N {
+        self.snap_distance
+    }
+
+    /// Sets the maximum distance the character is moved down to keep it on the ground.
+    ///
+    /// Set this to zero to disable snapping to the ground.
+    pub fn set_snap_distance(&mut self, distance: N) {
+        self.snap_distance = distance
+    }
+
+    /// The force applied to the dynamic bodies the character runs into.
+    pub fn push_force(&self) -> N {
+        self.push_force
+    }
+
+    /// Sets the force applied to the dynamic bodies the character runs into.
+    pub fn set_push_force(&mut self, force: N) {
+        self.push_force = force
+    }
+
+    /// The maximum number of obstacles the character can slide along during one move.
+    pub fn max_iterations(&self) -> usize {
+        self.max_iterations
+    }
+
+    /// Sets the maximum number of obstacles the character can slide along during one move.
+    pub fn set_max_iterations(&mut self, max_iterations: usize) {
+        self.max_iterations = max_iterations
+    }
+
+    /// Whether the character stood on a walkable floor at the end of its last move.
+    pub fn is_grounded(&self) -> bool {
+        self.ground.is_some()
+    }
+
+    /// The collider the character stood on at the end of its last move, if any.
+    pub fn ground_collider(&self) -> Option<CollHandle> {
+        self.ground.map(|g| g.0)
+    }
+
+    /// The world-space normal of the floor the character stood on at the end of its last move, if any.
+    pub fn ground_normal(&self) -> Option<Unit<Vector<N>>> {
+        self.ground.map(|g| g.1)
+    }
+
+    /// Moves the character as close as possible to the given translation during the next time step of length `dt`.
+    ///
+    /// The character slides along the obstacles it meets, climbs steps and walkable slopes, follows
+    /// the floor it stands on if it moves, and pushes the dynamic bodies it runs into. Returns the
+    /// translation actually applied, which excludes the displacement of the body before the next
+    /// time step.
+    pub fn move_character<Bodies, Colliders>(
+        &mut self,
+        gworld: &GeometricalWorld<N, Handle, CollHandle>,
+        bodies: &mut Bodies,
+        colliders: &Colliders,
+        desired_translation: &Vector<N>,
+        dt: N,
+    ) -> Vector<N>
+    where
+        Bodies: BodySet<N, Handle = Handle>,
+        Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
+    {
+        let collider = try_ret!(colliders.get(self.collider), Vector::zeros());
+        let shape = collider.shape();
+        let groups = *collider.collision_groups();
+        let start = *collider.position();
+        let mut position = start;
+        let mut remaining = *desired_translation;
+        let was_grounded = self.ground.is_some();
+
+        if let Some((ground, _)) = self.ground {
+            remaining += self.platform_velocity(&*bodies, colliders, ground, &start) * dt;
+        }
+
+        for _ in 0..self.max_iterations {
+            let length = remaining.norm();
+
+            if length <= N::default_epsilon() {
+                break;
+            }
+
+            let (handle, hit) = match self.cast(
+                gworld, &*bodies, colliders, shape, &position, &remaining, &groups,
+            ) {
+                Some(hit) => hit,
+                None => {
+                    position.translation.vector += remaining;
+                    break;
+                }
+            };
+
+            let dir = remaining / length;
+            let travel = (hit.toi * length - self.offset).max(N::zero());
+            position.translation.vector += dir * travel;
+            remaining -= dir * travel;
+            self.push(bodies, colliders, handle, &hit, &dir);
+
+            if !self.is_walkable(&hit.normal2) && was_grounded {
+                if let Some((stepped, rest)) = self.try_step(
+                    gworld, &*bodies, colliders, shape, &position, &remaining, &groups,
+                ) {
+                    position = stepped;
+                    remaining = rest;
+                    continue;
+                }
+            }
+
+            remaining = self.slide(&remaining, &hit.normal2);
+        }
+
+        if was_grounded && desired_translation.dot(&self.up) <= N::zero() {
+            self.snap_to_ground(gworld, &*bodies, colliders, shape, &mut position, &groups);
+        }
+
+        self.ground = self.detect_ground(gworld, &*bodies, colliders, shape, &position, &groups);
+
+        let translation = position.translation.vector - start.translation.vector;
+
+        if let Some(rb) = bodies
+            .get_mut(self.body)
+            .and_then(|b| b.downcast_mut::<RigidBody<N>>())
+        {
+            if dt > N::zero() {
+                rb.set_linear_velocity(translation / dt);
+            }
+        }
+
+        translation
+    }
+
+    fn is_walkable(&self, normal: &Unit<Vector<N>>) -> bool {
+        normal.dot(&self.up) >= self.max_slope_angle.cos()
+    }
+
+    // Removes from the motion the part going into the obstacle with the given normal.
+    fn slide(&self, motion: &Vector<N>, normal: &Unit<Vector<N>>) -> Vector<N> {
+        let mut normal = *normal;
+
+        // Do not climb steep slopes by sliding along them.
+        if !self.is_walkable(&normal) && motion.dot(&self.up) >= N::zero() {
+            let horizontal = *normal - *self.up * normal.dot(&self.up);
+
+            if let Some(horizontal) = Unit::try_new(horizontal, N::default_epsilon()) {
+                normal = horizontal;
+            }
+        }
+
+        let dot = motion.dot(&normal);
+
+        if dot < N::zero() {
+            motion - *normal * dot
+        } else {
+            *motion
+        }
+    }
+
+    // Climbs the obstacle blocking the horizontal part of the motion, if it is low enough.
+    fn try_step<Bodies, Colliders>(
+        &self,
+        gworld: &GeometricalWorld<N, Handle, CollHandle>,
+        bodies: &Bodies,
+        colliders: &Colliders,
+        shape: &dyn Shape<N>,
+        position: &Isometry<N>,
+        motion: &Vector<N>,
+        groups: &CollisionGroups,
+    ) -> Option<(Isometry<N>, Vector<N>)>
+    where
+        Bodies: BodySet<N, Handle = Handle>,
+        Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
+    {
+        let horizontal = motion - *self.up * motion.dot(&self.up);
+        let horizontal_length = horizontal.norm();
+
+        if horizontal_length <= N::default_epsilon() || self.max_step_height <= N::zero() {
+            return None;
+        }
+
+        let mut stepped = *position;
+        let up_motion = *self.up * self.max_step_height;
+        let up_travel = self.travel(
+            gworld, bodies, colliders, shape, &stepped, &up_motion, groups,
+        );
+
+        if up_travel <= self.offset {
+            return None;
+        }
+
+        stepped.translation.vector += *self.up * up_travel;
+
+        // Check there is a walkable floor at least `self.min_step_width` ahead of the obstacle, so
+        // the character does not stand on its edge.
+        let dir = horizontal / horizontal_length;
+        let probe_length = horizontal_length.max(self.min_step_width);
+        let probe_motion = dir * probe_length;
+        let probe_travel = self.travel(
+            gworld,
+            bodies,
+            colliders,
+            shape,
+            &stepped,
+            &probe_motion,
+            groups,
+        );
+
+        if probe_travel < probe_length {
+            return None;
+        }
+
+        let mut probe = stepped;
+        probe.translation.vector += probe_motion;
+
+        let down_motion = -*self.up * up_travel;
+        let (_, hit) = self.cast(
+            gworld,
+            bodies,
+            colliders,
+            shape,
+            &probe,
+            &down_motion,
+            groups,
+        )?;
+
+        if !self.is_walkable(&hit.normal2) {
+            return None;
+        }
+
+        let down_travel = (hit.toi * up_travel - self.offset).max(N::zero());
+        stepped.translation.vector -= *self.up * down_travel;
+
+        let forward_travel = self.travel(
+            gworld,
+            bodies,
+            colliders,
+            shape,
+            &stepped,
+            &horizontal,
+            groups,
+        );
+        stepped.translation.vector += dir * forward_travel;
+
+        let rest = dir * (horizontal_length - forward_travel);
+        Some((stepped, rest))
+    }
+
+    fn snap_to_ground<Bodies, Colliders>(
+        &self,
+        gworld: &GeometricalWorld<N, Handle, CollHandle>,
+        bodies: &Bodies,
+        colliders: &Colliders,
+        shape: &dyn Shape<N>,
+        position: &mut Isometry<N>,
+        groups: &CollisionGroups,
+    ) where
+        Bodies: BodySet<N, Handle = Handle>,
+        Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
+    {
+        let down_motion = -*self.up * self.snap_distance;
+
+        if let Some((_, hit)) = self.cast(
+            gworld,
+            bodies,
+            colliders,
+            shape,
+            position,
+            &down_motion,
+            groups,
+        ) {
+            if self.is_walkable(&hit.normal2) {
+                let travel = (hit.toi * self.snap_distance - self.offset).max(N::zero());
+                position.translation.vector -= *self.up * travel;
+            }
+        }
+    }
+
+    fn detect_ground<Bodies, Colliders>(
+        &self,
+        gworld: &GeometricalWorld<N, Handle, CollHandle>,
+        bodies: &Bodies,
+        colliders: &Colliders,
+        shape: &dyn Shape<N>,
+        position: &Isometry<N>,
+        groups: &CollisionGroups,
+    ) -> Option<(CollHandle, Unit<Vector<N>>)>
+    where
+        Bodies: BodySet<N, Handle = Handle>,
+        Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
+    {
+        let down_motion = -*self.up * (self.offset * na::convert(2.0));
+        let (handle, hit) = self.cast(
+            gworld,
+            bodies,
+            colliders,
+            shape,
+            position,
+            &down_motion,
+            groups,
+        )?;
+
+        if self.is_walkable(&hit.normal2) {
+            Some((handle, hit.normal2))
+        } else {
+            None
+        }
+    }
+
+    // The distance the character can travel along `motion` before reaching the offset
+    // distance from an obstacle.
+    fn travel<Bodies, Colliders>(
+        &self,
+        gworld: &GeometricalWorld<N, Handle, CollHandle>,
+        bodies: &Bodies,
+        colliders: &Colliders,
+        shape: &dyn Shape<N>,
+        position: &Isometry<N>,
+        motion: &Vector<N>,
+        groups: &CollisionGroups,
+    ) -> N
+    where
+        Bodies: BodySet<N, Handle = Handle>,
+        Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
+    {
+        let length = motion.norm();
+
+        match self.cast(gworld, bodies, colliders, shape, position, motion, groups) {
+            Some((_, hit)) => (hit.toi * length - self.offset).max(N::zero()),
+            None => length,
+        }
+    }
+
+    fn cast<Bodies, Colliders>(
+        &self,
+        gworld: &GeometricalWorld<N, Handle, CollHandle>,
+        bodies: &Bodies,
+        colliders: &Colliders,
+        shape: &dyn Shape<N>,
+        position: &Isometry<N>,
+        motion: &Vector<N>,
+        groups: &CollisionGroups,
+    ) -> Option<(CollHandle, ShapeCastHit<N>)>
+    where
+        Bodies: BodySet<N, Handle = Handle>,
+        Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
+    {
+        let body = self.body;
+
+        // Obstacles the character already penetrates are ignored so it can get out of them.
+        gworld
+            .cast_shape(
+                bodies,
+                colliders,
+                shape,
+                position,
+                motion,
+                N::one(),
+                groups,
+                |_, c, _| c.body() != body && !c.is_sensor(),
+            )
+            .into_iter()
+            .find(|hit| hit.2.status != TOIStatus::Penetrating)
+            .map(|(handle, _, hit)| (handle, hit))
+    }
+
+    fn platform_velocity<Bodies, Colliders>(
+        &self,
+        bodies: &Bodies,
+        colliders: &Colliders,
+        ground: CollHandle,
+        position: &Isometry<N>,
+    ) -> Vector<N>
+    where
+        Bodies: BodySet<N, Handle = Handle>,
+        Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
+    {
+        let collider = try_ret!(colliders.get(ground), Vector::zeros());
+        let body = try_ret!(bodies.get(collider.body()), Vector::zeros());
+
+        if body.is_static() {
+            return Vector::zeros();
+        }
+
+        let point = position.translation.vector.into();
+        body.velocity_at_point(collider.body_part(0).1, &point)
+            .linear
+    }
+
+    fn push<Bodies, Colliders>(
+        &self,
+        bodies: &mut Bodies,
+        colliders: &Colliders,
+        handle: CollHandle,
+        hit: &ShapeCastHit<N>,
+        dir: &Vector<N>,
+    ) where
+        Bodies: BodySet<N, Handle = Handle>,
+        Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
+    {
+        let collider = try_ret!(colliders.get(handle));
+        let body = try_ret!(bodies.get_mut(collider.body()));
+        let intensity = -dir.dot(&hit.normal2) * self.push_force;
+
+        if body.is_dynamic() && intensity > N::zero() {
+            let force = -*hit.normal2 * intensity;
+            body.apply_force_at_point(
+                collider.body_part(0).1,
+                &force,
+                &hit.witness2,
+                ForceType::Force,
+                true,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::CharacterController;
+    use crate::force_generator::DefaultForceGeneratorSet;
+    use crate::joint::DefaultJointConstraintSet;
+    use crate::math::Vector;
+    use crate::object::{
+        BodyPartHandle, BodyStatus, ColliderDesc, DefaultBodyHandle, DefaultBodySet,
+        DefaultColliderHandle, DefaultColliderSet, Ground, RigidBodyDesc,
+    };
+    use crate::world::{DefaultGeometricalWorld, DefaultMechanicalWorld};
+    use ncollide::shape::{Capsule, Cuboid, ShapeHandle};
+
+    struct Scene {
+        geometrical_world: DefaultGeometricalWorld<f64>,
+        bodies: DefaultBodySet<f64>,
+        colliders: DefaultColliderSet<f64>,
+        character: DefaultBodyHandle,
+        character_collider: DefaultColliderHandle,
+    }
+
+    impl Scene {
+        // A capsule standing on a flat floor at the origin, in front of the boxes `obstacles`
+        // given by their half-extents and centers.
+        fn new(obstacles: &[(Vector<f64>, Vector<f64>)]) -> Self {
+            let mut bodies = DefaultBodySet::new();
+            let mut colliders = DefaultColliderSet::new();
+
+            let ground = bodies.insert(Ground::new());
+            let floor = ShapeHandle::new(Cuboid::new(Vector::repeat(20.0)));
+            let _ = colliders.insert(
+                ColliderDesc::new(floor)
+                    .translation(Vector::y() * -20.0)
+                    .build(BodyPartHandle(ground, 0)),
+            );
+
+            for (half_extents, center) in obstacles {
+                let _ = colliders.insert(
+                    ColliderDesc::new(ShapeHandle::new(Cuboid::new(*half_extents)))
+                        .translation(*center)
+                        .build(BodyPartHandle(ground, 0)),
+                );
+            }
+
+            // The capsule is 1.6 high, and starts at the offset distance from the floor.
+            let character = RigidBodyDesc::new()
+                .status(BodyStatus::Kinematic)
+                .translation(Vector::y() * 0.81)
+                .build();
+            let character = bodies.insert(character);
+            let character_collider = colliders.insert(
+                ColliderDesc::new(ShapeHandle::new(Capsule::new(0.5, 0.3)))
+                    .build(BodyPartHandle(character, 0)),
+            );
+
+            // One step to add the colliders to the broad phase.
+            let mut geometrical_world = DefaultGeometricalWorld::new();
+            let _ = DefaultMechanicalWorld::new(Vector::zeros()).step(
+                &mut geometrical_world,
+                &mut bodies,
+                &mut colliders,
+                &mut DefaultJointConstraintSet::new(),
+                &mut DefaultForceGeneratorSet::new(),
+            );
+
+            Scene {
+                geometrical_world,
+                bodies,
+                colliders,
+                character,
+                character_collider,
+            }
+        }
+
+        // A controller standing on the floor.
+        fn controller(
+            &mut self,
+        ) -> CharacterController<f64, DefaultBodyHandle, DefaultColliderHandle> {
+            let mut controller =
+                CharacterController::new(self.character, self.character_collider, Vector::y_axis());
+            let _ = self.move_character(&mut controller, &Vector::zeros());
+            assert!(controller.is_grounded());
+            controller
+        }
+
+        fn move_character(
+            &mut self,
+            controller: &mut CharacterController<f64, DefaultBodyHandle, DefaultColliderHandle>,
+            translation: &Vector<f64>,
+        ) -> Vector<f64> {
+            controller.move_character(
+                &self.geometrical_world,
+                &mut self.bodies,
+                &self.colliders,
+                translation,
+                1.0 / 60.0,
+            )
+        }
+    }
+
+    // A box of the given half-extents along `x` and `y`, long along `z` in 3D.
+    fn half_extents(x: f64, y: f64) -> Vector<f64> {
+        let mut half_extents = Vector::repeat(5.0);
+        half_extents.x = x;
+        half_extents.y = y;
+        half_extents
+    }
+
+    #[test]
+    fn character_slides_along_walls() {
+        // A wall facing the character, 0.7 away from the capsule.
+        let wall = (
+            half_extents(0.5, 2.0),
+            Vector::x() * 1.5 + Vector::y() * 2.0,
+        );
+        let mut scene = Scene::new(&[wall]);
+        let mut controller = scene.controller();
+
+        #[cfg(feature = "dim2")]
+        let along_wall = Vector::zeros();
+        #[cfg(feature = "dim3")]
+        let along_wall = Vector::z();
+        let translation = scene.move_character(&mut controller, &(Vector::x() * 2.0 + along_wall));
+
+        // Stopped at the offset distance from the wall, with the motion along the wall preserved.
+        assert_relative_eq!(translation.x, 0.69, epsilon = 1.0e-2);
+        assert_relative_eq!(translation.y, 0.0, epsilon = 1.0e-3);
+        assert_relative_eq!(
+            translation - Vector::x() * translation.x,
+            along_wall,
+            epsilon = 1.0e-3
+        );
+        assert!(controller.is_grounded());
+    }
+
+    #[test]
+    fn character_climbs_steps_lower_than_max_step_height() {
+        // A step 0.2 high, 0.7 away from the capsule.
+        let step = (
+            half_extents(1.0, 0.1),
+            Vector::x() * 2.0 + Vector::y() * 0.1,
+        );
+        let mut scene = Scene::new(&[step]);
+        let mut controller = scene.controller();
+        controller.set_max_step_height(0.1);
+
+        // Too high for this controller. The rounded bottom of the capsule stops on the edge of the step.
+        let translation = scene.move_character(&mut controller, &(Vector::x() * 2.0));
+        assert_relative_eq!(translation.x, 0.71, epsilon = 1.0e-2);
+        assert_relative_eq!(translation.y, 0.0, epsilon = 1.0e-3);
+
+        let mut controller = scene.controller();
+        let translation = scene.move_character(&mut controller, &(Vector::x() * 2.0));
+        assert_relative_eq!(translation.x, 2.0, epsilon = 1.0e-3);
+        assert_relative_eq!(translation.y, 0.2, epsilon = 1.0e-3);
+        assert!(controller.is_grounded());
+    }
+}
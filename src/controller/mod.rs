@@ -0,0 +1,7 @@
+//! High-level controllers driving bodies of the physics world.
+
+pub use self::character_controller::CharacterController;
+pub use self::raycast_vehicle::{RaycastVehicle, Wheel, WheelContact};
+
+mod character_controller;
+mod raycast_vehicle;
@@ -0,0 +1,686 @@
+use na::{RealField, Unit};
+use ncollide::pipeline::CollisionGroups;
+use ncollide::query::Ray;
+
+use crate::force_generator::ForceGenerator;
+#[cfg(feature = "dim3")]
+use crate::math::Rotation;
+use crate::math::{Force, ForceType, Point, Vector};
+use crate::object::{
+    Body, BodyHandle, BodyPart, BodyPartHandle, BodySet, ColliderHandle, ColliderSet,
+};
+use crate::solver::IntegrationParameters;
+use crate::world::GeometricalWorld;
+
+/// The contact between a wheel of a vehicle and the ground.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct WheelContact<N: RealField, Handle: BodyHandle> {
+    /// The body part the wheel is touching.
+    pub ground: BodyPartHandle<Handle>,
+    /// The contact point, in world-space.
+    pub point: Point<N>,
+    /// The normal of the ground at the contact point, in world-space.
+    pub normal: Unit<Vector<N>>,
+}
+
+/// A wheel of a raycast vehicle.
+///
+/// The wheel is modeled by a ray cast from its suspension attach point along its suspension direction.
+/// Its suspension, and its tire, apply forces to the chassis at the point hit by this ray.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct Wheel<N: RealField, Handle: BodyHandle> {
+    chassis_connection: Point<N>,
+    direction: Unit<Vector<N>>,
+    radius: N,
+    suspension_rest_length: N,
+    suspension_stiffness: N,
+    suspension_damping: N,
+    max_suspension_force: N,
+    friction_slip: N,
+    #[cfg(feature = "dim3")]
+    steering: N,
+    engine_force: N,
+    brake: N,
+    rotation: N,
+    angular_velocity: N,
+    suspension_length: N,
+    suspension_force: N,
+    skidding: bool,
+    contact: Option<WheelContact<N, Handle>>,
+}
+
+impl<N: RealField, Handle: BodyHandle> Wheel<N, Handle> {
+    /// Creates a wheel attached to the chassis at the point `chassis_connection`, with its suspension pointing toward `direction`.
+    ///
+    /// Both the connection point and the direction are expressed in the local coordinates of the
+    /// chassis. The direction usually points down, i.e., toward the ground.
+    pub fn new(
+        chassis_connection: Point<N>,
+        direction: Unit<Vector<N>>,
+        radius: N,
+        suspension_rest_length: N,
+    ) -> Self {
+        Wheel {
+            chassis_connection,
+            direction,
+            radius,
+            suspension_rest_length,
+            suspension_stiffness: na::convert(20_000.0),
+            suspension_damping: na::convert(2_000.0),
+            max_suspension_force: na::convert(60_000.0),
+            friction_slip: na::convert(1.5),
+            #[cfg(feature = "dim3")]
+            steering: N::zero(),
+            engine_force: N::zero(),
+            brake: N::zero(),
+            rotation: N::zero(),
+            angular_velocity: N::zero(),
+            suspension_length: suspension_rest_length,
+            suspension_force: N::zero(),
+            skidding: false,
+            contact: None,
+        }
+    }
+
+    /// The point the suspension is attached to, in the local coordinates of the chassis.
+    pub fn chassis_connection(&self) -> &Point<N> {
+        &self.chassis_connection
+    }
+
+    /// The direction of the suspension, in the local coordinates of the chassis.
+    pub fn direction(&self) -> &Unit<Vector<N>> {
+        &self.direction
+    }
+
+    /// The radius of this wheel.
+    pub fn radius(&self) -> N {
+        self.radius
+    }
+
+    /// The length of the suspension when it is neither compressed nor extended.
+    ///
+    /// This is also the maximum length of the suspension.
+    pub fn suspension_rest_length(&self) -> N {
+        self.suspension_rest_length
+    }
+
+    /// Sets the length of the suspension when it is neither compressed nor extended.
+    pub fn set_suspension_rest_length(&mut self, length: N) {
+        self.suspension_rest_length = length
+    }
+
+    /// The stiffness of the suspension spring.
+    pub fn suspension_stiffness(&self) -> N {
+        self.suspension_stiffness
+    }
+
+    /// Sets the stiffness of the suspension spring.
+    pub fn set_suspension_stiffness(&mut self, stiffness: N) {
+        self.suspension_stiffness = stiffness
+    }
+
+    /// The damping coefficient of the suspension damper.
+    pub fn suspension_damping(&self) -> N {
+        self.suspension_damping
+    }
+
+    /// Sets the damping coefficient of the suspension damper.
+    pub fn set_suspension_damping(&mut self, damping: N) {
+        self.suspension_damping = damping
+    }
+
+    /// The maximum force the suspension can apply to the chassis.
+    pub fn max_suspension_force(&self) -> N {
+        self.max_suspension_force
+    }
+
+    /// Sets the maximum force the suspension can apply to the chassis.
+    pub fn set_max_suspension_force(&mut self, force: N) {
+        self.max_suspension_force = force
+    }
+
+    /// The friction coefficient of the tire.
+    ///
+    /// The tire slips when the friction force needed to prevent it from sliding exceeds this
+    /// coefficient multiplied by the suspension force.
+    pub fn friction_slip(&self) -> N {
+        self.friction_slip
+    }
+
+    /// Sets the friction coefficient of the tire.
+    pub fn set_friction_slip(&mut self, friction_slip: N) {
+        self.friction_slip = friction_slip
+    }
+
+    /// The steering angle of this wheel, in radians.
+    #[cfg(feature = "dim3")]
+    pub fn steering(&self) -> N {
+        self.steering
+    }
+
+    /// Sets the steering angle of this wheel, in radians.
+    ///
+    /// The wheel is rotated by this angle about the opposite of its suspension direction.
+    #[cfg(feature = "dim3")]
+    pub fn set_steering(&mut self, angle: N) {
+        self.steering = angle
+    }
+
+    /// The force applied by the engine at the contact point of this wheel, along its rolling direction.
+    pub fn engine_force(&self) -> N {
+        self.engine_force
+    }
+
+    /// Sets the force applied by the engine at the contact point of this wheel, along its rolling direction.
+    ///
+    /// This is the engine torque divided by the wheel radius. A negative force makes the vehicle drive backward.
+    pub fn set_engine_force(&mut self, force: N) {
+        self.engine_force = force
+    }
+
+    /// The maximum force applied by the brake at the contact point of this wheel.
+    pub fn brake(&self) -> N {
+        self.brake
+    }
+
+    /// Sets the maximum force applied by the brake at the contact point of this wheel.
+    pub fn set_brake(&mut self, brake: N) {
+        self.brake = brake
+    }
+
+    /// The rotation angle of this wheel about its axle, in radians.
+    pub fn rotation(&self) -> N {
+        self.rotation
+    }
+
+    /// The angular velocity of this wheel about its axle.
+    pub fn angular_velocity(&self) -> N {
+        self.angular_velocity
+    }
+
+    /// The current length of the suspension.
+    pub fn suspension_length(&self) -> N {
+        self.suspension_length
+    }
+
+    /// The force applied by the suspension during the last time step.
+    pub fn suspension_force(&self) -> N {
+        self.suspension_force
+    }
+
+    /// Whether the tire slipped on the ground during the last time step.
+    pub fn is_skidding(&self) -> bool {
+        self.skidding
+    }
+
+    /// The contact between this wheel and the ground, if any.
+    pub fn contact(&self) -> Option<&WheelContact<N, Handle>> {
+        self.contact.as_ref()
+    }
+
+    /// Whether this wheel touches the ground.
+    pub fn is_in_contact(&self) -> bool {
+        self.contact.is_some()
+    }
+
+    /// The position of the center of this wheel, in the local coordinates of the chassis.
+    pub fn center(&self) -> Point<N> {
+        self.chassis_connection + *self.direction * self.suspension_length
+    }
+}
+
+/// A vehicle simulated by casting one ray per wheel from its chassis.
+///
+/// The chassis is a dynamic rigid body, and the wheels are not bodies: they only apply suspension
+/// and tire forces to the chassis, and reaction forces to the dynamic bodies they roll on. This
+/// is a force generator: once added to the force generator set passed to
+/// `MechanicalWorld::step`, the contacts of its wheels must be updated by calling
+/// `self.update_wheels` before each step. The vehicle can be retrieved from the force generator
+/// set with `ForceGenerator::downcast_mut`.
+///
+/// In 2D, the vehicle is seen from the side and its wheels do not steer.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct RaycastVehicle<N: RealField, Handle: BodyHandle> {
+    chassis: Handle,
+    forward: Unit<Vector<N>>,
+    wheels: Vec<Wheel<N, Handle>>,
+}
+
+impl<N: RealField, Handle: BodyHandle> RaycastVehicle<N, Handle> {
+    /// Creates a vehicle without wheels, with the given chassis rigid body.
+    ///
+    /// The `forward` direction, expressed in the local coordinates of the chassis, is the
+    /// direction the wheels roll toward when they are not steered.
+    pub fn new(chassis: Handle, forward: Unit<Vector<N>>) -> Self {
+        RaycastVehicle {
+            chassis,
+            forward,
+            wheels: Vec::new(),
+        }
+    }
+
+    /// The handle of the chassis rigid body.
+    pub fn chassis(&self) -> Handle {
+        self.chassis
+    }
+
+    /// The forward direction of the vehicle, in the local coordinates of the chassis.
+    pub fn forward(&self) -> &Unit<Vector<N>> {
+        &self.forward
+    }
+
+    /// Adds a wheel to this vehicle and returns its index.
+    pub fn add_wheel(&mut self, wheel: Wheel<N, Handle>) -> usize {
+        self.wheels.push(wheel);
+        self.wheels.len() - 1
+    }
+
+    /// All the wheels of this vehicle.
+    pub fn wheels(&self) -> &[Wheel<N, Handle>] {
+        &self.wheels
+    }
+
+    /// All the wheels of this vehicle.
+    pub fn wheels_mut(&mut self) -> &mut [Wheel<N, Handle>] {
+        &mut self.wheels
+    }
+
+    /// The wheel with the given index.
+    pub fn wheel(&self, i: usize) -> Option<&Wheel<N, Handle>> {
+        self.wheels.get(i)
+    }
+
+    /// The wheel with the given index.
+    pub fn wheel_mut(&mut self, i: usize) -> Option<&mut Wheel<N, Handle>> {
+        self.wheels.get_mut(i)
+    }
+
+    /// The speed of the chassis along its forward direction.
+    pub fn forward_speed<Bodies: BodySet<N, Handle = Handle>>(&self, bodies: &Bodies) -> N {
+        let body = try_ret!(bodies.get(self.chassis), N::zero());
+        let part = try_ret!(body.part(0), N::zero());
+        let forward = part.position() * self.forward;
+        part.velocity().linear.dot(&forward)
+    }
+
+    /// Casts the ray of each wheel to find its contact with the ground, and updates the length of its suspension.
+    ///
+    /// Only the colliders that can interact with `groups` are taken into account. The colliders
+    /// attached to the chassis, and the sensors, are ignored.
+    pub fn update_wheels<Bodies, Colliders, CollHandle>(
+        &mut self,
+        gworld: &GeometricalWorld<N, Handle, CollHandle>,
+        bodies: &Bodies,
+        colliders: &Colliders,
+        groups: &CollisionGroups,
+    ) where
+        Bodies: BodySet<N, Handle = Handle>,
+        Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
+        CollHandle: ColliderHandle,
+    {
+        let body = try_ret!(bodies.get(self.chassis));
+        let chassis_pos = try_ret!(body.part(0)).position();
+
+        for wheel in &mut self.wheels {
+            let origin = chassis_pos * wheel.chassis_connection;
+            let dir = chassis_pos * wheel.direction;
+            let ray = Ray::new(origin, *dir);
+            let max_toi = wheel.suspension_rest_length + wheel.radius;
+            let mut closest = None;
+
+            for (_, collider, inter) in
+                gworld.interferences_with_ray(colliders, &ray, max_toi, groups)
+            {
+                if collider.body() == self.chassis || collider.is_sensor() {
+                    continue;
+                }
+
+                if closest
+                    .as_ref()
+                    .map(|c: &(_, _, _)| inter.toi < c.1)
+                    .unwrap_or(true)
+                {
+                    closest = Some((collider.body_part(0), inter.toi, inter.normal));
+                }
+            }
+
+            wheel.contact = None;
+            wheel.suspension_length = wheel.suspension_rest_length;
+
+            if let Some((ground, toi, normal)) = closest {
+                if let Some(normal) = Unit::try_new(normal, N::default_epsilon()) {
+                    wheel.suspension_length = (toi - wheel.radius).max(N::zero());
+                    wheel.contact = Some(WheelContact {
+                        ground,
+                        point: ray.point_at(toi),
+                        normal,
+                    });
+                }
+            }
+        }
+    }
+}
+
+impl<N: RealField, Handle: BodyHandle> ForceGenerator<N, Handle> for RaycastVehicle<N, Handle> {
+    fn apply(
+        &mut self,
+        parameters: &IntegrationParameters<N>,
+        bodies: &mut dyn BodySet<N, Handle = Handle>,
+    ) {
+        let dt = parameters.dt();
+        let chassis = try_ret!(bodies.get(self.chassis));
+        let chassis_part = try_ret!(chassis.part(0));
+        let chassis_pos = chassis_part.position();
+        let num_contacts = self.wheels.iter().filter(|w| w.is_in_contact()).count();
+        let mut forces = Vec::with_capacity(num_contacts);
+
+        for wheel in &mut self.wheels {
+            wheel.suspension_force = N::zero();
+            wheel.skidding = false;
+
+            let contact = match wheel.contact {
+                Some(contact) => contact,
+                None => {
+                    // The wheel spins freely in the air.
+                    wheel.angular_velocity *= na::convert(0.99);
+                    wheel.rotation += wheel.angular_velocity * dt;
+                    continue;
+                }
+            };
+
+            let normal = contact.normal;
+            let ground = bodies
+                .get(contact.ground.0)
+                .and_then(|b| b.part(contact.ground.1).map(|p| (b, p)));
+            let mut relative_vel = chassis.velocity_at_point(0, &contact.point).linear;
+
+            if let Some((body, _)) = ground {
+                relative_vel -= body
+                    .velocity_at_point(contact.ground.1, &contact.point)
+                    .linear;
+            }
+
+            // Suspension.
+            let compression = wheel.suspension_rest_length - wheel.suspension_length;
+            let compression_speed = -relative_vel.dot(&normal);
+            wheel.suspension_force = (compression * wheel.suspension_stiffness
+                + compression_speed * wheel.suspension_damping)
+                .max(N::zero())
+                .min(wheel.max_suspension_force);
+
+            let mut force = *normal * wheel.suspension_force;
+
+            // Tire friction.
+            #[cfg(feature = "dim2")]
+            let forward = chassis_pos * self.forward;
+            #[cfg(feature = "dim3")]
+            let forward = {
+                let steering = Rotation::from_axis_angle(&-wheel.direction, wheel.steering);
+                chassis_pos * (steering * self.forward)
+            };
+
+            let forward = *forward - *normal * forward.dot(&normal);
+
+            if let Some(forward) = Unit::try_new(forward, N::default_epsilon()) {
+                // Share the forces needed to stop the sliding of the tires between all the wheels touching the ground.
+                let share = dt * na::convert(num_contacts as f64);
+                let forward_speed = relative_vel.dot(&forward);
+                let inv_mass = inv_effective_mass(chassis, &contact.point, &forward, ground);
+                let brake_force = if inv_mass > N::zero() {
+                    -forward_speed / (inv_mass * share)
+                } else {
+                    N::zero()
+                };
+                let longitudinal =
+                    wheel.engine_force + brake_force.max(-wheel.brake).min(wheel.brake);
+                let mut friction = *forward * longitudinal;
+
+                #[cfg(feature = "dim3")]
+                {
+                    let side = Unit::new_unchecked(normal.cross(&forward));
+                    let side_speed = relative_vel.dot(&side);
+                    let inv_mass = inv_effective_mass(chassis, &contact.point, &side, ground);
+
+                    if inv_mass > N::zero() {
+                        friction -= *side * (side_speed / (inv_mass * share));
+                    }
+                }
+
+                let max_friction = wheel.friction_slip * wheel.suspension_force;
+                let friction_norm = friction.norm();
+
+                if friction_norm > max_friction {
+                    wheel.skidding = true;
+                    friction *= max_friction / friction_norm;
+                }
+
+                force += friction;
+
+                wheel.angular_velocity = if wheel.skidding && !wheel.brake.is_zero() {
+                    N::zero()
+                } else {
+                    forward_speed / wheel.radius
+                };
+            }
+
+            wheel.rotation += wheel.angular_velocity * dt;
+            forces.push((contact, force));
+        }
+
+        for (contact, force) in forces {
+            if let Some(chassis) = bodies.get_mut(self.chassis) {
+                chassis.apply_force_at_point(0, &force, &contact.point, ForceType::Force, true);
+            }
+
+            if let Some(ground) = bodies.get_mut(contact.ground.0) {
+                if ground.is_dynamic() {
+                    ground.apply_force_at_point(
+                        contact.ground.1,
+                        &-force,
+                        &contact.point,
+                        ForceType::Force,
+                        true,
+                    );
+                }
+            }
+        }
+    }
+}
+
+// The inverse of the mass of the chassis and ground seen by a force applied at `point` along `dir`.
+fn inv_effective_mass<N: RealField>(
+    chassis: &dyn Body<N>,
+    point: &Point<N>,
+    dir: &Unit<Vector<N>>,
+    ground: Option<(&dyn Body<N>, &dyn BodyPart<N>)>,
+) -> N {
+    let inv_mass_of = |body: &dyn Body<N>, part: &dyn BodyPart<N>| {
+        if !body.is_dynamic() {
+            return N::zero();
+        }
+
+        let r = point - part.center_of_mass();
+        let force = Force::linear_at_point(**dir, &Point::from(r));
+        let vel = part.inertia().inverse() * force;
+        vel.shift(&r).linear.dot(dir)
+    };
+
+    let mut res = match chassis.part(0) {
+        Some(part) => inv_mass_of(chassis, part),
+        None => N::zero(),
+    };
+
+    if let Some((body, part)) = ground {
+        res += inv_mass_of(body, part);
+    }
+
+    res
+}
+
+#[cfg(test)]
+mod test {
+    use super::{RaycastVehicle, Wheel};
+    use crate::force_generator::{DefaultForceGeneratorHandle, DefaultForceGeneratorSet};
+    use crate::joint::DefaultJointConstraintSet;
+    use crate::math::{Point, Vector};
+    use crate::object::{
+        BodyPartHandle, ColliderDesc, DefaultBodyHandle, DefaultBodySet, DefaultColliderSet,
+        Ground, RigidBodyDesc,
+    };
+    use crate::world::{DefaultGeometricalWorld, DefaultMechanicalWorld};
+    use ncollide::pipeline::CollisionGroups;
+    use ncollide::shape::{Cuboid, ShapeHandle};
+
+    const CHASSIS_MASS: f64 = 800.0;
+
+    struct Scene {
+        mechanical_world: DefaultMechanicalWorld<f64>,
+        geometrical_world: DefaultGeometricalWorld<f64>,
+        bodies: DefaultBodySet<f64>,
+        colliders: DefaultColliderSet<f64>,
+        joint_constraints: DefaultJointConstraintSet<f64>,
+        force_generators: DefaultForceGeneratorSet<f64>,
+        vehicle: DefaultForceGeneratorHandle,
+    }
+
+    impl Scene {
+        // A vehicle moving along `x` above a flat floor, with two wheels in 2D and four in 3D.
+        fn new() -> Self {
+            let mut bodies = DefaultBodySet::new();
+            let mut colliders = DefaultColliderSet::new();
+
+            let ground = bodies.insert(Ground::new());
+            let floor = ShapeHandle::new(Cuboid::new(Vector::repeat(100.0)));
+            let _ = colliders.insert(
+                ColliderDesc::new(floor)
+                    .translation(Vector::y() * -100.0)
+                    .build(BodyPartHandle(ground, 0)),
+            );
+
+            let mut half_extents = Vector::repeat(1.0);
+            half_extents.x = 2.0;
+            half_extents.y = 0.25;
+            // The chassis is 2 square meters in 2D, and 4 cubic meters in 3D.
+            #[cfg(feature = "dim2")]
+            let density = CHASSIS_MASS / 2.0;
+            #[cfg(feature = "dim3")]
+            let density = CHASSIS_MASS / 4.0;
+            let chassis = RigidBodyDesc::new().translation(Vector::y()).build();
+            let chassis = bodies.insert(chassis);
+            let _ = colliders.insert(
+                ColliderDesc::new(ShapeHandle::new(Cuboid::new(half_extents)))
+                    .density(density)
+                    .build(BodyPartHandle(chassis, 0)),
+            );
+
+            let mut vehicle = RaycastVehicle::new(chassis, Vector::x_axis());
+            #[cfg(feature = "dim2")]
+            let connections = [Vector::x() * 1.5, Vector::x() * -1.5];
+            #[cfg(feature = "dim3")]
+            let connections = [
+                Vector::new(1.5, 0.0, 0.8),
+                Vector::new(1.5, 0.0, -0.8),
+                Vector::new(-1.5, 0.0, 0.8),
+                Vector::new(-1.5, 0.0, -0.8),
+            ];
+
+            for connection in connections.iter() {
+                let wheel = Wheel::new(Point::from(*connection), -Vector::y_axis(), 0.4, 0.5);
+                let _ = vehicle.add_wheel(wheel);
+            }
+
+            let mut force_generators = DefaultForceGeneratorSet::new();
+            let vehicle = force_generators.insert(Box::new(vehicle));
+
+            Scene {
+                mechanical_world: DefaultMechanicalWorld::new(Vector::y() * -9.81),
+                geometrical_world: DefaultGeometricalWorld::new(),
+                bodies,
+                colliders,
+                joint_constraints: DefaultJointConstraintSet::new(),
+                force_generators,
+                vehicle,
+            }
+        }
+
+        fn vehicle(&mut self) -> &mut RaycastVehicle<f64, DefaultBodyHandle> {
+            self.force_generators
+                .get_mut(self.vehicle)
+                .and_then(|f| f.downcast_mut())
+                .unwrap()
+        }
+
+        fn forward_speed(&self) -> f64 {
+            self.force_generators
+                .get(self.vehicle)
+                .and_then(|f| f.downcast_ref::<RaycastVehicle<f64, DefaultBodyHandle>>())
+                .unwrap()
+                .forward_speed(&self.bodies)
+        }
+
+        fn step(&mut self, nsteps: usize) {
+            for _ in 0..nsteps {
+                let vehicle = self
+                    .force_generators
+                    .get_mut(self.vehicle)
+                    .and_then(|f| f.downcast_mut::<RaycastVehicle<f64, DefaultBodyHandle>>())
+                    .unwrap();
+                vehicle.update_wheels(
+                    &self.geometrical_world,
+                    &self.bodies,
+                    &self.colliders,
+                    &CollisionGroups::new(),
+                );
+
+                let _ = self.mechanical_world.step(
+                    &mut self.geometrical_world,
+                    &mut self.bodies,
+                    &mut self.colliders,
+                    &mut self.joint_constraints,
+                    &mut self.force_generators,
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn vehicle_rests_on_its_suspension() {
+        let mut scene = Scene::new();
+        scene.step(300);
+
+        let vehicle = scene.vehicle();
+        assert!(vehicle.wheels().iter().all(|w| w.is_in_contact()));
+        let support: f64 = vehicle.wheels().iter().map(|w| w.suspension_force()).sum();
+        assert_relative_eq!(support, CHASSIS_MASS * 9.81, max_relative = 1.0e-2);
+    }
+
+    #[test]
+    fn vehicle_accelerates_and_brakes() {
+        let mut scene = Scene::new();
+        scene.step(300);
+
+        for wheel in scene.vehicle().wheels_mut() {
+            wheel.set_engine_force(1000.0);
+        }
+
+        scene.step(60);
+        let speed = scene.forward_speed();
+        assert!(speed > 1.0, "The vehicle did not accelerate: {}", speed);
+
+        for wheel in scene.vehicle().wheels_mut() {
+            wheel.set_engine_force(0.0);
+            wheel.set_brake(2000.0);
+        }
+
+        scene.step(120);
+        let speed = scene.forward_speed();
+        assert_relative_eq!(speed, 0.0, epsilon = 1.0e-2);
+        assert!(scene
+            .vehicle()
+            .wheels()
+            .iter()
+            .all(|w| w.angular_velocity().abs() < 0.1));
+    }
+}
@@ -15,7 +15,7 @@ mod stages_counters;
 mod timer;
 
 /// Aggregation of all the performances counters tracked by nphysics.
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Default)]
 pub struct Counters {
     /// Whether thi counter is enabled or not.
     pub enabled: bool,
@@ -100,6 +100,11 @@ impl Counters {
         self.custom.time()
     }
 
+    /// Set the number of islands solved.
+    pub fn set_nislands(&mut self, n: usize) {
+        self.solver.nislands = n;
+    }
+
     /// Set the number of constraints generated.
     pub fn set_nconstraints(&mut self, n: usize) {
         self.solver.nconstraints = n;
@@ -4,6 +4,8 @@ use std::fmt::{Display, Formatter, Result};
 /// Performance counters related to constraints resolution.
 #[derive(Default, Clone, Copy)]
 pub struct SolverCounters {
+    /// Number of islands solved.
+    pub nislands: usize,
     /// Number of constraints generated.
     pub nconstraints: usize,
     /// Number of contacts found.
@@ -22,6 +24,7 @@ impl SolverCounters {
     /// Creates a new counter initialized to zero.
     pub fn new() -> Self {
         SolverCounters {
+            nislands: 0,
             nconstraints: 0,
             ncontacts: 0,
             assembly_time: Timer::new(),
@@ -34,6 +37,7 @@ impl SolverCounters {
 
 impl Display for SolverCounters {
     fn fmt(&self, f: &mut Formatter) -> Result {
+        writeln!(f, "Number of islands: {}", self.nislands)?;
         writeln!(f, "Number of contacts: {}", self.ncontacts)?;
         writeln!(f, "Number of constraints: {}", self.nconstraints)?;
         writeln!(f, "Assembly time: {}", self.assembly_time)?;
@@ -0,0 +1,22 @@
+/// The reason why a sleeping body woke up.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub enum WakeUpCause {
+    /// The body is in contact, directly or through other sleeping bodies, with an active body.
+    Contact,
+    /// The body is attached, directly or through other sleeping bodies, to an active body by a joint, or one
+    /// of its joints has been inserted or removed.
+    Joint,
+    /// The body has been woken up by the user, e.g., by modifying it or by calling its `activate` method.
+    User,
+}
+
+/// Event generated when a body falls asleep or wakes up.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub enum ActivationEvent<Handle> {
+    /// The body identified by the given handle fell asleep.
+    FellAsleep(Handle),
+    /// The body identified by the given handle woke up.
+    WokeUp(Handle, WakeUpCause),
+}
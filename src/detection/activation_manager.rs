@@ -1,3 +1,6 @@
+use std::collections::HashMap;
+
+use crate::detection::{ActivationEvent, WakeUpCause};
 use crate::joint::{JointConstraint, JointConstraintSet};
 use crate::object::{Body, BodyHandle, BodySet, ColliderSet};
 use crate::utils::union_find;
@@ -7,14 +10,27 @@ use na::{self, RealField};
 
 /// Structure that monitors island-based activation/deactivation of bodies.
 ///
-/// It is responsible for making objects sleep or wake up.
+/// It is responsible for making objects sleep or wake up. It also builds the simulation
+/// islands, i.e., the groups of active bodies connected by contacts or joints, that can be
+/// solved independently from each other. The bodies that fell asleep or woke up during the last
+/// update are reported as activation events.
 #[derive(Clone)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct ActivationManager<N: RealField, Handle: BodyHandle> {
     mix_factor: N,
     ufind: Vec<UnionFindSet>,
     can_deactivate: Vec<bool>,
     to_activate: Vec<Handle>,
     id_to_body: Vec<Handle>,
+    body_islands: Vec<usize>,
+    islands: Vec<Vec<Handle>>,
+    // Whether each body was active at the end of the last update.
+    activation_states: HashMap<Handle, bool>,
+    #[cfg_attr(feature = "serde-serialize", serde(skip, default = "HashMap::new"))]
+    prev_activation_states: HashMap<Handle, bool>,
+    wake_up_causes: Vec<(Handle, WakeUpCause)>,
+    island_wake_up_causes: Vec<Option<WakeUpCause>>,
+    events: Vec<ActivationEvent<Handle>>,
 }
 
 impl<N: RealField, Handle: BodyHandle> ActivationManager<N, Handle> {
@@ -35,9 +51,60 @@ impl<N: RealField, Handle: BodyHandle> ActivationManager<N, Handle> {
             can_deactivate: Vec::new(),
             to_activate: Vec::new(),
             id_to_body: Vec::new(),
+            body_islands: Vec::new(),
+            islands: Vec::new(),
+            activation_states: HashMap::new(),
+            prev_activation_states: HashMap::new(),
+            wake_up_causes: Vec::new(),
+            island_wake_up_causes: Vec::new(),
+            events: Vec::new(),
         }
     }
 
+    /// The active islands computed by the last update.
+    ///
+    /// Each island contains the handles of the dynamic bodies it is made of.
+    pub fn islands(&self) -> &[Vec<Handle>] {
+        &self.islands
+    }
+
+    /// The index of the active island containing the given body, as computed by the last update.
+    ///
+    /// Returns `None` if the body is not dynamic, is sleeping, or does not exist.
+    pub fn body_island(
+        &self,
+        bodies: &dyn BodySet<N, Handle = Handle>,
+        handle: Handle,
+    ) -> Option<usize> {
+        let id = bodies.get(handle)?.companion_id();
+
+        if self.id_to_body.get(id) != Some(&handle) {
+            return None;
+        }
+
+        let island = self.body_islands[id];
+
+        if island != usize::max_value() {
+            Some(island)
+        } else {
+            None
+        }
+    }
+
+    /// The bodies that fell asleep or woke up during the last update.
+    ///
+    /// This includes the bodies put to sleep or woken up by the user since the update before it.
+    pub fn events(&self) -> &[ActivationEvent<Handle>] {
+        &self.events
+    }
+
+    /// Sets the cause reported if the given body is found to have been woken up at the next update.
+    ///
+    /// Bodies woken up between two updates without any known cause are reported as woken up by the user.
+    pub(crate) fn set_wake_up_cause(&mut self, handle: Handle, cause: WakeUpCause) {
+        self.wake_up_causes.push((handle, cause))
+    }
+
     /// Notify the `ActivationManager2` that is has to activate an object at the next update.
     // FIXME: this is not a very good name
     pub fn deferred_activate(&mut self, handle: Handle) {
@@ -58,13 +125,14 @@ impl<N: RealField, Handle: BodyHandle> ActivationManager<N, Handle> {
     }
 
     /// Update the activation manager, activating and deactivating objects when needed.
+    ///
+    /// This also computes the active islands which can then be retrieved with `self.islands()`.
     pub fn update<Colliders, Constraints>(
         &mut self,
         bodies: &mut dyn BodySet<N, Handle = Handle>,
         colliders: &Colliders,
         gworld: &GeometricalWorld<N, Handle, Colliders::Handle>,
         constraints: &Constraints,
-        active_bodies: &mut Vec<Handle>,
     ) where
         Colliders: ColliderSet<N, Handle>,
         Constraints: JointConstraintSet<N, Handle>,
@@ -75,9 +143,31 @@ impl<N: RealField, Handle: BodyHandle> ActivationManager<N, Handle> {
          *
          */
         self.id_to_body.clear();
+        self.events.clear();
+        std::mem::swap(
+            &mut self.activation_states,
+            &mut self.prev_activation_states,
+        );
+        self.activation_states.clear();
 
         bodies.foreach_mut(&mut |handle, body: &mut dyn Body<N>| {
             if body.status_dependent_ndofs() != 0 {
+                // Report the bodies put to sleep or woken up since the last update.
+                match self.prev_activation_states.get(&handle) {
+                    Some(true) if !body.is_active() => {
+                        self.events.push(ActivationEvent::FellAsleep(handle))
+                    }
+                    Some(false) if body.is_active() => {
+                        let cause = self
+                            .wake_up_causes
+                            .iter()
+                            .find(|(h, _)| *h == handle)
+                            .map_or(WakeUpCause::User, |(_, cause)| *cause);
+                        self.events.push(ActivationEvent::WokeUp(handle, cause))
+                    }
+                    _ => {}
+                }
+
                 if body.is_active() {
                     self.update_energy(body);
                 }
@@ -101,11 +191,17 @@ impl<N: RealField, Handle: BodyHandle> ActivationManager<N, Handle> {
             let body = try_continue!(bodies.get_mut(*handle));
 
             if body.activation_status().deactivation_threshold().is_some() {
+                if !body.is_active() {
+                    self.events
+                        .push(ActivationEvent::WokeUp(*handle, WakeUpCause::User));
+                }
+
                 body.activate()
             }
         }
 
         self.to_activate.clear();
+        self.wake_up_causes.clear();
 
         /*
          *
@@ -116,6 +212,13 @@ impl<N: RealField, Handle: BodyHandle> ActivationManager<N, Handle> {
         self.ufind
             .resize(self.id_to_body.len(), UnionFindSet::new(0));
         self.can_deactivate.resize(self.id_to_body.len(), true);
+        self.island_wake_up_causes.clear();
+        self.island_wake_up_causes
+            .resize(self.id_to_body.len(), None);
+        self.body_islands.clear();
+        self.body_islands
+            .resize(self.id_to_body.len(), usize::max_value());
+        self.islands.clear();
 
         // Init the union find.
         // FIXME: are there more efficient ways of doing those?
@@ -129,32 +232,58 @@ impl<N: RealField, Handle: BodyHandle> ActivationManager<N, Handle> {
 
         // Run the union-find.
         // FIXME: use the union-find from petgraph?
+        //
+        // The sleeping bodies directly connected to an active body also record the
+        // kind of connection, which will be the cause reported if they wake up.
         #[inline(always)]
         fn make_union<N: RealField, Handle: BodyHandle>(
             bodies: &dyn BodySet<N, Handle = Handle>,
             b1: Handle,
             b2: Handle,
+            cause: WakeUpCause,
             ufs: &mut [UnionFindSet],
+            causes: &mut [Option<WakeUpCause>],
         ) {
             let b1 = try_ret!(bodies.get(b1));
             let b2 = try_ret!(bodies.get(b2));
             if (b1.status_dependent_ndofs() != 0 || b1.is_kinematic())
                 && (b2.status_dependent_ndofs() != 0 || b2.is_kinematic())
             {
-                union_find::union(b1.companion_id(), b2.companion_id(), ufs)
+                let (id1, id2) = (b1.companion_id(), b2.companion_id());
+                union_find::union(id1, id2, ufs);
+
+                if b1.is_active() && !b2.is_active() && causes[id2].is_none() {
+                    causes[id2] = Some(cause);
+                } else if b2.is_active() && !b1.is_active() && causes[id1].is_none() {
+                    causes[id1] = Some(cause);
+                }
             }
         }
 
         for (_, c1, _, c2, _, manifold) in gworld.contact_pairs(colliders, false) {
             if manifold.len() > 0 {
-                make_union(bodies, c1.body(), c2.body(), &mut self.ufind)
+                make_union(
+                    bodies,
+                    c1.body(),
+                    c2.body(),
+                    WakeUpCause::Contact,
+                    &mut self.ufind,
+                    &mut self.island_wake_up_causes,
+                )
             }
         }
 
         constraints.foreach(|_, c| {
             if !c.is_broken() {
                 let (b1, b2) = c.anchors();
-                make_union(bodies, b1.0, b2.0, &mut self.ufind);
+                make_union(
+                    bodies,
+                    b1.0,
+                    b2.0,
+                    WakeUpCause::Joint,
+                    &mut self.ufind,
+                    &mut self.island_wake_up_causes,
+                );
             }
         });
 
@@ -175,7 +304,21 @@ impl<N: RealField, Handle: BodyHandle> ActivationManager<N, Handle> {
             };
         }
 
+        // Sleeping bodies indirectly connected to an active body wake up for the
+        // same reason as the first body of their set directly connected to it.
+        for i in 0usize..self.ufind.len() {
+            let root = union_find::find(i, &mut self.ufind[..]);
+
+            if self.island_wake_up_causes[root].is_none() {
+                self.island_wake_up_causes[root] = self.island_wake_up_causes[i];
+            }
+        }
+
         // Activate/deactivate islands.
+        // Maps the root of each active set to the index of its island.
+        // FIXME: avoid this allocation.
+        let mut root_islands = vec![usize::max_value(); self.ufind.len()];
+
         for i in 0usize..self.ufind.len() {
             let root = union_find::find(i, &mut self.ufind[..]);
             let handle = self.id_to_body[i];
@@ -185,18 +328,32 @@ impl<N: RealField, Handle: BodyHandle> ActivationManager<N, Handle> {
                 // Everybody in this set can be deactivacted.
                 if body.is_active() {
                     body.deactivate();
+                    self.events.push(ActivationEvent::FellAsleep(handle));
                 }
             } else if !body.is_kinematic() {
                 // Everybody in this set must be reactivated.
-                active_bodies.push(handle);
+                if root_islands[root] == usize::max_value() {
+                    root_islands[root] = self.islands.len();
+                    self.islands.push(Vec::new());
+                }
+
+                self.body_islands[i] = root_islands[root];
+                self.islands[root_islands[root]].push(handle);
 
                 // FIXME: avoid the Copy when NLL lands ?
                 let status = *body.activation_status();
 
                 if !status.is_active() && status.deactivation_threshold().is_some() {
-                    body.activate()
+                    body.activate();
+
+                    let cause = self.island_wake_up_causes[i]
+                        .or(self.island_wake_up_causes[root])
+                        .unwrap_or(WakeUpCause::Contact);
+                    self.events.push(ActivationEvent::WokeUp(handle, cause));
                 }
             }
+
+            let _ = self.activation_states.insert(handle, body.is_active());
         }
     }
 }
@@ -1,7 +1,10 @@
 use na::RealField;
 use ncollide::query::{ContactManifold, TrackedContact};
 use ncollide::shape::FeatureId;
+use std::cmp::Ordering;
 
+use crate::detection::ModifiableContact;
+use crate::material::LocalMaterialProperties;
 use crate::object::{BodyHandle, BodyPartHandle, Collider, ColliderAnchor, ColliderHandle};
 
 /// A contact manifold between two bodies.
@@ -18,6 +21,10 @@ pub struct ColliderContactManifold<'a, N: RealField, Handle: BodyHandle, CollHan
     pub collider2: &'a Collider<N, Handle>,
     /// The contact manifold.
     pub manifold: &'a ContactManifold<N>,
+    /// The contacts resulting from the execution of a `ContactModifier` on this manifold, if any.
+    ///
+    /// If set, these contacts are used instead of the contacts of `self.manifold`.
+    pub modified_contacts: Option<Vec<ModifiableContact<N>>>,
 }
 
 impl<'a, N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
@@ -37,22 +44,57 @@ impl<'a, N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
             handle2,
             collider2,
             manifold,
+            modified_contacts: None,
         }
     }
 
     /// The number of contacts on the manifold.
     pub fn len(&self) -> usize {
-        self.manifold.len()
+        match &self.modified_contacts {
+            Some(contacts) => contacts.len(),
+            None => self.manifold.len(),
+        }
     }
 
     /// Get all the contacts from the manifold.
     pub fn contacts(&self) -> impl Iterator<Item = &TrackedContact<N>> {
-        self.manifold.contacts()
+        let modified = self.modified_contacts.as_ref();
+        let original = if modified.is_none() {
+            Some(self.manifold)
+        } else {
+            None
+        };
+
+        modified
+            .into_iter()
+            .flat_map(|contacts| contacts.iter().map(|c| &c.contact))
+            .chain(original.into_iter().flat_map(|m| m.contacts()))
     }
 
     /// Get the deepest contact, if any, from the manifold.
     pub fn deepest_contact(&self) -> Option<&TrackedContact<N>> {
-        self.manifold.deepest_contact()
+        match &self.modified_contacts {
+            Some(contacts) => contacts.iter().map(|c| &c.contact).max_by(|a, b| {
+                a.contact
+                    .depth
+                    .partial_cmp(&b.contact.depth)
+                    .unwrap_or(Ordering::Equal)
+            }),
+            None => self.manifold.deepest_contact(),
+        }
+    }
+
+    /// Replaces the material properties computed for the given contact by the ones set by a `ContactModifier`, if any.
+    pub fn override_material_properties(
+        &self,
+        contact: &TrackedContact<N>,
+        props: &mut LocalMaterialProperties<N>,
+    ) {
+        if let Some(contacts) = &self.modified_contacts {
+            if let Some(modified) = contacts.iter().find(|c| c.contact.id == contact.id) {
+                modified.override_material_properties(props)
+            }
+        }
     }
 
     /// The handle of the first body involved in the contact.
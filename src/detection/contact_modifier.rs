@@ -0,0 +1,120 @@
+use na::{RealField, Unit};
+use ncollide::query::TrackedContact;
+
+use crate::detection::ColliderContactManifold;
+use crate::material::LocalMaterialProperties;
+use crate::math::Vector;
+use crate::object::{BodyHandle, BodySet, ColliderHandle};
+
+/// A contact that can be modified by a `ContactModifier` before its resolution.
+#[derive(Clone, Debug)]
+pub struct ModifiableContact<N: RealField> {
+    /// The contact. Its normal, points and penetration depth may be modified.
+    pub contact: TrackedContact<N>,
+    /// If set, replaces the friction coefficient resulting from the combination of the collider materials.
+    pub friction: Option<N>,
+    /// If set, replaces the restitution coefficient resulting from the combination of the collider materials.
+    pub restitution: Option<N>,
+    /// If set, replaces the target surface velocity resulting from the combination of the collider materials.
+    pub surface_velocity: Option<Vector<N>>,
+    /// If set, this contact is one-way: it is ignored unless its normal points toward this world-space direction.
+    pub one_way_normal: Option<Unit<Vector<N>>>,
+}
+
+impl<N: RealField> ModifiableContact<N> {
+    /// Initializes a modifiable contact that does not modify anything yet.
+    pub fn new(contact: TrackedContact<N>) -> Self {
+        ModifiableContact {
+            contact,
+            friction: None,
+            restitution: None,
+            surface_velocity: None,
+            one_way_normal: None,
+        }
+    }
+
+    /// Whether this contact should be ignored because of its one-way direction.
+    pub fn is_one_way_blocked(&self) -> bool {
+        self.one_way_normal
+            .map(|n| self.contact.contact.normal.dot(&n) <= N::zero())
+            .unwrap_or(false)
+    }
+
+    /// Replaces the given material properties by the ones set on this contact, if any.
+    pub fn override_material_properties(&self, props: &mut LocalMaterialProperties<N>) {
+        if let Some(friction) = self.friction {
+            props.friction.0 = friction;
+        }
+
+        if let Some(restitution) = self.restitution {
+            props.restitution.0 = restitution;
+        }
+
+        if let Some(surface_velocity) = self.surface_velocity {
+            props.surface_velocity = surface_velocity;
+        }
+    }
+}
+
+/// A user-defined hook executed on each contact manifold before the generation of contact constraints.
+///
+/// This is executed after the collection of all the contact manifolds that will be handed to the
+/// constraints solver. Contrary to `Material::local_properties`, it has access to both colliders
+/// and may discard contacts.
+pub trait ContactModifier<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>:
+    Send + Sync
+{
+    /// Modifies the contacts of the given contact manifold.
+    ///
+    /// The contacts removed from `contacts` are ignored by the constraints solver during this step.
+    fn modify_contacts(
+        &mut self,
+        bodies: &dyn BodySet<N, Handle = Handle>,
+        manifold: &ColliderContactManifold<N, Handle, CollHandle>,
+        contacts: &mut Vec<ModifiableContact<N>>,
+    );
+}
+
+impl<N, Handle, CollHandle, F> ContactModifier<N, Handle, CollHandle> for F
+where
+    N: RealField,
+    Handle: BodyHandle,
+    CollHandle: ColliderHandle,
+    F: FnMut(
+            &dyn BodySet<N, Handle = Handle>,
+            &ColliderContactManifold<N, Handle, CollHandle>,
+            &mut Vec<ModifiableContact<N>>,
+        ) + Send
+        + Sync,
+{
+    fn modify_contacts(
+        &mut self,
+        bodies: &dyn BodySet<N, Handle = Handle>,
+        manifold: &ColliderContactManifold<N, Handle, CollHandle>,
+        contacts: &mut Vec<ModifiableContact<N>>,
+    ) {
+        self(bodies, manifold, contacts)
+    }
+}
+
+/// Executes the contact modifier on the given manifold and stores the modified contacts on it.
+pub(crate) fn modify_manifold_contacts<N, Handle, CollHandle>(
+    modifier: &mut dyn ContactModifier<N, Handle, CollHandle>,
+    bodies: &dyn BodySet<N, Handle = Handle>,
+    manifold: &mut ColliderContactManifold<N, Handle, CollHandle>,
+) where
+    N: RealField,
+    Handle: BodyHandle,
+    CollHandle: ColliderHandle,
+{
+    let mut contacts = manifold.modified_contacts.take().unwrap_or_else(|| {
+        manifold
+            .manifold
+            .contacts()
+            .map(|c| ModifiableContact::new(*c))
+            .collect()
+    });
+    modifier.modify_contacts(bodies, manifold, &mut contacts);
+    contacts.retain(|c| !c.is_one_way_blocked());
+    manifold.modified_contacts = Some(contacts);
+}
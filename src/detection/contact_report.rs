@@ -0,0 +1,57 @@
+use na::{RealField, Unit};
+use ncollide::query::{ContactId, TrackedContact};
+
+use crate::math::Vector;
+use crate::object::Body;
+
+/// The impulses applied at one contact point during the last timestep.
+///
+/// All the quantities are expressed in world-space and describe the action on the second collider
+/// of the contact manifold. The first collider experiences the opposite impulses.
+#[derive(Copy, Clone, Debug)]
+pub struct ContactReport<N: RealField> {
+    /// The identifier of the contact this report is about.
+    pub id: ContactId,
+    /// The contact normal, pointing from the first collider toward the second one.
+    pub normal: Unit<Vector<N>>,
+    /// The magnitude of the impulse applied along the contact normal.
+    pub normal_impulse: N,
+    /// The impulse applied tangentially to the contact by friction.
+    pub friction_impulse: Vector<N>,
+    /// The velocity of the second collider relative to the first one at the contact point,
+    /// before the resolution of the contacts.
+    pub relative_velocity: Vector<N>,
+    /// The speed at which the colliders were approaching each other along the contact normal,
+    /// before the resolution of the contacts.
+    ///
+    /// This is negative if the colliders were moving apart.
+    pub approach_velocity: N,
+}
+
+impl<N: RealField> ContactReport<N> {
+    /// A report of the given contact without any impulse, recording the current velocities of the bodies.
+    pub(crate) fn before_resolution(
+        contact: &TrackedContact<N>,
+        body1: &dyn Body<N>,
+        part1: usize,
+        body2: &dyn Body<N>,
+        part2: usize,
+    ) -> Self {
+        let vel1 = body1
+            .velocity_at_point(part1, &contact.contact.world1)
+            .linear;
+        let vel2 = body2
+            .velocity_at_point(part2, &contact.contact.world2)
+            .linear;
+        let relative_velocity = vel2 - vel1;
+
+        ContactReport {
+            id: contact.id,
+            normal: contact.contact.normal,
+            normal_impulse: N::zero(),
+            friction_impulse: Vector::zeros(),
+            relative_velocity,
+            approach_velocity: -contact.contact.normal.dot(&relative_velocity),
+        }
+    }
+}
@@ -1,7 +1,16 @@
 //! Collision detection information.
 
+pub use self::activation_event::{ActivationEvent, WakeUpCause};
 pub use self::activation_manager::ActivationManager;
 pub use self::collider_contact_manifold::ColliderContactManifold;
+pub(crate) use self::contact_modifier::modify_manifold_contacts;
+pub use self::contact_modifier::{ContactModifier, ModifiableContact};
+pub use self::contact_report::ContactReport;
+pub(crate) use self::one_way_platforms::OneWayPlatforms;
 
+mod activation_event;
 mod activation_manager;
 mod collider_contact_manifold;
+mod contact_modifier;
+mod contact_report;
+mod one_way_platforms;
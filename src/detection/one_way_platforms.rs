@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+
+use na::RealField;
+use ncollide::query::TrackedContact;
+
+use crate::detection::ColliderContactManifold;
+use crate::object::{BodyHandle, ColliderHandle};
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+enum OneWayPairState {
+    // The contacts are solved normally until the manifold becomes empty.
+    Allowed,
+    // The contacts are ignored until the colliders stop penetrating.
+    Forbidden,
+}
+
+/// Tracks the collider pairs involving a one-way platform.
+///
+/// The decision to ignore the contacts of a pair is taken when the pair starts touching, and kept
+/// as long as it remains valid. This prevents a collider sliding on a platform from falling through
+/// it, and a collider partially crossing a platform from the wrong side from being pushed out on
+/// the solid side.
+pub(crate) struct OneWayPlatforms<CollHandle: ColliderHandle> {
+    pairs: HashMap<(CollHandle, CollHandle), (OneWayPairState, usize)>,
+    timestamp: usize,
+}
+
+impl<CollHandle: ColliderHandle> OneWayPlatforms<CollHandle> {
+    /// Creates an empty set of one-way platform pairs.
+    pub fn new() -> Self {
+        OneWayPlatforms {
+            pairs: HashMap::new(),
+            timestamp: 0,
+        }
+    }
+
+    /// Starts a new collection of contact manifolds.
+    pub fn begin(&mut self) {
+        self.timestamp += 1;
+    }
+
+    /// Forgets the pairs that have not been seen since the last call to `self.begin()`.
+    pub fn end(&mut self) {
+        let timestamp = self.timestamp;
+        self.pairs.retain(|_, state| state.1 == timestamp)
+    }
+
+    /// Discards all the contacts of the given manifold if they have to be ignored because of a one-way platform.
+    pub fn filter_contacts<N: RealField, Handle: BodyHandle>(
+        &mut self,
+        manifold: &mut ColliderContactManifold<N, Handle, CollHandle>,
+    ) {
+        // The contact normals point toward the exterior of the first collider.
+        let (normal, sign) = if let Some(n) = manifold.collider1.one_way_normal() {
+            (manifold.collider1.position() * n, N::one())
+        } else if let Some(n) = manifold.collider2.one_way_normal() {
+            (manifold.collider2.position() * n, -N::one())
+        } else {
+            return;
+        };
+
+        let key = (manifold.handle1, manifold.handle2);
+
+        if manifold.len() == 0 {
+            let _ = self.pairs.remove(&key);
+            return;
+        }
+
+        let is_penetrating = |c: &TrackedContact<N>| c.contact.depth > N::zero();
+        let is_on_solid_side =
+            |c: &TrackedContact<N>| c.contact.normal.dot(&normal) * sign > N::zero();
+
+        let state = match self.pairs.get(&key) {
+            Some((OneWayPairState::Allowed, _)) => OneWayPairState::Allowed,
+            Some((OneWayPairState::Forbidden, _)) if manifold.contacts().any(is_penetrating) => {
+                OneWayPairState::Forbidden
+            }
+            _ => {
+                if manifold.contacts().all(is_on_solid_side) {
+                    OneWayPairState::Allowed
+                } else {
+                    OneWayPairState::Forbidden
+                }
+            }
+        };
+
+        let _ = self.pairs.insert(key, (state, self.timestamp));
+
+        if state == OneWayPairState::Forbidden {
+            manifold.modified_contacts = Some(Vec::new());
+        }
+    }
+}
+
+// The pairs are serialized as a sequence because some formats only support maps with string keys.
+#[cfg(feature = "serde-serialize")]
+impl<CollHandle: ColliderHandle + serde::Serialize> serde::Serialize
+    for OneWayPlatforms<CollHandle>
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let pairs: Vec<_> = self.pairs.iter().collect();
+        (pairs, self.timestamp).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde-serialize")]
+impl<'de, CollHandle: ColliderHandle + serde::Deserialize<'de>> serde::Deserialize<'de>
+    for OneWayPlatforms<CollHandle>
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (pairs, timestamp): (Vec<_>, usize) = serde::Deserialize::deserialize(deserializer)?;
+
+        Ok(OneWayPlatforms {
+            pairs: pairs.into_iter().collect(),
+            timestamp,
+        })
+    }
+}
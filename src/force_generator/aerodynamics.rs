@@ -0,0 +1,501 @@
+use na::{self, RealField, Unit};
+use ncollide::shape::Shape;
+use ncollide::utils;
+
+use crate::force_generator::tessellation::foreach_leaf_shape;
+#[cfg(feature = "dim2")]
+use crate::force_generator::tessellation::shape_polygon;
+#[cfg(feature = "dim3")]
+use crate::force_generator::tessellation::shape_trimesh;
+use crate::force_generator::ForceGenerator;
+use crate::math::{Dim, Force, ForceType, Isometry, Point, Vector, DIM};
+#[cfg(feature = "dim2")]
+use crate::object::FEMSurface;
+use crate::object::{
+    Body, BodyHandle, BodyPartHandle, BodySet, ColliderHandle, ColliderSet, FiniteElementIndices,
+    MassConstraintSystem, MassSpringSystem,
+};
+use crate::solver::IntegrationParameters;
+use crate::world::GeometricalWorld;
+
+// The wave vectors, frequencies, and phases of the sinusoids summed by the turbulent wind field.
+const TURBULENCE_WAVES: [[f64; 5]; 4] = [
+    [0.83, -0.41, 0.37, 1.13, 0.0],
+    [-0.29, 0.91, -0.63, 0.71, 2.39],
+    [0.57, 0.33, 0.89, 1.67, 4.21],
+    [-0.71, -0.59, 0.23, 0.53, 1.07],
+];
+
+/// The velocity field of the air surrounding the bodies affected by an `Aerodynamics` force generator.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub enum WindField<N: RealField> {
+    /// The wind velocity is the same everywhere, at all times.
+    Constant(Vector<N>),
+    /// The wind velocity is a mean velocity perturbed by a smooth pseudo-random noise.
+    Turbulent {
+        /// The mean wind velocity.
+        mean: Vector<N>,
+        /// The root mean square of the perturbation of each component of the wind velocity.
+        intensity: N,
+        /// The characteristic distance over which the perturbation changes.
+        length_scale: N,
+        /// The characteristic duration over which the perturbation changes.
+        time_scale: N,
+    },
+    /// The wind velocity is computed by a closure given the world-space point and the current time.
+    ///
+    /// Serializing this wind field fails with an error.
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    Custom(Box<dyn Fn(&Point<N>, N) -> Vector<N> + Send + Sync>),
+}
+
+impl<N: RealField> WindField<N> {
+    /// The wind velocity at the given world-space point and time.
+    pub fn velocity_at(&self, point: &Point<N>, time: N) -> Vector<N> {
+        match self {
+            WindField::Constant(velocity) => *velocity,
+            WindField::Turbulent {
+                mean,
+                intensity,
+                length_scale,
+                time_scale,
+            } => {
+                let p = point.coords / *length_scale;
+                let t = time / *time_scale;
+                // Each sinusoid has a variance of 1/2.
+                let scale = *intensity * na::convert::<_, N>(0.5f64).sqrt();
+                let mut velocity = *mean;
+
+                for k in 0..DIM {
+                    let mut noise = N::zero();
+
+                    for j in 0..TURBULENCE_WAVES.len() {
+                        let wave = &TURBULENCE_WAVES[(j + k) % TURBULENCE_WAVES.len()];
+                        let direction = Vector::from_fn(|i, _| na::convert::<_, N>(wave[i]));
+                        let phase = na::convert::<_, N>(wave[4] + k as f64 * 1.7);
+                        noise += (p.dot(&direction) + t * na::convert(wave[3]) + phase).sin();
+                    }
+
+                    velocity[k] += noise * scale;
+                }
+
+                velocity
+            }
+            WindField::Custom(f) => f(point, time),
+        }
+    }
+}
+
+// A flat piece of the surface of a body, exposed to the air.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+struct Panel<N: RealField> {
+    center: Point<N>,
+    normal: Unit<Vector<N>>,
+    // The area of the panel (resp. its length in 2D).
+    area: N,
+}
+
+/// Force generator applying aerodynamic drag and lift to a set of bodies.
+///
+/// The surface of each rigid body part and multibody link is approximated by a set of flat panels
+/// computed from the shapes of the colliders attached to it. Only balls, cuboids, capsules, convex
+/// polyhedra (resp. polygons in 2D), and compounds of those, are taken into account. The panels
+/// must be computed by calling `self.update` after the body parts have been added, and each time
+/// their colliders are modified.
+///
+/// Mass-spring systems and mass-constraint systems are affected through their triangles (resp.
+/// segments in 2D), seen as two-sided panels. Two-dimensional FEM surfaces are affected through
+/// their boundary.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct Aerodynamics<N: RealField, Handle: BodyHandle> {
+    wind: WindField<N>,
+    air_density: N,
+    drag_coefficient: N,
+    lift_coefficient: N,
+    parts: Vec<(BodyPartHandle<Handle>, Vec<Panel<N>>)>,
+    deformable_bodies: Vec<Handle>,
+}
+
+impl<N: RealField, Handle: BodyHandle> Aerodynamics<N, Handle> {
+    /// Creates an aerodynamics force generator with the given wind field.
+    ///
+    /// The air density is set to 1.2, and the drag and lift coefficients are set to 1.0.
+    pub fn new(wind: WindField<N>) -> Self {
+        Aerodynamics {
+            wind,
+            air_density: na::convert(1.2),
+            drag_coefficient: N::one(),
+            lift_coefficient: N::one(),
+            parts: Vec::new(),
+            deformable_bodies: Vec::new(),
+        }
+    }
+
+    /// The wind field.
+    pub fn wind(&self) -> &WindField<N> {
+        &self.wind
+    }
+
+    /// Sets the wind field.
+    pub fn set_wind(&mut self, wind: WindField<N>) {
+        self.wind = wind
+    }
+
+    /// The density of the air.
+    pub fn air_density(&self) -> N {
+        self.air_density
+    }
+
+    /// Sets the density of the air.
+    pub fn set_air_density(&mut self, density: N) {
+        self.air_density = density
+    }
+
+    /// The drag coefficient of each panel.
+    pub fn drag_coefficient(&self) -> N {
+        self.drag_coefficient
+    }
+
+    /// Sets the drag coefficient of each panel.
+    ///
+    /// The drag force applied to a panel is opposite to its velocity relative to the air, with a
+    /// magnitude equal to `0.5 * air_density * drag_coefficient * area * cos(angle) * speed²`, where
+    /// `angle` is the angle between the panel normal and its velocity relative to the air.
+    pub fn set_drag_coefficient(&mut self, coefficient: N) {
+        self.drag_coefficient = coefficient
+    }
+
+    /// The lift coefficient of each panel.
+    pub fn lift_coefficient(&self) -> N {
+        self.lift_coefficient
+    }
+
+    /// Sets the lift coefficient of each panel.
+    ///
+    /// The lift force applied to a panel is orthogonal to its velocity relative to the air, with a
+    /// magnitude equal to `0.5 * air_density * lift_coefficient * area * cos(angle) * sin(angle) * speed²`.
+    pub fn set_lift_coefficient(&mut self, coefficient: N) {
+        self.lift_coefficient = coefficient
+    }
+
+    /// Add a rigid body part or a multibody link to be affected by this force generator.
+    pub fn add_body_part(&mut self, body: BodyPartHandle<Handle>) {
+        self.parts.push((body, Vec::new()))
+    }
+
+    /// Add a mass-spring system, a mass-constraint system, or a 2D FEM surface to be affected by this force generator.
+    pub fn add_deformable_body(&mut self, body: Handle) {
+        self.deformable_bodies.push(body)
+    }
+
+    /// Computes the panels of each body part affected by this force generator from the shapes of their colliders.
+    ///
+    /// The sensors are ignored.
+    pub fn update<CollHandle, Colliders>(
+        &mut self,
+        gworld: &GeometricalWorld<N, Handle, CollHandle>,
+        colliders: &Colliders,
+    ) where
+        CollHandle: ColliderHandle,
+        Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
+    {
+        for (part, panels) in &mut self.parts {
+            panels.clear();
+
+            for handle in gworld.body_colliders(part.0).unwrap_or(&[]) {
+                if let Some(collider) = colliders.get(*handle) {
+                    if collider.body_part(0) == *part && !collider.is_sensor() {
+                        shape_panels(collider.shape(), &collider.position_wrt_body(), panels);
+                    }
+                }
+            }
+        }
+    }
+
+    // The aerodynamic force applied to a panel moving at the given velocity relative to the air.
+    fn panel_force(
+        &self,
+        panel: &Panel<N>,
+        velocity: &Vector<N>,
+        two_sided: bool,
+    ) -> Option<Vector<N>> {
+        let (dir, speed) = Unit::try_new_and_get(*velocity, N::default_epsilon())?;
+        let mut normal = panel.normal;
+        let mut cos = normal.dot(&dir);
+
+        if cos < N::zero() && two_sided {
+            normal = -normal;
+            cos = -cos;
+        }
+
+        if cos <= N::zero() {
+            // The panel is not facing the flow.
+            return None;
+        }
+
+        let pressure = na::convert::<_, N>(0.5) * self.air_density * panel.area * speed * speed;
+        let drag = -dir.into_inner() * (pressure * self.drag_coefficient * cos);
+        let lift = -(normal.into_inner() - dir.into_inner() * cos)
+            * (pressure * self.lift_coefficient * cos);
+
+        Some(drag + lift)
+    }
+
+    // The aerodynamic forces applied to the elements of a deformable body.
+    //
+    // Each force is given with the index of the element it is applied to, and its application
+    // point. This point is expressed in the local coordinates of the element if the last tuple
+    // element is `true`, and in world-space otherwise.
+    fn deformable_forces(
+        &self,
+        body: &dyn Body<N>,
+        time: N,
+    ) -> Vec<(usize, Vector<N>, Point<N>, bool)> {
+        let mut forces = Vec::new();
+        let (_, positions) = try_ret!(body.deformed_positions(), forces);
+        let velocities = body.generalized_velocity();
+        let position = |i: usize| Point::from_slice(&positions[i..i + DIM]);
+        let velocity = |i: usize| velocities.fixed_rows::<Dim>(i).into_owned();
+
+        if let Some(mss) = body.downcast_ref::<MassSpringSystem<N>>() {
+            self.element_forces(
+                mss.element_indices(),
+                &position,
+                &velocity,
+                time,
+                &mut forces,
+            );
+        } else if let Some(mcs) = body.downcast_ref::<MassConstraintSystem<N>>() {
+            self.element_forces(
+                mcs.element_indices(),
+                &position,
+                &velocity,
+                time,
+                &mut forces,
+            );
+        }
+
+        #[cfg(feature = "dim2")]
+        {
+            if let Some(surface) = body.downcast_ref::<FEMSurface<N>>() {
+                for (edge, element) in surface.boundary() {
+                    let a = position(edge.x);
+                    let b = position(edge.y);
+                    let ab = b - a;
+                    // The interior of the surface lies on the right of its boundary edges.
+                    let (normal, area) = try_continue!(Unit::try_new_and_get(
+                        Vector::new(-ab.y, ab.x),
+                        N::default_epsilon()
+                    ));
+                    let center = na::center(&a, &b);
+                    let panel = Panel {
+                        center,
+                        normal,
+                        area,
+                    };
+                    let v = (velocity(edge.x) + velocity(edge.y)) * na::convert::<_, N>(0.5)
+                        - self.wind.velocity_at(&center, time);
+
+                    if let Some(force) = self.panel_force(&panel, &v, false) {
+                        forces.push((element, force, center, false));
+                    }
+                }
+            }
+        }
+
+        forces
+    }
+
+    // The aerodynamic forces applied to the triangles (resp. segments in 2D) of a mass-spring or mass-constraint system.
+    fn element_forces(
+        &self,
+        elements: impl Iterator<Item = FiniteElementIndices>,
+        position: &dyn Fn(usize) -> Point<N>,
+        velocity: &dyn Fn(usize) -> Vector<N>,
+        time: N,
+        forces: &mut Vec<(usize, Vector<N>, Point<N>, bool)>,
+    ) {
+        for (i, element) in elements.enumerate() {
+            match element {
+                #[cfg(feature = "dim3")]
+                FiniteElementIndices::Triangle(idx) => {
+                    let a = position(idx.x);
+                    let b = position(idx.y);
+                    let c = position(idx.z);
+                    let (normal, norm) = try_continue!(Unit::try_new_and_get(
+                        (b - a).cross(&(c - a)),
+                        N::default_epsilon()
+                    ));
+                    let third = na::convert::<_, N>(1.0 / 3.0);
+                    let center = Point::from((a.coords + b.coords + c.coords) * third);
+                    let panel = Panel {
+                        center,
+                        normal,
+                        area: norm * na::convert(0.5),
+                    };
+                    let v = (velocity(idx.x) + velocity(idx.y) + velocity(idx.z)) * third
+                        - self.wind.velocity_at(&center, time);
+
+                    if let Some(force) = self.panel_force(&panel, &v, true) {
+                        forces.push((i, force, Point::new(third, third, N::zero()), true));
+                    }
+                }
+                #[cfg(feature = "dim2")]
+                FiniteElementIndices::Segment(idx) => {
+                    let a = position(idx.x);
+                    let b = position(idx.y);
+                    let ab = b - a;
+                    let (normal, area) = try_continue!(Unit::try_new_and_get(
+                        Vector::new(-ab.y, ab.x),
+                        N::default_epsilon()
+                    ));
+                    let half = na::convert::<_, N>(0.5);
+                    let center = na::center(&a, &b);
+                    let panel = Panel {
+                        center,
+                        normal,
+                        area,
+                    };
+                    let v = (velocity(idx.x) + velocity(idx.y)) * half
+                        - self.wind.velocity_at(&center, time);
+
+                    if let Some(force) = self.panel_force(&panel, &v, true) {
+                        forces.push((i, force, Point::new(half, N::zero()), true));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl<N: RealField, Handle: BodyHandle> ForceGenerator<N, Handle> for Aerodynamics<N, Handle> {
+    fn apply(
+        &mut self,
+        parameters: &IntegrationParameters<N>,
+        bodies: &mut dyn BodySet<N, Handle = Handle>,
+    ) {
+        self.parts.retain(|(h, _)| bodies.get(h.0).is_some());
+        self.deformable_bodies.retain(|h| bodies.get(*h).is_some());
+
+        for (part, panels) in &self.parts {
+            if panels.is_empty() {
+                continue;
+            }
+
+            let body = try_continue!(bodies.get_mut(part.0));
+            let (position, center_of_mass) = {
+                let body_part = try_continue!(body.part(part.1));
+                (body_part.position(), body_part.center_of_mass())
+            };
+            let mut total = Force::zero();
+
+            for panel in panels {
+                let center = position * panel.center;
+                let panel = Panel {
+                    center,
+                    normal: position * panel.normal,
+                    area: panel.area,
+                };
+                let velocity = body.velocity_at_point(part.1, &center).linear
+                    - self.wind.velocity_at(&center, parameters.t);
+
+                if let Some(force) = self.panel_force(&panel, &velocity, false) {
+                    total += Force::linear_at_point(force, &Point::from(center - center_of_mass));
+                }
+            }
+
+            body.apply_force(part.1, &total, ForceType::Force, true);
+        }
+
+        for handle in &self.deformable_bodies {
+            let body = try_continue!(bodies.get_mut(*handle));
+
+            for (element, force, point, local) in self.deformable_forces(body, parameters.t) {
+                if local {
+                    body.apply_force_at_local_point(
+                        element,
+                        &force,
+                        &point,
+                        ForceType::Force,
+                        true,
+                    );
+                } else {
+                    body.apply_force_at_point(element, &force, &point, ForceType::Force, true);
+                }
+            }
+        }
+    }
+}
+
+// Adds to `panels` the panels approximating the surface of the given shape.
+fn shape_panels<N: RealField>(
+    shape: &dyn Shape<N>,
+    position: &Isometry<N>,
+    panels: &mut Vec<Panel<N>>,
+) {
+    foreach_leaf_shape(shape, position, &mut |shape, position| {
+        convex_shape_panels(shape, position, panels)
+    })
+}
+
+// Adds to `panels` the panels approximating the surface of the given shape, which is not a compound.
+fn convex_shape_panels<N: RealField>(
+    shape: &dyn Shape<N>,
+    position: &Isometry<N>,
+    panels: &mut Vec<Panel<N>>,
+) {
+    #[cfg(feature = "dim2")]
+    {
+        let vertices = try_ret!(shape_polygon(shape));
+        let center = utils::center(&vertices);
+
+        for i in 0..vertices.len() {
+            let a = vertices[i];
+            let b = vertices[(i + 1) % vertices.len()];
+            let ab = b - a;
+            let (mut normal, area) = try_continue!(Unit::try_new_and_get(
+                Vector::new(ab.y, -ab.x),
+                N::default_epsilon()
+            ));
+
+            if normal.dot(&(a - center)) < N::zero() {
+                normal = -normal;
+            }
+
+            panels.push(Panel {
+                center: position * na::center(&a, &b),
+                normal: position * normal,
+                area,
+            });
+        }
+    }
+
+    #[cfg(feature = "dim3")]
+    {
+        let (vertices, triangles) = try_ret!(shape_trimesh(shape));
+        let center = utils::center(&vertices);
+
+        for t in triangles {
+            let a = vertices[t.x as usize];
+            let b = vertices[t.y as usize];
+            let c = vertices[t.z as usize];
+            let (mut normal, norm) = try_continue!(Unit::try_new_and_get(
+                (b - a).cross(&(c - a)),
+                N::default_epsilon()
+            ));
+
+            if normal.dot(&(a - center)) < N::zero() {
+                normal = -normal;
+            }
+
+            panels.push(Panel {
+                center: position
+                    * Point::from((a.coords + b.coords + c.coords) / na::convert::<_, N>(3.0)),
+                normal: position * normal,
+                area: norm * na::convert(0.5),
+            });
+        }
+    }
+}
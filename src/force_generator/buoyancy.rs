@@ -0,0 +1,690 @@
+#[cfg(feature = "dim3")]
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use na::{RealField, Unit};
+#[cfg(feature = "dim3")]
+use ncollide::procedural::IndexBuffer;
+#[cfg(feature = "dim3")]
+use ncollide::shape::ConvexHull;
+#[cfg(feature = "dim2")]
+use ncollide::shape::ConvexPolygon;
+use ncollide::shape::{Cuboid, Plane, Shape, ShapeHandle};
+#[cfg(feature = "dim3")]
+use ncollide::transformation;
+#[cfg(feature = "dim3")]
+use ncollide::utils;
+
+use crate::force_generator::tessellation::foreach_leaf_shape;
+#[cfg(feature = "dim2")]
+use crate::force_generator::tessellation::shape_polygon;
+#[cfg(feature = "dim3")]
+use crate::force_generator::tessellation::shape_trimesh;
+use crate::force_generator::ForceGenerator;
+use crate::math::{Force, ForceType, Isometry, Point, Vector, DIM};
+use crate::object::{BodyHandle, BodyPartHandle, BodySet, ColliderHandle, ColliderSet};
+use crate::solver::IntegrationParameters;
+#[cfg(feature = "dim2")]
+use crate::volumetric::convex_polyline_area_and_center_of_mass_unchecked;
+use crate::volumetric::Volumetric;
+use crate::world::GeometricalWorld;
+
+/// The region of space filled by the fluid of a `Buoyancy` force generator.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub enum FluidRegion<N: RealField, CollHandle: ColliderHandle> {
+    /// The fluid fills the half-space bounded by the plane passing through `point` with the outward normal `normal`.
+    ///
+    /// The normal is expressed in world-space and points toward the exterior of the fluid, i.e.,
+    /// usually opposite to the gravity.
+    HalfSpace {
+        /// A point of the fluid surface, in world-space.
+        point: Point<N>,
+        /// The outward normal of the fluid surface, in world-space.
+        normal: Unit<Vector<N>>,
+    },
+    /// The fluid fills the volume of the given collider.
+    ///
+    /// The shape of this collider must be a `Plane`, a `Cuboid`, a convex polyhedron (resp. polygon
+    /// in 2D), or a compound of those. The parts of a compound must not overlap, otherwise the
+    /// submerged volumes are overestimated. The parts of any other shape, including triangle meshes
+    /// (resp. polylines in 2D), are ignored since they do not enclose a volume.
+    Collider(CollHandle),
+}
+
+// The submerged part of a body part, computed by `Buoyancy::update`.
+struct Submersion<N: RealField, Handle: BodyHandle> {
+    part: BodyPartHandle<Handle>,
+    // The submerged volume.
+    volume: N,
+    // The center of the submerged volume, in world-space.
+    center: Point<N>,
+    // The submerged volume divided by the total volume of the colliders of the part.
+    fraction: N,
+}
+
+// The planes bounding a convex region of fluid, given by a point and the outward normal of each plane.
+type FluidPlanes<N> = Vec<(Point<N>, Unit<Vector<N>>)>;
+
+// A convex polygon given by its vertices in counterclockwise order.
+#[cfg(feature = "dim2")]
+type ConvexPolytope<N> = Vec<Point<N>>;
+// A convex polyhedron given by its faces, each being a convex polygon.
+#[cfg(feature = "dim3")]
+type ConvexPolytope<N> = Vec<Vec<Point<N>>>;
+
+// The convex polytope approximating a shape that is not a compound.
+struct ShapePolytope<N: RealField> {
+    // The polytope, in the local space of the collider.
+    polytope: ConvexPolytope<N>,
+    // The volume of the polytope, which may differ from the volume of the shape if it is curved.
+    polytope_volume: N,
+    // The volume of the shape.
+    volume: N,
+    // The center of mass of the shape, in the local space of the collider.
+    center_of_mass: Point<N>,
+}
+
+// A value computed from the shape of a collider, and the shape it was computed from.
+//
+// Keeping a handle to the shape ensures the value is recomputed whenever the collider shape is replaced.
+struct ShapeCache<N: RealField, T> {
+    shape: ShapeHandle<N>,
+    value: T,
+}
+
+impl<N: RealField, T> ShapeCache<N, T> {
+    // Reuses `cache` if it has been computed from `shape`, otherwise computes a new value with `f`.
+    fn get_or_compute(
+        cache: Option<Self>,
+        shape: &ShapeHandle<N>,
+        f: impl FnOnce(&dyn Shape<N>) -> T,
+    ) -> Self {
+        match cache {
+            Some(cache) if Arc::ptr_eq(cache.shape.as_arc(), shape.as_arc()) => cache,
+            _ => ShapeCache {
+                shape: shape.clone(),
+                value: f(&**shape),
+            },
+        }
+    }
+}
+
+/// Force generator applying buoyancy and fluid drag to a set of body parts.
+///
+/// The submerged volume and the center of buoyancy of each body part are computed from the
+/// shapes of the colliders attached to it. Only balls, cuboids, capsules, convex polyhedra
+/// (resp. polygons in 2D), and compounds of those, are taken into account. This is a force
+/// generator: once added to the force generator set passed to `MechanicalWorld::step`, the
+/// submerged volumes must be updated by calling `self.update` before each step.
+///
+/// The polytopes approximating the collider shapes are computed the first time each shape is
+/// seen by `self.update`, and reused as long as the collider keeps the same shape handle.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct Buoyancy<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle> {
+    region: FluidRegion<N, CollHandle>,
+    density: N,
+    linear_drag: N,
+    angular_drag: N,
+    gravity: Vector<N>,
+    parts: Vec<BodyPartHandle<Handle>>,
+    #[cfg_attr(feature = "serde-serialize", serde(skip, default = "Vec::new"))]
+    submersions: Vec<Submersion<N, Handle>>,
+    // The planes bounding each convex part of the fluid collider, in the local space of the collider.
+    #[cfg_attr(feature = "serde-serialize", serde(skip, default = "Option::default"))]
+    fluid_planes: Option<ShapeCache<N, Vec<FluidPlanes<N>>>>,
+    #[cfg_attr(feature = "serde-serialize", serde(skip, default = "HashMap::new"))]
+    polytopes: HashMap<CollHandle, ShapeCache<N, Vec<ShapePolytope<N>>>>,
+}
+
+impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle> Buoyancy<N, Handle, CollHandle> {
+    /// Creates a buoyancy force generator for a fluid with the given region and density.
+    ///
+    /// The `gravity` should be the same as the gravity of the mechanical world. The buoyancy force
+    /// is the opposite of the weight of the fluid displaced by each body part. The drag
+    /// coefficients are set to zero.
+    pub fn new(region: FluidRegion<N, CollHandle>, density: N, gravity: Vector<N>) -> Self {
+        Buoyancy {
+            region,
+            density,
+            linear_drag: N::zero(),
+            angular_drag: N::zero(),
+            gravity,
+            parts: Vec::new(),
+            submersions: Vec::new(),
+            fluid_planes: None,
+            polytopes: HashMap::new(),
+        }
+    }
+
+    /// The region of space filled by the fluid.
+    pub fn region(&self) -> &FluidRegion<N, CollHandle> {
+        &self.region
+    }
+
+    /// Sets the region of space filled by the fluid.
+    pub fn set_region(&mut self, region: FluidRegion<N, CollHandle>) {
+        self.region = region
+    }
+
+    /// The density of the fluid.
+    pub fn density(&self) -> N {
+        self.density
+    }
+
+    /// Sets the density of the fluid.
+    pub fn set_density(&mut self, density: N) {
+        self.density = density
+    }
+
+    /// The linear drag coefficient of the fluid.
+    pub fn linear_drag(&self) -> N {
+        self.linear_drag
+    }
+
+    /// Sets the linear drag coefficient of the fluid.
+    ///
+    /// A fully submerged body part is subject to a force equal to the opposite of its velocity,
+    /// at its center of buoyancy, multiplied by this coefficient. This force is proportional to the
+    /// submerged fraction of the body part.
+    pub fn set_linear_drag(&mut self, drag: N) {
+        self.linear_drag = drag
+    }
+
+    /// The angular drag coefficient of the fluid.
+    pub fn angular_drag(&self) -> N {
+        self.angular_drag
+    }
+
+    /// Sets the angular drag coefficient of the fluid.
+    ///
+    /// A fully submerged body part is subject to a torque equal to the opposite of its angular
+    /// velocity multiplied by this coefficient. This torque is proportional to the submerged
+    /// fraction of the body part.
+    pub fn set_angular_drag(&mut self, drag: N) {
+        self.angular_drag = drag
+    }
+
+    /// The gravity used to compute the buoyancy force.
+    pub fn gravity(&self) -> &Vector<N> {
+        &self.gravity
+    }
+
+    /// Sets the gravity used to compute the buoyancy force.
+    pub fn set_gravity(&mut self, gravity: Vector<N>) {
+        self.gravity = gravity
+    }
+
+    /// Add a body part to be affected by this force generator.
+    pub fn add_body_part(&mut self, body: BodyPartHandle<Handle>) {
+        self.parts.push(body)
+    }
+
+    /// The submerged volume of the given body part, computed by the last call to `self.update`.
+    pub fn submerged_volume(&self, body: BodyPartHandle<Handle>) -> N {
+        self.submersions
+            .iter()
+            .find(|s| s.part == body)
+            .map_or(N::zero(), |s| s.volume)
+    }
+
+    /// Computes the submerged volume and center of buoyancy of each body part affected by this force generator.
+    ///
+    /// The sensors are ignored.
+    pub fn update<Colliders>(
+        &mut self,
+        gworld: &GeometricalWorld<N, Handle, CollHandle>,
+        colliders: &Colliders,
+    ) where
+        Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
+    {
+        self.submersions.clear();
+
+        let regions = match self.region {
+            FluidRegion::HalfSpace { point, normal } => vec![vec![(point, normal)]],
+            FluidRegion::Collider(handle) => {
+                let fluid = try_ret!(colliders.get(handle));
+                let position = fluid.position();
+                let planes = ShapeCache::get_or_compute(
+                    self.fluid_planes.take(),
+                    fluid.shape_handle(),
+                    fluid_planes,
+                );
+                let regions = planes
+                    .value
+                    .iter()
+                    .map(|planes| {
+                        planes
+                            .iter()
+                            .map(|(point, normal)| (position * point, position * *normal))
+                            .collect()
+                    })
+                    .collect();
+
+                self.fluid_planes = Some(planes);
+                regions
+            }
+        };
+
+        if regions.is_empty() {
+            return;
+        }
+
+        // Only keep the polytopes of the colliders that still exist.
+        let mut polytopes = HashMap::with_capacity(self.polytopes.len());
+
+        for part in &self.parts {
+            let mut total_volume = N::zero();
+            let mut volume = N::zero();
+            let mut moment = Vector::zeros();
+
+            for handle in gworld.body_colliders(part.0).unwrap_or(&[]) {
+                if let Some(collider) = colliders.get(*handle) {
+                    if collider.body_part(0) == *part && !collider.is_sensor() {
+                        let cache = ShapeCache::get_or_compute(
+                            self.polytopes.remove(handle),
+                            collider.shape_handle(),
+                            shape_polytopes,
+                        );
+
+                        for polytope in &cache.value {
+                            accumulate_submersion(
+                                polytope,
+                                collider.position(),
+                                &regions,
+                                &mut total_volume,
+                                &mut volume,
+                                &mut moment,
+                            );
+                        }
+
+                        let _ = polytopes.insert(*handle, cache);
+                    }
+                }
+            }
+
+            if volume > N::zero() {
+                self.submersions.push(Submersion {
+                    part: *part,
+                    volume,
+                    center: Point::from(moment / volume),
+                    fraction: (volume / total_volume).min(N::one()),
+                });
+            }
+        }
+
+        self.polytopes = polytopes;
+    }
+}
+
+impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle> ForceGenerator<N, Handle>
+    for Buoyancy<N, Handle, CollHandle>
+{
+    fn apply(
+        &mut self,
+        _: &IntegrationParameters<N>,
+        bodies: &mut dyn BodySet<N, Handle = Handle>,
+    ) {
+        self.parts.retain(|h| bodies.get(h.0).is_some());
+
+        for submersion in &self.submersions {
+            let body = try_continue!(bodies.get_mut(submersion.part.0));
+            let part_id = submersion.part.1;
+            let velocity = try_continue!(body.part(part_id)).velocity();
+            let velocity_at_center = body.velocity_at_point(part_id, &submersion.center).linear;
+
+            let buoyancy = -self.gravity * (self.density * submersion.volume);
+            let drag = -velocity_at_center * (self.linear_drag * submersion.fraction);
+            let angular_drag = -velocity.angular * (self.angular_drag * submersion.fraction);
+
+            body.apply_force_at_point(
+                part_id,
+                &(buoyancy + drag),
+                &submersion.center,
+                ForceType::Force,
+                true,
+            );
+            body.apply_force(
+                part_id,
+                &Force::torque(angular_drag),
+                ForceType::Force,
+                false,
+            );
+        }
+    }
+}
+
+// The planes bounding each convex part of the given fluid shape, with their outward normals.
+fn fluid_planes<N: RealField>(shape: &dyn Shape<N>) -> Vec<FluidPlanes<N>> {
+    let mut regions = Vec::new();
+
+    foreach_leaf_shape(shape, &Isometry::identity(), &mut |shape, position| {
+        if let Some(planes) = convex_fluid_planes(shape) {
+            regions.push(
+                planes
+                    .into_iter()
+                    .map(|(normal, offset)| {
+                        let normal = position * normal;
+                        let point = position * Point::from(*normal * offset);
+                        (point, normal)
+                    })
+                    .collect(),
+            )
+        }
+    });
+
+    regions
+}
+
+// The normals and offsets of the planes bounding the given convex fluid shape, in its local space.
+fn convex_fluid_planes<N: RealField>(shape: &dyn Shape<N>) -> Option<Vec<(Unit<Vector<N>>, N)>> {
+    let mut planes = Vec::new();
+
+    if let Some(plane) = shape.as_shape::<Plane<N>>() {
+        planes.push((plane.normal, N::zero()));
+    } else if let Some(cuboid) = shape.as_shape::<Cuboid<N>>() {
+        for i in 0..DIM {
+            let normal = Vector::ith_axis(i);
+            planes.push((normal, cuboid.half_extents[i]));
+            planes.push((-normal, cuboid.half_extents[i]));
+        }
+    } else {
+        #[cfg(feature = "dim2")]
+        {
+            let polygon = shape.as_shape::<ConvexPolygon<N>>()?;
+
+            for (pt, normal) in polygon.points().iter().zip(polygon.normals().iter()) {
+                planes.push((*normal, pt.coords.dot(normal)));
+            }
+        }
+        #[cfg(feature = "dim3")]
+        {
+            let hull = shape.as_shape::<ConvexHull<N>>()?;
+            let mesh = transformation::convex_hull(hull.points());
+            let center = utils::center(&mesh.coords);
+
+            if let IndexBuffer::Unified(ref idx) = mesh.indices {
+                for t in idx {
+                    let a = mesh.coords[t.x as usize];
+                    let b = mesh.coords[t.y as usize];
+                    let c = mesh.coords[t.z as usize];
+
+                    if let Some(mut normal) =
+                        Unit::try_new((b - a).cross(&(c - a)), N::default_epsilon())
+                    {
+                        if normal.dot(&(a - center)) < N::zero() {
+                            normal = -normal;
+                        }
+
+                        planes.push((normal, a.coords.dot(&normal)));
+                    }
+                }
+            }
+        }
+    }
+
+    Some(planes)
+}
+
+// The polytopes approximating the parts of the given shape that are not compounds.
+fn shape_polytopes<N: RealField>(shape: &dyn Shape<N>) -> Vec<ShapePolytope<N>> {
+    let mut polytopes = Vec::new();
+
+    foreach_leaf_shape(shape, &Isometry::identity(), &mut |shape, position| {
+        #[cfg(feature = "dim2")]
+        let polytope: ConvexPolytope<N> = {
+            let vertices = try_ret!(shape_polygon(shape));
+            vertices.iter().map(|pt| position * pt).collect()
+        };
+        #[cfg(feature = "dim3")]
+        let polytope = {
+            let (vertices, _) = try_ret!(shape_trimesh(shape));
+            let hull = transformation::convex_hull(&vertices);
+            let triangles = try_ret!(match &hull.indices {
+                IndexBuffer::Unified(triangles) => Some(triangles),
+                IndexBuffer::Split(_) => None,
+            });
+
+            triangles
+                .iter()
+                .map(|t| {
+                    vec![
+                        position * hull.coords[t.x as usize],
+                        position * hull.coords[t.y as usize],
+                        position * hull.coords[t.z as usize],
+                    ]
+                })
+                .collect()
+        };
+        let (polytope_volume, _) = polytope_volume_and_center(&polytope);
+
+        polytopes.push(ShapePolytope {
+            polytope,
+            polytope_volume,
+            volume: shape.volume(),
+            center_of_mass: position * shape.center_of_mass(),
+        })
+    });
+
+    polytopes
+}
+
+// Adds the total volume and the submerged volume of the given polytope to the accumulators.
+//
+// The `moment` is the sum of the submerged volumes multiplied by their world-space centers.
+fn accumulate_submersion<N: RealField>(
+    polytope: &ShapePolytope<N>,
+    position: &Isometry<N>,
+    regions: &[FluidPlanes<N>],
+    total_volume: &mut N,
+    volume: &mut N,
+    moment: &mut Vector<N>,
+) {
+    let world_polytope = transform_polytope(&polytope.polytope, position);
+
+    *total_volume += polytope.volume;
+
+    for planes in regions {
+        let is_inside = |pt: &Point<N>| {
+            planes
+                .iter()
+                .all(|(origin, normal)| (pt - origin).dot(normal) <= N::zero())
+        };
+
+        if polytope_vertices(&world_polytope).all(is_inside) {
+            // The shape is completely submerged.
+            *volume += polytope.volume;
+            *moment += (position * polytope.center_of_mass).coords * polytope.volume;
+            return;
+        }
+    }
+
+    if polytope.polytope_volume <= N::zero() {
+        return;
+    }
+
+    for planes in regions {
+        let (clipped_volume, clipped_center) = clipped_volume_and_center(&world_polytope, planes);
+
+        if clipped_volume > N::zero() {
+            // Compensate for the approximation of curved shapes by polytopes.
+            let clipped_volume = clipped_volume * (polytope.volume / polytope.polytope_volume);
+            *volume += clipped_volume;
+            *moment += clipped_center.coords * clipped_volume;
+        }
+    }
+}
+
+#[cfg(feature = "dim2")]
+fn transform_polytope<N: RealField>(
+    polygon: &ConvexPolytope<N>,
+    position: &Isometry<N>,
+) -> ConvexPolytope<N> {
+    polygon.iter().map(|pt| position * pt).collect()
+}
+
+#[cfg(feature = "dim3")]
+fn transform_polytope<N: RealField>(
+    faces: &ConvexPolytope<N>,
+    position: &Isometry<N>,
+) -> ConvexPolytope<N> {
+    faces
+        .iter()
+        .map(|face| face.iter().map(|pt| position * pt).collect())
+        .collect()
+}
+
+#[cfg(feature = "dim2")]
+fn polytope_vertices<N: RealField>(polygon: &ConvexPolytope<N>) -> impl Iterator<Item = &Point<N>> {
+    polygon.iter()
+}
+
+#[cfg(feature = "dim3")]
+fn polytope_vertices<N: RealField>(faces: &ConvexPolytope<N>) -> impl Iterator<Item = &Point<N>> {
+    faces.iter().flatten()
+}
+
+#[cfg(feature = "dim2")]
+fn polytope_volume_and_center<N: RealField>(polygon: &ConvexPolytope<N>) -> (N, Point<N>) {
+    if polygon.len() < 3 {
+        return (N::zero(), Point::origin());
+    }
+
+    convex_polyline_area_and_center_of_mass_unchecked(polygon)
+}
+
+// The volume and center of a convex polyhedron, computed by splitting it into tetrahedra
+// joining each triangle of its faces to an interior point.
+#[cfg(feature = "dim3")]
+fn polytope_volume_and_center<N: RealField>(faces: &ConvexPolytope<N>) -> (N, Point<N>) {
+    let nvertices = polytope_vertices(faces).count();
+
+    if nvertices < 4 {
+        return (N::zero(), Point::origin());
+    }
+
+    let interior = Point::from(
+        polytope_vertices(faces).fold(Vector::zeros(), |sum, pt| sum + pt.coords)
+            / na::convert::<_, N>(nvertices as f64),
+    );
+    let mut volume = N::zero();
+    let mut moment = Vector::zeros();
+
+    for face in faces {
+        for i in 1..face.len().saturating_sub(1) {
+            let (a, b, c) = (face[0], face[i], face[i + 1]);
+            let tetra_volume = (a - interior)
+                .dot(&(b - interior).cross(&(c - interior)))
+                .abs()
+                / na::convert::<_, N>(6.0);
+            let tetra_center =
+                (interior.coords + a.coords + b.coords + c.coords) / na::convert::<_, N>(4.0);
+
+            volume += tetra_volume;
+            moment += tetra_center * tetra_volume;
+        }
+    }
+
+    if volume > N::zero() {
+        (volume, Point::from(moment / volume))
+    } else {
+        (N::zero(), Point::origin())
+    }
+}
+
+// Clips the given convex polygon against the inner side of a plane, using the Sutherland-Hodgman algorithm.
+//
+// The points where the polygon boundary crosses the plane are added to `crossings`.
+fn clip_polygon<N: RealField>(
+    polygon: &[Point<N>],
+    origin: &Point<N>,
+    normal: &Unit<Vector<N>>,
+    crossings: &mut Vec<Point<N>>,
+) -> Vec<Point<N>> {
+    let mut clipped = Vec::with_capacity(polygon.len() + 1);
+
+    for i in 0..polygon.len() {
+        let a = polygon[i];
+        let b = polygon[(i + 1) % polygon.len()];
+        let da = (a - origin).dot(normal);
+        let db = (b - origin).dot(normal);
+
+        if da <= N::zero() {
+            clipped.push(a);
+        }
+
+        if (da <= N::zero()) != (db <= N::zero()) {
+            let crossing = a + (b - a) * (da / (da - db));
+            clipped.push(crossing);
+            crossings.push(crossing);
+        }
+    }
+
+    clipped
+}
+
+// The volume and center of the intersection between the given convex polygon and the inner
+// sides of the given planes.
+#[cfg(feature = "dim2")]
+fn clipped_volume_and_center<N: RealField>(
+    polygon: &ConvexPolytope<N>,
+    planes: &[(Point<N>, Unit<Vector<N>>)],
+) -> (N, Point<N>) {
+    let mut polygon = polygon.clone();
+
+    for (origin, normal) in planes {
+        polygon = clip_polygon(&polygon, origin, normal, &mut Vec::new());
+
+        if polygon.len() < 3 {
+            return (N::zero(), Point::origin());
+        }
+    }
+
+    polytope_volume_and_center(&polygon)
+}
+
+// The volume and center of the intersection between the given convex polyhedron and the
+// inner sides of the given planes.
+#[cfg(feature = "dim3")]
+fn clipped_volume_and_center<N: RealField>(
+    faces: &ConvexPolytope<N>,
+    planes: &[(Point<N>, Unit<Vector<N>>)],
+) -> (N, Point<N>) {
+    let mut faces = faces.clone();
+
+    for (origin, normal) in planes {
+        let mut crossings = Vec::new();
+
+        faces = faces
+            .iter()
+            .map(|face| clip_polygon(face, origin, normal, &mut crossings))
+            .filter(|face| face.len() >= 3)
+            .collect();
+
+        // Close the polyhedron with the section of the plane it crosses.
+        if crossings.len() >= 3 {
+            let center = Point::from(
+                crossings
+                    .iter()
+                    .fold(Vector::zeros(), |sum, pt| sum + pt.coords)
+                    / na::convert::<_, N>(crossings.len() as f64),
+            );
+            let u = try_continue!(crossings
+                .iter()
+                .filter_map(|pt| Unit::try_new(pt - center, N::default_epsilon()))
+                .next());
+            let v = normal.cross(&u);
+            let angle = |pt: &Point<N>| (pt - center).dot(&v).atan2((pt - center).dot(&u));
+
+            crossings.sort_by(|a, b| angle(a).partial_cmp(&angle(b)).unwrap_or(Ordering::Equal));
+            faces.push(crossings);
+        }
+
+        if faces.is_empty() {
+            return (N::zero(), Point::origin());
+        }
+    }
+
+    polytope_volume_and_center(&faces)
+}
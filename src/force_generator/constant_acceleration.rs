@@ -7,6 +7,7 @@ use crate::solver::IntegrationParameters;
 
 /// Force generator adding a constant acceleration
 /// at the center of mass of a set of body parts.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct ConstantAcceleration<N: RealField, Handle: BodyHandle> {
     parts: Vec<BodyPartHandle<Handle>>,
     acceleration: Velocity<N>,
@@ -8,6 +8,10 @@ use crate::object::{BodyHandle, BodySet, DefaultBodyHandle};
 use crate::solver::IntegrationParameters;
 
 /// Default force generator set based on an arena with generational indices.
+///
+/// Only the force generators provided by nphysics can be serialized, except `Buoyancy` with a
+/// collider handle other than `DefaultColliderHandle`, and `Aerodynamics` with a
+/// `WindField::Custom` wind field.
 pub type DefaultForceGeneratorSet<N: RealField, Handle: BodyHandle = DefaultBodyHandle> =
     Arena<Box<dyn ForceGenerator<N, Handle>>>;
 
@@ -71,6 +75,8 @@ pub type DefaultForceGeneratorHandle = generational_arena::Index;
 /// A persistent force generator.
 ///
 /// A force generator applies a force to one or several bodies at each step of the simulation.
+/// It only accesses bodies through the `BodySet` trait, so it can be used with any body set
+/// having `Handle` as its body handle type.
 pub trait ForceGenerator<N: RealField, Handle: BodyHandle>: Downcast + Send + Sync {
     /// Apply forces to some bodies.
     fn apply(
@@ -81,3 +87,247 @@ pub trait ForceGenerator<N: RealField, Handle: BodyHandle>: Downcast + Send + Sy
 }
 
 impl_downcast!(ForceGenerator<N, Handle> where N: RealField, Handle: BodyHandle);
+
+#[cfg(feature = "serde-serialize")]
+use crate::controller::RaycastVehicle;
+#[cfg(feature = "serde-serialize")]
+use crate::force_generator::{Aerodynamics, Buoyancy, ConstantAcceleration, Spring};
+#[cfg(feature = "serde-serialize")]
+use crate::object::DefaultColliderHandle;
+
+#[cfg(feature = "serde-serialize")]
+serde_trait_object!(ForceGenerator<N: RealField, Handle: BodyHandle>, ForceGeneratorRef, ForceGeneratorOwned {
+    ConstantAcceleration(ConstantAcceleration<N, Handle>),
+    Spring(Spring<N, Handle>),
+    RaycastVehicle(RaycastVehicle<N, Handle>),
+    Buoyancy(Buoyancy<N, Handle, DefaultColliderHandle>),
+    Aerodynamics(Aerodynamics<N, Handle>),
+});
+
+#[cfg(test)]
+mod test {
+    #[cfg(feature = "serde-serialize")]
+    use crate::force_generator::{Aerodynamics, ForceGenerator, WindField};
+    use crate::force_generator::{ConstantAcceleration, DefaultForceGeneratorSet, Spring};
+    use crate::joint::DefaultJointConstraintSet;
+    use crate::math::{Point, Vector};
+    use crate::object::{
+        Body, BodyPartHandle, BodySet, DefaultColliderHandle, DefaultColliderSet, RigidBodyDesc,
+    };
+    use crate::world::{GeometricalWorld, MechanicalWorld};
+
+    // An entity identifier, as found in ECS libraries.
+    #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+    struct Entity(u32);
+
+    // A body set that is not based on an arena.
+    struct EntityBodySet {
+        bodies: Vec<(Entity, Box<dyn Body<f64>>)>,
+        removed: Vec<Entity>,
+    }
+
+    impl EntityBodySet {
+        fn new() -> Self {
+            EntityBodySet {
+                bodies: Vec::new(),
+                removed: Vec::new(),
+            }
+        }
+
+        fn insert(&mut self, entity: Entity, body: impl Body<f64>) {
+            self.bodies.push((entity, Box::new(body)))
+        }
+
+        fn remove(&mut self, entity: Entity) {
+            self.bodies.retain(|(e, _)| *e != entity);
+            self.removed.push(entity);
+        }
+    }
+
+    impl BodySet<f64> for EntityBodySet {
+        type Handle = Entity;
+
+        fn get(&self, handle: Entity) -> Option<&dyn Body<f64>> {
+            self.bodies
+                .iter()
+                .find(|(e, _)| *e == handle)
+                .map(|(_, b)| &**b)
+        }
+
+        fn get_mut(&mut self, handle: Entity) -> Option<&mut dyn Body<f64>> {
+            self.bodies
+                .iter_mut()
+                .find(|(e, _)| *e == handle)
+                .map(|(_, b)| &mut **b)
+        }
+
+        fn contains(&self, handle: Entity) -> bool {
+            self.bodies.iter().any(|(e, _)| *e == handle)
+        }
+
+        fn foreach(&self, f: &mut dyn FnMut(Entity, &dyn Body<f64>)) {
+            for (e, b) in &self.bodies {
+                f(*e, &**b)
+            }
+        }
+
+        fn foreach_mut(&mut self, f: &mut dyn FnMut(Entity, &mut dyn Body<f64>)) {
+            for (e, b) in &mut self.bodies {
+                f(*e, &mut **b)
+            }
+        }
+
+        fn pop_removal_event(&mut self) -> Option<Entity> {
+            self.removed.pop()
+        }
+    }
+
+    struct TestWorld {
+        mechanical_world: MechanicalWorld<f64, Entity, DefaultColliderHandle>,
+        geometrical_world: GeometricalWorld<f64, Entity, DefaultColliderHandle>,
+        bodies: EntityBodySet,
+        colliders: DefaultColliderSet<f64, Entity>,
+        joint_constraints: DefaultJointConstraintSet<f64, Entity>,
+        force_generators: DefaultForceGeneratorSet<f64, Entity>,
+    }
+
+    impl TestWorld {
+        fn new() -> Self {
+            let mut bodies = EntityBodySet::new();
+
+            for (i, x) in [-2.0, 0.0, 2.0].iter().enumerate() {
+                let body = RigidBodyDesc::new()
+                    .translation(Vector::x() * *x)
+                    .mass(1.0)
+                    .build();
+                bodies.insert(Entity(i as u32 * 10), body);
+            }
+
+            TestWorld {
+                mechanical_world: MechanicalWorld::new(Vector::zeros()),
+                geometrical_world: GeometricalWorld::new(),
+                bodies,
+                colliders: DefaultColliderSet::new(),
+                joint_constraints: DefaultJointConstraintSet::new(),
+                force_generators: DefaultForceGeneratorSet::new(),
+            }
+        }
+
+        fn step(&mut self) {
+            let _ = self.mechanical_world.step(
+                &mut self.geometrical_world,
+                &mut self.bodies,
+                &mut self.colliders,
+                &mut self.joint_constraints,
+                &mut self.force_generators,
+            );
+        }
+
+        fn linear_velocity(&self, entity: Entity) -> Vector<f64> {
+            self.bodies
+                .get(entity)
+                .unwrap()
+                .part(0)
+                .unwrap()
+                .velocity()
+                .linear
+        }
+    }
+
+    #[test]
+    fn constant_acceleration_with_custom_body_set() {
+        let mut world = TestWorld::new();
+        let dt = world.mechanical_world.timestep();
+
+        let mut acceleration = ConstantAcceleration::new(Vector::x() * 2.0, na::zero());
+        acceleration.add_body_part(BodyPartHandle(Entity(0), 0));
+        acceleration.add_body_part(BodyPartHandle(Entity(10), 0));
+        let _ = world.force_generators.insert(Box::new(acceleration));
+
+        world.step();
+        assert_relative_eq!(world.linear_velocity(Entity(0)), Vector::x() * 2.0 * dt);
+        assert_relative_eq!(world.linear_velocity(Entity(10)), Vector::x() * 2.0 * dt);
+        assert_relative_eq!(world.linear_velocity(Entity(20)), Vector::zeros());
+
+        // The generator must keep working after one of its bodies is removed.
+        world.bodies.remove(Entity(0));
+        world.step();
+        assert_relative_eq!(world.linear_velocity(Entity(10)), Vector::x() * 4.0 * dt);
+    }
+
+    #[test]
+    fn spring_with_custom_body_set() {
+        let mut world = TestWorld::new();
+        let dt = world.mechanical_world.timestep();
+
+        // Stretched by 1.0, so each body is pulled toward the other with a force of 10.0.
+        let spring = Spring::new(
+            BodyPartHandle(Entity(0), 0),
+            BodyPartHandle(Entity(20), 0),
+            Point::origin(),
+            Point::origin(),
+            3.0,
+            10.0,
+        );
+        let spring = world.force_generators.insert(Box::new(spring));
+
+        world.step();
+        assert_relative_eq!(
+            world.linear_velocity(Entity(0)),
+            Vector::x() * 10.0 * dt,
+            epsilon = 1.0e-10
+        );
+        assert_relative_eq!(
+            world.linear_velocity(Entity(20)),
+            -Vector::x() * 10.0 * dt,
+            epsilon = 1.0e-10
+        );
+
+        // Removing one end of the spring must not affect the other one.
+        world.bodies.remove(Entity(20));
+        world.step();
+        let v0 = world.linear_velocity(Entity(0));
+        assert_relative_eq!(v0, Vector::x() * 10.0 * dt, epsilon = 1.0e-10);
+
+        let spring = world.force_generators.get(spring).unwrap();
+        assert!(spring.downcast_ref::<Spring<f64, Entity>>().is_some());
+    }
+
+    #[cfg(feature = "serde-serialize")]
+    #[test]
+    fn serialize_aerodynamics() {
+        let turbulent = WindField::Turbulent {
+            mean: Vector::x(),
+            intensity: 0.5,
+            length_scale: 10.0,
+            time_scale: 2.0,
+        };
+        let mut force_generators = DefaultForceGeneratorSet::<f64, Entity>::new();
+        let handle = force_generators.insert(Box::new(Aerodynamics::new(turbulent)));
+
+        let data = bincode::serialize(&force_generators).unwrap();
+        let force_generators: DefaultForceGeneratorSet<f64, Entity> =
+            bincode::deserialize(&data).unwrap();
+        let aerodynamics = force_generators[handle]
+            .downcast_ref::<Aerodynamics<f64, Entity>>()
+            .unwrap();
+        let point = Point::from(Vector::repeat(3.0));
+        assert_eq!(
+            aerodynamics.wind().velocity_at(&point, 1.0),
+            WindField::Turbulent {
+                mean: Vector::x(),
+                intensity: 0.5,
+                length_scale: 10.0,
+                time_scale: 2.0,
+            }
+            .velocity_at(&point, 1.0)
+        );
+
+        // Closures can't be serialized.
+        let custom = WindField::Custom(Box::new(|_: &Point<f64>, _| Vector::x()));
+        let aerodynamics: Box<dyn ForceGenerator<f64, Entity>> =
+            Box::new(Aerodynamics::new(custom));
+        assert!(bincode::serialize(&aerodynamics).is_err());
+    }
+}
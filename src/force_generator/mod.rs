@@ -1,11 +1,16 @@
 //! Persistent force generation.
 
+pub use self::aerodynamics::{Aerodynamics, WindField};
+pub use self::buoyancy::{Buoyancy, FluidRegion};
 pub use self::constant_acceleration::ConstantAcceleration;
 pub use self::force_generator::{
     DefaultForceGeneratorHandle, DefaultForceGeneratorSet, ForceGenerator, ForceGeneratorSet,
 };
 pub use self::spring::Spring;
 
+mod aerodynamics;
+mod buoyancy;
 mod constant_acceleration;
 mod force_generator;
 mod spring;
+mod tessellation;
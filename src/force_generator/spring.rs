@@ -6,6 +6,7 @@ use crate::object::{BodyHandle, BodyPartHandle, BodySet};
 use crate::solver::IntegrationParameters;
 
 /// Generator of a force proportional to the distance separating two bodies.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct Spring<N: RealField, Handle: BodyHandle> {
     b1: BodyPartHandle<Handle>,
     b2: BodyPartHandle<Handle>,
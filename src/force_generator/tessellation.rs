@@ -0,0 +1,78 @@
+// Polygonal approximations of the shapes of the colliders, used by the force generators
+// depending on the geometry of the bodies they affect.
+
+use na::RealField;
+#[cfg(feature = "dim3")]
+use ncollide::procedural::IndexBuffer;
+#[cfg(feature = "dim3")]
+use ncollide::shape::ConvexHull;
+#[cfg(feature = "dim2")]
+use ncollide::shape::ConvexPolygon;
+use ncollide::shape::{Ball, Capsule, Compound, Cuboid, Shape};
+#[cfg(feature = "dim3")]
+use ncollide::transformation;
+#[cfg(feature = "dim2")]
+use ncollide::transformation::ToPolyline;
+#[cfg(feature = "dim3")]
+use ncollide::transformation::ToTriMesh;
+
+use crate::math::{Isometry, Point};
+
+// Applies `f` to each shape that is not a compound, found by recursively traversing the
+// given shape, together with its position.
+pub(crate) fn foreach_leaf_shape<N: RealField>(
+    shape: &dyn Shape<N>,
+    position: &Isometry<N>,
+    f: &mut dyn FnMut(&dyn Shape<N>, &Isometry<N>),
+) {
+    if let Some(compound) = shape.as_shape::<Compound<N>>() {
+        for (delta, part) in compound.shapes() {
+            foreach_leaf_shape(part.as_ref(), &(position * delta), f);
+        }
+    } else {
+        f(shape, position)
+    }
+}
+
+// The vertices, in counterclockwise order, of the convex polygon approximating the given shape.
+//
+// Returns `None` if the shape is not a ball, a cuboid, a capsule, or a convex polygon.
+#[cfg(feature = "dim2")]
+pub(crate) fn shape_polygon<N: RealField>(shape: &dyn Shape<N>) -> Option<Vec<Point<N>>> {
+    if let Some(ball) = shape.as_shape::<Ball<N>>() {
+        Some(ball.to_polyline(32).unwrap().0)
+    } else if let Some(cuboid) = shape.as_shape::<Cuboid<N>>() {
+        Some(cuboid.to_polyline(()).unwrap().0)
+    } else if let Some(capsule) = shape.as_shape::<Capsule<N>>() {
+        Some(capsule.to_polyline(16).unwrap().0)
+    } else {
+        shape
+            .as_shape::<ConvexPolygon<N>>()
+            .map(|polygon| polygon.points().to_vec())
+    }
+}
+
+// The vertices and triangles of the convex triangle mesh approximating the given shape.
+//
+// Returns `None` if the shape is not a ball, a cuboid, a capsule, or a convex polyhedron.
+#[cfg(feature = "dim3")]
+pub(crate) fn shape_trimesh<N: RealField>(
+    shape: &dyn Shape<N>,
+) -> Option<(Vec<Point<N>>, Vec<na::Point3<u32>>)> {
+    let mut mesh = if let Some(ball) = shape.as_shape::<Ball<N>>() {
+        ball.to_trimesh((16, 16))
+    } else if let Some(cuboid) = shape.as_shape::<Cuboid<N>>() {
+        cuboid.to_trimesh(())
+    } else if let Some(capsule) = shape.as_shape::<Capsule<N>>() {
+        capsule.to_trimesh((16, 8))
+    } else {
+        transformation::convex_hull(shape.as_shape::<ConvexHull<N>>()?.points())
+    };
+
+    mesh.unify_index_buffer();
+
+    match mesh.indices {
+        IndexBuffer::Unified(triangles) => Some((mesh.coords, triangles)),
+        IndexBuffer::Split(_) => None,
+    }
+}
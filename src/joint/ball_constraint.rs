@@ -1,26 +1,46 @@
-use na::{DVector, RealField};
+use na::{DVector, RealField, Unit};
 use std::ops::Range;
 
-use crate::joint::JointConstraint;
-use crate::math::{Point, Vector, DIM};
+use crate::joint::{unit_constraint, JointCompliance, JointConstraint, JointMotor};
+use crate::math::{AngularVector, Force, Isometry, Point, Rotation, Vector, ANGULAR_DIM, DIM};
 use crate::object::{BodyHandle, BodyPartHandle, BodySet};
 use crate::solver::helper;
 use crate::solver::{
-    GenericNonlinearConstraint, IntegrationParameters, LinearConstraints,
+    ForceDirection, GenericNonlinearConstraint, IntegrationParameters, LinearConstraints,
     NonlinearConstraintGenerator,
 };
 
 /// A constraint that removes all relative linear motion between two body parts.
+///
+/// The relative rotation can be restricted by a cone limit, bounding the angle between the twist
+/// axis of both body parts, and by twist limits, bounding the rotation around those axis.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct BallConstraint<N: RealField, Handle: BodyHandle> {
     b1: BodyPartHandle<Handle>,
     b2: BodyPartHandle<Handle>,
     anchor1: Point<N>,
     anchor2: Point<N>,
+    axis1: Unit<AngularVector<N>>,
+    axis2: Unit<AngularVector<N>>,
+    ref1: Unit<AngularVector<N>>,
+    ref2: Unit<AngularVector<N>>,
     impulses: Vector<N>,
+    cone_impulse: N,
+    twist_impulse: N,
+    motor_impulses: AngularVector<N>,
     break_force_squared: N,
     broken: bool,
     bilateral_ground_rng: Range<usize>,
     bilateral_rng: Range<usize>,
+    unilateral_ground_rng: Range<usize>,
+    unilateral_rng: Range<usize>,
+    unit_forces: Vec<Force<N>>,
+    reaction: Force<N>,
+    max_cone_angle: Option<N>,
+    min_twist_angle: Option<N>,
+    max_twist_angle: Option<N>,
+    motor: JointMotor<AngularVector<N>, N>,
+    linear_compliance: JointCompliance<N>,
 }
 
 impl<N: RealField, Handle: BodyHandle> BallConstraint<N, Handle> {
@@ -28,22 +48,43 @@ impl<N: RealField, Handle: BodyHandle> BallConstraint<N, Handle> {
     ///
     /// This will ensure the two points identified by `anchor1` and `anchor2` will coincide.
     /// Both are given in the local-space of their corresponding body part.
+    /// The twist axis of both body parts are initialized to the local `x` axis.
     pub fn new(
         b1: BodyPartHandle<Handle>,
         b2: BodyPartHandle<Handle>,
         anchor1: Point<N>,
         anchor2: Point<N>,
     ) -> Self {
+        let axis1 = AngularVector::x_axis();
+        let axis2 = AngularVector::x_axis();
+        let (ref1, ref2) = unit_constraint::reference_directions(&axis1, &axis2);
+
         BallConstraint {
             b1,
             b2,
             anchor1,
             anchor2,
+            axis1,
+            axis2,
+            ref1,
+            ref2,
             impulses: Vector::zeros(),
+            cone_impulse: N::zero(),
+            twist_impulse: N::zero(),
+            motor_impulses: AngularVector::zeros(),
             break_force_squared: N::max_value(),
             broken: false,
             bilateral_ground_rng: 0..0,
             bilateral_rng: 0..0,
+            unilateral_ground_rng: 0..0,
+            unilateral_rng: 0..0,
+            unit_forces: vec![Force::zero(); DIM + 2 + ANGULAR_DIM],
+            reaction: Force::zero(),
+            max_cone_angle: None,
+            min_twist_angle: None,
+            max_twist_angle: None,
+            motor: JointMotor::new(),
+            linear_compliance: JointCompliance::Rigid,
         }
     }
 
@@ -57,10 +98,206 @@ impl<N: RealField, Handle: BodyHandle> BallConstraint<N, Handle> {
         self.anchor2 = anchor2;
     }
 
+    /// Change the twist axis of the first body part, expressed in its local space.
+    pub fn set_twist_axis_1(&mut self, axis1: Unit<AngularVector<N>>) {
+        self.axis1 = axis1;
+        self.update_reference_directions();
+    }
+
+    /// Change the twist axis of the second body part, expressed in its local space.
+    pub fn set_twist_axis_2(&mut self, axis2: Unit<AngularVector<N>>) {
+        self.axis2 = axis2;
+        self.update_reference_directions();
+    }
+
     /// The maximum force this joint can absorb before breaking.
     pub fn set_break_force(&mut self, break_force: N) {
         self.break_force_squared = break_force * break_force;
     }
+
+    /// The compliance of the constraints removing the relative linear motion of the body parts.
+    pub fn linear_compliance(&self) -> &JointCompliance<N> {
+        &self.linear_compliance
+    }
+
+    /// Sets the compliance of the constraints removing the relative linear motion of the body parts.
+    pub fn set_linear_compliance(&mut self, compliance: JointCompliance<N>) {
+        self.linear_compliance = compliance
+    }
+
+    /// The maximum angle allowed between the twist axis of both body parts.
+    pub fn max_cone_angle(&self) -> Option<N> {
+        self.max_cone_angle
+    }
+
+    /// Disable the cone limit.
+    pub fn disable_max_cone_angle(&mut self) {
+        self.max_cone_angle = None;
+    }
+
+    /// Enable and set the maximum angle allowed between the twist axis of both body parts.
+    pub fn enable_max_cone_angle(&mut self, limit: N) {
+        assert!(
+            limit >= N::zero(),
+            "BallConstraint limits: the max cone angle must be positive."
+        );
+        self.max_cone_angle = Some(limit);
+    }
+
+    /// The lower limit of the rotation angle around the twist axis.
+    pub fn min_twist_angle(&self) -> Option<N> {
+        self.min_twist_angle
+    }
+
+    /// The upper limit of the rotation angle around the twist axis.
+    pub fn max_twist_angle(&self) -> Option<N> {
+        self.max_twist_angle
+    }
+
+    /// Disable the lower limit of the rotation angle around the twist axis.
+    pub fn disable_min_twist_angle(&mut self) {
+        self.min_twist_angle = None;
+    }
+
+    /// Disable the upper limit of the rotation angle around the twist axis.
+    pub fn disable_max_twist_angle(&mut self) {
+        self.max_twist_angle = None;
+    }
+
+    /// Enable and set the lower limit of the rotation angle around the twist axis.
+    pub fn enable_min_twist_angle(&mut self, limit: N) {
+        self.min_twist_angle = Some(limit);
+        self.assert_limits();
+    }
+
+    /// Enable and set the upper limit of the rotation angle around the twist axis.
+    pub fn enable_max_twist_angle(&mut self, limit: N) {
+        self.max_twist_angle = Some(limit);
+        self.assert_limits();
+    }
+
+    /// Return `true` if the angular motor of this joint is enabled.
+    pub fn is_angular_motor_enabled(&self) -> bool {
+        self.motor.enabled
+    }
+
+    /// Enable the angular motor of this joint.
+    pub fn enable_angular_motor(&mut self) {
+        self.motor.enabled = true
+    }
+
+    /// Disable the angular motor of this joint.
+    pub fn disable_angular_motor(&mut self) {
+        self.motor.enabled = false;
+    }
+
+    /// The desired relative angular velocity of the joint motor.
+    ///
+    /// It is expressed in the local space of the first body part.
+    pub fn desired_angular_motor_velocity(&self) -> AngularVector<N> {
+        self.motor.desired_velocity
+    }
+
+    /// Set the desired relative angular velocity of the joint motor.
+    ///
+    /// It is expressed in the local space of the first body part.
+    pub fn set_desired_angular_motor_velocity(&mut self, vel: AngularVector<N>) {
+        self.motor.desired_velocity = vel;
+    }
+
+    /// The max angular velocity that the joint motor will attempt.
+    pub fn max_angular_motor_velocity(&self) -> N {
+        self.motor.max_velocity
+    }
+
+    /// Set the maximum angular velocity that the joint motor will attempt.
+    pub fn set_max_angular_motor_velocity(&mut self, max_vel: N) {
+        self.motor.max_velocity = max_vel;
+    }
+
+    /// The maximum torque that can be delivered by the joint motor along each axis.
+    pub fn max_angular_motor_torque(&self) -> N {
+        self.motor.max_force
+    }
+
+    /// Set the maximum torque that can be delivered by the joint motor along each axis.
+    pub fn set_max_angular_motor_torque(&mut self, torque: N) {
+        self.motor.max_force = torque;
+    }
+
+    /// The relative rotation, as an axis-angle vector, the joint motor attempts to reach.
+    ///
+    /// It is expressed in the local space of the first body part, and is taken into account only
+    /// if the motor stiffness is non-zero.
+    pub fn desired_angular_motor_position(&self) -> AngularVector<N> {
+        self.motor.desired_position
+    }
+
+    /// Set the relative rotation, as an axis-angle vector, the joint motor attempts to reach.
+    ///
+    /// It is expressed in the local space of the first body part, and is taken into account only
+    /// if the motor stiffness is non-zero.
+    pub fn set_desired_angular_motor_position(&mut self, pos: AngularVector<N>) {
+        self.motor.desired_position = pos;
+    }
+
+    /// The stiffness of the joint motor wrt. its desired position.
+    pub fn angular_motor_stiffness(&self) -> N {
+        self.motor.stiffness
+    }
+
+    /// Set the stiffness of the joint motor wrt. its desired position.
+    pub fn set_angular_motor_stiffness(&mut self, stiffness: N) {
+        self.motor.stiffness = stiffness;
+    }
+
+    /// The damping of the joint motor wrt. its desired velocity.
+    pub fn angular_motor_damping(&self) -> N {
+        self.motor.damping
+    }
+
+    /// Set the damping of the joint motor wrt. its desired velocity.
+    pub fn set_angular_motor_damping(&mut self, damping: N) {
+        self.motor.damping = damping;
+    }
+
+    fn assert_limits(&self) {
+        if let (Some(min_angle), Some(max_angle)) = (self.min_twist_angle, self.max_twist_angle) {
+            assert!(
+                min_angle <= max_angle,
+                "BallConstraint limits: the min twist angle must be smaller than (or equal to) the max twist angle.");
+        }
+    }
+
+    fn update_reference_directions(&mut self) {
+        let (ref1, ref2) = unit_constraint::reference_directions(&self.axis1, &self.axis2);
+        self.ref1 = ref1;
+        self.ref2 = ref2;
+    }
+
+    /// The direction and the angle of the rotation between the twist axis of both body parts.
+    fn cone(&self, pos1: &Isometry<N>, pos2: &Isometry<N>) -> Option<(Unit<AngularVector<N>>, N)> {
+        let axis1 = pos1 * self.axis1;
+        let axis2 = pos2 * self.axis2;
+
+        Unit::try_new(axis1.cross(&axis2), N::default_epsilon())
+            .map(|dir| (dir, axis1.angle(&axis2)))
+    }
+
+    /// The twist axis of the first body part and the rotation angle around it.
+    fn twist(&self, pos1: &Isometry<N>, pos2: &Isometry<N>) -> Option<(Unit<AngularVector<N>>, N)> {
+        let axis1 = pos1 * self.axis1;
+        let axis2 = pos2 * self.axis2;
+        // Remove the swing part of the relative rotation before measuring the twist.
+        let swing = Rotation::rotation_between_axis(&axis2, &axis1)?;
+        let ref1 = pos1 * self.ref1;
+        let ref2 = swing * (pos2 * self.ref2);
+
+        Some((
+            axis1,
+            unit_constraint::angle_around_axis(&axis1, &ref1, &ref2),
+        ))
+    }
 }
 
 impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle> for BallConstraint<N, Handle> {
@@ -68,8 +305,12 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle> for BallConstr
         self.broken
     }
 
+    fn reaction_force(&self) -> Force<N> {
+        self.reaction
+    }
+
     fn num_velocity_constraints(&self) -> usize {
-        DIM
+        DIM + 2 + ANGULAR_DIM
     }
 
     fn anchors(&self) -> (BodyPartHandle<Handle>, BodyPartHandle<Handle>) {
@@ -78,7 +319,7 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle> for BallConstr
 
     fn velocity_constraints(
         &mut self,
-        _: &IntegrationParameters<N>,
+        parameters: &IntegrationParameters<N>,
         bodies: &dyn BodySet<N, Handle = Handle>,
         ext_vels: &DVector<N>,
         ground_j_id: &mut usize,
@@ -96,12 +337,17 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle> for BallConstr
          * Joint constraints.
          *
          */
-        let anchor1 = body1.world_point_at_material_point(part1, &self.anchor1);
-        let anchor2 = body2.world_point_at_material_point(part2, &self.anchor2);
+        let pos1 = body1.position_at_material_point(part1, &self.anchor1);
+        let pos2 = body2.position_at_material_point(part2, &self.anchor2);
+
+        let anchor1 = Point::from(pos1.translation.vector);
+        let anchor2 = Point::from(pos2.translation.vector);
 
         let assembly_id1 = body1.companion_id();
         let assembly_id2 = body2.companion_id();
 
+        let first_unilateral_ground = constraints.unilateral_ground.len();
+        let first_unilateral = constraints.unilateral.len();
         let first_bilateral_ground = constraints.bilateral_ground.len();
         let first_bilateral = constraints.bilateral.len();
 
@@ -125,17 +371,192 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle> for BallConstr
             constraints,
         );
 
+        unit_constraint::soften_velocity_constraints(
+            parameters,
+            &self.linear_compliance,
+            (anchor1 - anchor2).as_slice(),
+            0,
+            first_bilateral_ground,
+            first_bilateral,
+            constraints,
+        );
+
+        unit_constraint::linear_basis_forces(&mut self.unit_forces[..DIM]);
+
+        /*
+         *
+         * Limit and motor constraints.
+         *
+         */
+        if let Some((dir, angle)) = self.cone(&pos1, &pos2) {
+            if let Some(dir) = unit_constraint::build_angular_limits_velocity_constraint(
+                body1,
+                part1,
+                self.b1,
+                body2,
+                part2,
+                self.b2,
+                assembly_id1,
+                assembly_id2,
+                &anchor1,
+                &anchor2,
+                &dir,
+                angle,
+                None,
+                self.max_cone_angle,
+                ext_vels,
+                self.cone_impulse,
+                DIM,
+                ground_j_id,
+                j_id,
+                jacobians,
+                constraints,
+            ) {
+                self.unit_forces[DIM] = unit_constraint::unit_force(&dir);
+            }
+        }
+
+        if let Some((axis, angle)) = self.twist(&pos1, &pos2) {
+            if let Some(dir) = unit_constraint::build_angular_limits_velocity_constraint(
+                body1,
+                part1,
+                self.b1,
+                body2,
+                part2,
+                self.b2,
+                assembly_id1,
+                assembly_id2,
+                &anchor1,
+                &anchor2,
+                &axis,
+                angle,
+                self.min_twist_angle,
+                self.max_twist_angle,
+                ext_vels,
+                self.twist_impulse,
+                DIM + 1,
+                ground_j_id,
+                j_id,
+                jacobians,
+                constraints,
+            ) {
+                self.unit_forces[DIM + 1] = unit_constraint::unit_force(&dir);
+            }
+        }
+
+        if self.motor.enabled {
+            let mut velocity = self.motor.desired_velocity;
+            let speed = velocity.norm();
+
+            if speed > self.motor.max_velocity {
+                velocity *= self.motor.max_velocity / speed;
+            }
+
+            // The rotation from the current relative orientation to the desired one, expressed
+            // in the local space of the first body part.
+            let rotation = pos1.rotation.inverse() * pos2.rotation;
+            let error =
+                (Rotation::new(self.motor.desired_position) * rotation.inverse()).scaled_axis();
+
+            for i in 0..ANGULAR_DIM {
+                let motor = JointMotor {
+                    desired_velocity: velocity[i],
+                    desired_position: error[i],
+                    stiffness: self.motor.stiffness,
+                    damping: self.motor.damping,
+                    max_velocity: self.motor.max_velocity,
+                    max_force: self.motor.max_force,
+                    enabled: true,
+                };
+                let axis = pos1.rotation * AngularVector::ith_axis(i);
+
+                if let Some(dir) = unit_constraint::build_motor_velocity_constraint(
+                    parameters,
+                    body1,
+                    part1,
+                    self.b1,
+                    body2,
+                    part2,
+                    self.b2,
+                    assembly_id1,
+                    assembly_id2,
+                    &anchor1,
+                    &anchor2,
+                    ForceDirection::Angular(axis),
+                    &motor,
+                    N::zero(),
+                    ext_vels,
+                    self.motor_impulses[i],
+                    DIM + 2 + i,
+                    ground_j_id,
+                    j_id,
+                    jacobians,
+                    constraints,
+                ) {
+                    self.unit_forces[DIM + 2 + i] = unit_constraint::unit_force(&dir);
+                }
+            }
+        }
+
         self.bilateral_ground_rng = first_bilateral_ground..constraints.bilateral_ground.len();
         self.bilateral_rng = first_bilateral..constraints.bilateral.len();
+        self.unilateral_ground_rng = first_unilateral_ground..constraints.unilateral_ground.len();
+        self.unilateral_rng = first_unilateral..constraints.unilateral.len();
     }
 
     fn cache_impulses(&mut self, constraints: &LinearConstraints<N, usize>, inv_dt: N) {
+        self.reaction = unit_constraint::reaction_force(
+            &self.unit_forces,
+            constraints,
+            self.bilateral_ground_rng.clone(),
+            self.bilateral_rng.clone(),
+            inv_dt,
+        ) + unit_constraint::limits_reaction_force(
+            &self.unit_forces,
+            constraints,
+            self.unilateral_ground_rng.clone(),
+            self.unilateral_rng.clone(),
+            inv_dt,
+        );
+
         for c in &constraints.bilateral_ground[self.bilateral_ground_rng.clone()] {
-            self.impulses[c.impulse_id] = c.impulse;
+            if c.impulse_id < DIM {
+                self.impulses[c.impulse_id] = c.impulse;
+            } else if c.impulse_id == DIM {
+                self.cone_impulse = c.impulse;
+            } else if c.impulse_id == DIM + 1 {
+                self.twist_impulse = c.impulse;
+            } else {
+                self.motor_impulses[c.impulse_id - DIM - 2] = c.impulse;
+            }
         }
 
         for c in &constraints.bilateral[self.bilateral_rng.clone()] {
-            self.impulses[c.impulse_id] = c.impulse;
+            if c.impulse_id < DIM {
+                self.impulses[c.impulse_id] = c.impulse;
+            } else if c.impulse_id == DIM {
+                self.cone_impulse = c.impulse;
+            } else if c.impulse_id == DIM + 1 {
+                self.twist_impulse = c.impulse;
+            } else {
+                self.motor_impulses[c.impulse_id - DIM - 2] = c.impulse;
+            }
+        }
+
+        for c in &constraints.unilateral_ground[self.unilateral_ground_rng.clone()] {
+            if c.impulse_id == DIM {
+                self.cone_impulse = c.impulse;
+            } else {
+                self.twist_impulse = c.impulse;
+            }
+        }
+
+        for c in &constraints.unilateral[self.unilateral_rng.clone()] {
+            if c.impulse_id == DIM {
+                self.cone_impulse = c.impulse;
+            } else {
+                self.twist_impulse = c.impulse;
+            }
         }
 
         if self.impulses.norm_squared() * inv_dt * inv_dt > self.break_force_squared {
@@ -150,7 +571,7 @@ impl<N: RealField, Handle: BodyHandle> NonlinearConstraintGenerator<N, Handle>
     fn num_position_constraints(&self, bodies: &dyn BodySet<N, Handle = Handle>) -> usize {
         // FIXME: calling this at each iteration of the non-linear resolution is costly.
         if self.is_active(bodies) {
-            1
+            3
         } else {
             0
         }
@@ -159,7 +580,7 @@ impl<N: RealField, Handle: BodyHandle> NonlinearConstraintGenerator<N, Handle>
     fn position_constraint(
         &self,
         parameters: &IntegrationParameters<N>,
-        _: usize,
+        i: usize,
         bodies: &mut dyn BodySet<N, Handle = Handle>,
         jacobians: &mut [N],
     ) -> Option<GenericNonlinearConstraint<N, Handle>> {
@@ -168,11 +589,36 @@ impl<N: RealField, Handle: BodyHandle> NonlinearConstraintGenerator<N, Handle>
         let part1 = body1.part(self.b1.1)?;
         let part2 = body2.part(self.b2.1)?;
 
-        let anchor1 = body1.world_point_at_material_point(part1, &self.anchor1);
-        let anchor2 = body2.world_point_at_material_point(part2, &self.anchor2);
+        let pos1 = body1.position_at_material_point(part1, &self.anchor1);
+        let pos2 = body2.position_at_material_point(part2, &self.anchor2);
+
+        let anchor1 = Point::from(pos1.translation.vector);
+        let anchor2 = Point::from(pos2.translation.vector);
+
+        if i == 0 {
+            let constraint = helper::cancel_relative_translation(
+                parameters, body1, part1, self.b1, body2, part2, self.b2, &anchor1, &anchor2,
+                jacobians,
+            );
+
+            return unit_constraint::soften_position_constraint(
+                parameters,
+                &self.linear_compliance,
+                constraint,
+            );
+        }
+
+        let (axis, angle, min, max) = if i == 1 {
+            let (dir, angle) = self.cone(&pos1, &pos2)?;
+            (dir, angle, None, self.max_cone_angle)
+        } else {
+            let (axis, angle) = self.twist(&pos1, &pos2)?;
+            (axis, angle, self.min_twist_angle, self.max_twist_angle)
+        };
 
-        helper::cancel_relative_translation(
-            parameters, body1, part1, self.b1, body2, part2, self.b2, &anchor1, &anchor2, jacobians,
+        unit_constraint::build_angular_limits_position_constraint(
+            parameters, body1, part1, self.b1, body2, part2, self.b2, &anchor1, &anchor2, &axis,
+            angle, min, max, jacobians,
         )
     }
 }
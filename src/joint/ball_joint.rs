@@ -10,6 +10,7 @@ use crate::utils::GeneralizedCross;
 
 /// A joint that allows only all rotational degrees of freedom between two multibody links.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct BallJoint<N: RealField> {
     rot: UnitQuaternion<N>,
 
@@ -1,16 +1,19 @@
 use na::{DVector, RealField};
 use std::ops::Range;
 
-use crate::joint::JointConstraint;
-use crate::math::{AngularVector, Point, Rotation, ANGULAR_DIM};
+use crate::joint::{unit_constraint, JointConstraint, JointMotor};
+use crate::math::{AngularVector, Force, Point, Rotation, Vector, ANGULAR_DIM, DIM};
 use crate::object::{BodyHandle, BodyPartHandle, BodySet};
 use crate::solver::helper;
 use crate::solver::{
-    GenericNonlinearConstraint, IntegrationParameters, LinearConstraints,
+    ForceDirection, GenericNonlinearConstraint, IntegrationParameters, LinearConstraints,
     NonlinearConstraintGenerator,
 };
 
 /// A constraint that removes all relative angular motion between two body parts.
+///
+/// The relative translations are measured along the axii of the first reference frame.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct CartesianConstraint<N: RealField, Handle: BodyHandle> {
     b1: BodyPartHandle<Handle>,
     b2: BodyPartHandle<Handle>,
@@ -19,10 +22,19 @@ pub struct CartesianConstraint<N: RealField, Handle: BodyHandle> {
     anchor2: Point<N>,
     ref_frame2: Rotation<N>,
     ang_impulses: AngularVector<N>,
+    limit_impulses: Vector<N>,
+    motor_impulses: Vector<N>,
     break_torque_squared: N,
     broken: bool,
     bilateral_ground_rng: Range<usize>,
     bilateral_rng: Range<usize>,
+    unilateral_ground_rng: Range<usize>,
+    unilateral_rng: Range<usize>,
+    unit_forces: Vec<Force<N>>,
+    reaction: Force<N>,
+    min_offsets: [Option<N>; DIM],
+    max_offsets: [Option<N>; DIM],
+    motor: JointMotor<Vector<N>, N>,
 }
 
 impl<N: RealField, Handle: BodyHandle> CartesianConstraint<N, Handle> {
@@ -48,8 +60,17 @@ impl<N: RealField, Handle: BodyHandle> CartesianConstraint<N, Handle> {
             break_torque_squared: N::max_value(),
             broken: false,
             ang_impulses: AngularVector::zeros(),
+            limit_impulses: Vector::zeros(),
+            motor_impulses: Vector::zeros(),
             bilateral_ground_rng: 0..0,
             bilateral_rng: 0..0,
+            unilateral_ground_rng: 0..0,
+            unilateral_rng: 0..0,
+            unit_forces: vec![Force::zero(); ANGULAR_DIM + 2 * DIM],
+            reaction: Force::zero(),
+            min_offsets: [None; DIM],
+            max_offsets: [None; DIM],
+            motor: JointMotor::new(),
         }
     }
 
@@ -77,6 +98,134 @@ impl<N: RealField, Handle: BodyHandle> CartesianConstraint<N, Handle> {
     pub fn set_break_torque(&mut self, break_torque: N) {
         self.break_torque_squared = break_torque * break_torque;
     }
+
+    /// The lower limit of the offset along the `i`-th axis of the first reference frame.
+    pub fn min_offset(&self, i: usize) -> Option<N> {
+        self.min_offsets[i]
+    }
+
+    /// The upper limit of the offset along the `i`-th axis of the first reference frame.
+    pub fn max_offset(&self, i: usize) -> Option<N> {
+        self.max_offsets[i]
+    }
+
+    /// Disable the lower limit of the offset along the `i`-th axis of the first reference frame.
+    pub fn disable_min_offset(&mut self, i: usize) {
+        self.min_offsets[i] = None;
+    }
+
+    /// Disable the upper limit of the offset along the `i`-th axis of the first reference frame.
+    pub fn disable_max_offset(&mut self, i: usize) {
+        self.max_offsets[i] = None;
+    }
+
+    /// Enable and set the lower limit of the offset along the `i`-th axis of the first reference frame.
+    pub fn enable_min_offset(&mut self, i: usize, limit: N) {
+        self.min_offsets[i] = Some(limit);
+        self.assert_limits();
+    }
+
+    /// Enable and set the upper limit of the offset along the `i`-th axis of the first reference frame.
+    pub fn enable_max_offset(&mut self, i: usize, limit: N) {
+        self.max_offsets[i] = Some(limit);
+        self.assert_limits();
+    }
+
+    /// Return `true` if the linear motor of this joint is enabled.
+    pub fn is_linear_motor_enabled(&self) -> bool {
+        self.motor.enabled
+    }
+
+    /// Enable the linear motor of this joint.
+    pub fn enable_linear_motor(&mut self) {
+        self.motor.enabled = true
+    }
+
+    /// Disable the linear motor of this joint.
+    pub fn disable_linear_motor(&mut self) {
+        self.motor.enabled = false;
+    }
+
+    /// The desired relative linear velocity of the joint motor.
+    ///
+    /// It is expressed in the first reference frame.
+    pub fn desired_linear_motor_velocity(&self) -> Vector<N> {
+        self.motor.desired_velocity
+    }
+
+    /// Set the desired relative linear velocity of the joint motor.
+    ///
+    /// It is expressed in the first reference frame.
+    pub fn set_desired_linear_motor_velocity(&mut self, vel: Vector<N>) {
+        self.motor.desired_velocity = vel;
+    }
+
+    /// The max linear velocity that the joint motor will attempt.
+    pub fn max_linear_motor_velocity(&self) -> N {
+        self.motor.max_velocity
+    }
+
+    /// Set the maximum linear velocity that the joint motor will attempt.
+    pub fn set_max_linear_motor_velocity(&mut self, max_vel: N) {
+        self.motor.max_velocity = max_vel;
+    }
+
+    /// The maximum force that can be delivered by the joint motor along each axis.
+    pub fn max_linear_motor_force(&self) -> N {
+        self.motor.max_force
+    }
+
+    /// Set the maximum force that can be delivered by the joint motor along each axis.
+    pub fn set_max_linear_motor_force(&mut self, force: N) {
+        self.motor.max_force = force;
+    }
+
+    /// The relative offset the joint motor attempts to reach.
+    ///
+    /// It is expressed in the first reference frame, and is taken into account only if the motor
+    /// stiffness is non-zero.
+    pub fn desired_linear_motor_position(&self) -> Vector<N> {
+        self.motor.desired_position
+    }
+
+    /// Set the relative offset the joint motor attempts to reach.
+    ///
+    /// It is expressed in the first reference frame, and is taken into account only if the motor
+    /// stiffness is non-zero.
+    pub fn set_desired_linear_motor_position(&mut self, pos: Vector<N>) {
+        self.motor.desired_position = pos;
+    }
+
+    /// The stiffness of the joint motor wrt. its desired position.
+    pub fn linear_motor_stiffness(&self) -> N {
+        self.motor.stiffness
+    }
+
+    /// Set the stiffness of the joint motor wrt. its desired position.
+    pub fn set_linear_motor_stiffness(&mut self, stiffness: N) {
+        self.motor.stiffness = stiffness;
+    }
+
+    /// The damping of the joint motor wrt. its desired velocity.
+    pub fn linear_motor_damping(&self) -> N {
+        self.motor.damping
+    }
+
+    /// Set the damping of the joint motor wrt. its desired velocity.
+    pub fn set_linear_motor_damping(&mut self, damping: N) {
+        self.motor.damping = damping;
+    }
+
+    fn assert_limits(&self) {
+        for i in 0..DIM {
+            if let (Some(min_offset), Some(max_offset)) = (self.min_offsets[i], self.max_offsets[i])
+            {
+                assert!(
+                    min_offset <= max_offset,
+                    "CartesianConstraint limits: the min offset must be smaller than (or equal to) the max offset.");
+            }
+        }
+    }
 }
 
 impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle>
@@ -86,8 +235,12 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle>
         self.broken
     }
 
+    fn reaction_force(&self) -> Force<N> {
+        self.reaction
+    }
+
     fn num_velocity_constraints(&self) -> usize {
-        ANGULAR_DIM
+        ANGULAR_DIM + 2 * DIM
     }
 
     fn anchors(&self) -> (BodyPartHandle<Handle>, BodyPartHandle<Handle>) {
@@ -96,7 +249,7 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle>
 
     fn velocity_constraints(
         &mut self,
-        _: &IntegrationParameters<N>,
+        parameters: &IntegrationParameters<N>,
         bodies: &dyn BodySet<N, Handle = Handle>,
         ext_vels: &DVector<N>,
         ground_j_id: &mut usize,
@@ -118,6 +271,8 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle>
         let assembly_id1 = body1.companion_id();
         let assembly_id2 = body2.companion_id();
 
+        let first_unilateral_ground = constraints.unilateral_ground.len();
+        let first_unilateral = constraints.unilateral.len();
         let first_bilateral_ground = constraints.bilateral_ground.len();
         let first_bilateral = constraints.bilateral.len();
 
@@ -141,17 +296,132 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle>
             constraints,
         );
 
+        unit_constraint::angular_basis_forces(&mut self.unit_forces[..ANGULAR_DIM]);
+
+        /*
+         *
+         * Limit and motor constraints.
+         *
+         */
+        let mut velocity = self.motor.desired_velocity;
+        let speed = velocity.norm();
+
+        if speed > self.motor.max_velocity {
+            velocity *= self.motor.max_velocity / speed;
+        }
+
+        for i in 0..DIM {
+            let axis = pos1.rotation * Vector::ith_axis(i);
+
+            if let Some(dir) = unit_constraint::build_linear_limits_velocity_constraint(
+                body1,
+                part1,
+                self.b1,
+                body2,
+                part2,
+                self.b2,
+                assembly_id1,
+                assembly_id2,
+                &anchor1,
+                &anchor2,
+                &axis,
+                self.min_offsets[i],
+                self.max_offsets[i],
+                ext_vels,
+                self.limit_impulses[i],
+                ANGULAR_DIM + i,
+                ground_j_id,
+                j_id,
+                jacobians,
+                constraints,
+            ) {
+                self.unit_forces[ANGULAR_DIM + i] = unit_constraint::unit_force(&dir);
+            }
+
+            let motor = JointMotor {
+                desired_velocity: velocity[i],
+                desired_position: self.motor.desired_position[i],
+                stiffness: self.motor.stiffness,
+                damping: self.motor.damping,
+                max_velocity: self.motor.max_velocity,
+                max_force: self.motor.max_force,
+                enabled: self.motor.enabled,
+            };
+
+            if let Some(dir) = unit_constraint::build_motor_velocity_constraint(
+                parameters,
+                body1,
+                part1,
+                self.b1,
+                body2,
+                part2,
+                self.b2,
+                assembly_id1,
+                assembly_id2,
+                &anchor1,
+                &anchor2,
+                ForceDirection::Linear(axis),
+                &motor,
+                axis.dot(&(anchor2 - anchor1)),
+                ext_vels,
+                self.motor_impulses[i],
+                ANGULAR_DIM + DIM + i,
+                ground_j_id,
+                j_id,
+                jacobians,
+                constraints,
+            ) {
+                self.unit_forces[ANGULAR_DIM + DIM + i] = unit_constraint::unit_force(&dir);
+            }
+        }
+
         self.bilateral_ground_rng = first_bilateral_ground..constraints.bilateral_ground.len();
         self.bilateral_rng = first_bilateral..constraints.bilateral.len();
+        self.unilateral_ground_rng = first_unilateral_ground..constraints.unilateral_ground.len();
+        self.unilateral_rng = first_unilateral..constraints.unilateral.len();
     }
 
     fn cache_impulses(&mut self, constraints: &LinearConstraints<N, usize>, inv_dt: N) {
+        self.reaction = unit_constraint::reaction_force(
+            &self.unit_forces,
+            constraints,
+            self.bilateral_ground_rng.clone(),
+            self.bilateral_rng.clone(),
+            inv_dt,
+        ) + unit_constraint::limits_reaction_force(
+            &self.unit_forces,
+            constraints,
+            self.unilateral_ground_rng.clone(),
+            self.unilateral_rng.clone(),
+            inv_dt,
+        );
+
         for c in &constraints.bilateral_ground[self.bilateral_ground_rng.clone()] {
-            self.ang_impulses[c.impulse_id] = c.impulse;
+            if c.impulse_id < ANGULAR_DIM {
+                self.ang_impulses[c.impulse_id] = c.impulse;
+            } else if c.impulse_id < ANGULAR_DIM + DIM {
+                self.limit_impulses[c.impulse_id - ANGULAR_DIM] = c.impulse;
+            } else {
+                self.motor_impulses[c.impulse_id - ANGULAR_DIM - DIM] = c.impulse;
+            }
         }
 
         for c in &constraints.bilateral[self.bilateral_rng.clone()] {
-            self.ang_impulses[c.impulse_id] = c.impulse;
+            if c.impulse_id < ANGULAR_DIM {
+                self.ang_impulses[c.impulse_id] = c.impulse;
+            } else if c.impulse_id < ANGULAR_DIM + DIM {
+                self.limit_impulses[c.impulse_id - ANGULAR_DIM] = c.impulse;
+            } else {
+                self.motor_impulses[c.impulse_id - ANGULAR_DIM - DIM] = c.impulse;
+            }
+        }
+
+        for c in &constraints.unilateral_ground[self.unilateral_ground_rng.clone()] {
+            self.limit_impulses[c.impulse_id - ANGULAR_DIM] = c.impulse;
+        }
+
+        for c in &constraints.unilateral[self.unilateral_rng.clone()] {
+            self.limit_impulses[c.impulse_id - ANGULAR_DIM] = c.impulse;
         }
 
         if self.ang_impulses.norm_squared() * inv_dt * inv_dt > self.break_torque_squared {
@@ -166,7 +436,7 @@ impl<N: RealField, Handle: BodyHandle> NonlinearConstraintGenerator<N, Handle>
     fn num_position_constraints(&self, bodies: &dyn BodySet<N, Handle = Handle>) -> usize {
         // FIXME: calling this at each iteration of the non-linear resolution is costly.
         if self.is_active(bodies) {
-            1
+            1 + DIM
         } else {
             0
         }
@@ -175,7 +445,7 @@ impl<N: RealField, Handle: BodyHandle> NonlinearConstraintGenerator<N, Handle>
     fn position_constraint(
         &self,
         parameters: &IntegrationParameters<N>,
-        _: usize,
+        i: usize,
         bodies: &mut dyn BodySet<N, Handle = Handle>,
         jacobians: &mut [N],
     ) -> Option<GenericNonlinearConstraint<N, Handle>> {
@@ -190,12 +460,32 @@ impl<N: RealField, Handle: BodyHandle> NonlinearConstraintGenerator<N, Handle>
         let anchor1 = Point::from(pos1.translation.vector);
         let anchor2 = Point::from(pos2.translation.vector);
 
-        let rotation1 = pos1.rotation;
-        let rotation2 = pos2.rotation;
+        if i == 0 {
+            let rotation1 = pos1.rotation;
+            let rotation2 = pos2.rotation;
+
+            return helper::cancel_relative_rotation(
+                parameters, body1, part1, self.b1, body2, part2, self.b2, &anchor1, &anchor2,
+                &rotation1, &rotation2, jacobians,
+            );
+        }
+
+        let axis = pos1.rotation * Vector::ith_axis(i - 1);
 
-        helper::cancel_relative_rotation(
-            parameters, body1, part1, self.b1, body2, part2, self.b2, &anchor1, &anchor2,
-            &rotation1, &rotation2, jacobians,
+        unit_constraint::build_linear_limits_position_constraint(
+            parameters,
+            body1,
+            part1,
+            self.b1,
+            body2,
+            part2,
+            self.b2,
+            &anchor1,
+            &anchor2,
+            &axis,
+            self.min_offsets[i - 1],
+            self.max_offsets[i - 1],
+            jacobians,
         )
     }
 }
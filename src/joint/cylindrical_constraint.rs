@@ -1,8 +1,8 @@
 use na::{DVector, RealField, Unit};
 use std::ops::Range;
 
-use crate::joint::JointConstraint;
-use crate::math::{AngularVector, Point, Vector, DIM, SPATIAL_DIM};
+use crate::joint::{unit_constraint, JointConstraint};
+use crate::math::{AngularVector, Force, Point, Vector, DIM, SPATIAL_DIM};
 use crate::object::{BodyHandle, BodyPartHandle, BodySet};
 use crate::solver::helper;
 use crate::solver::{
@@ -11,6 +11,7 @@ use crate::solver::{
 };
 
 /// A constraint that removes all degrees of freedom (of one body part relative to a second one) except one translation along an axis and one rotation along the same axis.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct CylindricalConstraint<N: RealField, Handle: BodyHandle> {
     b1: BodyPartHandle<Handle>,
     b2: BodyPartHandle<Handle>,
@@ -25,6 +26,8 @@ pub struct CylindricalConstraint<N: RealField, Handle: BodyHandle> {
     broken: bool,
     bilateral_ground_rng: Range<usize>,
     bilateral_rng: Range<usize>,
+    unit_forces: Vec<Force<N>>,
+    reaction: Force<N>,
     // min_offset: Option<N>,
     // max_offset: Option<N>,
 }
@@ -59,6 +62,8 @@ impl<N: RealField, Handle: BodyHandle> CylindricalConstraint<N, Handle> {
             broken: false,
             bilateral_ground_rng: 0..0,
             bilateral_rng: 0..0,
+            unit_forces: vec![Force::zero(); SPATIAL_DIM - 2],
+            reaction: Force::zero(),
             // min_offset,
             // max_offset,
         }
@@ -116,6 +121,10 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle>
         self.broken
     }
 
+    fn reaction_force(&self) -> Force<N> {
+        self.reaction
+    }
+
     fn num_velocity_constraints(&self) -> usize {
         SPATIAL_DIM - 2
     }
@@ -200,6 +209,9 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle>
             constraints,
         );
 
+        unit_constraint::orthogonal_linear_forces(&mut self.unit_forces[..DIM - 1], &axis1);
+        unit_constraint::orthogonal_angular_forces(&mut self.unit_forces[DIM - 1..], &axis1);
+
         /*
          *
          * Limit constraints.
@@ -211,6 +223,14 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle>
     }
 
     fn cache_impulses(&mut self, constraints: &LinearConstraints<N, usize>, inv_dt: N) {
+        self.reaction = unit_constraint::reaction_force(
+            &self.unit_forces,
+            constraints,
+            self.bilateral_ground_rng.clone(),
+            self.bilateral_rng.clone(),
+            inv_dt,
+        );
+
         for c in &constraints.bilateral_ground[self.bilateral_ground_rng.clone()] {
             if c.impulse_id < DIM {
                 self.lin_impulses[c.impulse_id] = c.impulse;
@@ -7,6 +7,7 @@ use crate::solver::{ConstraintSet, GenericNonlinearConstraint, IntegrationParame
 
 /// A joint that allows one translational and one rotational degrees of freedom along a single axis.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct CylindricalJoint<N: RealField> {
     prism: PrismaticJoint<N>,
     revo: RevoluteJoint<N>,
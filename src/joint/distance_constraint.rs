@@ -0,0 +1,418 @@
+use na::{DVector, RealField, Unit};
+use std::ops::Range;
+
+use crate::joint::{unit_constraint, JointConstraint, JointMotor};
+use crate::math::{Force, Point, Vector};
+use crate::object::{BodyHandle, BodyPartHandle, BodySet};
+use crate::solver::{
+    ForceDirection, GenericNonlinearConstraint, IntegrationParameters, LinearConstraints,
+    NonlinearConstraintGenerator,
+};
+
+/// A constraint that restricts the distance between two anchors attached to two body parts.
+///
+/// The distance can be bounded by a lower limit, an upper limit, or both:
+///
+/// * With only an upper limit, this behaves like a rope: the anchors can get closer to each other
+///   but the constraint can only pull them together when the rope is taut.
+/// * With equal lower and upper limits, this behaves like a rigid rod.
+///
+/// In addition, a spring-damper can drive the distance toward a rest length. It is integrated
+/// implicitly by the solver so it remains stable even with a large stiffness.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct DistanceConstraint<N: RealField, Handle: BodyHandle> {
+    b1: BodyPartHandle<Handle>,
+    b2: BodyPartHandle<Handle>,
+    anchor1: Point<N>,
+    anchor2: Point<N>,
+    limit_impulse: N,
+    spring_impulse: N,
+    break_force_squared: N,
+    broken: bool,
+    bilateral_ground_rng: Range<usize>,
+    bilateral_rng: Range<usize>,
+    unilateral_ground_rng: Range<usize>,
+    unilateral_rng: Range<usize>,
+    unit_forces: Vec<Force<N>>,
+    reaction: Force<N>,
+    min_length: Option<N>,
+    max_length: Option<N>,
+    spring: JointMotor<N, N>,
+}
+
+impl<N: RealField, Handle: BodyHandle> DistanceConstraint<N, Handle> {
+    /// Creates a distance constraint between the anchors `anchor1` and `anchor2`, without any limit.
+    ///
+    /// Both anchors are expressed in the local coordinate system of the corresponding body parts.
+    /// No force is applied by this constraint until a limit or the spring is enabled.
+    pub fn new(
+        b1: BodyPartHandle<Handle>,
+        b2: BodyPartHandle<Handle>,
+        anchor1: Point<N>,
+        anchor2: Point<N>,
+    ) -> Self {
+        DistanceConstraint {
+            b1,
+            b2,
+            anchor1,
+            anchor2,
+            limit_impulse: N::zero(),
+            spring_impulse: N::zero(),
+            break_force_squared: N::max_value(),
+            broken: false,
+            bilateral_ground_rng: 0..0,
+            bilateral_rng: 0..0,
+            unilateral_ground_rng: 0..0,
+            unilateral_rng: 0..0,
+            unit_forces: vec![Force::zero(); 2],
+            reaction: Force::zero(),
+            min_length: None,
+            max_length: None,
+            spring: JointMotor::new(),
+        }
+    }
+
+    /// Creates a rope of length `length` between the anchors `anchor1` and `anchor2`.
+    ///
+    /// The rope prevents the anchors from being further than `length` apart, but not from getting closer.
+    pub fn new_rope(
+        b1: BodyPartHandle<Handle>,
+        b2: BodyPartHandle<Handle>,
+        anchor1: Point<N>,
+        anchor2: Point<N>,
+        length: N,
+    ) -> Self {
+        let mut res = Self::new(b1, b2, anchor1, anchor2);
+        res.enable_max_length(length);
+        res
+    }
+
+    /// Creates a rigid rod of length `length` between the anchors `anchor1` and `anchor2`.
+    ///
+    /// The rod keeps the anchors exactly `length` apart. This length should not be zero.
+    pub fn new_rod(
+        b1: BodyPartHandle<Handle>,
+        b2: BodyPartHandle<Handle>,
+        anchor1: Point<N>,
+        anchor2: Point<N>,
+        length: N,
+    ) -> Self {
+        let mut res = Self::new(b1, b2, anchor1, anchor2);
+        res.enable_max_length(length);
+        res.enable_min_length(length);
+        res
+    }
+
+    /// The maximum force this joint can absorb before breaking.
+    pub fn set_break_force(&mut self, break_force: N) {
+        self.break_force_squared = break_force * break_force;
+    }
+
+    /// Change the first anchor, expressed in the local space of the first body part.
+    pub fn set_anchor_1(&mut self, anchor1: Point<N>) {
+        self.anchor1 = anchor1;
+    }
+
+    /// Change the second anchor, expressed in the local space of the second body part.
+    pub fn set_anchor_2(&mut self, anchor2: Point<N>) {
+        self.anchor2 = anchor2;
+    }
+
+    /// The lower limit, if any, of the distance between the two anchors.
+    pub fn min_length(&self) -> Option<N> {
+        self.min_length
+    }
+
+    /// The upper limit, if any, of the distance between the two anchors.
+    pub fn max_length(&self) -> Option<N> {
+        self.max_length
+    }
+
+    /// Disable the lower limit of the distance between the two anchors.
+    pub fn disable_min_length(&mut self) {
+        self.min_length = None;
+    }
+
+    /// Disable the upper limit of the distance between the two anchors.
+    pub fn disable_max_length(&mut self) {
+        self.max_length = None;
+    }
+
+    /// Enable and set the lower limit of the distance between the two anchors.
+    pub fn enable_min_length(&mut self, limit: N) {
+        self.min_length = Some(limit);
+        self.assert_limits();
+    }
+
+    /// Enable and set the upper limit of the distance between the two anchors.
+    pub fn enable_max_length(&mut self, limit: N) {
+        self.max_length = Some(limit);
+        self.assert_limits();
+    }
+
+    /// Returns `true` if the spring-damper of this joint is enabled.
+    pub fn is_spring_enabled(&self) -> bool {
+        self.spring.enabled
+    }
+
+    /// Enable the spring-damper driving the distance between the anchors toward `rest_length`.
+    ///
+    /// Both `stiffness` and `damping` should be positive. With a zero stiffness, the spring only
+    /// damps the relative velocity of the anchors along the line joining them.
+    pub fn enable_spring(&mut self, rest_length: N, stiffness: N, damping: N) {
+        self.spring.desired_position = rest_length;
+        self.spring.stiffness = stiffness;
+        self.spring.damping = damping;
+        self.spring.enabled = true;
+    }
+
+    /// Disable the spring-damper of this joint.
+    pub fn disable_spring(&mut self) {
+        self.spring.enabled = false;
+    }
+
+    /// The distance the spring-damper drives the anchors toward.
+    pub fn spring_rest_length(&self) -> N {
+        self.spring.desired_position
+    }
+
+    /// The stiffness of the spring-damper.
+    pub fn spring_stiffness(&self) -> N {
+        self.spring.stiffness
+    }
+
+    /// The damping of the spring-damper.
+    pub fn spring_damping(&self) -> N {
+        self.spring.damping
+    }
+
+    /// The maximum force that can be delivered by the spring-damper.
+    pub fn max_spring_force(&self) -> N {
+        self.spring.max_force
+    }
+
+    /// Set the maximum force that can be delivered by the spring-damper.
+    pub fn set_max_spring_force(&mut self, force: N) {
+        self.spring.max_force = force;
+    }
+
+    fn assert_limits(&self) {
+        if let (Some(min_length), Some(max_length)) = (self.min_length, self.max_length) {
+            assert!(
+                min_length <= max_length,
+                "DistanceConstraint limits: the min length must be smaller than (or equal to) the max length.");
+        }
+    }
+
+    /// The direction from the first anchor to the second one, and the distance between them.
+    fn axis_and_length(anchor1: &Point<N>, anchor2: &Point<N>) -> (Unit<Vector<N>>, N) {
+        Unit::try_new_and_get(anchor2 - anchor1, N::default_epsilon())
+            .unwrap_or((Vector::y_axis(), N::zero()))
+    }
+}
+
+impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle>
+    for DistanceConstraint<N, Handle>
+{
+    fn is_broken(&self) -> bool {
+        self.broken
+    }
+
+    fn reaction_force(&self) -> Force<N> {
+        self.reaction
+    }
+
+    fn num_velocity_constraints(&self) -> usize {
+        2
+    }
+
+    fn anchors(&self) -> (BodyPartHandle<Handle>, BodyPartHandle<Handle>) {
+        (self.b1, self.b2)
+    }
+
+    fn velocity_constraints(
+        &mut self,
+        parameters: &IntegrationParameters<N>,
+        bodies: &dyn BodySet<N, Handle = Handle>,
+        ext_vels: &DVector<N>,
+        ground_j_id: &mut usize,
+        j_id: &mut usize,
+        jacobians: &mut [N],
+        constraints: &mut LinearConstraints<N, usize>,
+    ) {
+        let body1 = try_ret!(bodies.get(self.b1.0));
+        let body2 = try_ret!(bodies.get(self.b2.0));
+        let part1 = try_ret!(body1.part(self.b1.1));
+        let part2 = try_ret!(body2.part(self.b2.1));
+
+        let anchor1 = body1.world_point_at_material_point(part1, &self.anchor1);
+        let anchor2 = body2.world_point_at_material_point(part2, &self.anchor2);
+        let (axis, length) = Self::axis_and_length(&anchor1, &anchor2);
+
+        let assembly_id1 = body1.companion_id();
+        let assembly_id2 = body2.companion_id();
+
+        let first_unilateral_ground = constraints.unilateral_ground.len();
+        let first_unilateral = constraints.unilateral.len();
+        let first_bilateral_ground = constraints.bilateral_ground.len();
+        let first_bilateral = constraints.bilateral.len();
+
+        /*
+         *
+         * Limit and spring constraints.
+         *
+         */
+        if let Some(dir) = unit_constraint::build_linear_limits_velocity_constraint(
+            body1,
+            part1,
+            self.b1,
+            body2,
+            part2,
+            self.b2,
+            assembly_id1,
+            assembly_id2,
+            &anchor1,
+            &anchor2,
+            &axis,
+            self.min_length,
+            self.max_length,
+            ext_vels,
+            self.limit_impulse,
+            0,
+            ground_j_id,
+            j_id,
+            jacobians,
+            constraints,
+        ) {
+            self.unit_forces[0] = unit_constraint::unit_force(&dir);
+        }
+
+        if let Some(dir) = unit_constraint::build_motor_velocity_constraint(
+            parameters,
+            body1,
+            part1,
+            self.b1,
+            body2,
+            part2,
+            self.b2,
+            assembly_id1,
+            assembly_id2,
+            &anchor1,
+            &anchor2,
+            ForceDirection::Linear(axis),
+            &self.spring,
+            length,
+            ext_vels,
+            self.spring_impulse,
+            1,
+            ground_j_id,
+            j_id,
+            jacobians,
+            constraints,
+        ) {
+            self.unit_forces[1] = unit_constraint::unit_force(&dir);
+        }
+
+        self.bilateral_ground_rng = first_bilateral_ground..constraints.bilateral_ground.len();
+        self.bilateral_rng = first_bilateral..constraints.bilateral.len();
+        self.unilateral_ground_rng = first_unilateral_ground..constraints.unilateral_ground.len();
+        self.unilateral_rng = first_unilateral..constraints.unilateral.len();
+    }
+
+    fn cache_impulses(&mut self, constraints: &LinearConstraints<N, usize>, inv_dt: N) {
+        self.reaction = unit_constraint::reaction_force(
+            &self.unit_forces,
+            constraints,
+            self.bilateral_ground_rng.clone(),
+            self.bilateral_rng.clone(),
+            inv_dt,
+        ) + unit_constraint::limits_reaction_force(
+            &self.unit_forces,
+            constraints,
+            self.unilateral_ground_rng.clone(),
+            self.unilateral_rng.clone(),
+            inv_dt,
+        );
+
+        self.limit_impulse = N::zero();
+        self.spring_impulse = N::zero();
+
+        for c in &constraints.bilateral_ground[self.bilateral_ground_rng.clone()] {
+            if c.impulse_id == 0 {
+                self.limit_impulse = c.impulse;
+            } else {
+                self.spring_impulse = c.impulse;
+            }
+        }
+
+        for c in &constraints.bilateral[self.bilateral_rng.clone()] {
+            if c.impulse_id == 0 {
+                self.limit_impulse = c.impulse;
+            } else {
+                self.spring_impulse = c.impulse;
+            }
+        }
+
+        for c in &constraints.unilateral_ground[self.unilateral_ground_rng.clone()] {
+            self.limit_impulse = c.impulse;
+        }
+
+        for c in &constraints.unilateral[self.unilateral_rng.clone()] {
+            self.limit_impulse = c.impulse;
+        }
+
+        if self.reaction.linear.norm_squared() > self.break_force_squared {
+            self.broken = true;
+        }
+    }
+}
+
+impl<N: RealField, Handle: BodyHandle> NonlinearConstraintGenerator<N, Handle>
+    for DistanceConstraint<N, Handle>
+{
+    fn num_position_constraints(&self, bodies: &dyn BodySet<N, Handle = Handle>) -> usize {
+        // FIXME: calling this at each iteration of the non-linear resolution is costly.
+        if self.is_active(bodies) && (self.min_length.is_some() || self.max_length.is_some()) {
+            1
+        } else {
+            0
+        }
+    }
+
+    fn position_constraint(
+        &self,
+        parameters: &IntegrationParameters<N>,
+        i: usize,
+        bodies: &mut dyn BodySet<N, Handle = Handle>,
+        jacobians: &mut [N],
+    ) -> Option<GenericNonlinearConstraint<N, Handle>> {
+        if i != 0 {
+            return None;
+        }
+
+        let body1 = bodies.get(self.b1.0)?;
+        let body2 = bodies.get(self.b2.0)?;
+        let part1 = body1.part(self.b1.1)?;
+        let part2 = body2.part(self.b2.1)?;
+
+        let anchor1 = body1.world_point_at_material_point(part1, &self.anchor1);
+        let anchor2 = body2.world_point_at_material_point(part2, &self.anchor2);
+        let (axis, _) = Self::axis_and_length(&anchor1, &anchor2);
+
+        unit_constraint::build_linear_limits_position_constraint(
+            parameters,
+            body1,
+            part1,
+            self.b1,
+            body2,
+            part2,
+            self.b2,
+            &anchor1,
+            &anchor2,
+            &axis,
+            self.min_length,
+            self.max_length,
+            jacobians,
+        )
+    }
+}
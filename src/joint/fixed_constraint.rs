@@ -1,8 +1,8 @@
 use na::{DVector, RealField};
 use std::ops::Range;
 
-use crate::joint::JointConstraint;
-use crate::math::{AngularVector, Point, Rotation, Vector, DIM, SPATIAL_DIM};
+use crate::joint::{unit_constraint, JointCompliance, JointConstraint};
+use crate::math::{AngularVector, Force, Point, Rotation, Vector, DIM, SPATIAL_DIM};
 use crate::object::{BodyHandle, BodyPartHandle, BodySet};
 use crate::solver::helper;
 use crate::solver::{
@@ -11,6 +11,7 @@ use crate::solver::{
 };
 
 /// A constraint that removes all degrees of freedom between two body parts.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct FixedConstraint<N: RealField, Handle: BodyHandle> {
     b1: BodyPartHandle<Handle>,
     b2: BodyPartHandle<Handle>,
@@ -25,6 +26,10 @@ pub struct FixedConstraint<N: RealField, Handle: BodyHandle> {
     broken: bool,
     bilateral_ground_rng: Range<usize>,
     bilateral_rng: Range<usize>,
+    unit_forces: Vec<Force<N>>,
+    reaction: Force<N>,
+    linear_compliance: JointCompliance<N>,
+    angular_compliance: JointCompliance<N>,
 }
 
 impl<N: RealField, Handle: BodyHandle> FixedConstraint<N, Handle> {
@@ -54,6 +59,10 @@ impl<N: RealField, Handle: BodyHandle> FixedConstraint<N, Handle> {
             broken: false,
             bilateral_ground_rng: 0..0,
             bilateral_rng: 0..0,
+            unit_forces: vec![Force::zero(); SPATIAL_DIM],
+            reaction: Force::zero(),
+            linear_compliance: JointCompliance::Rigid,
+            angular_compliance: JointCompliance::Rigid,
         }
     }
 
@@ -86,6 +95,26 @@ impl<N: RealField, Handle: BodyHandle> FixedConstraint<N, Handle> {
     pub fn set_break_torque(&mut self, break_torque: N) {
         self.break_torque_squared = break_torque * break_torque;
     }
+
+    /// The compliance of the constraints on the relative translation of the body parts.
+    pub fn linear_compliance(&self) -> &JointCompliance<N> {
+        &self.linear_compliance
+    }
+
+    /// Sets the compliance of the constraints on the relative translation of the body parts.
+    pub fn set_linear_compliance(&mut self, compliance: JointCompliance<N>) {
+        self.linear_compliance = compliance
+    }
+
+    /// The compliance of the constraints on the relative rotation of the body parts.
+    pub fn angular_compliance(&self) -> &JointCompliance<N> {
+        &self.angular_compliance
+    }
+
+    /// Sets the compliance of the constraints on the relative rotation of the body parts.
+    pub fn set_angular_compliance(&mut self, compliance: JointCompliance<N>) {
+        self.angular_compliance = compliance
+    }
 }
 
 impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle> for FixedConstraint<N, Handle> {
@@ -93,6 +122,10 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle> for FixedConst
         self.broken
     }
 
+    fn reaction_force(&self) -> Force<N> {
+        self.reaction
+    }
+
     fn num_velocity_constraints(&self) -> usize {
         SPATIAL_DIM
     }
@@ -103,7 +136,7 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle> for FixedConst
 
     fn velocity_constraints(
         &mut self,
-        _: &IntegrationParameters<N>,
+        parameters: &IntegrationParameters<N>,
         bodies: &dyn BodySet<N, Handle = Handle>,
         ext_vels: &DVector<N>,
         ground_j_id: &mut usize,
@@ -148,6 +181,19 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle> for FixedConst
             constraints,
         );
 
+        unit_constraint::soften_velocity_constraints(
+            parameters,
+            &self.linear_compliance,
+            (anchor1 - anchor2).as_slice(),
+            0,
+            first_bilateral_ground,
+            first_bilateral,
+            constraints,
+        );
+
+        let first_ang_bilateral_ground = constraints.bilateral_ground.len();
+        let first_ang_bilateral = constraints.bilateral.len();
+
         helper::cancel_relative_angular_velocity(
             body1,
             part1,
@@ -168,11 +214,32 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle> for FixedConst
             constraints,
         );
 
+        unit_constraint::soften_velocity_constraints(
+            parameters,
+            &self.angular_compliance,
+            (-(pos2.rotation / pos1.rotation).scaled_axis()).as_slice(),
+            DIM,
+            first_ang_bilateral_ground,
+            first_ang_bilateral,
+            constraints,
+        );
+
+        unit_constraint::linear_basis_forces(&mut self.unit_forces[..DIM]);
+        unit_constraint::angular_basis_forces(&mut self.unit_forces[DIM..]);
+
         self.bilateral_ground_rng = first_bilateral_ground..constraints.bilateral_ground.len();
         self.bilateral_rng = first_bilateral..constraints.bilateral.len();
     }
 
     fn cache_impulses(&mut self, constraints: &LinearConstraints<N, usize>, inv_dt: N) {
+        self.reaction = unit_constraint::reaction_force(
+            &self.unit_forces,
+            constraints,
+            self.bilateral_ground_rng.clone(),
+            self.bilateral_rng.clone(),
+            inv_dt,
+        );
+
         for c in &constraints.bilateral_ground[self.bilateral_ground_rng.clone()] {
             if c.impulse_id < DIM {
                 self.lin_impulses[c.impulse_id] = c.impulse;
@@ -233,14 +300,24 @@ impl<N: RealField, Handle: BodyHandle> NonlinearConstraintGenerator<N, Handle>
             let rotation1 = pos1.rotation;
             let rotation2 = pos2.rotation;
 
-            helper::cancel_relative_rotation(
+            let constraint = helper::cancel_relative_rotation(
                 parameters, body1, part1, self.b1, body2, part2, self.b2, &anchor1, &anchor2,
                 &rotation1, &rotation2, jacobians,
+            );
+            unit_constraint::soften_position_constraint(
+                parameters,
+                &self.angular_compliance,
+                constraint,
             )
         } else if i == 1 {
-            helper::cancel_relative_translation(
+            let constraint = helper::cancel_relative_translation(
                 parameters, body1, part1, self.b1, body2, part2, self.b2, &anchor1, &anchor2,
                 jacobians,
+            );
+            unit_constraint::soften_position_constraint(
+                parameters,
+                &self.linear_compliance,
+                constraint,
             )
         } else {
             None
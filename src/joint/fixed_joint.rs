@@ -7,6 +7,7 @@ use crate::solver::IntegrationParameters;
 
 /// A joint that does not allow any relative degrees of freedom.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct FixedJoint<N: RealField> {
     body_to_parent: Isometry<N>,
 }
@@ -0,0 +1,336 @@
+use na::{DVector, RealField, Unit};
+use std::ops::Range;
+
+use crate::joint::{unit_constraint, JointConstraint};
+use crate::math::{AngularVector, Force, Rotation, Vector};
+use crate::object::{BodyHandle, BodyPart, BodyPartHandle, BodySet};
+use crate::solver::{
+    CouplingTerm, ForceDirection, GenericNonlinearConstraint, IntegrationParameters,
+    LinearConstraints, NonlinearConstraintGenerator,
+};
+
+/// A constraint that couples the angular velocities of two body parts, like two gears.
+///
+/// This ensures that `ω2 = ratio * ω1` where `ω1` (resp. `ω2`) is the angular velocity of the first
+/// (resp. second) body part around its axis, relative to the carrier. The carrier is the frame rotating
+/// with the line joining the centers of mass of both body parts, so the gears may revolve around each
+/// other like in a planetary gear train. The rotation of the carrier around this line is ignored. For two
+/// meshing gears with radii `r1` and `r2`, the ratio is `-r1 / r2` since they rotate in opposite directions.
+///
+/// This does not prevent any other relative motion: the body parts are typically attached to the
+/// ground, or to a common body, by revolute joints. The angle error accumulated over time because of
+/// numerical errors is corrected at the position level.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct GearConstraint<N: RealField, Handle: BodyHandle> {
+    b1: BodyPartHandle<Handle>,
+    b2: BodyPartHandle<Handle>,
+    #[cfg(feature = "dim3")]
+    axis1: Unit<AngularVector<N>>,
+    #[cfg(feature = "dim3")]
+    axis2: Unit<AngularVector<N>>,
+    ratio: N,
+    impulse: N,
+    bilateral_ground_rng: Range<usize>,
+    bilateral_rng: Range<usize>,
+    unit_forces: Vec<Force<N>>,
+    reaction: Force<N>,
+    angle_error: N,
+    last_frames: Option<(Rotation<N>, Rotation<N>, Vector<N>)>,
+}
+
+impl<N: RealField, Handle: BodyHandle> GearConstraint<N, Handle> {
+    /// Creates a gear constraint between the rotations of `b1` around `axis1` and of `b2` around `axis2`.
+    ///
+    /// Both axii are expressed in the local coordinate system of the corresponding body parts.
+    #[cfg(feature = "dim3")]
+    pub fn new(
+        b1: BodyPartHandle<Handle>,
+        b2: BodyPartHandle<Handle>,
+        axis1: Unit<AngularVector<N>>,
+        axis2: Unit<AngularVector<N>>,
+        ratio: N,
+    ) -> Self {
+        GearConstraint {
+            b1,
+            b2,
+            axis1,
+            axis2,
+            ratio,
+            impulse: N::zero(),
+            bilateral_ground_rng: 0..0,
+            bilateral_rng: 0..0,
+            unit_forces: vec![Force::zero(); 1],
+            reaction: Force::zero(),
+            angle_error: N::zero(),
+            last_frames: None,
+        }
+    }
+
+    /// Creates a gear constraint between the rotations of `b1` and `b2`.
+    #[cfg(feature = "dim2")]
+    pub fn new(b1: BodyPartHandle<Handle>, b2: BodyPartHandle<Handle>, ratio: N) -> Self {
+        GearConstraint {
+            b1,
+            b2,
+            ratio,
+            impulse: N::zero(),
+            bilateral_ground_rng: 0..0,
+            bilateral_rng: 0..0,
+            unit_forces: vec![Force::zero(); 1],
+            reaction: Force::zero(),
+            angle_error: N::zero(),
+            last_frames: None,
+        }
+    }
+
+    /// The ratio between the angular velocities of the second body part and the first one.
+    pub fn ratio(&self) -> N {
+        self.ratio
+    }
+
+    /// Sets the ratio between the angular velocities of the second body part and the first one.
+    ///
+    /// This resets the angle error accumulated so far.
+    pub fn set_ratio(&mut self, ratio: N) {
+        self.ratio = ratio;
+        self.angle_error = N::zero();
+    }
+
+    /// The world-space rotation axii of both body parts.
+    #[cfg(feature = "dim3")]
+    fn axii(
+        &self,
+        part1: &dyn BodyPart<N>,
+        part2: &dyn BodyPart<N>,
+    ) -> (Unit<AngularVector<N>>, Unit<AngularVector<N>>) {
+        (part1.position() * self.axis1, part2.position() * self.axis2)
+    }
+
+    /// The world-space rotation axii of both body parts.
+    #[cfg(feature = "dim2")]
+    fn axii(
+        &self,
+        _: &dyn BodyPart<N>,
+        _: &dyn BodyPart<N>,
+    ) -> (Unit<AngularVector<N>>, Unit<AngularVector<N>>) {
+        (AngularVector::x_axis(), AngularVector::x_axis())
+    }
+
+    /// The terms of the coupled velocity `a2 . (ω2 - Ω) - ratio * a1 . (ω1 - Ω)` where `a1` (resp. `a2`) is
+    /// the world-space axis of the first (resp. second) body part, and `Ω` is the angular velocity of the
+    /// carrier.
+    fn coupling_terms(
+        &self,
+        part1: &dyn BodyPart<N>,
+        part2: &dyn BodyPart<N>,
+    ) -> (Vec<CouplingTerm<N>>, Vec<CouplingTerm<N>>) {
+        let (axis1, axis2) = self.axii(part1, part2);
+        let center1 = part1.center_of_mass();
+        let center2 = part2.center_of_mass();
+        let mut terms1 = vec![CouplingTerm::new(
+            center1,
+            ForceDirection::Angular(axis1),
+            -self.ratio,
+        )];
+        let mut terms2 = vec![CouplingTerm::new(
+            center2,
+            ForceDirection::Angular(axis2),
+            N::one(),
+        )];
+
+        // With `d` the vector joining the centers of mass, `Ω = d × (v2 - v1) / |d|²` so
+        // `(ratio * a1 - a2) . Ω = k . (v2 - v1)` where `k = (ratio * a1 - a2) × d / |d|²`.
+        let d = center2 - center1;
+        let sq_dist = d.norm_squared();
+
+        if sq_dist > N::default_epsilon() {
+            #[cfg(feature = "dim3")]
+            let k = (axis1.into_inner() * self.ratio - axis2.into_inner()).cross(&d) / sq_dist;
+            #[cfg(feature = "dim2")]
+            let k = Vector::new(-d.y, d.x) * ((self.ratio - N::one()) / sq_dist);
+
+            if let Some((dir, norm)) = Unit::try_new_and_get(k, N::default_epsilon()) {
+                terms1.push(CouplingTerm::new(
+                    center1,
+                    ForceDirection::Linear(dir),
+                    -norm,
+                ));
+                terms2.push(CouplingTerm::new(
+                    center2,
+                    ForceDirection::Linear(dir),
+                    norm,
+                ));
+            }
+        }
+
+        (terms1, terms2)
+    }
+
+    /// The rotations of both body parts, and the vector joining their centers of mass.
+    fn frames(
+        part1: &dyn BodyPart<N>,
+        part2: &dyn BodyPart<N>,
+    ) -> (Rotation<N>, Rotation<N>, Vector<N>) {
+        (
+            part1.position().rotation,
+            part2.position().rotation,
+            part2.center_of_mass() - part1.center_of_mass(),
+        )
+    }
+
+    /// The variation of the angle error since the frames of the body parts were last recorded.
+    fn angle_error_increment(&self, part1: &dyn BodyPart<N>, part2: &dyn BodyPart<N>) -> N {
+        let (last_rot1, last_rot2, last_d) = match &self.last_frames {
+            Some(frames) => frames,
+            None => return N::zero(),
+        };
+
+        let (rot1, rot2, d) = Self::frames(part1, part2);
+        let (axis1, axis2) = self.axii(part1, part2);
+        let eps = N::default_epsilon();
+
+        let inv_carrier_rot = if last_d.norm_squared() > eps && d.norm_squared() > eps {
+            carrier_rotation(last_d, &d).inverse()
+        } else {
+            Rotation::identity()
+        };
+
+        let angle1 =
+            unit_constraint::twist_angle(&(inv_carrier_rot * rot1 * last_rot1.inverse()), &axis1);
+        let angle2 =
+            unit_constraint::twist_angle(&(inv_carrier_rot * rot2 * last_rot2.inverse()), &axis2);
+
+        angle2 - self.ratio * angle1
+    }
+}
+
+/// The rotation of the carrier when the vector joining the centers of mass goes from `d1` to `d2`.
+#[cfg(feature = "dim3")]
+fn carrier_rotation<N: RealField>(d1: &Vector<N>, d2: &Vector<N>) -> Rotation<N> {
+    Rotation::rotation_between(d1, d2).unwrap_or_else(Rotation::identity)
+}
+
+/// The rotation of the carrier when the vector joining the centers of mass goes from `d1` to `d2`.
+#[cfg(feature = "dim2")]
+fn carrier_rotation<N: RealField>(d1: &Vector<N>, d2: &Vector<N>) -> Rotation<N> {
+    Rotation::rotation_between(d1, d2)
+}
+
+impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle> for GearConstraint<N, Handle> {
+    fn reaction_force(&self) -> Force<N> {
+        self.reaction
+    }
+
+    fn num_velocity_constraints(&self) -> usize {
+        1
+    }
+
+    fn anchors(&self) -> (BodyPartHandle<Handle>, BodyPartHandle<Handle>) {
+        (self.b1, self.b2)
+    }
+
+    fn velocity_constraints(
+        &mut self,
+        _: &IntegrationParameters<N>,
+        bodies: &dyn BodySet<N, Handle = Handle>,
+        ext_vels: &DVector<N>,
+        ground_j_id: &mut usize,
+        j_id: &mut usize,
+        jacobians: &mut [N],
+        constraints: &mut LinearConstraints<N, usize>,
+    ) {
+        let body1 = try_ret!(bodies.get(self.b1.0));
+        let body2 = try_ret!(bodies.get(self.b2.0));
+        let part1 = try_ret!(body1.part(self.b1.1));
+        let part2 = try_ret!(body2.part(self.b2.1));
+
+        self.angle_error += self.angle_error_increment(part1, part2);
+        self.last_frames = Some(Self::frames(part1, part2));
+
+        let (_, axis2) = self.axii(part1, part2);
+        let (terms1, terms2) = self.coupling_terms(part1, part2);
+
+        let first_bilateral_ground = constraints.bilateral_ground.len();
+        let first_bilateral = constraints.bilateral.len();
+
+        unit_constraint::build_coupled_velocity_constraint(
+            body1,
+            part1,
+            self.b1,
+            body2,
+            part2,
+            self.b2,
+            body1.companion_id(),
+            body2.companion_id(),
+            &terms1,
+            &terms2,
+            ext_vels,
+            self.impulse,
+            0,
+            ground_j_id,
+            j_id,
+            jacobians,
+            constraints,
+        );
+
+        self.unit_forces[0] = -unit_constraint::unit_force(&ForceDirection::Angular(axis2));
+
+        self.bilateral_ground_rng = first_bilateral_ground..constraints.bilateral_ground.len();
+        self.bilateral_rng = first_bilateral..constraints.bilateral.len();
+    }
+
+    fn cache_impulses(&mut self, constraints: &LinearConstraints<N, usize>, inv_dt: N) {
+        self.reaction = unit_constraint::reaction_force(
+            &self.unit_forces,
+            constraints,
+            self.bilateral_ground_rng.clone(),
+            self.bilateral_rng.clone(),
+            inv_dt,
+        );
+
+        for c in &constraints.bilateral_ground[self.bilateral_ground_rng.clone()] {
+            self.impulse = c.impulse;
+        }
+
+        for c in &constraints.bilateral[self.bilateral_rng.clone()] {
+            self.impulse = c.impulse;
+        }
+    }
+}
+
+impl<N: RealField, Handle: BodyHandle> NonlinearConstraintGenerator<N, Handle>
+    for GearConstraint<N, Handle>
+{
+    fn num_position_constraints(&self, bodies: &dyn BodySet<N, Handle = Handle>) -> usize {
+        if self.is_active(bodies) {
+            1
+        } else {
+            0
+        }
+    }
+
+    fn position_constraint(
+        &self,
+        parameters: &IntegrationParameters<N>,
+        i: usize,
+        bodies: &mut dyn BodySet<N, Handle = Handle>,
+        jacobians: &mut [N],
+    ) -> Option<GenericNonlinearConstraint<N, Handle>> {
+        if i != 0 {
+            return None;
+        }
+
+        let body1 = bodies.get(self.b1.0)?;
+        let body2 = bodies.get(self.b2.0)?;
+        let part1 = body1.part(self.b1.1)?;
+        let part2 = body2.part(self.b2.1)?;
+
+        let error = self.angle_error + self.angle_error_increment(part1, part2);
+        let (terms1, terms2) = self.coupling_terms(part1, part2);
+
+        unit_constraint::build_coupled_position_constraint(
+            parameters, body1, part1, self.b1, body2, part2, self.b2, &terms1, &terms2, true,
+            error, jacobians,
+        )
+    }
+}
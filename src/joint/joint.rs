@@ -91,3 +91,34 @@ pub trait Joint<N: RealField>: Downcast + Send + Sync {
 }
 
 impl_downcast!(Joint<N> where N: RealField);
+
+#[cfg(all(feature = "serde-serialize", feature = "dim3"))]
+use crate::joint::{
+    BallJoint, CylindricalJoint, HelicalJoint, PinSlotJoint, PlanarJoint, RectangularJoint,
+    UniversalJoint,
+};
+#[cfg(feature = "serde-serialize")]
+use crate::joint::{CartesianJoint, FixedJoint, FreeJoint, PrismaticJoint, RevoluteJoint};
+
+#[cfg(feature = "serde-serialize")]
+serde_trait_object!(Joint<N: RealField>, JointRef, JointOwned {
+    CartesianJoint(CartesianJoint<N>),
+    FixedJoint(FixedJoint<N>),
+    FreeJoint(FreeJoint<N>),
+    PrismaticJoint(PrismaticJoint<N>),
+    RevoluteJoint(RevoluteJoint<N>),
+    #[cfg(feature = "dim3")]
+    BallJoint(BallJoint<N>),
+    #[cfg(feature = "dim3")]
+    CylindricalJoint(CylindricalJoint<N>),
+    #[cfg(feature = "dim3")]
+    HelicalJoint(HelicalJoint<N>),
+    #[cfg(feature = "dim3")]
+    PinSlotJoint(PinSlotJoint<N>),
+    #[cfg(feature = "dim3")]
+    PlanarJoint(PlanarJoint<N>),
+    #[cfg(feature = "dim3")]
+    RectangularJoint(RectangularJoint<N>),
+    #[cfg(feature = "dim3")]
+    UniversalJoint(UniversalJoint<N>),
+});
@@ -0,0 +1,86 @@
+use na::RealField;
+
+/// Description of the compliance of some of the constraints of a joint.
+///
+/// A compliant constraint behaves like a spring-damper pulling the joint back toward its rest
+/// configuration instead of enforcing it exactly. This spring-damper is integrated implicitly by
+/// the solver so it remains stable even with large stiffnesses. The position correction of a
+/// compliant constraint is softened the same way, but still applied to prevent drift, so the joint
+/// deflects a bit less than the equivalent spring-damper would.
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub enum JointCompliance<N: RealField> {
+    /// The constraints are rigid.
+    #[default]
+    Rigid,
+    /// The constraints behave like a spring-damper with the given stiffness and damping.
+    ///
+    /// They should not be both zero.
+    SpringDamper {
+        /// The stiffness of the spring.
+        stiffness: N,
+        /// The damping coefficient of the spring.
+        damping: N,
+    },
+    /// The constraints behave like a spring-damper oscillating at the given frequency.
+    ///
+    /// The stiffness and damping are computed from the effective mass of each constraint, so the
+    /// behavior of the joint does not depend on the masses of the bodies it is attached to.
+    Frequency {
+        /// The natural frequency of the spring, in Hertz. Should be positive.
+        frequency: N,
+        /// The damping ratio of the spring. The spring is critically damped if this is one.
+        damping_ratio: N,
+    },
+}
+
+impl<N: RealField> JointCompliance<N> {
+    /// Returns `true` if this compliance makes the constraints rigid.
+    pub fn is_rigid(&self) -> bool {
+        matches!(self, JointCompliance::Rigid)
+    }
+
+    /// The stiffness and damping applied to a constraint with the given effective mass.
+    ///
+    /// Returns `None` if the constraint is rigid.
+    pub fn stiffness_and_damping(&self, effective_mass: N) -> Option<(N, N)> {
+        match *self {
+            JointCompliance::Rigid => None,
+            JointCompliance::SpringDamper { stiffness, damping } => Some((stiffness, damping)),
+            JointCompliance::Frequency {
+                frequency,
+                damping_ratio,
+            } => {
+                let omega = N::two_pi() * frequency;
+                let stiffness = effective_mass * omega * omega;
+                let damping = effective_mass * omega * damping_ratio * na::convert(2.0);
+                Some((stiffness, damping))
+            }
+        }
+    }
+
+    /// The velocity a compliant constraint attempts to reach during the next timestep of length `dt`,
+    /// given its positional `error`.
+    ///
+    /// The second returned value is the regularization (constraint force mixing) to apply to the
+    /// constraint. Returns `None` if the constraint is rigid.
+    pub fn velocity_target(&self, error: N, effective_mass: N, dt: N) -> Option<(N, N)> {
+        let (stiffness, damping) = self.stiffness_and_damping(effective_mass)?;
+        let gain = stiffness * dt + damping;
+
+        if gain.is_zero() {
+            return None;
+        }
+
+        Some((-stiffness * error / gain, N::one() / (dt * gain)))
+    }
+
+    /// The regularization (constraint force mixing) to apply to a constraint with the given effective
+    /// mass, during a timestep of length `dt`.
+    ///
+    /// Returns `None` if the constraint is rigid.
+    pub fn cfm(&self, effective_mass: N, dt: N) -> Option<N> {
+        self.velocity_target(N::zero(), effective_mass, dt)
+            .map(|(_, cfm)| cfm)
+    }
+}
@@ -4,6 +4,7 @@ use downcast_rs::Downcast;
 use generational_arena::Arena;
 use na::{DVector, RealField};
 
+use crate::math::Force;
 use crate::object::{BodyHandle, BodyPartHandle, BodySet, DefaultBodyHandle};
 use crate::solver::{IntegrationParameters, LinearConstraints, NonlinearConstraintGenerator};
 
@@ -58,6 +59,7 @@ pub trait JointConstraintSet<N: RealField, Handle: BodyHandle> {
 /// A set containing all the joint-constraints added to the world.
 ///
 /// It is based on an arena using generational indices to avoid the ABA problem.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct DefaultJointConstraintSet<N: RealField, Handle: BodyHandle = DefaultBodyHandle> {
     constraints: Arena<Box<dyn JointConstraint<N, Handle>>>,
     inserted: Vec<(
@@ -248,6 +250,50 @@ pub trait JointConstraint<N: RealField, Handle: BodyHandle>:
     fn is_broken(&self) -> bool {
         false // FIXME: we provide a default impl just to avoid a breaking change.
     }
+
+    /// The force and torque applied by this joint on its second body part during the last timestep.
+    ///
+    /// It is expressed in world-space, at the anchor of the second body part, and includes the
+    /// contributions of the joint limits and motors. The first body part receives the opposite force.
+    fn reaction_force(&self) -> Force<N> {
+        Force::zero()
+    }
 }
 
 impl_downcast!(JointConstraint<N, Handle> where N: RealField, Handle: BodyHandle);
+
+#[cfg(all(feature = "serde-serialize", feature = "dim3"))]
+use crate::joint::{
+    BallConstraint, CylindricalConstraint, PinSlotConstraint, PlanarConstraint,
+    RectangularConstraint, UniversalConstraint,
+};
+#[cfg(feature = "serde-serialize")]
+use crate::joint::{
+    CartesianConstraint, DistanceConstraint, FixedConstraint, GearConstraint, MouseConstraint,
+    PrismaticConstraint, PulleyConstraint, RackAndPinionConstraint, RevoluteConstraint,
+};
+
+#[cfg(feature = "serde-serialize")]
+serde_trait_object!(JointConstraint<N: RealField, Handle: BodyHandle>, JointConstraintRef, JointConstraintOwned {
+    CartesianConstraint(CartesianConstraint<N, Handle>),
+    DistanceConstraint(DistanceConstraint<N, Handle>),
+    FixedConstraint(FixedConstraint<N, Handle>),
+    GearConstraint(GearConstraint<N, Handle>),
+    MouseConstraint(MouseConstraint<N, Handle>),
+    PrismaticConstraint(PrismaticConstraint<N, Handle>),
+    PulleyConstraint(PulleyConstraint<N, Handle>),
+    RackAndPinionConstraint(RackAndPinionConstraint<N, Handle>),
+    RevoluteConstraint(RevoluteConstraint<N, Handle>),
+    #[cfg(feature = "dim3")]
+    BallConstraint(BallConstraint<N, Handle>),
+    #[cfg(feature = "dim3")]
+    CylindricalConstraint(CylindricalConstraint<N, Handle>),
+    #[cfg(feature = "dim3")]
+    PinSlotConstraint(PinSlotConstraint<N, Handle>),
+    #[cfg(feature = "dim3")]
+    PlanarConstraint(PlanarConstraint<N, Handle>),
+    #[cfg(feature = "dim3")]
+    RectangularConstraint(RectangularConstraint<N, Handle>),
+    #[cfg(feature = "dim3")]
+    UniversalConstraint(UniversalConstraint<N, Handle>),
+});
@@ -3,10 +3,20 @@ use na::RealField;
 use num::Zero;
 
 /// Description of a motor applied to a joint.
+///
+/// With a zero stiffness and a zero damping, the motor is rigid: it attempts to reach exactly
+/// the `desired_velocity`. Otherwise, it behaves like a spring-damper (i.e. a PD controller)
+/// driving the joint toward the desired position and the `desired_velocity`. This spring-damper
+/// is integrated implicitly by the solver so it remains stable even with large gains. See
+/// `JointMotor::servo` and `JointMotor::set_gains`.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct JointMotor<V, N: RealField> {
     /// The velocity the motor will attempt to reach.
     pub desired_velocity: V,
+    pub(crate) desired_position: V,
+    pub(crate) stiffness: N,
+    pub(crate) damping: N,
     /// The maximum velocity the motor will attempt to reach.
     pub max_velocity: N,
     /// The maximum force deliverable by the motor.
@@ -22,12 +32,58 @@ impl<V: Zero, N: RealField> JointMotor<V, N> {
     pub fn new() -> Self {
         JointMotor {
             desired_velocity: V::zero(),
+            desired_position: V::zero(),
+            stiffness: N::zero(),
+            damping: N::zero(),
             max_velocity: N::max_value(),
             max_force: N::max_value(),
             enabled: false,
         }
     }
 
+    /// Create an enabled motor driving the joint toward `desired_position` with the given gains.
+    ///
+    /// The desired velocity is zero and the max force is initialized to a virtually infinite value.
+    pub fn servo(desired_position: V, stiffness: N, damping: N) -> Self {
+        JointMotor {
+            desired_position,
+            stiffness,
+            damping,
+            enabled: true,
+            ..Self::new()
+        }
+    }
+
+    /// The position the motor will attempt to reach if its stiffness is non-zero.
+    ///
+    /// For angular joints, the motor reaches it through the shortest rotation.
+    pub fn desired_position(&self) -> &V {
+        &self.desired_position
+    }
+
+    /// Sets the position the motor will attempt to reach if its stiffness is non-zero.
+    pub fn set_desired_position(&mut self, desired_position: V) {
+        self.desired_position = desired_position;
+    }
+
+    /// The stiffness of the motor wrt. the desired position.
+    pub fn stiffness(&self) -> N {
+        self.stiffness
+    }
+
+    /// The damping of the motor wrt. the `desired_velocity`.
+    pub fn damping(&self) -> N {
+        self.damping
+    }
+
+    /// Sets the stiffness and the damping of the motor.
+    ///
+    /// The motor is rigid if both are zero.
+    pub fn set_gains(&mut self, stiffness: N, damping: N) {
+        self.stiffness = stiffness;
+        self.damping = damping;
+    }
+
     /// The limits of the impulse applicable by the motor on the body parts.
     pub fn impulse_limits(&self) -> ImpulseLimits<N> {
         ImpulseLimits::Independent {
@@ -37,6 +93,41 @@ impl<V: Zero, N: RealField> JointMotor<V, N> {
     }
 }
 
+impl<N: RealField> JointMotor<N, N> {
+    /// The velocity the motor attempts to reach during the next timestep of length `dt`, given the
+    /// current `position` of the joint.
+    ///
+    /// The second returned value is the regularization (constraint force mixing) to apply to the
+    /// motor constraint. It is zero if the motor is rigid.
+    pub fn velocity_target(&self, position: N, dt: N) -> (N, N) {
+        self.velocity_target_with_error(self.desired_position - position, dt)
+    }
+
+    /// The velocity the motor attempts to reach during the next timestep of length `dt`, given the
+    /// current `angle` of the joint.
+    ///
+    /// Unlike `self.velocity_target`, the difference between the desired position and `angle` is
+    /// wrapped to `[-pi, pi]` so the motor never makes a full turn to reach its target.
+    pub fn angular_velocity_target(&self, angle: N, dt: N) -> (N, N) {
+        let error = self.desired_position - angle;
+        let wrapped = error - (error / N::two_pi()).round() * N::two_pi();
+        self.velocity_target_with_error(wrapped, dt)
+    }
+
+    fn velocity_target_with_error(&self, error: N, dt: N) -> (N, N) {
+        let gain = self.stiffness * dt + self.damping;
+
+        let (velocity, cfm) = if gain.is_zero() {
+            (self.desired_velocity, N::zero())
+        } else {
+            let velocity = (self.stiffness * error + self.damping * self.desired_velocity) / gain;
+            (velocity, N::one() / (dt * gain))
+        };
+
+        (velocity.clamp(-self.max_velocity, self.max_velocity), cfm)
+    }
+}
+
 impl<V: Zero, N: RealField> Default for JointMotor<V, N> {
     fn default() -> Self {
         Self::new()
@@ -27,13 +27,18 @@ pub use self::rectangular_joint::RectangularJoint;
 pub use self::universal_joint::UniversalJoint;
 
 pub use self::cartesian_constraint::CartesianConstraint;
+pub use self::distance_constraint::DistanceConstraint;
 pub use self::fixed_constraint::FixedConstraint;
+pub use self::gear_constraint::GearConstraint;
+pub use self::joint_compliance::JointCompliance;
 pub use self::joint_constraint::{
     DefaultJointConstraintHandle, DefaultJointConstraintSet, JointConstraint, JointConstraintSet,
 };
 pub use self::joint_motor::JointMotor;
 pub use self::mouse_constraint::MouseConstraint;
 pub use self::prismatic_constraint::PrismaticConstraint;
+pub use self::pulley_constraint::PulleyConstraint;
+pub use self::rack_and_pinion_constraint::RackAndPinionConstraint;
 pub use self::revolute_constraint::RevoluteConstraint;
 
 #[cfg(feature = "dim3")]
@@ -73,11 +78,16 @@ mod rectangular_joint;
 mod universal_joint;
 
 mod cartesian_constraint;
+mod distance_constraint;
 mod fixed_constraint;
+mod gear_constraint;
+mod joint_compliance;
 mod joint_constraint;
 mod joint_motor;
 mod mouse_constraint;
 mod prismatic_constraint;
+mod pulley_constraint;
+mod rack_and_pinion_constraint;
 mod revolute_constraint;
 mod unit_constraint;
 
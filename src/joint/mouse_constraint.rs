@@ -1,7 +1,8 @@
 use na::{DVector, RealField, Unit};
+use std::ops::Range;
 
-use crate::joint::JointConstraint;
-use crate::math::{Point, Vector, DIM};
+use crate::joint::{unit_constraint, JointConstraint};
+use crate::math::{Force, Point, Vector, DIM};
 use crate::object::{BodyHandle, BodyPartHandle, BodySet};
 use crate::solver::{
     helper, BilateralConstraint, BilateralGroundConstraint, ForceDirection, ImpulseLimits,
@@ -12,12 +13,17 @@ use crate::solver::{
 };
 
 /// A spring-like constraint to be used to drag a body part with the mouse.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct MouseConstraint<N: RealField, Handle: BodyHandle> {
     b1: BodyPartHandle<Handle>,
     b2: BodyPartHandle<Handle>,
     anchor1: Point<N>,
     anchor2: Point<N>,
     limit: N,
+    bilateral_ground_rng: Range<usize>,
+    bilateral_rng: Range<usize>,
+    unit_forces: Vec<Force<N>>,
+    reaction: Force<N>,
 }
 
 impl<N: RealField, Handle: BodyHandle> MouseConstraint<N, Handle> {
@@ -38,6 +44,10 @@ impl<N: RealField, Handle: BodyHandle> MouseConstraint<N, Handle> {
             anchor1,
             anchor2,
             limit,
+            bilateral_ground_rng: 0..0,
+            bilateral_rng: 0..0,
+            unit_forces: vec![Force::zero(); DIM],
+            reaction: Force::zero(),
         }
     }
 
@@ -87,6 +97,9 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle> for MouseConst
         let assembly_id1 = body1.companion_id();
         let assembly_id2 = body2.companion_id();
 
+        let first_bilateral_ground = constraints.bilateral_ground.len();
+        let first_bilateral = constraints.bilateral.len();
+
         let limits = ImpulseLimits::Independent {
             min: -self.limit,
             max: self.limit,
@@ -101,7 +114,7 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle> for MouseConst
         #[cfg(feature = "dim3")]
         let canonical_basis = [Vector::x(), Vector::y(), Vector::z()];
 
-        for dir in &canonical_basis {
+        for (i, dir) in canonical_basis.iter().enumerate() {
             let fdir = ForceDirection::Linear(Unit::new_unchecked(*dir));
             let mut rhs = -error.dot(&*dir) * parameters.erp * parameters.inv_dt();
             let geom = helper::constraint_pair_geometry(
@@ -132,7 +145,7 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle> for MouseConst
                         limits,
                         rhs,
                         N::zero(),
-                        0,
+                        i,
                     ));
             } else {
                 constraints.bilateral.push(BilateralConstraint::new(
@@ -142,13 +155,30 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle> for MouseConst
                     limits,
                     rhs,
                     N::zero(),
-                    0,
+                    i,
                 ));
             }
         }
+
+        unit_constraint::linear_basis_forces(&mut self.unit_forces);
+
+        self.bilateral_ground_rng = first_bilateral_ground..constraints.bilateral_ground.len();
+        self.bilateral_rng = first_bilateral..constraints.bilateral.len();
     }
 
-    fn cache_impulses(&mut self, _: &LinearConstraints<N, usize>, _: N) {}
+    fn cache_impulses(&mut self, constraints: &LinearConstraints<N, usize>, inv_dt: N) {
+        self.reaction = unit_constraint::reaction_force(
+            &self.unit_forces,
+            constraints,
+            self.bilateral_ground_rng.clone(),
+            self.bilateral_rng.clone(),
+            inv_dt,
+        );
+    }
+
+    fn reaction_force(&self) -> Force<N> {
+        self.reaction
+    }
 }
 
 impl<N: RealField, Handle: BodyHandle> NonlinearConstraintGenerator<N, Handle>
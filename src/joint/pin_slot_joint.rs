@@ -9,6 +9,7 @@ use crate::solver::{ConstraintSet, GenericNonlinearConstraint, IntegrationParame
 ///
 /// Both are not required to be along the same direction.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct PinSlotJoint<N: RealField> {
     prism: PrismaticJoint<N>,
     revo: RevoluteJoint<N>,
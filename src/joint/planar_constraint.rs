@@ -1,18 +1,20 @@
 use na::{DVector, RealField, Unit};
 use std::ops::Range;
 
-use crate::joint::JointConstraint;
-use crate::math::{AngularVector, Point};
+use crate::joint::{unit_constraint, JointConstraint, JointMotor};
+use crate::math::{AngularVector, Force, Isometry, Point, Vector};
 use crate::object::{BodyHandle, BodyPartHandle, BodySet};
 use crate::solver::helper;
 use crate::solver::{
-    GenericNonlinearConstraint, IntegrationParameters, LinearConstraints,
+    ForceDirection, GenericNonlinearConstraint, IntegrationParameters, LinearConstraints,
     NonlinearConstraintGenerator,
 };
 
 /// A constraint that removes one relative translational degree of freedom, and all but one rotational degrees of freedom.
 ///
-/// This ensures a body moves only on a plane wrt. its parent.
+/// This ensures a body moves only on a plane wrt. its parent. The translations on this plane are
+/// measured along the two axii returned by `.in_plane_axii()`.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct PlanarConstraint<N: RealField, Handle: BodyHandle> {
     b1: BodyPartHandle<Handle>,
     b2: BodyPartHandle<Handle>,
@@ -20,19 +22,37 @@ pub struct PlanarConstraint<N: RealField, Handle: BodyHandle> {
     anchor2: Point<N>,
     axis1: Unit<AngularVector<N>>,
     axis2: Unit<AngularVector<N>>,
+    in_plane_axii: [Unit<Vector<N>>; 2],
+    ref1: Unit<AngularVector<N>>,
+    ref2: Unit<AngularVector<N>>,
     lin_impulse: N,
     ang_impulses: [N; 2],
+    lin_limit_impulses: [N; 2],
+    ang_limit_impulse: N,
+    lin_motor_impulses: [N; 2],
+    ang_motor_impulse: N,
     break_torque_squared: N,
     break_force_squared: N,
     broken: bool,
     bilateral_ground_rng: Range<usize>,
     bilateral_rng: Range<usize>,
+    unilateral_ground_rng: Range<usize>,
+    unilateral_rng: Range<usize>,
+    unit_forces: Vec<Force<N>>,
+    reaction: Force<N>,
+    min_offsets: [Option<N>; 2],
+    max_offsets: [Option<N>; 2],
+    min_angle: Option<N>,
+    max_angle: Option<N>,
+    lin_motors: [JointMotor<N, N>; 2],
+    ang_motor: JointMotor<N, N>,
 }
 
 impl<N: RealField, Handle: BodyHandle> PlanarConstraint<N, Handle> {
     /// Create a new planar constraint which ensures the two provided axii always coincide.
     ///
     /// All anchros and axii are expressed in their corresponding body part local coordinate frame.
+    /// The rotation angle is zero when both body parts have the same orientation and `axis1 == axis2`.
     pub fn new(
         b1: BodyPartHandle<Handle>,
         b2: BodyPartHandle<Handle>,
@@ -41,6 +61,9 @@ impl<N: RealField, Handle: BodyHandle> PlanarConstraint<N, Handle> {
         anchor2: Point<N>,
         axis2: Unit<AngularVector<N>>,
     ) -> Self {
+        let in_plane_axii = unit_constraint::orthonormal_basis(&axis1);
+        let (ref1, ref2) = unit_constraint::reference_directions(&axis1, &axis2);
+
         PlanarConstraint {
             b1,
             b2,
@@ -48,16 +71,40 @@ impl<N: RealField, Handle: BodyHandle> PlanarConstraint<N, Handle> {
             anchor2,
             axis1,
             axis2,
+            in_plane_axii,
+            ref1,
+            ref2,
             lin_impulse: N::zero(),
             ang_impulses: [N::zero(), N::zero()],
+            lin_limit_impulses: [N::zero(); 2],
+            ang_limit_impulse: N::zero(),
+            lin_motor_impulses: [N::zero(); 2],
+            ang_motor_impulse: N::zero(),
             break_force_squared: N::max_value(),
             break_torque_squared: N::max_value(),
             broken: false,
             bilateral_ground_rng: 0..0,
             bilateral_rng: 0..0,
+            unilateral_ground_rng: 0..0,
+            unilateral_rng: 0..0,
+            unit_forces: vec![Force::zero(); 9],
+            reaction: Force::zero(),
+            min_offsets: [None; 2],
+            max_offsets: [None; 2],
+            min_angle: None,
+            max_angle: None,
+            lin_motors: [JointMotor::new(); 2],
+            ang_motor: JointMotor::new(),
         }
     }
 
+    /// The two axii, orthogonal to `axis1`, along which the translations on the plane are measured.
+    ///
+    /// They are expressed in the local coordinate frame of the first body part.
+    pub fn in_plane_axii(&self) -> [Unit<Vector<N>>; 2] {
+        self.in_plane_axii
+    }
+
     /// The maximum force this joint can absorb before breaking.
     pub fn set_break_force(&mut self, break_force: N) {
         self.break_force_squared = break_force * break_force;
@@ -67,6 +114,353 @@ impl<N: RealField, Handle: BodyHandle> PlanarConstraint<N, Handle> {
     pub fn set_break_torque(&mut self, break_torque: N) {
         self.break_torque_squared = break_torque * break_torque;
     }
+
+    /// The lower limit of the offset along the first in-plane axis.
+    pub fn min_offset_1(&self) -> Option<N> {
+        self.min_offsets[0]
+    }
+
+    /// The upper limit of the offset along the first in-plane axis.
+    pub fn max_offset_1(&self) -> Option<N> {
+        self.max_offsets[0]
+    }
+
+    /// Disable the lower limit of the offset along the first in-plane axis.
+    pub fn disable_min_offset_1(&mut self) {
+        self.min_offsets[0] = None;
+    }
+
+    /// Disable the upper limit of the offset along the first in-plane axis.
+    pub fn disable_max_offset_1(&mut self) {
+        self.max_offsets[0] = None;
+    }
+
+    /// Enable and set the lower limit of the offset along the first in-plane axis.
+    pub fn enable_min_offset_1(&mut self, limit: N) {
+        self.min_offsets[0] = Some(limit);
+        self.assert_limits();
+    }
+
+    /// Enable and set the upper limit of the offset along the first in-plane axis.
+    pub fn enable_max_offset_1(&mut self, limit: N) {
+        self.max_offsets[0] = Some(limit);
+        self.assert_limits();
+    }
+
+    /// Return `true` if the linear motor along the first in-plane axis is enabled.
+    pub fn is_linear_motor_enabled_1(&self) -> bool {
+        self.lin_motors[0].enabled
+    }
+
+    /// Enable the linear motor along the first in-plane axis.
+    pub fn enable_linear_motor_1(&mut self) {
+        self.lin_motors[0].enabled = true
+    }
+
+    /// Disable the linear motor along the first in-plane axis.
+    pub fn disable_linear_motor_1(&mut self) {
+        self.lin_motors[0].enabled = false;
+    }
+
+    /// The desired linear velocity of the joint motor along the first in-plane axis.
+    pub fn desired_linear_motor_velocity_1(&self) -> N {
+        self.lin_motors[0].desired_velocity
+    }
+
+    /// Set the desired linear velocity of the joint motor along the first in-plane axis.
+    pub fn set_desired_linear_motor_velocity_1(&mut self, vel: N) {
+        self.lin_motors[0].desired_velocity = vel;
+    }
+
+    /// The max linear velocity that the joint motor along the first in-plane axis will attempt.
+    pub fn max_linear_motor_velocity_1(&self) -> N {
+        self.lin_motors[0].max_velocity
+    }
+
+    /// Set the maximum linear velocity that the joint motor along the first in-plane axis will attempt.
+    pub fn set_max_linear_motor_velocity_1(&mut self, max_vel: N) {
+        self.lin_motors[0].max_velocity = max_vel;
+    }
+
+    /// The maximum force that can be delivered by the joint motor along the first in-plane axis.
+    pub fn max_linear_motor_force_1(&self) -> N {
+        self.lin_motors[0].max_force
+    }
+
+    /// Set the maximum force that can be delivered by the joint motor along the first in-plane axis.
+    pub fn set_max_linear_motor_force_1(&mut self, force: N) {
+        self.lin_motors[0].max_force = force;
+    }
+
+    /// The offset the joint motor along the first in-plane axis attempts to reach if its stiffness is non-zero.
+    pub fn desired_linear_motor_position_1(&self) -> N {
+        self.lin_motors[0].desired_position
+    }
+
+    /// Set the offset the joint motor along the first in-plane axis attempts to reach if its stiffness is non-zero.
+    pub fn set_desired_linear_motor_position_1(&mut self, pos: N) {
+        self.lin_motors[0].desired_position = pos;
+    }
+
+    /// The stiffness of the joint motor along the first in-plane axis wrt. its desired position.
+    pub fn linear_motor_stiffness_1(&self) -> N {
+        self.lin_motors[0].stiffness
+    }
+
+    /// Set the stiffness of the joint motor along the first in-plane axis wrt. its desired position.
+    pub fn set_linear_motor_stiffness_1(&mut self, stiffness: N) {
+        self.lin_motors[0].stiffness = stiffness;
+    }
+
+    /// The damping of the joint motor along the first in-plane axis wrt. its desired velocity.
+    pub fn linear_motor_damping_1(&self) -> N {
+        self.lin_motors[0].damping
+    }
+
+    /// Set the damping of the joint motor along the first in-plane axis wrt. its desired velocity.
+    pub fn set_linear_motor_damping_1(&mut self, damping: N) {
+        self.lin_motors[0].damping = damping;
+    }
+
+    /// The lower limit of the offset along the second in-plane axis.
+    pub fn min_offset_2(&self) -> Option<N> {
+        self.min_offsets[1]
+    }
+
+    /// The upper limit of the offset along the second in-plane axis.
+    pub fn max_offset_2(&self) -> Option<N> {
+        self.max_offsets[1]
+    }
+
+    /// Disable the lower limit of the offset along the second in-plane axis.
+    pub fn disable_min_offset_2(&mut self) {
+        self.min_offsets[1] = None;
+    }
+
+    /// Disable the upper limit of the offset along the second in-plane axis.
+    pub fn disable_max_offset_2(&mut self) {
+        self.max_offsets[1] = None;
+    }
+
+    /// Enable and set the lower limit of the offset along the second in-plane axis.
+    pub fn enable_min_offset_2(&mut self, limit: N) {
+        self.min_offsets[1] = Some(limit);
+        self.assert_limits();
+    }
+
+    /// Enable and set the upper limit of the offset along the second in-plane axis.
+    pub fn enable_max_offset_2(&mut self, limit: N) {
+        self.max_offsets[1] = Some(limit);
+        self.assert_limits();
+    }
+
+    /// Return `true` if the linear motor along the second in-plane axis is enabled.
+    pub fn is_linear_motor_enabled_2(&self) -> bool {
+        self.lin_motors[1].enabled
+    }
+
+    /// Enable the linear motor along the second in-plane axis.
+    pub fn enable_linear_motor_2(&mut self) {
+        self.lin_motors[1].enabled = true
+    }
+
+    /// Disable the linear motor along the second in-plane axis.
+    pub fn disable_linear_motor_2(&mut self) {
+        self.lin_motors[1].enabled = false;
+    }
+
+    /// The desired linear velocity of the joint motor along the second in-plane axis.
+    pub fn desired_linear_motor_velocity_2(&self) -> N {
+        self.lin_motors[1].desired_velocity
+    }
+
+    /// Set the desired linear velocity of the joint motor along the second in-plane axis.
+    pub fn set_desired_linear_motor_velocity_2(&mut self, vel: N) {
+        self.lin_motors[1].desired_velocity = vel;
+    }
+
+    /// The max linear velocity that the joint motor along the second in-plane axis will attempt.
+    pub fn max_linear_motor_velocity_2(&self) -> N {
+        self.lin_motors[1].max_velocity
+    }
+
+    /// Set the maximum linear velocity that the joint motor along the second in-plane axis will attempt.
+    pub fn set_max_linear_motor_velocity_2(&mut self, max_vel: N) {
+        self.lin_motors[1].max_velocity = max_vel;
+    }
+
+    /// The maximum force that can be delivered by the joint motor along the second in-plane axis.
+    pub fn max_linear_motor_force_2(&self) -> N {
+        self.lin_motors[1].max_force
+    }
+
+    /// Set the maximum force that can be delivered by the joint motor along the second in-plane axis.
+    pub fn set_max_linear_motor_force_2(&mut self, force: N) {
+        self.lin_motors[1].max_force = force;
+    }
+
+    /// The offset the joint motor along the second in-plane axis attempts to reach if its stiffness is non-zero.
+    pub fn desired_linear_motor_position_2(&self) -> N {
+        self.lin_motors[1].desired_position
+    }
+
+    /// Set the offset the joint motor along the second in-plane axis attempts to reach if its stiffness is non-zero.
+    pub fn set_desired_linear_motor_position_2(&mut self, pos: N) {
+        self.lin_motors[1].desired_position = pos;
+    }
+
+    /// The stiffness of the joint motor along the second in-plane axis wrt. its desired position.
+    pub fn linear_motor_stiffness_2(&self) -> N {
+        self.lin_motors[1].stiffness
+    }
+
+    /// Set the stiffness of the joint motor along the second in-plane axis wrt. its desired position.
+    pub fn set_linear_motor_stiffness_2(&mut self, stiffness: N) {
+        self.lin_motors[1].stiffness = stiffness;
+    }
+
+    /// The damping of the joint motor along the second in-plane axis wrt. its desired velocity.
+    pub fn linear_motor_damping_2(&self) -> N {
+        self.lin_motors[1].damping
+    }
+
+    /// Set the damping of the joint motor along the second in-plane axis wrt. its desired velocity.
+    pub fn set_linear_motor_damping_2(&mut self, damping: N) {
+        self.lin_motors[1].damping = damping;
+    }
+
+    /// The lower limit of the rotation angle around the plane normal.
+    pub fn min_angle(&self) -> Option<N> {
+        self.min_angle
+    }
+
+    /// The upper limit of the rotation angle around the plane normal.
+    pub fn max_angle(&self) -> Option<N> {
+        self.max_angle
+    }
+
+    /// Disable the lower limit of the rotation angle around the plane normal.
+    pub fn disable_min_angle(&mut self) {
+        self.min_angle = None;
+    }
+
+    /// Disable the upper limit of the rotation angle around the plane normal.
+    pub fn disable_max_angle(&mut self) {
+        self.max_angle = None;
+    }
+
+    /// Enable and set the lower limit of the rotation angle around the plane normal.
+    pub fn enable_min_angle(&mut self, limit: N) {
+        self.min_angle = Some(limit);
+        self.assert_limits();
+    }
+
+    /// Enable and set the upper limit of the rotation angle around the plane normal.
+    pub fn enable_max_angle(&mut self, limit: N) {
+        self.max_angle = Some(limit);
+        self.assert_limits();
+    }
+
+    /// Return `true` if the angular motor around the plane normal is enabled.
+    pub fn is_angular_motor_enabled(&self) -> bool {
+        self.ang_motor.enabled
+    }
+
+    /// Enable the angular motor around the plane normal.
+    pub fn enable_angular_motor(&mut self) {
+        self.ang_motor.enabled = true
+    }
+
+    /// Disable the angular motor around the plane normal.
+    pub fn disable_angular_motor(&mut self) {
+        self.ang_motor.enabled = false;
+    }
+
+    /// The desired angular velocity of the joint motor around the plane normal.
+    pub fn desired_angular_motor_velocity(&self) -> N {
+        self.ang_motor.desired_velocity
+    }
+
+    /// Set the desired angular velocity of the joint motor around the plane normal.
+    pub fn set_desired_angular_motor_velocity(&mut self, vel: N) {
+        self.ang_motor.desired_velocity = vel;
+    }
+
+    /// The max angular velocity that the joint motor around the plane normal will attempt.
+    pub fn max_angular_motor_velocity(&self) -> N {
+        self.ang_motor.max_velocity
+    }
+
+    /// Set the maximum angular velocity that the joint motor around the plane normal will attempt.
+    pub fn set_max_angular_motor_velocity(&mut self, max_vel: N) {
+        self.ang_motor.max_velocity = max_vel;
+    }
+
+    /// The maximum torque that can be delivered by the joint motor around the plane normal.
+    pub fn max_angular_motor_torque(&self) -> N {
+        self.ang_motor.max_force
+    }
+
+    /// Set the maximum torque that can be delivered by the joint motor around the plane normal.
+    pub fn set_max_angular_motor_torque(&mut self, torque: N) {
+        self.ang_motor.max_force = torque;
+    }
+
+    /// The angle the joint motor around the plane normal attempts to reach if its stiffness is non-zero.
+    pub fn desired_angular_motor_position(&self) -> N {
+        self.ang_motor.desired_position
+    }
+
+    /// Set the angle the joint motor around the plane normal attempts to reach if its stiffness is non-zero.
+    pub fn set_desired_angular_motor_position(&mut self, pos: N) {
+        self.ang_motor.desired_position = pos;
+    }
+
+    /// The stiffness of the joint motor around the plane normal wrt. its desired position.
+    pub fn angular_motor_stiffness(&self) -> N {
+        self.ang_motor.stiffness
+    }
+
+    /// Set the stiffness of the joint motor around the plane normal wrt. its desired position.
+    pub fn set_angular_motor_stiffness(&mut self, stiffness: N) {
+        self.ang_motor.stiffness = stiffness;
+    }
+
+    /// The damping of the joint motor around the plane normal wrt. its desired velocity.
+    pub fn angular_motor_damping(&self) -> N {
+        self.ang_motor.damping
+    }
+
+    /// Set the damping of the joint motor around the plane normal wrt. its desired velocity.
+    pub fn set_angular_motor_damping(&mut self, damping: N) {
+        self.ang_motor.damping = damping;
+    }
+
+    fn assert_limits(&self) {
+        for i in 0..2 {
+            if let (Some(min_offset), Some(max_offset)) = (self.min_offsets[i], self.max_offsets[i])
+            {
+                assert!(
+                    min_offset <= max_offset,
+                    "PlanarConstraint limits: the min offset must be smaller than (or equal to) the max offset.");
+            }
+        }
+
+        if let (Some(min_angle), Some(max_angle)) = (self.min_angle, self.max_angle) {
+            assert!(
+                min_angle <= max_angle,
+                "PlanarConstraint limits: the min angle must be smaller than (or equal to) the max angle.");
+        }
+    }
+
+    /// The rotation angle of the second body part wrt. the first one around the plane normal.
+    fn angle(&self, pos1: &Isometry<N>, pos2: &Isometry<N>) -> N {
+        let axis1 = pos1 * self.axis1;
+        let ref1 = pos1 * self.ref1;
+        let ref2 = pos2 * self.ref2;
+
+        unit_constraint::angle_around_axis(&axis1, &ref1, &ref2)
+    }
 }
 
 impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle> for PlanarConstraint<N, Handle> {
@@ -74,8 +468,12 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle> for PlanarCons
         self.broken
     }
 
+    fn reaction_force(&self) -> Force<N> {
+        self.reaction
+    }
+
     fn num_velocity_constraints(&self) -> usize {
-        3
+        9
     }
 
     fn anchors(&self) -> (BodyPartHandle<Handle>, BodyPartHandle<Handle>) {
@@ -84,7 +482,7 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle> for PlanarCons
 
     fn velocity_constraints(
         &mut self,
-        _: &IntegrationParameters<N>,
+        parameters: &IntegrationParameters<N>,
         bodies: &dyn BodySet<N, Handle = Handle>,
         ext_vels: &DVector<N>,
         ground_j_id: &mut usize,
@@ -111,6 +509,8 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle> for PlanarCons
         let assembly_id1 = body1.companion_id();
         let assembly_id2 = body2.companion_id();
 
+        let first_unilateral_ground = constraints.unilateral_ground.len();
+        let first_unilateral = constraints.unilateral.len();
         let first_bilateral_ground = constraints.bilateral_ground.len();
         let first_bilateral = constraints.bilateral.len();
 
@@ -158,30 +558,179 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle> for PlanarCons
             constraints,
         );
 
+        self.unit_forces[0] = Force::linear(axis1.into_inner());
+        unit_constraint::orthogonal_angular_forces(&mut self.unit_forces[1..3], &axis1);
+
         /*
          *
-         * Limit constraints.
+         * Limit and motor constraints.
          *
          */
+        for i in 0..2 {
+            let axis = pos1 * self.in_plane_axii[i];
+
+            if let Some(dir) = unit_constraint::build_linear_limits_velocity_constraint(
+                body1,
+                part1,
+                self.b1,
+                body2,
+                part2,
+                self.b2,
+                assembly_id1,
+                assembly_id2,
+                &anchor1,
+                &anchor2,
+                &axis,
+                self.min_offsets[i],
+                self.max_offsets[i],
+                ext_vels,
+                self.lin_limit_impulses[i],
+                3 + i,
+                ground_j_id,
+                j_id,
+                jacobians,
+                constraints,
+            ) {
+                self.unit_forces[3 + i] = unit_constraint::unit_force(&dir);
+            }
+
+            if let Some(dir) = unit_constraint::build_motor_velocity_constraint(
+                parameters,
+                body1,
+                part1,
+                self.b1,
+                body2,
+                part2,
+                self.b2,
+                assembly_id1,
+                assembly_id2,
+                &anchor1,
+                &anchor2,
+                ForceDirection::Linear(axis),
+                &self.lin_motors[i],
+                axis.dot(&(anchor2 - anchor1)),
+                ext_vels,
+                self.lin_motor_impulses[i],
+                6 + i,
+                ground_j_id,
+                j_id,
+                jacobians,
+                constraints,
+            ) {
+                self.unit_forces[6 + i] = unit_constraint::unit_force(&dir);
+            }
+        }
+
+        let angle = self.angle(&pos1, &pos2);
+
+        if let Some(dir) = unit_constraint::build_angular_limits_velocity_constraint(
+            body1,
+            part1,
+            self.b1,
+            body2,
+            part2,
+            self.b2,
+            assembly_id1,
+            assembly_id2,
+            &anchor1,
+            &anchor2,
+            &axis1,
+            angle,
+            self.min_angle,
+            self.max_angle,
+            ext_vels,
+            self.ang_limit_impulse,
+            5,
+            ground_j_id,
+            j_id,
+            jacobians,
+            constraints,
+        ) {
+            self.unit_forces[5] = unit_constraint::unit_force(&dir);
+        }
+
+        if let Some(dir) = unit_constraint::build_motor_velocity_constraint(
+            parameters,
+            body1,
+            part1,
+            self.b1,
+            body2,
+            part2,
+            self.b2,
+            assembly_id1,
+            assembly_id2,
+            &anchor1,
+            &anchor2,
+            ForceDirection::Angular(axis1),
+            &self.ang_motor,
+            angle,
+            ext_vels,
+            self.ang_motor_impulse,
+            8,
+            ground_j_id,
+            j_id,
+            jacobians,
+            constraints,
+        ) {
+            self.unit_forces[8] = unit_constraint::unit_force(&dir);
+        }
 
         self.bilateral_ground_rng = first_bilateral_ground..constraints.bilateral_ground.len();
         self.bilateral_rng = first_bilateral..constraints.bilateral.len();
+        self.unilateral_ground_rng = first_unilateral_ground..constraints.unilateral_ground.len();
+        self.unilateral_rng = first_unilateral..constraints.unilateral.len();
     }
 
     fn cache_impulses(&mut self, constraints: &LinearConstraints<N, usize>, inv_dt: N) {
+        self.reaction = unit_constraint::reaction_force(
+            &self.unit_forces,
+            constraints,
+            self.bilateral_ground_rng.clone(),
+            self.bilateral_rng.clone(),
+            inv_dt,
+        ) + unit_constraint::limits_reaction_force(
+            &self.unit_forces,
+            constraints,
+            self.unilateral_ground_rng.clone(),
+            self.unilateral_rng.clone(),
+            inv_dt,
+        );
+
         for c in &constraints.bilateral_ground[self.bilateral_ground_rng.clone()] {
-            if c.impulse_id == 0 {
-                self.lin_impulse = c.impulse
-            } else {
-                self.ang_impulses[c.impulse_id - 1] = c.impulse;
+            match c.impulse_id {
+                0 => self.lin_impulse = c.impulse,
+                1 | 2 => self.ang_impulses[c.impulse_id - 1] = c.impulse,
+                3 | 4 => self.lin_limit_impulses[c.impulse_id - 3] = c.impulse,
+                5 => self.ang_limit_impulse = c.impulse,
+                6 | 7 => self.lin_motor_impulses[c.impulse_id - 6] = c.impulse,
+                _ => self.ang_motor_impulse = c.impulse,
             }
         }
 
         for c in &constraints.bilateral[self.bilateral_rng.clone()] {
-            if c.impulse_id == 0 {
-                self.lin_impulse = c.impulse
+            match c.impulse_id {
+                0 => self.lin_impulse = c.impulse,
+                1 | 2 => self.ang_impulses[c.impulse_id - 1] = c.impulse,
+                3 | 4 => self.lin_limit_impulses[c.impulse_id - 3] = c.impulse,
+                5 => self.ang_limit_impulse = c.impulse,
+                6 | 7 => self.lin_motor_impulses[c.impulse_id - 6] = c.impulse,
+                _ => self.ang_motor_impulse = c.impulse,
+            }
+        }
+
+        for c in &constraints.unilateral_ground[self.unilateral_ground_rng.clone()] {
+            if c.impulse_id == 5 {
+                self.ang_limit_impulse = c.impulse;
+            } else {
+                self.lin_limit_impulses[c.impulse_id - 3] = c.impulse;
+            }
+        }
+
+        for c in &constraints.unilateral[self.unilateral_rng.clone()] {
+            if c.impulse_id == 5 {
+                self.ang_limit_impulse = c.impulse;
             } else {
-                self.ang_impulses[c.impulse_id - 1] = c.impulse;
+                self.lin_limit_impulses[c.impulse_id - 3] = c.impulse;
             }
         }
 
@@ -203,7 +752,7 @@ impl<N: RealField, Handle: BodyHandle> NonlinearConstraintGenerator<N, Handle>
     fn num_position_constraints(&self, bodies: &dyn BodySet<N, Handle = Handle>) -> usize {
         // FIXME: calling this at each iteration of the non-linear resolution is costly.
         if self.is_active(bodies) {
-            2
+            5
         } else {
             0
         }
@@ -245,6 +794,46 @@ impl<N: RealField, Handle: BodyHandle> NonlinearConstraintGenerator<N, Handle>
             );
         }
 
+        if i == 2 || i == 3 {
+            let k = i - 2;
+            let axis = pos1 * self.in_plane_axii[k];
+
+            return unit_constraint::build_linear_limits_position_constraint(
+                parameters,
+                body1,
+                part1,
+                self.b1,
+                body2,
+                part2,
+                self.b2,
+                &anchor1,
+                &anchor2,
+                &axis,
+                self.min_offsets[k],
+                self.max_offsets[k],
+                jacobians,
+            );
+        }
+
+        if i == 4 {
+            return unit_constraint::build_angular_limits_position_constraint(
+                parameters,
+                body1,
+                part1,
+                self.b1,
+                body2,
+                part2,
+                self.b2,
+                &anchor1,
+                &anchor2,
+                &axis1,
+                self.angle(&pos1, &pos2),
+                self.min_angle,
+                self.max_angle,
+                jacobians,
+            );
+        }
+
         None
     }
 }
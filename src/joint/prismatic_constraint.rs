@@ -1,16 +1,17 @@
 use na::{DVector, RealField, Unit};
 use std::ops::Range;
 
-use crate::joint::{unit_constraint, JointConstraint};
-use crate::math::{AngularVector, Point, Vector, DIM, SPATIAL_DIM};
+use crate::joint::{unit_constraint, JointConstraint, JointMotor};
+use crate::math::{AngularVector, Force, Point, Vector, DIM, SPATIAL_DIM};
 use crate::object::{BodyHandle, BodyPartHandle, BodySet};
 use crate::solver::helper;
 use crate::solver::{
-    GenericNonlinearConstraint, IntegrationParameters, LinearConstraints,
+    ForceDirection, GenericNonlinearConstraint, IntegrationParameters, LinearConstraints,
     NonlinearConstraintGenerator,
 };
 
 /// A constraint that remove all be one translational degrees of freedom.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct PrismaticConstraint<N: RealField, Handle: BodyHandle> {
     b1: BodyPartHandle<Handle>,
     b2: BodyPartHandle<Handle>,
@@ -23,11 +24,17 @@ pub struct PrismaticConstraint<N: RealField, Handle: BodyHandle> {
     break_force_squared: N,
     broken: bool,
     limit_impulse: N,
+    motor_impulse: N,
     bilateral_ground_rng: Range<usize>,
     bilateral_rng: Range<usize>,
+    unilateral_ground_rng: Range<usize>,
+    unilateral_rng: Range<usize>,
+    unit_forces: Vec<Force<N>>,
+    reaction: Force<N>,
 
     min_offset: Option<N>,
     max_offset: Option<N>,
+    motor: JointMotor<N, N>,
 }
 
 impl<N: RealField, Handle: BodyHandle> PrismaticConstraint<N, Handle> {
@@ -56,10 +63,16 @@ impl<N: RealField, Handle: BodyHandle> PrismaticConstraint<N, Handle> {
             break_torque_squared: N::max_value(),
             broken: false,
             limit_impulse: N::zero(),
+            motor_impulse: N::zero(),
             bilateral_ground_rng: 0..0,
             bilateral_rng: 0..0,
+            unilateral_ground_rng: 0..0,
+            unilateral_rng: 0..0,
+            unit_forces: vec![Force::zero(); SPATIAL_DIM + 1],
+            reaction: Force::zero(),
             min_offset,
             max_offset,
+            motor: JointMotor::new(),
         }
     }
 
@@ -105,6 +118,81 @@ impl<N: RealField, Handle: BodyHandle> PrismaticConstraint<N, Handle> {
         self.assert_limits();
     }
 
+    /// Returns `true` if the joint motor is enabled.
+    pub fn is_linear_motor_enabled(&self) -> bool {
+        self.motor.enabled
+    }
+
+    /// Enable the joint motor.
+    pub fn enable_linear_motor(&mut self) {
+        self.motor.enabled = true
+    }
+
+    /// Disable the joint motor.
+    pub fn disable_linear_motor(&mut self) {
+        self.motor.enabled = false;
+    }
+
+    /// The desired relative velocity to be enforced by the joint motor.
+    pub fn desired_linear_motor_velocity(&self) -> N {
+        self.motor.desired_velocity
+    }
+
+    /// Set the desired relative velocity to be enforced by the joint motor.
+    pub fn set_desired_linear_motor_velocity(&mut self, vel: N) {
+        self.motor.desired_velocity = vel;
+    }
+
+    /// The maximum velocity this motor will attempt to reach.
+    pub fn max_linear_motor_velocity(&self) -> N {
+        self.motor.max_velocity
+    }
+
+    /// Set the maximum velocity this motor will attempt to reach.
+    pub fn set_max_linear_motor_velocity(&mut self, max_vel: N) {
+        self.motor.max_velocity = max_vel;
+    }
+
+    /// The maximum force that can be output by the joint motor.
+    pub fn max_linear_motor_force(&self) -> N {
+        self.motor.max_force
+    }
+
+    /// Set the maximum force that can be output by the joint motor.
+    pub fn set_max_linear_motor_force(&mut self, force: N) {
+        self.motor.max_force = force;
+    }
+
+    /// The offset the joint motor attempts to reach if its stiffness is non-zero.
+    pub fn desired_linear_motor_position(&self) -> N {
+        self.motor.desired_position
+    }
+
+    /// Set the offset the joint motor attempts to reach if its stiffness is non-zero.
+    pub fn set_desired_linear_motor_position(&mut self, pos: N) {
+        self.motor.desired_position = pos;
+    }
+
+    /// The stiffness of the joint motor wrt. its desired position.
+    pub fn linear_motor_stiffness(&self) -> N {
+        self.motor.stiffness
+    }
+
+    /// Set the stiffness of the joint motor wrt. its desired position.
+    pub fn set_linear_motor_stiffness(&mut self, stiffness: N) {
+        self.motor.stiffness = stiffness;
+    }
+
+    /// The damping of the joint motor wrt. its desired velocity.
+    pub fn linear_motor_damping(&self) -> N {
+        self.motor.damping
+    }
+
+    /// Set the damping of the joint motor wrt. its desired velocity.
+    pub fn set_linear_motor_damping(&mut self, damping: N) {
+        self.motor.damping = damping;
+    }
+
     fn assert_limits(&self) {
         if let (Some(min_offset), Some(max_offset)) = (self.min_offset, self.max_offset) {
             assert!(
@@ -121,6 +209,10 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle>
         self.broken
     }
 
+    fn reaction_force(&self) -> Force<N> {
+        self.reaction
+    }
+
     fn num_velocity_constraints(&self) -> usize {
         (SPATIAL_DIM - 1) + 2
     }
@@ -131,7 +223,7 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle>
 
     fn velocity_constraints(
         &mut self,
-        _: &IntegrationParameters<N>,
+        parameters: &IntegrationParameters<N>,
         bodies: &dyn BodySet<N, Handle = Handle>,
         ext_vels: &DVector<N>,
         ground_j_id: &mut usize,
@@ -158,6 +250,8 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle>
         let assembly_id1 = body1.companion_id();
         let assembly_id2 = body2.companion_id();
 
+        let first_unilateral_ground = constraints.unilateral_ground.len();
+        let first_unilateral = constraints.unilateral.len();
         let first_bilateral_ground = constraints.bilateral_ground.len();
         let first_bilateral = constraints.bilateral.len();
 
@@ -204,12 +298,15 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle>
             constraints,
         );
 
+        unit_constraint::orthogonal_linear_forces(&mut self.unit_forces[..DIM - 1], &axis);
+        unit_constraint::angular_basis_forces(&mut self.unit_forces[DIM - 1..SPATIAL_DIM - 1]);
+
         /*
          *
          * Limit constraints.
          *
          */
-        unit_constraint::build_linear_limits_velocity_constraint(
+        if let Some(dir) = unit_constraint::build_linear_limits_velocity_constraint(
             body1,
             part1,
             self.b1,
@@ -230,20 +327,66 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle>
             j_id,
             jacobians,
             constraints,
-        );
+        ) {
+            self.unit_forces[SPATIAL_DIM - 1] = unit_constraint::unit_force(&dir);
+        }
+
+        if let Some(dir) = unit_constraint::build_motor_velocity_constraint(
+            parameters,
+            body1,
+            part1,
+            self.b1,
+            body2,
+            part2,
+            self.b2,
+            assembly_id1,
+            assembly_id2,
+            &anchor1,
+            &anchor2,
+            ForceDirection::Linear(axis),
+            &self.motor,
+            axis.dot(&(anchor2 - anchor1)),
+            ext_vels,
+            self.motor_impulse,
+            SPATIAL_DIM,
+            ground_j_id,
+            j_id,
+            jacobians,
+            constraints,
+        ) {
+            self.unit_forces[SPATIAL_DIM] = unit_constraint::unit_force(&dir);
+        }
 
         self.bilateral_ground_rng = first_bilateral_ground..constraints.bilateral_ground.len();
         self.bilateral_rng = first_bilateral..constraints.bilateral.len();
+        self.unilateral_ground_rng = first_unilateral_ground..constraints.unilateral_ground.len();
+        self.unilateral_rng = first_unilateral..constraints.unilateral.len();
     }
 
     fn cache_impulses(&mut self, constraints: &LinearConstraints<N, usize>, inv_dt: N) {
+        self.reaction = unit_constraint::reaction_force(
+            &self.unit_forces,
+            constraints,
+            self.bilateral_ground_rng.clone(),
+            self.bilateral_rng.clone(),
+            inv_dt,
+        ) + unit_constraint::limits_reaction_force(
+            &self.unit_forces,
+            constraints,
+            self.unilateral_ground_rng.clone(),
+            self.unilateral_rng.clone(),
+            inv_dt,
+        );
+
         for c in &constraints.bilateral_ground[self.bilateral_ground_rng.clone()] {
             if c.impulse_id < DIM - 1 {
                 self.lin_impulses[c.impulse_id] = c.impulse;
             } else if c.impulse_id < SPATIAL_DIM - 1 {
                 self.ang_impulses[c.impulse_id + 1 - DIM] = c.impulse;
-            } else {
+            } else if c.impulse_id == SPATIAL_DIM - 1 {
                 self.limit_impulse = c.impulse
+            } else {
+                self.motor_impulse = c.impulse
             }
         }
 
@@ -252,11 +395,21 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle>
                 self.lin_impulses[c.impulse_id] = c.impulse;
             } else if c.impulse_id < SPATIAL_DIM - 1 {
                 self.ang_impulses[c.impulse_id + 1 - DIM] = c.impulse;
-            } else {
+            } else if c.impulse_id == SPATIAL_DIM - 1 {
                 self.limit_impulse = c.impulse
+            } else {
+                self.motor_impulse = c.impulse
             }
         }
 
+        for c in &constraints.unilateral_ground[self.unilateral_ground_rng.clone()] {
+            self.limit_impulse = c.impulse;
+        }
+
+        for c in &constraints.unilateral[self.unilateral_rng.clone()] {
+            self.limit_impulse = c.impulse;
+        }
+
         let inv_dt2 = inv_dt * inv_dt;
 
         if self.lin_impulses.norm_squared() * inv_dt2 > self.break_force_squared
@@ -9,6 +9,7 @@ use crate::solver::{ConstraintSet, GenericNonlinearConstraint, IntegrationParame
 
 /// A unit joint that allows only one translational degree on freedom.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct PrismaticJoint<N: RealField> {
     axis: Unit<Vector<N>>,
     jacobian: Velocity<N>,
@@ -138,6 +139,36 @@ impl<N: RealField> PrismaticJoint<N> {
         self.motor.max_force = force;
     }
 
+    /// The offset the joint motor attempts to reach if its stiffness is non-zero.
+    pub fn desired_linear_motor_position(&self) -> N {
+        self.motor.desired_position
+    }
+
+    /// Set the offset the joint motor attempts to reach if its stiffness is non-zero.
+    pub fn set_desired_linear_motor_position(&mut self, pos: N) {
+        self.motor.desired_position = pos;
+    }
+
+    /// The stiffness of the joint motor wrt. its desired position.
+    pub fn linear_motor_stiffness(&self) -> N {
+        self.motor.stiffness
+    }
+
+    /// Set the stiffness of the joint motor wrt. its desired position.
+    pub fn set_linear_motor_stiffness(&mut self, stiffness: N) {
+        self.motor.stiffness = stiffness;
+    }
+
+    /// The damping of the joint motor wrt. its desired velocity.
+    pub fn linear_motor_damping(&self) -> N {
+        self.motor.damping
+    }
+
+    /// Set the damping of the joint motor wrt. its desired velocity.
+    pub fn set_linear_motor_damping(&mut self, damping: N) {
+        self.motor.damping = damping;
+    }
+
     fn assert_limits(&self) {
         if let (Some(min_offset), Some(max_offset)) = (self.min_offset, self.max_offset) {
             assert!(
@@ -295,7 +326,13 @@ macro_rules! prismatic_motor_limit_methods (
             desired_linear_motor_velocity,
             set_desired_linear_motor_velocity,
             max_linear_motor_force,
-            set_max_linear_motor_force);
+            set_max_linear_motor_force,
+            desired_linear_motor_position,
+            set_desired_linear_motor_position,
+            linear_motor_stiffness,
+            set_linear_motor_stiffness,
+            linear_motor_damping,
+            set_linear_motor_damping);
     }
 );
 
@@ -317,7 +354,13 @@ macro_rules! prismatic_motor_limit_methods_1 (
             desired_linear_motor_velocity_1,
             set_desired_linear_motor_velocity_1,
             max_linear_motor_force_1,
-            set_max_linear_motor_force_1);
+            set_max_linear_motor_force_1,
+            desired_linear_motor_position_1,
+            set_desired_linear_motor_position_1,
+            linear_motor_stiffness_1,
+            set_linear_motor_stiffness_1,
+            linear_motor_damping_1,
+            set_linear_motor_damping_1);
     }
 );
 
@@ -339,7 +382,13 @@ macro_rules! prismatic_motor_limit_methods_2 (
             desired_linear_motor_velocity_2,
             set_desired_linear_motor_velocity_2,
             max_linear_motor_force2,
-            set_max_linear_motor_force_2);
+            set_max_linear_motor_force_2,
+            desired_linear_motor_position_2,
+            set_desired_linear_motor_position_2,
+            linear_motor_stiffness_2,
+            set_linear_motor_stiffness_2,
+            linear_motor_damping_2,
+            set_linear_motor_damping_2);
     }
 );
 
@@ -358,7 +407,13 @@ macro_rules! _prismatic_motor_limit_methods (
      $desired_motor_velocity:     ident,
      $set_desired_motor_velocity: ident,
      $max_motor_force:           ident,
-     $set_max_motor_force:       ident
+     $set_max_motor_force:       ident,
+     $desired_motor_position:     ident,
+     $set_desired_motor_position: ident,
+     $motor_stiffness:            ident,
+     $set_motor_stiffness:        ident,
+     $motor_damping:              ident,
+     $set_motor_damping:          ident
      ) => {
         impl<N: RealField> $ty<N> {
             /// The lower limit of the relative translational displacement of the attached multibody links along the joint axis.
@@ -425,6 +480,36 @@ macro_rules! _prismatic_motor_limit_methods (
             pub fn $set_max_motor_force(&mut self, force: N) {
                 self.$prism.set_max_linear_motor_force(force)
             }
+
+            /// The offset the joint motor attempts to reach if its stiffness is non-zero.
+            pub fn $desired_motor_position(&self) -> N {
+                self.$prism.desired_linear_motor_position()
+            }
+
+            /// Set the offset the joint motor attempts to reach if its stiffness is non-zero.
+            pub fn $set_desired_motor_position(&mut self, pos: N) {
+                self.$prism.set_desired_linear_motor_position(pos)
+            }
+
+            /// The stiffness of the joint motor wrt. its desired position.
+            pub fn $motor_stiffness(&self) -> N {
+                self.$prism.linear_motor_stiffness()
+            }
+
+            /// Set the stiffness of the joint motor wrt. its desired position.
+            pub fn $set_motor_stiffness(&mut self, stiffness: N) {
+                self.$prism.set_linear_motor_stiffness(stiffness)
+            }
+
+            /// The damping of the joint motor wrt. its desired velocity.
+            pub fn $motor_damping(&self) -> N {
+                self.$prism.linear_motor_damping()
+            }
+
+            /// Set the damping of the joint motor wrt. its desired velocity.
+            pub fn $set_motor_damping(&mut self, damping: N) {
+                self.$prism.set_linear_motor_damping(damping)
+            }
         }
     }
 );
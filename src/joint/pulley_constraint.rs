@@ -0,0 +1,246 @@
+use na::{DVector, RealField, Unit};
+use std::ops::Range;
+
+use crate::joint::{unit_constraint, JointConstraint};
+use crate::math::{Force, Point, Vector};
+use crate::object::{BodyHandle, BodyPartHandle, BodySet};
+use crate::solver::{
+    CouplingTerm, ForceDirection, GenericNonlinearConstraint, IntegrationParameters,
+    LinearConstraints, NonlinearConstraintGenerator,
+};
+
+/// A constraint that couples the distances of two body parts to two fixed points, like a pulley.
+///
+/// Each body part hangs from its own fixed point of the world, the ground anchor, with a rope passing over
+/// the pulley. This ensures that `length1 + ratio * length2 = length` where `length1` (resp. `length2`)
+/// is the distance between the anchor of the first (resp. second) body part and its ground anchor.
+/// A ratio different from one simulates a block and tackle.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct PulleyConstraint<N: RealField, Handle: BodyHandle> {
+    b1: BodyPartHandle<Handle>,
+    b2: BodyPartHandle<Handle>,
+    anchor1: Point<N>,
+    anchor2: Point<N>,
+    ground_anchor1: Point<N>,
+    ground_anchor2: Point<N>,
+    ratio: N,
+    length: N,
+    impulse: N,
+    bilateral_ground_rng: Range<usize>,
+    bilateral_rng: Range<usize>,
+    unit_forces: Vec<Force<N>>,
+    reaction: Force<N>,
+}
+
+impl<N: RealField, Handle: BodyHandle> PulleyConstraint<N, Handle> {
+    /// Creates a pulley between the anchors `anchor1` and `anchor2`, hanging respectively from the
+    /// points `ground_anchor1` and `ground_anchor2`.
+    ///
+    /// The anchors are expressed in the local coordinate system of the corresponding body parts, and the
+    /// ground anchors are expressed in world-space.
+    pub fn new(
+        b1: BodyPartHandle<Handle>,
+        b2: BodyPartHandle<Handle>,
+        anchor1: Point<N>,
+        anchor2: Point<N>,
+        ground_anchor1: Point<N>,
+        ground_anchor2: Point<N>,
+        ratio: N,
+        length: N,
+    ) -> Self {
+        PulleyConstraint {
+            b1,
+            b2,
+            anchor1,
+            anchor2,
+            ground_anchor1,
+            ground_anchor2,
+            ratio,
+            length,
+            impulse: N::zero(),
+            bilateral_ground_rng: 0..0,
+            bilateral_rng: 0..0,
+            unit_forces: vec![Force::zero(); 1],
+            reaction: Force::zero(),
+        }
+    }
+
+    /// The ratio by which the length of the second side of the pulley is multiplied.
+    pub fn ratio(&self) -> N {
+        self.ratio
+    }
+
+    /// Sets the ratio by which the length of the second side of the pulley is multiplied.
+    pub fn set_ratio(&mut self, ratio: N) {
+        self.ratio = ratio
+    }
+
+    /// The total length `length1 + ratio * length2` maintained by this constraint.
+    pub fn length(&self) -> N {
+        self.length
+    }
+
+    /// Sets the total length `length1 + ratio * length2` maintained by this constraint.
+    pub fn set_length(&mut self, length: N) {
+        self.length = length
+    }
+
+    /// The world-space fixed point the first body part hangs from.
+    pub fn ground_anchor_1(&self) -> &Point<N> {
+        &self.ground_anchor1
+    }
+
+    /// The world-space fixed point the second body part hangs from.
+    pub fn ground_anchor_2(&self) -> &Point<N> {
+        &self.ground_anchor2
+    }
+
+    /// Sets the world-space fixed point the first body part hangs from.
+    pub fn set_ground_anchor_1(&mut self, ground_anchor1: Point<N>) {
+        self.ground_anchor1 = ground_anchor1
+    }
+
+    /// Sets the world-space fixed point the second body part hangs from.
+    pub fn set_ground_anchor_2(&mut self, ground_anchor2: Point<N>) {
+        self.ground_anchor2 = ground_anchor2
+    }
+
+    /// The direction from a ground anchor to the corresponding anchor, and the distance between them.
+    fn side(ground_anchor: &Point<N>, anchor: &Point<N>) -> (ForceDirection<N>, N) {
+        let (dir, length) = Unit::try_new_and_get(anchor - ground_anchor, N::default_epsilon())
+            .unwrap_or((Vector::y_axis(), N::zero()));
+        (ForceDirection::Linear(dir), length)
+    }
+}
+
+impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle> for PulleyConstraint<N, Handle> {
+    fn reaction_force(&self) -> Force<N> {
+        self.reaction
+    }
+
+    fn num_velocity_constraints(&self) -> usize {
+        1
+    }
+
+    fn anchors(&self) -> (BodyPartHandle<Handle>, BodyPartHandle<Handle>) {
+        (self.b1, self.b2)
+    }
+
+    fn velocity_constraints(
+        &mut self,
+        _: &IntegrationParameters<N>,
+        bodies: &dyn BodySet<N, Handle = Handle>,
+        ext_vels: &DVector<N>,
+        ground_j_id: &mut usize,
+        j_id: &mut usize,
+        jacobians: &mut [N],
+        constraints: &mut LinearConstraints<N, usize>,
+    ) {
+        let body1 = try_ret!(bodies.get(self.b1.0));
+        let body2 = try_ret!(bodies.get(self.b2.0));
+        let part1 = try_ret!(body1.part(self.b1.1));
+        let part2 = try_ret!(body2.part(self.b2.1));
+
+        let anchor1 = body1.world_point_at_material_point(part1, &self.anchor1);
+        let anchor2 = body2.world_point_at_material_point(part2, &self.anchor2);
+        let (dir1, _) = Self::side(&self.ground_anchor1, &anchor1);
+        let (dir2, _) = Self::side(&self.ground_anchor2, &anchor2);
+
+        let first_bilateral_ground = constraints.bilateral_ground.len();
+        let first_bilateral = constraints.bilateral.len();
+
+        unit_constraint::build_coupled_velocity_constraint(
+            body1,
+            part1,
+            self.b1,
+            body2,
+            part2,
+            self.b2,
+            body1.companion_id(),
+            body2.companion_id(),
+            &[CouplingTerm::new(anchor1, dir1, N::one())],
+            &[CouplingTerm::new(anchor2, dir2, self.ratio)],
+            ext_vels,
+            self.impulse,
+            0,
+            ground_j_id,
+            j_id,
+            jacobians,
+            constraints,
+        );
+
+        self.unit_forces[0] = -unit_constraint::unit_force(&dir2) * self.ratio;
+
+        self.bilateral_ground_rng = first_bilateral_ground..constraints.bilateral_ground.len();
+        self.bilateral_rng = first_bilateral..constraints.bilateral.len();
+    }
+
+    fn cache_impulses(&mut self, constraints: &LinearConstraints<N, usize>, inv_dt: N) {
+        self.reaction = unit_constraint::reaction_force(
+            &self.unit_forces,
+            constraints,
+            self.bilateral_ground_rng.clone(),
+            self.bilateral_rng.clone(),
+            inv_dt,
+        );
+
+        for c in &constraints.bilateral_ground[self.bilateral_ground_rng.clone()] {
+            self.impulse = c.impulse;
+        }
+
+        for c in &constraints.bilateral[self.bilateral_rng.clone()] {
+            self.impulse = c.impulse;
+        }
+    }
+}
+
+impl<N: RealField, Handle: BodyHandle> NonlinearConstraintGenerator<N, Handle>
+    for PulleyConstraint<N, Handle>
+{
+    fn num_position_constraints(&self, bodies: &dyn BodySet<N, Handle = Handle>) -> usize {
+        // FIXME: calling this at each iteration of the non-linear resolution is costly.
+        if self.is_active(bodies) {
+            1
+        } else {
+            0
+        }
+    }
+
+    fn position_constraint(
+        &self,
+        parameters: &IntegrationParameters<N>,
+        i: usize,
+        bodies: &mut dyn BodySet<N, Handle = Handle>,
+        jacobians: &mut [N],
+    ) -> Option<GenericNonlinearConstraint<N, Handle>> {
+        if i != 0 {
+            return None;
+        }
+
+        let body1 = bodies.get(self.b1.0)?;
+        let body2 = bodies.get(self.b2.0)?;
+        let part1 = body1.part(self.b1.1)?;
+        let part2 = body2.part(self.b2.1)?;
+
+        let anchor1 = body1.world_point_at_material_point(part1, &self.anchor1);
+        let anchor2 = body2.world_point_at_material_point(part2, &self.anchor2);
+        let (dir1, length1) = Self::side(&self.ground_anchor1, &anchor1);
+        let (dir2, length2) = Self::side(&self.ground_anchor2, &anchor2);
+        let error = length1 + self.ratio * length2 - self.length;
+
+        unit_constraint::build_coupled_position_constraint(
+            parameters,
+            body1,
+            part1,
+            self.b1,
+            body2,
+            part2,
+            self.b2,
+            &[CouplingTerm::new(anchor1, dir1, N::one())],
+            &[CouplingTerm::new(anchor2, dir2, self.ratio)],
+            false,
+            error,
+            jacobians,
+        )
+    }
+}
@@ -0,0 +1,289 @@
+use na::{DVector, RealField, Unit};
+use std::ops::Range;
+
+use crate::joint::{unit_constraint, JointConstraint};
+use crate::math::{AngularVector, Force, Point, Rotation, Vector};
+use crate::object::{BodyHandle, BodyPart, BodyPartHandle, BodySet};
+use crate::solver::{
+    CouplingTerm, ForceDirection, GenericNonlinearConstraint, IntegrationParameters,
+    LinearConstraints, NonlinearConstraintGenerator,
+};
+
+/// A constraint that couples the angular velocity of a body part with the linear velocity of another,
+/// like a rack and pinion.
+///
+/// This ensures that `v2 = ratio * ω1` where `ω1` is the angular velocity of the first body part (the
+/// pinion) around its axis, and `v2` is the linear velocity of the second body part (the rack) along its
+/// axis. Both velocities are measured relative to the rack: `ω1` is the angular velocity of the pinion
+/// relative to the rack, and `v2` the velocity of the rack relative to the center of mass of the pinion.
+/// For a pinion with radius `r`, the ratio is either `r` or `-r` depending on the side of the rack it is on.
+///
+/// This does not prevent any other relative motion: the pinion is typically attached by a revolute joint,
+/// and the rack by a prismatic joint. The error accumulated over time because of numerical errors is
+/// corrected at the position level.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct RackAndPinionConstraint<N: RealField, Handle: BodyHandle> {
+    b1: BodyPartHandle<Handle>,
+    b2: BodyPartHandle<Handle>,
+    #[cfg(feature = "dim3")]
+    axis1: Unit<AngularVector<N>>,
+    axis2: Unit<Vector<N>>,
+    ratio: N,
+    impulse: N,
+    bilateral_ground_rng: Range<usize>,
+    bilateral_rng: Range<usize>,
+    unit_forces: Vec<Force<N>>,
+    reaction: Force<N>,
+    error: N,
+    last_frame: Option<(Rotation<N>, Point<N>)>,
+}
+
+impl<N: RealField, Handle: BodyHandle> RackAndPinionConstraint<N, Handle> {
+    /// Creates a constraint between the rotation of the pinion `b1` around `axis1` and the translation of
+    /// the rack `b2` along `axis2`.
+    ///
+    /// Both axii are expressed in the local coordinate system of the corresponding body parts.
+    #[cfg(feature = "dim3")]
+    pub fn new(
+        b1: BodyPartHandle<Handle>,
+        b2: BodyPartHandle<Handle>,
+        axis1: Unit<AngularVector<N>>,
+        axis2: Unit<Vector<N>>,
+        ratio: N,
+    ) -> Self {
+        RackAndPinionConstraint {
+            b1,
+            b2,
+            axis1,
+            axis2,
+            ratio,
+            impulse: N::zero(),
+            bilateral_ground_rng: 0..0,
+            bilateral_rng: 0..0,
+            unit_forces: vec![Force::zero(); 1],
+            reaction: Force::zero(),
+            error: N::zero(),
+            last_frame: None,
+        }
+    }
+
+    /// Creates a constraint between the rotation of the pinion `b1` and the translation of the rack `b2`
+    /// along `axis2`.
+    ///
+    /// The axis is expressed in the local coordinate system of the rack.
+    #[cfg(feature = "dim2")]
+    pub fn new(
+        b1: BodyPartHandle<Handle>,
+        b2: BodyPartHandle<Handle>,
+        axis2: Unit<Vector<N>>,
+        ratio: N,
+    ) -> Self {
+        RackAndPinionConstraint {
+            b1,
+            b2,
+            axis2,
+            ratio,
+            impulse: N::zero(),
+            bilateral_ground_rng: 0..0,
+            bilateral_rng: 0..0,
+            unit_forces: vec![Force::zero(); 1],
+            reaction: Force::zero(),
+            error: N::zero(),
+            last_frame: None,
+        }
+    }
+
+    /// The ratio between the linear velocity of the rack and the angular velocity of the pinion.
+    pub fn ratio(&self) -> N {
+        self.ratio
+    }
+
+    /// Sets the ratio between the linear velocity of the rack and the angular velocity of the pinion.
+    ///
+    /// This resets the error accumulated so far.
+    pub fn set_ratio(&mut self, ratio: N) {
+        self.ratio = ratio;
+        self.error = N::zero();
+    }
+
+    /// The rotation axis of the pinion when its orientation is `rot`.
+    #[cfg(feature = "dim3")]
+    fn pinion_axis(&self, rot: &Rotation<N>) -> Unit<AngularVector<N>> {
+        rot * self.axis1
+    }
+
+    /// The rotation axis of the pinion when its orientation is `rot`.
+    #[cfg(feature = "dim2")]
+    fn pinion_axis(&self, _: &Rotation<N>) -> Unit<AngularVector<N>> {
+        AngularVector::x_axis()
+    }
+
+    /// The terms of the coupled velocity `(v2 - v1) . b2 - ratio * (ω1 - ω2) . a1` where `v1` (resp. `v2`) is
+    /// the velocity of the pinion (resp. rack) at the center of mass of the pinion, and `a1` (resp. `b2`) is the
+    /// world-space axis of the pinion (resp. rack).
+    fn coupling_terms(
+        &self,
+        part1: &dyn BodyPart<N>,
+        part2: &dyn BodyPart<N>,
+    ) -> (Vec<CouplingTerm<N>>, Vec<CouplingTerm<N>>) {
+        let center = part1.center_of_mass();
+        let axis1 = ForceDirection::Angular(self.pinion_axis(&part1.position().rotation));
+        let axis2 = ForceDirection::Linear(part2.position() * self.axis2);
+
+        (
+            vec![
+                CouplingTerm::new(center, axis2, -N::one()),
+                CouplingTerm::new(center, axis1, -self.ratio),
+            ],
+            vec![
+                CouplingTerm::new(center, axis2, N::one()),
+                CouplingTerm::new(center, axis1, self.ratio),
+            ],
+        )
+    }
+
+    /// The rotation of the pinion and the position of its center of mass, relative to the rack.
+    fn frame(part1: &dyn BodyPart<N>, part2: &dyn BodyPart<N>) -> (Rotation<N>, Point<N>) {
+        let inv_pos2 = part2.position().inverse();
+        (
+            inv_pos2.rotation * part1.position().rotation,
+            inv_pos2 * part1.center_of_mass(),
+        )
+    }
+
+    /// The variation of the error since the frame of the pinion was last recorded.
+    fn error_increment(&self, part1: &dyn BodyPart<N>, part2: &dyn BodyPart<N>) -> N {
+        let (last_rot, last_center) = match &self.last_frame {
+            Some(frame) => frame,
+            None => return N::zero(),
+        };
+
+        let (rot, center) = Self::frame(part1, part2);
+        let shift = -(center - last_center).dot(&self.axis2);
+        let angle =
+            unit_constraint::twist_angle(&(rot * last_rot.inverse()), &self.pinion_axis(&rot));
+
+        shift - self.ratio * angle
+    }
+}
+
+impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle>
+    for RackAndPinionConstraint<N, Handle>
+{
+    fn reaction_force(&self) -> Force<N> {
+        self.reaction
+    }
+
+    fn num_velocity_constraints(&self) -> usize {
+        1
+    }
+
+    fn anchors(&self) -> (BodyPartHandle<Handle>, BodyPartHandle<Handle>) {
+        (self.b1, self.b2)
+    }
+
+    fn velocity_constraints(
+        &mut self,
+        _: &IntegrationParameters<N>,
+        bodies: &dyn BodySet<N, Handle = Handle>,
+        ext_vels: &DVector<N>,
+        ground_j_id: &mut usize,
+        j_id: &mut usize,
+        jacobians: &mut [N],
+        constraints: &mut LinearConstraints<N, usize>,
+    ) {
+        let body1 = try_ret!(bodies.get(self.b1.0));
+        let body2 = try_ret!(bodies.get(self.b2.0));
+        let part1 = try_ret!(body1.part(self.b1.1));
+        let part2 = try_ret!(body2.part(self.b2.1));
+
+        self.error += self.error_increment(part1, part2);
+        self.last_frame = Some(Self::frame(part1, part2));
+
+        let axis2 = ForceDirection::Linear(part2.position() * self.axis2);
+        let (terms1, terms2) = self.coupling_terms(part1, part2);
+
+        let first_bilateral_ground = constraints.bilateral_ground.len();
+        let first_bilateral = constraints.bilateral.len();
+
+        unit_constraint::build_coupled_velocity_constraint(
+            body1,
+            part1,
+            self.b1,
+            body2,
+            part2,
+            self.b2,
+            body1.companion_id(),
+            body2.companion_id(),
+            &terms1,
+            &terms2,
+            ext_vels,
+            self.impulse,
+            0,
+            ground_j_id,
+            j_id,
+            jacobians,
+            constraints,
+        );
+
+        self.unit_forces[0] = -unit_constraint::unit_force(&axis2);
+
+        self.bilateral_ground_rng = first_bilateral_ground..constraints.bilateral_ground.len();
+        self.bilateral_rng = first_bilateral..constraints.bilateral.len();
+    }
+
+    fn cache_impulses(&mut self, constraints: &LinearConstraints<N, usize>, inv_dt: N) {
+        self.reaction = unit_constraint::reaction_force(
+            &self.unit_forces,
+            constraints,
+            self.bilateral_ground_rng.clone(),
+            self.bilateral_rng.clone(),
+            inv_dt,
+        );
+
+        for c in &constraints.bilateral_ground[self.bilateral_ground_rng.clone()] {
+            self.impulse = c.impulse;
+        }
+
+        for c in &constraints.bilateral[self.bilateral_rng.clone()] {
+            self.impulse = c.impulse;
+        }
+    }
+}
+
+impl<N: RealField, Handle: BodyHandle> NonlinearConstraintGenerator<N, Handle>
+    for RackAndPinionConstraint<N, Handle>
+{
+    fn num_position_constraints(&self, bodies: &dyn BodySet<N, Handle = Handle>) -> usize {
+        if self.is_active(bodies) {
+            1
+        } else {
+            0
+        }
+    }
+
+    fn position_constraint(
+        &self,
+        parameters: &IntegrationParameters<N>,
+        i: usize,
+        bodies: &mut dyn BodySet<N, Handle = Handle>,
+        jacobians: &mut [N],
+    ) -> Option<GenericNonlinearConstraint<N, Handle>> {
+        if i != 0 {
+            return None;
+        }
+
+        let body1 = bodies.get(self.b1.0)?;
+        let body2 = bodies.get(self.b2.0)?;
+        let part1 = body1.part(self.b1.1)?;
+        let part2 = body2.part(self.b2.1)?;
+
+        let error = self.error + self.error_increment(part1, part2);
+        let (terms1, terms2) = self.coupling_terms(part1, part2);
+
+        unit_constraint::build_coupled_position_constraint(
+            parameters, body1, part1, self.b1, body2, part2, self.b2, &terms1, &terms2, false,
+            error, jacobians,
+        )
+    }
+}
@@ -1,8 +1,8 @@
 use na::{DVector, RealField, Unit, Vector3};
 use std::ops::Range;
 
-use crate::joint::JointConstraint;
-use crate::math::{AngularVector, Point};
+use crate::joint::{unit_constraint, JointConstraint};
+use crate::math::{AngularVector, Force, Point};
 use crate::object::{BodyHandle, BodyPartHandle, BodySet};
 use crate::solver::helper;
 use crate::solver::{
@@ -11,6 +11,7 @@ use crate::solver::{
 };
 
 /// A constraint that remove all relative rotations and one relative translation between two body parts.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct RectangularConstraint<N: RealField, Handle: BodyHandle> {
     b1: BodyPartHandle<Handle>,
     b2: BodyPartHandle<Handle>,
@@ -24,6 +25,8 @@ pub struct RectangularConstraint<N: RealField, Handle: BodyHandle> {
     broken: bool,
     bilateral_ground_rng: Range<usize>,
     bilateral_rng: Range<usize>,
+    unit_forces: Vec<Force<N>>,
+    reaction: Force<N>,
 }
 
 impl<N: RealField, Handle: BodyHandle> RectangularConstraint<N, Handle> {
@@ -51,6 +54,8 @@ impl<N: RealField, Handle: BodyHandle> RectangularConstraint<N, Handle> {
             broken: false,
             bilateral_ground_rng: 0..0,
             bilateral_rng: 0..0,
+            unit_forces: vec![Force::zero(); 4],
+            reaction: Force::zero(),
         }
     }
 
@@ -72,6 +77,10 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle>
         self.broken
     }
 
+    fn reaction_force(&self) -> Force<N> {
+        self.reaction
+    }
+
     fn num_velocity_constraints(&self) -> usize {
         4
     }
@@ -155,6 +164,9 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle>
             constraints,
         );
 
+        self.unit_forces[0] = Force::linear(axis1.into_inner());
+        unit_constraint::angular_basis_forces(&mut self.unit_forces[1..]);
+
         /*
          *
          * Limit constraints.
@@ -166,6 +178,14 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle>
     }
 
     fn cache_impulses(&mut self, constraints: &LinearConstraints<N, usize>, inv_dt: N) {
+        self.reaction = unit_constraint::reaction_force(
+            &self.unit_forces,
+            constraints,
+            self.bilateral_ground_rng.clone(),
+            self.bilateral_rng.clone(),
+            inv_dt,
+        );
+
         for c in &constraints.bilateral_ground[self.bilateral_ground_rng.clone()] {
             if c.impulse_id == 0 {
                 self.lin_impulse = c.impulse
@@ -1,19 +1,18 @@
-#[cfg(feature = "dim3")]
-use na::Unit;
-use na::{DVector, RealField};
+use na::{DVector, RealField, Unit};
 use std::ops::Range;
 
-use crate::joint::JointConstraint;
-use crate::math::{AngularVector, Point, Vector, DIM, SPATIAL_DIM};
+use crate::joint::{unit_constraint, JointCompliance, JointConstraint, JointMotor};
+use crate::math::{AngularVector, Force, Isometry, Point, Vector, DIM, SPATIAL_DIM};
 use crate::object::{BodyHandle, BodyPartHandle, BodySet};
 use crate::solver::helper;
 use crate::solver::{
-    GenericNonlinearConstraint, IntegrationParameters, LinearConstraints,
+    ForceDirection, GenericNonlinearConstraint, IntegrationParameters, LinearConstraints,
     NonlinearConstraintGenerator,
 };
 
 /// A constraint that removes all relative motions except the rotation between two body parts.
 #[cfg(feature = "dim2")]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct RevoluteConstraint<N: RealField, Handle: BodyHandle> {
     b1: BodyPartHandle<Handle>,
     b2: BodyPartHandle<Handle>,
@@ -21,18 +20,27 @@ pub struct RevoluteConstraint<N: RealField, Handle: BodyHandle> {
     anchor2: Point<N>,
     lin_impulses: Vector<N>,
     ang_impulses: AngularVector<N>,
+    limit_impulse: N,
+    motor_impulse: N,
     break_force_squared: N,
     break_torque_squared: N,
     broken: bool,
     // FIXME: not actually needed in 2D.
     bilateral_ground_rng: Range<usize>,
     bilateral_rng: Range<usize>,
-    // min_angle: Option<N>,
-    // max_angle: Option<N>,
+    unilateral_ground_rng: Range<usize>,
+    unilateral_rng: Range<usize>,
+    unit_forces: Vec<Force<N>>,
+    reaction: Force<N>,
+    min_angle: Option<N>,
+    max_angle: Option<N>,
+    motor: JointMotor<N, N>,
+    linear_compliance: JointCompliance<N>,
 }
 
 /// A constraint that removes all relative motions except one rotation between two body parts.
 #[cfg(feature = "dim3")]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct RevoluteConstraint<N: RealField, Handle: BodyHandle> {
     b1: BodyPartHandle<Handle>,
     b2: BodyPartHandle<Handle>,
@@ -40,21 +48,33 @@ pub struct RevoluteConstraint<N: RealField, Handle: BodyHandle> {
     anchor2: Point<N>,
     axis1: Unit<AngularVector<N>>,
     axis2: Unit<AngularVector<N>>,
+    ref1: Unit<AngularVector<N>>,
+    ref2: Unit<AngularVector<N>>,
     lin_impulses: Vector<N>,
     ang_impulses: AngularVector<N>,
+    limit_impulse: N,
+    motor_impulse: N,
     break_force_squared: N,
     break_torque_squared: N,
     broken: bool,
     bilateral_ground_rng: Range<usize>,
     bilateral_rng: Range<usize>,
-    // min_angle: Option<N>,
-    // max_angle: Option<N>,
+    unilateral_ground_rng: Range<usize>,
+    unilateral_rng: Range<usize>,
+    unit_forces: Vec<Force<N>>,
+    reaction: Force<N>,
+    min_angle: Option<N>,
+    max_angle: Option<N>,
+    motor: JointMotor<N, N>,
+    linear_compliance: JointCompliance<N>,
+    angular_compliance: JointCompliance<N>,
 }
 
 impl<N: RealField, Handle: BodyHandle> RevoluteConstraint<N, Handle> {
     /// Create a new revolute constraint which ensures the provided axii and anchors always coincide.
     ///
     /// All axii and achors are expressed in the local coordinate system of the corresponding body parts.
+    /// The rotation angle is zero when both body parts have the same orientation and `axis1 == axis2`.
     #[cfg(feature = "dim3")]
     pub fn new(
         b1: BodyPartHandle<Handle>,
@@ -64,8 +84,8 @@ impl<N: RealField, Handle: BodyHandle> RevoluteConstraint<N, Handle> {
         anchor2: Point<N>,
         axis2: Unit<AngularVector<N>>,
     ) -> Self {
-        // let min_angle = None;
-        // let max_angle = None;
+        let (ref1, ref2) = unit_constraint::reference_directions(&axis1, &axis2);
+
         RevoluteConstraint {
             b1,
             b2,
@@ -73,21 +93,33 @@ impl<N: RealField, Handle: BodyHandle> RevoluteConstraint<N, Handle> {
             anchor2,
             axis1,
             axis2,
+            ref1,
+            ref2,
             lin_impulses: Vector::zeros(),
             ang_impulses: AngularVector::zeros(),
+            limit_impulse: N::zero(),
+            motor_impulse: N::zero(),
             break_force_squared: N::max_value(),
             break_torque_squared: N::max_value(),
             broken: false,
             bilateral_ground_rng: 0..0,
             bilateral_rng: 0..0,
-            // min_angle,
-            // max_angle,
+            unilateral_ground_rng: 0..0,
+            unilateral_rng: 0..0,
+            unit_forces: vec![Force::zero(); SPATIAL_DIM + 1],
+            reaction: Force::zero(),
+            min_angle: None,
+            max_angle: None,
+            motor: JointMotor::new(),
+            linear_compliance: JointCompliance::Rigid,
+            angular_compliance: JointCompliance::Rigid,
         }
     }
 
     /// Create a new revolute constraint which ensures the provided anchors always coincide.
     ///
     /// Both achors are expressed in the local coordinate system of the corresponding body parts.
+    /// The rotation angle is zero when both body parts have the same orientation.
     #[cfg(feature = "dim2")]
     pub fn new(
         b1: BodyPartHandle<Handle>,
@@ -95,9 +127,6 @@ impl<N: RealField, Handle: BodyHandle> RevoluteConstraint<N, Handle> {
         anchor1: Point<N>,
         anchor2: Point<N>,
     ) -> Self {
-        // let min_angle = None;
-        // let max_angle = None;
-
         RevoluteConstraint {
             b1,
             b2,
@@ -105,13 +134,21 @@ impl<N: RealField, Handle: BodyHandle> RevoluteConstraint<N, Handle> {
             anchor2,
             lin_impulses: Vector::zeros(),
             ang_impulses: AngularVector::zeros(),
+            limit_impulse: N::zero(),
+            motor_impulse: N::zero(),
             break_force_squared: N::max_value(),
             break_torque_squared: N::max_value(),
             broken: false,
             bilateral_ground_rng: 0..0,
             bilateral_rng: 0..0,
-            // min_angle,
-            // max_angle,
+            unilateral_ground_rng: 0..0,
+            unilateral_rng: 0..0,
+            unit_forces: vec![Force::zero(); SPATIAL_DIM + 1],
+            reaction: Force::zero(),
+            min_angle: None,
+            max_angle: None,
+            motor: JointMotor::new(),
+            linear_compliance: JointCompliance::Rigid,
         }
     }
 
@@ -125,39 +162,170 @@ impl<N: RealField, Handle: BodyHandle> RevoluteConstraint<N, Handle> {
         self.break_torque_squared = break_torque * break_torque;
     }
 
-    // pub fn min_angle(&self) -> Option<N> {
-    //     self.min_angle
-    // }
+    /// The compliance of the constraints removing the relative linear motion of the body parts.
+    pub fn linear_compliance(&self) -> &JointCompliance<N> {
+        &self.linear_compliance
+    }
+
+    /// Sets the compliance of the constraints removing the relative linear motion of the body parts.
+    pub fn set_linear_compliance(&mut self, compliance: JointCompliance<N>) {
+        self.linear_compliance = compliance
+    }
+
+    /// The compliance of the constraints keeping the rotation axii of the body parts aligned.
+    #[cfg(feature = "dim3")]
+    pub fn angular_compliance(&self) -> &JointCompliance<N> {
+        &self.angular_compliance
+    }
+
+    /// Sets the compliance of the constraints keeping the rotation axii of the body parts aligned.
+    #[cfg(feature = "dim3")]
+    pub fn set_angular_compliance(&mut self, compliance: JointCompliance<N>) {
+        self.angular_compliance = compliance
+    }
+
+    /// The lower limit of the rotation angle.
+    pub fn min_angle(&self) -> Option<N> {
+        self.min_angle
+    }
+
+    /// The upper limit of the rotation angle.
+    pub fn max_angle(&self) -> Option<N> {
+        self.max_angle
+    }
+
+    /// Disable the lower limit of the rotation angle.
+    pub fn disable_min_angle(&mut self) {
+        self.min_angle = None;
+    }
+
+    /// Disable the upper limit of the rotation angle.
+    pub fn disable_max_angle(&mut self) {
+        self.max_angle = None;
+    }
+
+    /// Enable and set the lower limit of the rotation angle.
+    pub fn enable_min_angle(&mut self, limit: N) {
+        self.min_angle = Some(limit);
+        self.assert_limits();
+    }
+
+    /// Enable and set the upper limit of the rotation angle.
+    pub fn enable_max_angle(&mut self, limit: N) {
+        self.max_angle = Some(limit);
+        self.assert_limits();
+    }
+
+    /// Return `true` if the angular motor of this joint is enabled.
+    pub fn is_angular_motor_enabled(&self) -> bool {
+        self.motor.enabled
+    }
+
+    /// Enable the angular motor of this joint.
+    pub fn enable_angular_motor(&mut self) {
+        self.motor.enabled = true
+    }
 
-    // pub fn max_angle(&self) -> Option<N> {
-    //     self.max_angle
-    // }
+    /// Disable the angular motor of this joint.
+    pub fn disable_angular_motor(&mut self) {
+        self.motor.enabled = false;
+    }
 
-    // pub fn disable_min_angle(&mut self) {
-    //     self.min_angle = None;
-    // }
+    /// The desired angular velocity of the joint motor.
+    pub fn desired_angular_motor_velocity(&self) -> N {
+        self.motor.desired_velocity
+    }
 
-    // pub fn disable_max_angle(&mut self) {
-    //     self.max_angle = None;
-    // }
+    /// Set the desired angular velocity of the joint motor.
+    pub fn set_desired_angular_motor_velocity(&mut self, vel: N) {
+        self.motor.desired_velocity = vel;
+    }
 
-    // pub fn enable_min_angle(&mut self, limit: N) {
-    //     self.min_angle = Some(limit);
-    //     self.assert_limits();
-    // }
+    /// The max angular velocity that the joint motor will attempt.
+    pub fn max_angular_motor_velocity(&self) -> N {
+        self.motor.max_velocity
+    }
 
-    // pub fn enable_max_angle(&mut self, limit: N) {
-    //     self.max_angle = Some(limit);
-    //     self.assert_limits();
-    // }
+    /// Set the maximum angular velocity that the joint motor will attempt.
+    pub fn set_max_angular_motor_velocity(&mut self, max_vel: N) {
+        self.motor.max_velocity = max_vel;
+    }
+
+    /// The maximum torque that can be delivered by the joint motor.
+    pub fn max_angular_motor_torque(&self) -> N {
+        self.motor.max_force
+    }
 
-    // fn assert_limits(&self) {
-    //     if let (Some(min_angle), Some(max_angle)) = (self.min_angle, self.max_angle) {
-    //         assert!(
-    //             min_angle <= max_angle,
-    //             "RevoluteJoint constraint limits: the min angle must be larger than (or equal to) the max angle.");
-    //     }
-    // }
+    /// Set the maximum torque that can be delivered by the joint motor.
+    pub fn set_max_angular_motor_torque(&mut self, torque: N) {
+        self.motor.max_force = torque;
+    }
+
+    /// The angle the joint motor attempts to reach if its stiffness is non-zero.
+    pub fn desired_angular_motor_position(&self) -> N {
+        self.motor.desired_position
+    }
+
+    /// Set the angle the joint motor attempts to reach if its stiffness is non-zero.
+    pub fn set_desired_angular_motor_position(&mut self, pos: N) {
+        self.motor.desired_position = pos;
+    }
+
+    /// The stiffness of the joint motor wrt. its desired position.
+    pub fn angular_motor_stiffness(&self) -> N {
+        self.motor.stiffness
+    }
+
+    /// Set the stiffness of the joint motor wrt. its desired position.
+    pub fn set_angular_motor_stiffness(&mut self, stiffness: N) {
+        self.motor.stiffness = stiffness;
+    }
+
+    /// The damping of the joint motor wrt. its desired velocity.
+    pub fn angular_motor_damping(&self) -> N {
+        self.motor.damping
+    }
+
+    /// Set the damping of the joint motor wrt. its desired velocity.
+    pub fn set_angular_motor_damping(&mut self, damping: N) {
+        self.motor.damping = damping;
+    }
+
+    fn assert_limits(&self) {
+        if let (Some(min_angle), Some(max_angle)) = (self.min_angle, self.max_angle) {
+            assert!(
+                min_angle <= max_angle,
+                "RevoluteConstraint limits: the min angle must be smaller than (or equal to) the max angle.");
+        }
+    }
+
+    /// The rotation axis and the rotation angle of the second body part wrt. the first one.
+    #[cfg(feature = "dim2")]
+    fn axis_and_angle(
+        &self,
+        pos1: &Isometry<N>,
+        pos2: &Isometry<N>,
+    ) -> (Unit<AngularVector<N>>, N) {
+        let angle = (pos1.rotation.inverse() * pos2.rotation).angle();
+        (AngularVector::x_axis(), angle)
+    }
+
+    /// The rotation axis and the rotation angle of the second body part wrt. the first one.
+    #[cfg(feature = "dim3")]
+    fn axis_and_angle(
+        &self,
+        pos1: &Isometry<N>,
+        pos2: &Isometry<N>,
+    ) -> (Unit<AngularVector<N>>, N) {
+        let axis1 = pos1 * self.axis1;
+        let ref1 = pos1 * self.ref1;
+        let ref2 = pos2 * self.ref2;
+
+        (
+            axis1,
+            unit_constraint::angle_around_axis(&axis1, &ref1, &ref2),
+        )
+    }
 }
 
 impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle>
@@ -167,8 +335,12 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle>
         self.broken
     }
 
+    fn reaction_force(&self) -> Force<N> {
+        self.reaction
+    }
+
     fn num_velocity_constraints(&self) -> usize {
-        SPATIAL_DIM - 1
+        (SPATIAL_DIM - 1) + 2
     }
 
     fn anchors(&self) -> (BodyPartHandle<Handle>, BodyPartHandle<Handle>) {
@@ -177,7 +349,7 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle>
 
     fn velocity_constraints(
         &mut self,
-        _: &IntegrationParameters<N>,
+        parameters: &IntegrationParameters<N>,
         bodies: &dyn BodySet<N, Handle = Handle>,
         ext_vels: &DVector<N>,
         ground_j_id: &mut usize,
@@ -204,6 +376,8 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle>
         let assembly_id1 = body1.companion_id();
         let assembly_id2 = body2.companion_id();
 
+        let first_unilateral_ground = constraints.unilateral_ground.len();
+        let first_unilateral = constraints.unilateral.len();
         let first_bilateral_ground = constraints.bilateral_ground.len();
         let first_bilateral = constraints.bilateral.len();
 
@@ -227,9 +401,24 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle>
             constraints,
         );
 
+        unit_constraint::soften_velocity_constraints(
+            parameters,
+            &self.linear_compliance,
+            (anchor1 - anchor2).as_slice(),
+            0,
+            first_bilateral_ground,
+            first_bilateral,
+            constraints,
+        );
+
+        unit_constraint::linear_basis_forces(&mut self.unit_forces[..DIM]);
+
         #[cfg(feature = "dim3")]
         {
             let axis1 = pos1 * self.axis1;
+            let axis2 = pos2 * self.axis2;
+            let first_ang_bilateral_ground = constraints.bilateral_ground.len();
+            let first_ang_bilateral = constraints.bilateral.len();
 
             helper::restrict_relative_angular_velocity_to_axis(
                 body1,
@@ -251,35 +440,145 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle>
                 jacobians,
                 constraints,
             );
+
+            // The misalignment of the axii along each direction constrained above.
+            let misalignment = axis2.cross(&axis1);
+            let mut errors = [N::zero(); 2];
+            let mut i = 0;
+            AngularVector::orthonormal_subspace_basis(&[axis1.into_inner()], |dir| {
+                errors[i] = misalignment.dot(dir);
+                i += 1;
+                true
+            });
+
+            unit_constraint::soften_velocity_constraints(
+                parameters,
+                &self.angular_compliance,
+                &errors,
+                DIM,
+                first_ang_bilateral_ground,
+                first_ang_bilateral,
+                constraints,
+            );
+
+            unit_constraint::orthogonal_angular_forces(
+                &mut self.unit_forces[DIM..SPATIAL_DIM - 1],
+                &axis1,
+            );
         }
 
         /*
          *
-         * Limit constraints.
+         * Limit and motor constraints.
          *
          */
+        let (axis, angle) = self.axis_and_angle(&pos1, &pos2);
+
+        if let Some(dir) = unit_constraint::build_angular_limits_velocity_constraint(
+            body1,
+            part1,
+            self.b1,
+            body2,
+            part2,
+            self.b2,
+            assembly_id1,
+            assembly_id2,
+            &anchor1,
+            &anchor2,
+            &axis,
+            angle,
+            self.min_angle,
+            self.max_angle,
+            ext_vels,
+            self.limit_impulse,
+            SPATIAL_DIM - 1,
+            ground_j_id,
+            j_id,
+            jacobians,
+            constraints,
+        ) {
+            self.unit_forces[SPATIAL_DIM - 1] = unit_constraint::unit_force(&dir);
+        }
+
+        if let Some(dir) = unit_constraint::build_motor_velocity_constraint(
+            parameters,
+            body1,
+            part1,
+            self.b1,
+            body2,
+            part2,
+            self.b2,
+            assembly_id1,
+            assembly_id2,
+            &anchor1,
+            &anchor2,
+            ForceDirection::Angular(axis),
+            &self.motor,
+            angle,
+            ext_vels,
+            self.motor_impulse,
+            SPATIAL_DIM,
+            ground_j_id,
+            j_id,
+            jacobians,
+            constraints,
+        ) {
+            self.unit_forces[SPATIAL_DIM] = unit_constraint::unit_force(&dir);
+        }
 
         self.bilateral_ground_rng = first_bilateral_ground..constraints.bilateral_ground.len();
         self.bilateral_rng = first_bilateral..constraints.bilateral.len();
+        self.unilateral_ground_rng = first_unilateral_ground..constraints.unilateral_ground.len();
+        self.unilateral_rng = first_unilateral..constraints.unilateral.len();
     }
 
     fn cache_impulses(&mut self, constraints: &LinearConstraints<N, usize>, inv_dt: N) {
+        self.reaction = unit_constraint::reaction_force(
+            &self.unit_forces,
+            constraints,
+            self.bilateral_ground_rng.clone(),
+            self.bilateral_rng.clone(),
+            inv_dt,
+        ) + unit_constraint::limits_reaction_force(
+            &self.unit_forces,
+            constraints,
+            self.unilateral_ground_rng.clone(),
+            self.unilateral_rng.clone(),
+            inv_dt,
+        );
+
         for c in &constraints.bilateral_ground[self.bilateral_ground_rng.clone()] {
             if c.impulse_id < DIM {
                 self.lin_impulses[c.impulse_id] = c.impulse;
-            } else {
+            } else if c.impulse_id < SPATIAL_DIM - 1 {
                 self.ang_impulses[c.impulse_id - DIM] = c.impulse;
+            } else if c.impulse_id == SPATIAL_DIM - 1 {
+                self.limit_impulse = c.impulse;
+            } else {
+                self.motor_impulse = c.impulse;
             }
         }
 
         for c in &constraints.bilateral[self.bilateral_rng.clone()] {
             if c.impulse_id < DIM {
                 self.lin_impulses[c.impulse_id] = c.impulse;
-            } else {
+            } else if c.impulse_id < SPATIAL_DIM - 1 {
                 self.ang_impulses[c.impulse_id - DIM] = c.impulse;
+            } else if c.impulse_id == SPATIAL_DIM - 1 {
+                self.limit_impulse = c.impulse;
+            } else {
+                self.motor_impulse = c.impulse;
             }
         }
 
+        for c in &constraints.unilateral_ground[self.unilateral_ground_rng.clone()] {
+            self.limit_impulse = c.impulse;
+        }
+
+        for c in &constraints.unilateral[self.unilateral_rng.clone()] {
+            self.limit_impulse = c.impulse;
+        }
+
         let inv_dt2 = inv_dt * inv_dt;
 
         if self.lin_impulses.norm_squared() * inv_dt2 > self.break_force_squared
@@ -296,10 +595,16 @@ impl<N: RealField, Handle: BodyHandle> NonlinearConstraintGenerator<N, Handle>
     fn num_position_constraints(&self, bodies: &dyn BodySet<N, Handle = Handle>) -> usize {
         // FIXME: calling this at each iteration of the non-linear resolution is costly.
         if self.is_active(bodies) {
+            let nlimits = if self.min_angle.is_some() || self.max_angle.is_some() {
+                1
+            } else {
+                0
+            };
+
             if DIM == 3 {
-                2
+                2 + nlimits
             } else {
-                1
+                1 + nlimits
             }
         } else {
             0
@@ -325,10 +630,16 @@ impl<N: RealField, Handle: BodyHandle> NonlinearConstraintGenerator<N, Handle>
         let anchor2 = Point::from(pos2.translation.vector);
 
         if i == 0 {
-            return helper::cancel_relative_translation(
+            let constraint = helper::cancel_relative_translation(
                 parameters, body1, part1, self.b1, body2, part2, self.b2, &anchor1, &anchor2,
                 jacobians,
             );
+
+            return unit_constraint::soften_position_constraint(
+                parameters,
+                &self.linear_compliance,
+                constraint,
+            );
         }
 
         #[cfg(feature = "dim3")]
@@ -337,13 +648,40 @@ impl<N: RealField, Handle: BodyHandle> NonlinearConstraintGenerator<N, Handle>
                 let axis1 = pos1 * self.axis1;
                 let axis2 = pos2 * self.axis2;
 
-                return helper::align_axis(
+                let constraint = helper::align_axis(
                     parameters, body1, part1, self.b1, body2, part2, self.b2, &anchor1, &anchor2,
                     &axis1, &axis2, jacobians,
                 );
+
+                return unit_constraint::soften_position_constraint(
+                    parameters,
+                    &self.angular_compliance,
+                    constraint,
+                );
             }
         }
 
+        if i == DIM - 1 {
+            let (axis, angle) = self.axis_and_angle(&pos1, &pos2);
+
+            return unit_constraint::build_angular_limits_position_constraint(
+                parameters,
+                body1,
+                part1,
+                self.b1,
+                body2,
+                part2,
+                self.b2,
+                &anchor1,
+                &anchor2,
+                &axis,
+                angle,
+                self.min_angle,
+                self.max_angle,
+                jacobians,
+            );
+        }
+
         None
     }
 }
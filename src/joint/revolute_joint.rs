@@ -12,6 +12,7 @@ use crate::utils::GeneralizedCross;
 
 /// A unit joint that allows only one relative rotational degree of freedom between two multibody links.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct RevoluteJoint<N: RealField> {
     axis: Unit<AngularVector<N>>,
     jacobian: Velocity<N>,
@@ -179,6 +180,36 @@ impl<N: RealField> RevoluteJoint<N> {
         self.motor.max_force = torque;
     }
 
+    /// The angle the joint motor attempts to reach if its stiffness is non-zero.
+    pub fn desired_angular_motor_position(&self) -> N {
+        self.motor.desired_position
+    }
+
+    /// Set the angle the joint motor attempts to reach if its stiffness is non-zero.
+    pub fn set_desired_angular_motor_position(&mut self, pos: N) {
+        self.motor.desired_position = pos;
+    }
+
+    /// The stiffness of the joint motor wrt. its desired position.
+    pub fn angular_motor_stiffness(&self) -> N {
+        self.motor.stiffness
+    }
+
+    /// Set the stiffness of the joint motor wrt. its desired position.
+    pub fn set_angular_motor_stiffness(&mut self, stiffness: N) {
+        self.motor.stiffness = stiffness;
+    }
+
+    /// The damping of the joint motor wrt. its desired velocity.
+    pub fn angular_motor_damping(&self) -> N {
+        self.motor.damping
+    }
+
+    /// Set the damping of the joint motor wrt. its desired velocity.
+    pub fn set_angular_motor_damping(&mut self, damping: N) {
+        self.motor.damping = damping;
+    }
+
     fn assert_limits(&self) {
         if let (Some(min_angle), Some(max_angle)) = (self.min_angle, self.max_angle) {
             assert!(
@@ -328,6 +359,10 @@ impl<N: RealField> UnitJoint<N> for RevoluteJoint<N> {
     fn max_position(&self) -> Option<N> {
         self.max_angle
     }
+
+    fn is_angular(&self) -> bool {
+        true
+    }
 }
 
 #[cfg(feature = "dim3")]
@@ -348,7 +383,13 @@ macro_rules! revolute_motor_limit_methods (
             desired_angular_motor_velocity,
             set_desired_angular_motor_velocity,
             max_angular_motor_torque,
-            set_max_angular_motor_torque);
+            set_max_angular_motor_torque,
+            desired_angular_motor_position,
+            set_desired_angular_motor_position,
+            angular_motor_stiffness,
+            set_angular_motor_stiffness,
+            angular_motor_damping,
+            set_angular_motor_damping);
     }
 );
 
@@ -370,7 +411,13 @@ macro_rules! revolute_motor_limit_methods_1 (
             desired_angular_motor_velocity_1,
             set_desired_angular_motor_velocity_1,
             max_angular_motor_torque_1,
-            set_max_angular_motor_torque_1);
+            set_max_angular_motor_torque_1,
+            desired_angular_motor_position_1,
+            set_desired_angular_motor_position_1,
+            angular_motor_stiffness_1,
+            set_angular_motor_stiffness_1,
+            angular_motor_damping_1,
+            set_angular_motor_damping_1);
     }
 );
 
@@ -392,7 +439,13 @@ macro_rules! revolute_motor_limit_methods_2 (
             desired_angular_motor_velocity_2,
             set_desired_angular_motor_velocity_2,
             max_angular_motor_torque_2,
-            set_max_angular_motor_torque_2);
+            set_max_angular_motor_torque_2,
+            desired_angular_motor_position_2,
+            set_desired_angular_motor_position_2,
+            angular_motor_stiffness_2,
+            set_angular_motor_stiffness_2,
+            angular_motor_damping_2,
+            set_angular_motor_damping_2);
     }
 );
 
@@ -411,7 +464,13 @@ macro_rules! _revolute_motor_limit_methods (
      $desired_motor_velocity:     ident,
      $set_desired_motor_velocity: ident,
      $max_motor_torque:           ident,
-     $set_max_motor_torque:       ident
+     $set_max_motor_torque:       ident,
+     $desired_motor_position:     ident,
+     $set_desired_motor_position: ident,
+     $motor_stiffness:            ident,
+     $set_motor_stiffness:        ident,
+     $motor_damping:              ident,
+     $set_motor_damping:          ident
      ) => {
         impl<N: RealField> $ty<N> {
             /// The lower limit of the rotation angle.
@@ -478,6 +537,36 @@ macro_rules! _revolute_motor_limit_methods (
             pub fn $set_max_motor_torque(&mut self, torque: N) {
                 self.$revo.set_max_angular_motor_torque(torque)
             }
+
+            /// The angle the joint motor attempts to reach if its stiffness is non-zero.
+            pub fn $desired_motor_position(&self) -> N {
+                self.$revo.desired_angular_motor_position()
+            }
+
+            /// Set the angle the joint motor attempts to reach if its stiffness is non-zero.
+            pub fn $set_desired_motor_position(&mut self, pos: N) {
+                self.$revo.set_desired_angular_motor_position(pos)
+            }
+
+            /// The stiffness of the joint motor wrt. its desired position.
+            pub fn $motor_stiffness(&self) -> N {
+                self.$revo.angular_motor_stiffness()
+            }
+
+            /// Set the stiffness of the joint motor wrt. its desired position.
+            pub fn $set_motor_stiffness(&mut self, stiffness: N) {
+                self.$revo.set_angular_motor_stiffness(stiffness)
+            }
+
+            /// The damping of the joint motor wrt. its desired velocity.
+            pub fn $motor_damping(&self) -> N {
+                self.$revo.angular_motor_damping()
+            }
+
+            /// Set the damping of the joint motor wrt. its desired velocity.
+            pub fn $set_motor_damping(&mut self, damping: N) {
+                self.$revo.set_angular_motor_damping(damping)
+            }
         }
     }
 );
@@ -1,10 +1,13 @@
 use na::{DVector, RealField, Unit};
+use std::ops::Range;
 
-use crate::math::{Point, Vector};
+use crate::joint::{JointCompliance, JointMotor};
+use crate::math::{AngularVector, Force, Point, Rotation, Vector};
 use crate::object::{Body, BodyHandle, BodyPart, BodyPartHandle};
 use crate::solver::{
-    helper, BilateralConstraint, BilateralGroundConstraint, ForceDirection,
+    helper, BilateralConstraint, BilateralGroundConstraint, CouplingTerm, ForceDirection,
     GenericNonlinearConstraint, ImpulseLimits, IntegrationParameters, LinearConstraints,
+    UnilateralConstraint, UnilateralGroundConstraint,
 };
 
 pub fn build_linear_limits_velocity_constraint<N: RealField, B: ?Sized + Body<N>, H: BodyHandle>(
@@ -28,56 +31,251 @@ pub fn build_linear_limits_velocity_constraint<N: RealField, B: ?Sized + Body<N>
     j_id: &mut usize,
     jacobians: &mut [N],
     constraints: &mut LinearConstraints<N, usize>,
-) {
+) -> Option<ForceDirection<N>> {
     let offset = axis.dot(&(anchor2 - anchor1));
+    let (dir, unilateral) = active_limit(offset, min, max, ForceDirection::Linear(*axis))?;
 
-    let (unilateral, dir) = match (min, max) {
-        (None, None) => {
-            return;
-        }
-        (Some(min), Some(max)) => {
-            if relative_eq!(min, max) {
-                (false, *axis)
-            } else {
-                if offset <= min {
-                    (true, -*axis)
-                } else if offset >= max {
-                    (true, *axis)
-                } else {
-                    return;
-                }
-            }
-        }
-        (Some(min), None) => {
-            if offset <= min {
-                (true, -*axis)
-            } else {
-                return;
-            }
-        }
-        (None, Some(max)) => {
-            if offset >= max {
-                (true, *axis)
-            } else {
-                return;
-            }
-        }
+    build_limit_velocity_constraint(
+        body1,
+        part1,
+        handle1,
+        body2,
+        part2,
+        handle2,
+        assembly_id1,
+        assembly_id2,
+        anchor1,
+        anchor2,
+        &dir,
+        unilateral,
+        ext_vels,
+        impulse,
+        impulse_id,
+        ground_j_id,
+        j_id,
+        jacobians,
+        constraints,
+    );
+
+    Some(dir)
+}
+
+pub fn build_linear_limits_position_constraint<N: RealField, B: ?Sized + Body<N>, H: BodyHandle>(
+    parameters: &IntegrationParameters<N>,
+    body1: &B,
+    part1: &dyn BodyPart<N>,
+    handle1: BodyPartHandle<H>,
+    body2: &B,
+    part2: &dyn BodyPart<N>,
+    handle2: BodyPartHandle<H>,
+    anchor1: &Point<N>,
+    anchor2: &Point<N>,
+    axis: &Unit<Vector<N>>,
+    min: Option<N>,
+    max: Option<N>,
+    jacobians: &mut [N],
+) -> Option<GenericNonlinearConstraint<N, H>> {
+    let offset = axis.dot(&(anchor2 - anchor1));
+    let (error, dir) = limit_error(offset, min, max, ForceDirection::Linear(*axis));
+
+    if error > parameters.allowed_linear_error {
+        Some(build_position_constraint(
+            body1, part1, handle1, body2, part2, handle2, anchor1, anchor2, &dir, false, error,
+            jacobians,
+        ))
+    } else {
+        None
+    }
+}
+
+/// Limits the `angle` of the rotation of the second body part relative to the first one around `axis`.
+pub fn build_angular_limits_velocity_constraint<
+    N: RealField,
+    B: ?Sized + Body<N>,
+    H: BodyHandle,
+>(
+    body1: &B,
+    part1: &dyn BodyPart<N>,
+    handle1: BodyPartHandle<H>,
+    body2: &B,
+    part2: &dyn BodyPart<N>,
+    handle2: BodyPartHandle<H>,
+    assembly_id1: usize,
+    assembly_id2: usize,
+    anchor1: &Point<N>,
+    anchor2: &Point<N>,
+    axis: &Unit<AngularVector<N>>,
+    angle: N,
+    min: Option<N>,
+    max: Option<N>,
+    ext_vels: &DVector<N>,
+    impulse: N,
+    impulse_id: usize,
+    ground_j_id: &mut usize,
+    j_id: &mut usize,
+    jacobians: &mut [N],
+    constraints: &mut LinearConstraints<N, usize>,
+) -> Option<ForceDirection<N>> {
+    let (dir, unilateral) = active_limit(angle, min, max, ForceDirection::Angular(*axis))?;
+
+    build_limit_velocity_constraint(
+        body1,
+        part1,
+        handle1,
+        body2,
+        part2,
+        handle2,
+        assembly_id1,
+        assembly_id2,
+        anchor1,
+        anchor2,
+        &dir,
+        unilateral,
+        ext_vels,
+        impulse,
+        impulse_id,
+        ground_j_id,
+        j_id,
+        jacobians,
+        constraints,
+    );
+
+    Some(dir)
+}
+
+pub fn build_angular_limits_position_constraint<
+    N: RealField,
+    B: ?Sized + Body<N>,
+    H: BodyHandle,
+>(
+    parameters: &IntegrationParameters<N>,
+    body1: &B,
+    part1: &dyn BodyPart<N>,
+    handle1: BodyPartHandle<H>,
+    body2: &B,
+    part2: &dyn BodyPart<N>,
+    handle2: BodyPartHandle<H>,
+    anchor1: &Point<N>,
+    anchor2: &Point<N>,
+    axis: &Unit<AngularVector<N>>,
+    angle: N,
+    min: Option<N>,
+    max: Option<N>,
+    jacobians: &mut [N],
+) -> Option<GenericNonlinearConstraint<N, H>> {
+    let (error, dir) = limit_error(angle, min, max, ForceDirection::Angular(*axis));
+
+    if error > parameters.allowed_angular_error {
+        Some(build_position_constraint(
+            body1, part1, handle1, body2, part2, handle2, anchor1, anchor2, &dir, true, error,
+            jacobians,
+        ))
+    } else {
+        None
+    }
+}
+
+/// Drives the relative velocity of the second body part wrt. the first one along `dir` toward
+/// the velocity target of the motor.
+///
+/// For angular directions, this is the relative angular velocity around the given axis. The
+/// `position` is the current joint coordinate the motor acts on.
+pub fn build_motor_velocity_constraint<N: RealField, B: ?Sized + Body<N>, H: BodyHandle>(
+    parameters: &IntegrationParameters<N>,
+    body1: &B,
+    part1: &dyn BodyPart<N>,
+    handle1: BodyPartHandle<H>,
+    body2: &B,
+    part2: &dyn BodyPart<N>,
+    handle2: BodyPartHandle<H>,
+    assembly_id1: usize,
+    assembly_id2: usize,
+    anchor1: &Point<N>,
+    anchor2: &Point<N>,
+    dir: ForceDirection<N>,
+    motor: &JointMotor<N, N>,
+    position: N,
+    ext_vels: &DVector<N>,
+    impulse: N,
+    impulse_id: usize,
+    ground_j_id: &mut usize,
+    j_id: &mut usize,
+    jacobians: &mut [N],
+    constraints: &mut LinearConstraints<N, usize>,
+) -> Option<ForceDirection<N>> {
+    if !motor.enabled {
+        return None;
+    }
+
+    let (velocity, cfm) = match dir {
+        ForceDirection::Linear(_) => motor.velocity_target(position, parameters.dt()),
+        ForceDirection::Angular(_) => motor.angular_velocity_target(position, parameters.dt()),
     };
 
+    // The constraint velocity is `dir.v1 - dir.v2` so we use the opposite direction to
+    // obtain the velocity of the second body part relative to the first one.
+    build_velocity_constraint(
+        body1,
+        part1,
+        handle1,
+        body2,
+        part2,
+        handle2,
+        assembly_id1,
+        assembly_id2,
+        anchor1,
+        anchor2,
+        &-dir,
+        velocity,
+        cfm,
+        motor.impulse_limits(),
+        ext_vels,
+        impulse,
+        impulse_id,
+        ground_j_id,
+        j_id,
+        jacobians,
+        constraints,
+    );
+
+    Some(-dir)
+}
+
+/// Cancels the coupled velocity of two body parts, i.e., the sum of the terms `terms1` evaluated on the first
+/// body part plus the sum of the terms `terms2` evaluated on the second one.
+///
+/// See `helper::coupled_constraint_geometry` for details.
+pub fn build_coupled_velocity_constraint<N: RealField, B: ?Sized + Body<N>, H: BodyHandle>(
+    body1: &B,
+    part1: &dyn BodyPart<N>,
+    handle1: BodyPartHandle<H>,
+    body2: &B,
+    part2: &dyn BodyPart<N>,
+    handle2: BodyPartHandle<H>,
+    assembly_id1: usize,
+    assembly_id2: usize,
+    terms1: &[CouplingTerm<N>],
+    terms2: &[CouplingTerm<N>],
+    ext_vels: &DVector<N>,
+    impulse: N,
+    impulse_id: usize,
+    ground_j_id: &mut usize,
+    j_id: &mut usize,
+    jacobians: &mut [N],
+    constraints: &mut LinearConstraints<N, usize>,
+) {
     let (ext_vels1, ext_vels2) =
         helper::split_ext_vels(body1, body2, assembly_id1, assembly_id2, ext_vels);
-    let force = ForceDirection::Linear(dir);
     let mut rhs = N::zero();
-    let geom = helper::constraint_pair_geometry(
+    let geom = helper::coupled_constraint_geometry(
         body1,
         part1,
         handle1,
         body2,
         part2,
         handle2,
-        anchor1,
-        anchor2,
-        &force,
+        terms1,
+        terms2,
         ground_j_id,
         j_id,
         jacobians,
@@ -86,17 +284,9 @@ pub fn build_linear_limits_velocity_constraint<N: RealField, B: ?Sized + Body<N>
         Some(&mut rhs),
     );
 
-    // FIXME: generate unilateral constraints for unilateral limits.
-    let limits = if unilateral {
-        ImpulseLimits::Independent {
-            min: N::zero(),
-            max: N::max_value(),
-        }
-    } else {
-        ImpulseLimits::Independent {
-            min: -N::max_value(),
-            max: N::max_value(),
-        }
+    let limits = ImpulseLimits::Independent {
+        min: -N::max_value(),
+        max: N::max_value(),
     };
 
     if geom.ndofs1 == 0 || geom.ndofs2 == 0 {
@@ -124,7 +314,11 @@ pub fn build_linear_limits_velocity_constraint<N: RealField, B: ?Sized + Body<N>
     }
 }
 
-pub fn build_linear_limits_position_constraint<N: RealField, B: ?Sized + Body<N>, H: BodyHandle>(
+/// Corrects the positional `error` of a coupling between two body parts.
+///
+/// The coupled velocity described by the terms `terms1` and `terms2` must be the time derivative of the
+/// quantity this error is measured on. See `build_coupled_velocity_constraint` for details.
+pub fn build_coupled_position_constraint<N: RealField, B: ?Sized + Body<N>, H: BodyHandle>(
     parameters: &IntegrationParameters<N>,
     body1: &B,
     part1: &dyn BodyPart<N>,
@@ -132,91 +326,581 @@ pub fn build_linear_limits_position_constraint<N: RealField, B: ?Sized + Body<N>
     body2: &B,
     part2: &dyn BodyPart<N>,
     handle2: BodyPartHandle<H>,
-    anchor1: &Point<N>,
-    anchor2: &Point<N>,
+    terms1: &[CouplingTerm<N>],
+    terms2: &[CouplingTerm<N>],
+    is_angular: bool,
+    error: N,
+    jacobians: &mut [N],
+) -> Option<GenericNonlinearConstraint<N, H>> {
+    let allowed_error = if is_angular {
+        parameters.allowed_angular_error
+    } else {
+        parameters.allowed_linear_error
+    };
+
+    if error.abs() <= allowed_error {
+        return None;
+    }
+
+    // The correction must decrease the error, so the jacobian is the opposite of its gradient.
+    let sign = if error > N::zero() {
+        -N::one()
+    } else {
+        N::one()
+    };
+    let mut j_id = 0;
+    let mut ground_j_id = 0;
+
+    let terms1: Vec<_> = terms1
+        .iter()
+        .map(|t| CouplingTerm::new(t.point, t.dir, t.coeff * sign))
+        .collect();
+    let terms2: Vec<_> = terms2
+        .iter()
+        .map(|t| CouplingTerm::new(t.point, t.dir, t.coeff * sign))
+        .collect();
+
+    let geom = helper::coupled_constraint_geometry(
+        body1,
+        part1,
+        handle1,
+        body2,
+        part2,
+        handle2,
+        &terms1,
+        &terms2,
+        &mut ground_j_id,
+        &mut j_id,
+        jacobians,
+        None,
+        None,
+        None,
+    );
+
+    Some(GenericNonlinearConstraint::new(
+        handle1,
+        Some(handle2),
+        is_angular,
+        geom.ndofs1,
+        geom.ndofs2,
+        geom.wj_id1,
+        geom.wj_id2,
+        -error.abs(),
+        geom.r,
+    ))
+}
+
+/// Makes compliant the velocity constraints added to `constraints` after the indices `first_bilateral_ground`
+/// and `first_bilateral`.
+///
+/// The positional error of the constraint with the impulse id `first_impulse_id + i` is `errors[i]`. This
+/// is the quantity which time derivative is the velocity of this constraint, and which is zero at rest.
+pub fn soften_velocity_constraints<N: RealField>(
+    parameters: &IntegrationParameters<N>,
+    compliance: &JointCompliance<N>,
+    errors: &[N],
+    first_impulse_id: usize,
+    first_bilateral_ground: usize,
+    first_bilateral: usize,
+    constraints: &mut LinearConstraints<N, usize>,
+) {
+    if compliance.is_rigid() {
+        return;
+    }
+
+    let dt = parameters.dt();
+
+    for c in &mut constraints.bilateral_ground[first_bilateral_ground..] {
+        let error = errors[c.impulse_id - first_impulse_id];
+
+        if let Some((velocity, cfm)) = compliance.velocity_target(error, c.r, dt) {
+            c.rhs -= velocity;
+            c.soften(cfm);
+        }
+    }
+
+    for c in &mut constraints.bilateral[first_bilateral..] {
+        let error = errors[c.impulse_id - first_impulse_id];
+
+        if let Some((velocity, cfm)) = compliance.velocity_target(error, c.r, dt) {
+            c.rhs -= velocity;
+            c.soften(cfm);
+        }
+    }
+}
+
+/// Makes compliant the position constraint `constraint`, so it applies a smaller correction.
+///
+/// This uses the same regularization as `soften_velocity_constraints` so the position correction
+/// does not pull the joint back to its rest configuration more rigidly than its compliance allows.
+pub fn soften_position_constraint<N: RealField, H: BodyHandle>(
+    parameters: &IntegrationParameters<N>,
+    compliance: &JointCompliance<N>,
+    constraint: Option<GenericNonlinearConstraint<N, H>>,
+) -> Option<GenericNonlinearConstraint<N, H>> {
+    let mut constraint = constraint?;
+
+    if !constraint.r.is_zero() {
+        if let Some(cfm) = compliance.cfm(constraint.r, parameters.dt()) {
+            constraint.r = N::one() / (N::one() / constraint.r + cfm);
+        }
+    }
+
+    Some(constraint)
+}
+
+/// The force applied on the first body part by a unit impulse along `dir`.
+pub fn unit_force<N: RealField>(dir: &ForceDirection<N>) -> Force<N> {
+    dir.at_point(&Point::origin())
+}
+
+/// Sets `forces` to the unit forces along the canonical basis of the linear space.
+pub fn linear_basis_forces<N: RealField>(forces: &mut [Force<N>]) {
+    for (i, force) in forces.iter_mut().enumerate() {
+        *force = Force::linear(Vector::ith(i, N::one()));
+    }
+}
+
+/// Sets `forces` to the unit torques along the canonical basis of the angular space.
+pub fn angular_basis_forces<N: RealField>(forces: &mut [Force<N>]) {
+    for (i, force) in forces.iter_mut().enumerate() {
+        *force = Force::torque_from_vector(AngularVector::ith(i, N::one()));
+    }
+}
+
+/// Sets `forces` to the unit forces along the directions orthogonal to `axis`.
+///
+/// The directions are enumerated in the same order as the constraints generated by
+/// `helper::restrict_relative_linear_velocity_to_axis`.
+pub fn orthogonal_linear_forces<N: RealField>(forces: &mut [Force<N>], axis: &Unit<Vector<N>>) {
+    let mut i = 0;
+
+    Vector::orthonormal_subspace_basis(&[axis.into_inner()], |e| {
+        forces[i] = Force::linear(*e);
+        i += 1;
+        true
+    });
+}
+
+/// Sets `forces` to the unit torques along the directions orthogonal to `axis`.
+///
+/// The directions are enumerated in the same order as the constraints generated by
+/// `helper::restrict_relative_angular_velocity_to_axis`.
+#[cfg(feature = "dim3")]
+pub fn orthogonal_angular_forces<N: RealField>(forces: &mut [Force<N>], axis: &Unit<Vector<N>>) {
+    let mut i = 0;
+
+    Vector::orthonormal_subspace_basis(&[axis.into_inner()], |e| {
+        forces[i] = Force::torque_from_vector(*e);
+        i += 1;
+        true
+    });
+}
+
+/// The force applied on the second body part by the velocity constraints in the given ranges.
+///
+/// The unit force applied on the first body part by each constraint is read from `unit_forces`,
+/// indexed by impulse id.
+pub fn reaction_force<N: RealField>(
+    unit_forces: &[Force<N>],
+    constraints: &LinearConstraints<N, usize>,
+    bilateral_ground_rng: Range<usize>,
+    bilateral_rng: Range<usize>,
+    inv_dt: N,
+) -> Force<N> {
+    let mut res = Force::zero();
+
+    for c in &constraints.bilateral_ground[bilateral_ground_rng] {
+        res -= unit_forces[c.impulse_id] * c.impulse;
+    }
+
+    for c in &constraints.bilateral[bilateral_rng] {
+        res -= unit_forces[c.impulse_id] * c.impulse;
+    }
+
+    res * inv_dt
+}
+
+/// The force applied on the second body part by the unilateral velocity constraints, i.e., the joint limits,
+/// in the given ranges.
+///
+/// This must be added to the `reaction_force` of the bilateral velocity constraints.
+pub fn limits_reaction_force<N: RealField>(
+    unit_forces: &[Force<N>],
+    constraints: &LinearConstraints<N, usize>,
+    unilateral_ground_rng: Range<usize>,
+    unilateral_rng: Range<usize>,
+    inv_dt: N,
+) -> Force<N> {
+    let mut res = Force::zero();
+
+    for c in &constraints.unilateral_ground[unilateral_ground_rng] {
+        res -= unit_forces[c.impulse_id] * c.impulse;
+    }
+
+    for c in &constraints.unilateral[unilateral_rng] {
+        res -= unit_forces[c.impulse_id] * c.impulse;
+    }
+
+    res * inv_dt
+}
+
+/// The signed angle of the rotation around `axis` that maps `from` to `to`.
+///
+/// Both vectors are projected on the plane orthogonal to `axis` first.
+#[cfg(feature = "dim3")]
+pub fn angle_around_axis<N: RealField>(
     axis: &Unit<Vector<N>>,
+    from: &Vector<N>,
+    to: &Vector<N>,
+) -> N {
+    let from = from - axis.into_inner() * axis.dot(from);
+    let to = to - axis.into_inner() * axis.dot(to);
+
+    axis.dot(&from.cross(&to)).atan2(from.dot(&to))
+}
+
+/// Computes two directions, orthogonal to `axis1` and `axis2` respectively, used as the origin of
+/// the angles measured around those axis.
+///
+/// The directions coincide when the two body parts have the same orientation and `axis1 == axis2`.
+#[cfg(feature = "dim3")]
+pub fn reference_directions<N: RealField>(
+    axis1: &Unit<Vector<N>>,
+    axis2: &Unit<Vector<N>>,
+) -> (Unit<Vector<N>>, Unit<Vector<N>>) {
+    let ref1 = orthonormal_basis(axis1)[0];
+    let rot = Rotation::rotation_between_axis(axis1, axis2)
+        .unwrap_or_else(|| Rotation::from_axis_angle(&ref1, N::pi()));
+
+    (ref1, rot * ref1)
+}
+
+/// Two unit vectors forming, together with `axis`, an orthonormal basis.
+#[cfg(feature = "dim3")]
+pub fn orthonormal_basis<N: RealField>(axis: &Unit<Vector<N>>) -> [Unit<Vector<N>>; 2] {
+    let mut basis = [Vector::x_axis(); 2];
+    let mut i = 0;
+
+    Vector::orthonormal_subspace_basis(&[axis.into_inner()], |e| {
+        basis[i] = Unit::new_unchecked(*e);
+        i += 1;
+        true
+    });
+
+    basis
+}
+
+/// Determines which limit, if any, is reached by `value`.
+///
+/// Returns the direction of the constraint to apply, and whether this constraint is unilateral.
+/// Equal lower and upper limits lock the joint coordinate with a bilateral constraint.
+fn active_limit<N: RealField>(
+    value: N,
     min: Option<N>,
     max: Option<N>,
-    jacobians: &mut [N],
-) -> Option<GenericNonlinearConstraint<N, H>> {
-    let offset = axis.dot(&(anchor2 - anchor1));
-    let mut error = N::zero();
-    let mut dir = *axis;
+    dir: ForceDirection<N>,
+) -> Option<(ForceDirection<N>, bool)> {
+    match (min, max) {
+        (None, None) => None,
+        (Some(min), Some(max)) => {
+            if relative_eq!(min, max) {
+                Some((dir, false))
+            } else if value <= min {
+                Some((-dir, true))
+            } else if value >= max {
+                Some((dir, true))
+            } else {
+                None
+            }
+        }
+        (Some(min), None) => {
+            if value <= min {
+                Some((-dir, true))
+            } else {
+                None
+            }
+        }
+        (None, Some(max)) => {
+            if value >= max {
+                Some((dir, true))
+            } else {
+                None
+            }
+        }
+    }
+}
 
+/// The amount by which `value` exceeds its limits, and the direction of the correction.
+fn limit_error<N: RealField>(
+    value: N,
+    min: Option<N>,
+    max: Option<N>,
+    dir: ForceDirection<N>,
+) -> (N, ForceDirection<N>) {
     if let Some(min) = min {
-        error = min - offset;
-        dir = -*axis;
+        if value < min {
+            return (min - value, -dir);
+        }
     }
 
-    if error < N::zero() {
-        if let Some(max) = max {
-            error = offset - max;
-            dir = *axis;
+    if let Some(max) = max {
+        if value > max {
+            return (value - max, dir);
         }
     }
 
-    if error > parameters.allowed_linear_error {
-        let mut j_id = 0;
-        let mut ground_j_id = 0;
+    (N::zero(), dir)
+}
+
+/// Prevents the relative velocity of the two body parts along `dir` from increasing the violation of
+/// a joint limit.
+///
+/// Unilateral limits only push the body parts apart. Otherwise, the relative velocity along `dir` is cancelled.
+fn build_limit_velocity_constraint<N: RealField, B: ?Sized + Body<N>, H: BodyHandle>(
+    body1: &B,
+    part1: &dyn BodyPart<N>,
+    handle1: BodyPartHandle<H>,
+    body2: &B,
+    part2: &dyn BodyPart<N>,
+    handle2: BodyPartHandle<H>,
+    assembly_id1: usize,
+    assembly_id2: usize,
+    anchor1: &Point<N>,
+    anchor2: &Point<N>,
+    dir: &ForceDirection<N>,
+    unilateral: bool,
+    ext_vels: &DVector<N>,
+    impulse: N,
+    impulse_id: usize,
+    ground_j_id: &mut usize,
+    j_id: &mut usize,
+    jacobians: &mut [N],
+    constraints: &mut LinearConstraints<N, usize>,
+) {
+    if !unilateral {
+        let limits = ImpulseLimits::Independent {
+            min: -N::max_value(),
+            max: N::max_value(),
+        };
 
-        let geom = helper::constraint_pair_geometry(
+        return build_velocity_constraint(
             body1,
             part1,
             handle1,
             body2,
             part2,
             handle2,
+            assembly_id1,
+            assembly_id2,
             anchor1,
             anchor2,
-            &ForceDirection::Linear(dir),
-            &mut ground_j_id,
-            &mut j_id,
+            dir,
+            N::zero(),
+            N::zero(),
+            limits,
+            ext_vels,
+            impulse,
+            impulse_id,
+            ground_j_id,
+            j_id,
             jacobians,
-            None,
-            None,
-            None,
+            constraints,
         );
+    }
 
-        let rhs = -error;
-        let constraint = GenericNonlinearConstraint::new(
-            handle1,
-            Some(handle2),
-            false,
-            geom.ndofs1,
-            geom.ndofs2,
-            geom.wj_id1,
-            geom.wj_id2,
-            rhs,
-            geom.r,
-        );
+    let (ext_vels1, ext_vels2) =
+        helper::split_ext_vels(body1, body2, assembly_id1, assembly_id2, ext_vels);
+    let mut rhs = N::zero();
+    let geom = helper::constraint_pair_geometry(
+        body1,
+        part1,
+        handle1,
+        body2,
+        part2,
+        handle2,
+        anchor1,
+        anchor2,
+        dir,
+        ground_j_id,
+        j_id,
+        jacobians,
+        Some(&ext_vels1),
+        Some(&ext_vels2),
+        Some(&mut rhs),
+    );
 
-        Some(constraint)
+    if geom.ndofs1 == 0 || geom.ndofs2 == 0 {
+        constraints
+            .unilateral_ground
+            .push(UnilateralGroundConstraint::new(
+                geom,
+                assembly_id1,
+                assembly_id2,
+                rhs,
+                impulse,
+                impulse_id,
+            ));
     } else {
-        None
+        constraints.unilateral.push(UnilateralConstraint::new(
+            geom,
+            assembly_id1,
+            assembly_id2,
+            rhs,
+            impulse,
+            impulse_id,
+        ));
     }
 }
 
-/*
-pub fn build_angular_limit_velocity_constraint<N: RealField>(
-    parameters: &IntegrationParameters<N>,
-    body1: &BodyPart<N>,
-    body2: &BodyPart<N>,
-    pos1: &Isometry<N>,
-    pos2: &Isometry<N>,
+fn build_velocity_constraint<N: RealField, B: ?Sized + Body<N>, H: BodyHandle>(
+    body1: &B,
+    part1: &dyn BodyPart<N>,
+    handle1: BodyPartHandle<H>,
+    body2: &B,
+    part2: &dyn BodyPart<N>,
+    handle2: BodyPartHandle<H>,
     assembly_id1: usize,
     assembly_id2: usize,
     anchor1: &Point<N>,
     anchor2: &Point<N>,
-    axis: &Unit<Vector<N>>,
-    min: Option<N>,
-    max: Option<N>,
+    dir: &ForceDirection<N>,
+    target_velocity: N,
+    cfm: N,
+    limits: ImpulseLimits<N>,
     ext_vels: &DVector<N>,
     impulse: N,
     impulse_id: usize,
     ground_j_id: &mut usize,
     j_id: &mut usize,
     jacobians: &mut [N],
-    constraints: &mut ConstraintSet<N, usize>,
+    constraints: &mut LinearConstraints<N, usize>,
 ) {
+    let (ext_vels1, ext_vels2) =
+        helper::split_ext_vels(body1, body2, assembly_id1, assembly_id2, ext_vels);
+    let mut rhs = -target_velocity;
+    let geom = helper::constraint_pair_geometry(
+        body1,
+        part1,
+        handle1,
+        body2,
+        part2,
+        handle2,
+        anchor1,
+        anchor2,
+        dir,
+        ground_j_id,
+        j_id,
+        jacobians,
+        Some(&ext_vels1),
+        Some(&ext_vels2),
+        Some(&mut rhs),
+    );
+
+    if geom.ndofs1 == 0 || geom.ndofs2 == 0 {
+        let mut constraint = BilateralGroundConstraint::new(
+            geom,
+            assembly_id1,
+            assembly_id2,
+            limits,
+            rhs,
+            impulse,
+            impulse_id,
+        );
+
+        if !cfm.is_zero() {
+            constraint.soften(cfm);
+        }
+
+        constraints.bilateral_ground.push(constraint);
+    } else {
+        let mut constraint = BilateralConstraint::new(
+            geom,
+            assembly_id1,
+            assembly_id2,
+            limits,
+            rhs,
+            impulse,
+            impulse_id,
+        );
+
+        if !cfm.is_zero() {
+            constraint.soften(cfm);
+        }
+
+        constraints.bilateral.push(constraint);
+    }
+}
+
+fn build_position_constraint<N: RealField, B: ?Sized + Body<N>, H: BodyHandle>(
+    body1: &B,
+    part1: &dyn BodyPart<N>,
+    handle1: BodyPartHandle<H>,
+    body2: &B,
+    part2: &dyn BodyPart<N>,
+    handle2: BodyPartHandle<H>,
+    anchor1: &Point<N>,
+    anchor2: &Point<N>,
+    dir: &ForceDirection<N>,
+    is_angular: bool,
+    error: N,
+    jacobians: &mut [N],
+) -> GenericNonlinearConstraint<N, H> {
+    let mut j_id = 0;
+    let mut ground_j_id = 0;
+
+    let geom = helper::constraint_pair_geometry(
+        body1,
+        part1,
+        handle1,
+        body2,
+        part2,
+        handle2,
+        anchor1,
+        anchor2,
+        dir,
+        &mut ground_j_id,
+        &mut j_id,
+        jacobians,
+        None,
+        None,
+        None,
+    );
+
+    let rhs = -error;
+    GenericNonlinearConstraint::new(
+        handle1,
+        Some(handle2),
+        is_angular,
+        geom.ndofs1,
+        geom.ndofs2,
+        geom.wj_id1,
+        geom.wj_id2,
+        rhs,
+        geom.r,
+    )
+}
+
+/// The angle, in `]-pi, pi]`, of the rotation `rot` around the unit `axis`.
+///
+/// The rotation is decomposed as a rotation around `axis` (the twist) followed by a rotation around
+/// an axis orthogonal to `axis`. Only the angle of the twist is returned.
+#[cfg(feature = "dim3")]
+pub fn twist_angle<N: RealField>(rot: &Rotation<N>, axis: &Unit<AngularVector<N>>) -> N {
+    let q = rot.quaternion();
+    let angle = N::atan2(q.imag().dot(axis), q.w) * na::convert(2.0);
+
+    if angle > N::pi() {
+        angle - N::two_pi()
+    } else if angle <= -N::pi() {
+        angle + N::two_pi()
+    } else {
+        angle
+    }
+}
+
+/// The angle, in `]-pi, pi]`, of the rotation `rot`.
+#[cfg(feature = "dim2")]
+pub fn twist_angle<N: RealField>(rot: &Rotation<N>, _: &Unit<AngularVector<N>>) -> N {
+    rot.angle()
 }
-*/
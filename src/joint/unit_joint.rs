@@ -19,6 +19,12 @@ pub trait UnitJoint<N: RealField>: Joint<N> {
     fn min_position(&self) -> Option<N>;
     /// The upper limit, if any, set to the generalized coordinate of this unit joint.
     fn max_position(&self) -> Option<N>;
+    /// Whether the generalized coordinate of this unit joint is an angle, e.g., for a revolute joint.
+    ///
+    /// The position target of the motor of an angular joint is reached through the shortest rotation.
+    fn is_angular(&self) -> bool {
+        false
+    }
 }
 
 impl_downcast!(UnitJoint<N> where N: RealField);
@@ -71,17 +77,20 @@ pub fn unit_joint_velocity_constraints<N: RealField, J: UnitJoint<N>>(
         multibody.inv_mass_mul_unit_joint_force(link, dof_id, N::one(), &mut jacobians[wj_id..]);
 
         let inv_r = jacobians[wj_id + link.assembly_id + dof_id]; // = J^t * M^-1 J
-        let velocity = motor
-            .desired_velocity
-            .clamp(-motor.max_velocity, motor.max_velocity);
+        let (velocity, cfm) = if joint.is_angular() {
+            motor.angular_velocity_target(joint.position(), parameters.dt())
+        } else {
+            motor.velocity_target(joint.position(), parameters.dt())
+        };
         let rhs = dvel - velocity;
         let limits = motor.impulse_limits();
         let impulse_id = link.impulse_id + dof_id * 3;
 
         let constraint = BilateralGroundConstraint {
             impulse: impulses[impulse_id] * parameters.warmstart_coeff,
-            r: N::one() / inv_r,
+            r: N::one() / (inv_r + cfm),
             rhs,
+            cfm,
             limits,
             impulse_id,
             assembly_id,
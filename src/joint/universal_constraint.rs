@@ -1,16 +1,17 @@
 use na::{DVector, RealField, Unit};
 use std::ops::Range;
 
-use crate::joint::JointConstraint;
-use crate::math::{AngularVector, Point, Vector, DIM};
+use crate::joint::{unit_constraint, JointConstraint, JointMotor};
+use crate::math::{AngularVector, Force, Isometry, Point, Vector, DIM};
 use crate::object::{BodyHandle, BodyPartHandle, BodySet};
 use crate::solver::helper;
 use crate::solver::{
-    GenericNonlinearConstraint, IntegrationParameters, LinearConstraints,
+    ForceDirection, GenericNonlinearConstraint, IntegrationParameters, LinearConstraints,
     NonlinearConstraintGenerator,
 };
 
 /// A constraint that removes all but two relative rotations along distinct axii.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct UniversalConstraint<N: RealField, Handle: BodyHandle> {
     b1: BodyPartHandle<Handle>,
     b2: BodyPartHandle<Handle>,
@@ -18,20 +19,32 @@ pub struct UniversalConstraint<N: RealField, Handle: BodyHandle> {
     anchor2: Point<N>,
     axis1: Unit<AngularVector<N>>,
     axis2: Unit<AngularVector<N>>,
+    ref1: Unit<AngularVector<N>>,
+    ref2: Unit<AngularVector<N>>,
     angle: N,
     lin_impulses: Vector<N>,
     ang_impulse: N,
+    limit_impulses: [N; 2],
+    motor_impulses: [N; 2],
     break_force_squared: N,
     break_torque_squared: N,
     broken: bool,
     bilateral_ground_rng: Range<usize>,
     bilateral_rng: Range<usize>,
+    unilateral_ground_rng: Range<usize>,
+    unilateral_rng: Range<usize>,
+    unit_forces: Vec<Force<N>>,
+    reaction: Force<N>,
+    min_angles: [Option<N>; 2],
+    max_angles: [Option<N>; 2],
+    motors: [JointMotor<N, N>; 2],
 }
 
 impl<N: RealField, Handle: BodyHandle> UniversalConstraint<N, Handle> {
     /// Create a new universal constraint that ensure the angle between `axis1` and `axis2` is always equal to `angle`.
     ///
     /// All anchors and axii are expressed in the local coordinate systems of the corresponding body parts.
+    /// The rotation angles around both axii are zero when both body parts have the same orientation.
     pub fn new(
         b1: BodyPartHandle<Handle>,
         b2: BodyPartHandle<Handle>,
@@ -41,6 +54,19 @@ impl<N: RealField, Handle: BodyHandle> UniversalConstraint<N, Handle> {
         axis2: Unit<AngularVector<N>>,
         angle: N,
     ) -> Self {
+        // The angle around one axis is measured by the direction of the other axis, so the
+        // reference directions are the directions of the other axis when both frames coincide.
+        let ref1 = Unit::try_new(
+            axis2.into_inner() - axis1.into_inner() * axis1.dot(&axis2),
+            N::default_epsilon(),
+        )
+        .unwrap_or_else(|| unit_constraint::orthonormal_basis(&axis1)[0]);
+        let ref2 = Unit::try_new(
+            axis1.into_inner() - axis2.into_inner() * axis2.dot(&axis1),
+            N::default_epsilon(),
+        )
+        .unwrap_or_else(|| unit_constraint::orthonormal_basis(&axis2)[0]);
+
         UniversalConstraint {
             b1,
             b2,
@@ -48,14 +74,25 @@ impl<N: RealField, Handle: BodyHandle> UniversalConstraint<N, Handle> {
             anchor2,
             axis1,
             axis2,
+            ref1,
+            ref2,
             angle,
             lin_impulses: Vector::zeros(),
             ang_impulse: N::zero(),
+            limit_impulses: [N::zero(); 2],
+            motor_impulses: [N::zero(); 2],
             break_force_squared: N::max_value(),
             break_torque_squared: N::max_value(),
             broken: false,
             bilateral_ground_rng: 0..0,
             bilateral_rng: 0..0,
+            unilateral_ground_rng: 0..0,
+            unilateral_rng: 0..0,
+            unit_forces: vec![Force::zero(); 8],
+            reaction: Force::zero(),
+            min_angles: [None; 2],
+            max_angles: [None; 2],
+            motors: [JointMotor::new(); 2],
         }
     }
 
@@ -68,6 +105,253 @@ impl<N: RealField, Handle: BodyHandle> UniversalConstraint<N, Handle> {
     pub fn set_break_torque(&mut self, break_torque: N) {
         self.break_torque_squared = break_torque * break_torque;
     }
+
+    /// The lower limit of the rotation angle around the first axis.
+    pub fn min_angle_1(&self) -> Option<N> {
+        self.min_angles[0]
+    }
+
+    /// The upper limit of the rotation angle around the first axis.
+    pub fn max_angle_1(&self) -> Option<N> {
+        self.max_angles[0]
+    }
+
+    /// Disable the lower limit of the rotation angle around the first axis.
+    pub fn disable_min_angle_1(&mut self) {
+        self.min_angles[0] = None;
+    }
+
+    /// Disable the upper limit of the rotation angle around the first axis.
+    pub fn disable_max_angle_1(&mut self) {
+        self.max_angles[0] = None;
+    }
+
+    /// Enable and set the lower limit of the rotation angle around the first axis.
+    pub fn enable_min_angle_1(&mut self, limit: N) {
+        self.min_angles[0] = Some(limit);
+        self.assert_limits();
+    }
+
+    /// Enable and set the upper limit of the rotation angle around the first axis.
+    pub fn enable_max_angle_1(&mut self, limit: N) {
+        self.max_angles[0] = Some(limit);
+        self.assert_limits();
+    }
+
+    /// Return `true` if the angular motor around the first axis is enabled.
+    pub fn is_angular_motor_enabled_1(&self) -> bool {
+        self.motors[0].enabled
+    }
+
+    /// Enable the angular motor around the first axis.
+    pub fn enable_angular_motor_1(&mut self) {
+        self.motors[0].enabled = true
+    }
+
+    /// Disable the angular motor around the first axis.
+    pub fn disable_angular_motor_1(&mut self) {
+        self.motors[0].enabled = false;
+    }
+
+    /// The desired angular velocity of the joint motor around the first axis.
+    pub fn desired_angular_motor_velocity_1(&self) -> N {
+        self.motors[0].desired_velocity
+    }
+
+    /// Set the desired angular velocity of the joint motor around the first axis.
+    pub fn set_desired_angular_motor_velocity_1(&mut self, vel: N) {
+        self.motors[0].desired_velocity = vel;
+    }
+
+    /// The max angular velocity that the joint motor around the first axis will attempt.
+    pub fn max_angular_motor_velocity_1(&self) -> N {
+        self.motors[0].max_velocity
+    }
+
+    /// Set the maximum angular velocity that the joint motor around the first axis will attempt.
+    pub fn set_max_angular_motor_velocity_1(&mut self, max_vel: N) {
+        self.motors[0].max_velocity = max_vel;
+    }
+
+    /// The maximum torque that can be delivered by the joint motor around the first axis.
+    pub fn max_angular_motor_torque_1(&self) -> N {
+        self.motors[0].max_force
+    }
+
+    /// Set the maximum torque that can be delivered by the joint motor around the first axis.
+    pub fn set_max_angular_motor_torque_1(&mut self, torque: N) {
+        self.motors[0].max_force = torque;
+    }
+
+    /// The angle the joint motor around the first axis attempts to reach if its stiffness is non-zero.
+    pub fn desired_angular_motor_position_1(&self) -> N {
+        self.motors[0].desired_position
+    }
+
+    /// Set the angle the joint motor around the first axis attempts to reach if its stiffness is non-zero.
+    pub fn set_desired_angular_motor_position_1(&mut self, pos: N) {
+        self.motors[0].desired_position = pos;
+    }
+
+    /// The stiffness of the joint motor around the first axis wrt. its desired position.
+    pub fn angular_motor_stiffness_1(&self) -> N {
+        self.motors[0].stiffness
+    }
+
+    /// Set the stiffness of the joint motor around the first axis wrt. its desired position.
+    pub fn set_angular_motor_stiffness_1(&mut self, stiffness: N) {
+        self.motors[0].stiffness = stiffness;
+    }
+
+    /// The damping of the joint motor around the first axis wrt. its desired velocity.
+    pub fn angular_motor_damping_1(&self) -> N {
+        self.motors[0].damping
+    }
+
+    /// Set the damping of the joint motor around the first axis wrt. its desired velocity.
+    pub fn set_angular_motor_damping_1(&mut self, damping: N) {
+        self.motors[0].damping = damping;
+    }
+
+    /// The lower limit of the rotation angle around the second axis.
+    pub fn min_angle_2(&self) -> Option<N> {
+        self.min_angles[1]
+    }
+
+    /// The upper limit of the rotation angle around the second axis.
+    pub fn max_angle_2(&self) -> Option<N> {
+        self.max_angles[1]
+    }
+
+    /// Disable the lower limit of the rotation angle around the second axis.
+    pub fn disable_min_angle_2(&mut self) {
+        self.min_angles[1] = None;
+    }
+
+    /// Disable the upper limit of the rotation angle around the second axis.
+    pub fn disable_max_angle_2(&mut self) {
+        self.max_angles[1] = None;
+    }
+
+    /// Enable and set the lower limit of the rotation angle around the second axis.
+    pub fn enable_min_angle_2(&mut self, limit: N) {
+        self.min_angles[1] = Some(limit);
+        self.assert_limits();
+    }
+
+    /// Enable and set the upper limit of the rotation angle around the second axis.
+    pub fn enable_max_angle_2(&mut self, limit: N) {
+        self.max_angles[1] = Some(limit);
+        self.assert_limits();
+    }
+
+    /// Return `true` if the angular motor around the second axis is enabled.
+    pub fn is_angular_motor_enabled_2(&self) -> bool {
+        self.motors[1].enabled
+    }
+
+    /// Enable the angular motor around the second axis.
+    pub fn enable_angular_motor_2(&mut self) {
+        self.motors[1].enabled = true
+    }
+
+    /// Disable the angular motor around the second axis.
+    pub fn disable_angular_motor_2(&mut self) {
+        self.motors[1].enabled = false;
+    }
+
+    /// The desired angular velocity of the joint motor around the second axis.
+    pub fn desired_angular_motor_velocity_2(&self) -> N {
+        self.motors[1].desired_velocity
+    }
+
+    /// Set the desired angular velocity of the joint motor around the second axis.
+    pub fn set_desired_angular_motor_velocity_2(&mut self, vel: N) {
+        self.motors[1].desired_velocity = vel;
+    }
+
+    /// The max angular velocity that the joint motor around the second axis will attempt.
+    pub fn max_angular_motor_velocity_2(&self) -> N {
+        self.motors[1].max_velocity
+    }
+
+    /// Set the maximum angular velocity that the joint motor around the second axis will attempt.
+    pub fn set_max_angular_motor_velocity_2(&mut self, max_vel: N) {
+        self.motors[1].max_velocity = max_vel;
+    }
+
+    /// The maximum torque that can be delivered by the joint motor around the second axis.
+    pub fn max_angular_motor_torque_2(&self) -> N {
+        self.motors[1].max_force
+    }
+
+    /// Set the maximum torque that can be delivered by the joint motor around the second axis.
+    pub fn set_max_angular_motor_torque_2(&mut self, torque: N) {
+        self.motors[1].max_force = torque;
+    }
+
+    /// The angle the joint motor around the second axis attempts to reach if its stiffness is non-zero.
+    pub fn desired_angular_motor_position_2(&self) -> N {
+        self.motors[1].desired_position
+    }
+
+    /// Set the angle the joint motor around the second axis attempts to reach if its stiffness is non-zero.
+    pub fn set_desired_angular_motor_position_2(&mut self, pos: N) {
+        self.motors[1].desired_position = pos;
+    }
+
+    /// The stiffness of the joint motor around the second axis wrt. its desired position.
+    pub fn angular_motor_stiffness_2(&self) -> N {
+        self.motors[1].stiffness
+    }
+
+    /// Set the stiffness of the joint motor around the second axis wrt. its desired position.
+    pub fn set_angular_motor_stiffness_2(&mut self, stiffness: N) {
+        self.motors[1].stiffness = stiffness;
+    }
+
+    /// The damping of the joint motor around the second axis wrt. its desired velocity.
+    pub fn angular_motor_damping_2(&self) -> N {
+        self.motors[1].damping
+    }
+
+    /// Set the damping of the joint motor around the second axis wrt. its desired velocity.
+    pub fn set_angular_motor_damping_2(&mut self, damping: N) {
+        self.motors[1].damping = damping;
+    }
+
+    fn assert_limits(&self) {
+        for i in 0..2 {
+            if let (Some(min_angle), Some(max_angle)) = (self.min_angles[i], self.max_angles[i]) {
+                assert!(
+                    min_angle <= max_angle,
+                    "UniversalConstraint limits: the min angle must be smaller than (or equal to) the max angle.");
+            }
+        }
+    }
+
+    /// The world-space axii and the rotation angles of the second body part wrt. the first one around them.
+    fn axii_and_angles(
+        &self,
+        pos1: &Isometry<N>,
+        pos2: &Isometry<N>,
+    ) -> [(Unit<AngularVector<N>>, N); 2] {
+        let axis1 = pos1 * self.axis1;
+        let axis2 = pos2 * self.axis2;
+        let ref1 = pos1 * self.ref1;
+        let ref2 = pos2 * self.ref2;
+
+        [
+            (
+                axis1,
+                unit_constraint::angle_around_axis(&axis1, &ref1, &axis2),
+            ),
+            (
+                axis2,
+                unit_constraint::angle_around_axis(&axis2, &axis1, &ref2),
+            ),
+        ]
+    }
 }
 
 impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle>
@@ -77,8 +361,12 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle>
         self.broken
     }
 
+    fn reaction_force(&self) -> Force<N> {
+        self.reaction
+    }
+
     fn num_velocity_constraints(&self) -> usize {
-        4
+        8
     }
 
     fn anchors(&self) -> (BodyPartHandle<Handle>, BodyPartHandle<Handle>) {
@@ -87,7 +375,7 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle>
 
     fn velocity_constraints(
         &mut self,
-        _: &IntegrationParameters<N>,
+        parameters: &IntegrationParameters<N>,
         bodies: &dyn BodySet<N, Handle = Handle>,
         ext_vels: &DVector<N>,
         ground_j_id: &mut usize,
@@ -114,6 +402,8 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle>
         let assembly_id1 = body1.companion_id();
         let assembly_id2 = body2.companion_id();
 
+        let first_unilateral_ground = constraints.unilateral_ground.len();
+        let first_unilateral = constraints.unilateral.len();
         let first_bilateral_ground = constraints.bilateral_ground.len();
         let first_bilateral = constraints.bilateral.len();
 
@@ -137,6 +427,8 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle>
             constraints,
         );
 
+        unit_constraint::linear_basis_forces(&mut self.unit_forces[..DIM]);
+
         let axis1 = pos1 * self.axis1;
         let axis2 = pos2 * self.axis2;
         if let Some(orth) = Unit::try_new(axis1.cross(&*axis2), N::default_epsilon()) {
@@ -160,35 +452,124 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle>
                 jacobians,
                 constraints,
             );
+
+            self.unit_forces[DIM] = Force::torque_from_vector(orth.into_inner());
         }
 
         /*
          *
-         * Limit constraints.
+         * Limit and motor constraints.
          *
          */
+        let axii_and_angles = self.axii_and_angles(&pos1, &pos2);
+
+        for (i, (axis, angle)) in axii_and_angles.iter().enumerate() {
+            if let Some(dir) = unit_constraint::build_angular_limits_velocity_constraint(
+                body1,
+                part1,
+                self.b1,
+                body2,
+                part2,
+                self.b2,
+                assembly_id1,
+                assembly_id2,
+                &anchor1,
+                &anchor2,
+                axis,
+                *angle,
+                self.min_angles[i],
+                self.max_angles[i],
+                ext_vels,
+                self.limit_impulses[i],
+                DIM + 1 + i,
+                ground_j_id,
+                j_id,
+                jacobians,
+                constraints,
+            ) {
+                self.unit_forces[DIM + 1 + i] = unit_constraint::unit_force(&dir);
+            }
+
+            if let Some(dir) = unit_constraint::build_motor_velocity_constraint(
+                parameters,
+                body1,
+                part1,
+                self.b1,
+                body2,
+                part2,
+                self.b2,
+                assembly_id1,
+                assembly_id2,
+                &anchor1,
+                &anchor2,
+                ForceDirection::Angular(*axis),
+                &self.motors[i],
+                *angle,
+                ext_vels,
+                self.motor_impulses[i],
+                DIM + 3 + i,
+                ground_j_id,
+                j_id,
+                jacobians,
+                constraints,
+            ) {
+                self.unit_forces[DIM + 3 + i] = unit_constraint::unit_force(&dir);
+            }
+        }
 
         self.bilateral_ground_rng = first_bilateral_ground..constraints.bilateral_ground.len();
         self.bilateral_rng = first_bilateral..constraints.bilateral.len();
+        self.unilateral_ground_rng = first_unilateral_ground..constraints.unilateral_ground.len();
+        self.unilateral_rng = first_unilateral..constraints.unilateral.len();
     }
 
     fn cache_impulses(&mut self, constraints: &LinearConstraints<N, usize>, inv_dt: N) {
+        self.reaction = unit_constraint::reaction_force(
+            &self.unit_forces,
+            constraints,
+            self.bilateral_ground_rng.clone(),
+            self.bilateral_rng.clone(),
+            inv_dt,
+        ) + unit_constraint::limits_reaction_force(
+            &self.unit_forces,
+            constraints,
+            self.unilateral_ground_rng.clone(),
+            self.unilateral_rng.clone(),
+            inv_dt,
+        );
+
         for c in &constraints.bilateral_ground[self.bilateral_ground_rng.clone()] {
             if c.impulse_id < DIM {
                 self.lin_impulses[c.impulse_id] = c.impulse;
-            } else {
+            } else if c.impulse_id == DIM {
                 self.ang_impulse = c.impulse;
+            } else if c.impulse_id < DIM + 3 {
+                self.limit_impulses[c.impulse_id - DIM - 1] = c.impulse;
+            } else {
+                self.motor_impulses[c.impulse_id - DIM - 3] = c.impulse;
             }
         }
 
         for c in &constraints.bilateral[self.bilateral_rng.clone()] {
             if c.impulse_id < DIM {
                 self.lin_impulses[c.impulse_id] = c.impulse;
-            } else {
+            } else if c.impulse_id == DIM {
                 self.ang_impulse = c.impulse;
+            } else if c.impulse_id < DIM + 3 {
+                self.limit_impulses[c.impulse_id - DIM - 1] = c.impulse;
+            } else {
+                self.motor_impulses[c.impulse_id - DIM - 3] = c.impulse;
             }
         }
 
+        for c in &constraints.unilateral_ground[self.unilateral_ground_rng.clone()] {
+            self.limit_impulses[c.impulse_id - DIM - 1] = c.impulse;
+        }
+
+        for c in &constraints.unilateral[self.unilateral_rng.clone()] {
+            self.limit_impulses[c.impulse_id - DIM - 1] = c.impulse;
+        }
+
         let inv_dt2 = inv_dt * inv_dt;
 
         if self.lin_impulses.norm_squared() * inv_dt2 > self.break_force_squared
@@ -205,7 +586,7 @@ impl<N: RealField, Handle: BodyHandle> NonlinearConstraintGenerator<N, Handle>
     fn num_position_constraints(&self, bodies: &dyn BodySet<N, Handle = Handle>) -> usize {
         // FIXME: calling this at each iteration of the non-linear resolution is costly.
         if self.is_active(bodies) {
-            2
+            4
         } else {
             0
         }
@@ -246,6 +627,28 @@ impl<N: RealField, Handle: BodyHandle> NonlinearConstraintGenerator<N, Handle>
             );
         }
 
+        if i == 2 || i == 3 {
+            let k = i - 2;
+            let (axis, angle) = self.axii_and_angles(&pos1, &pos2)[k];
+
+            return unit_constraint::build_angular_limits_position_constraint(
+                parameters,
+                body1,
+                part1,
+                self.b1,
+                body2,
+                part2,
+                self.b2,
+                &anchor1,
+                &anchor2,
+                &axis,
+                angle,
+                self.min_angles[k],
+                self.max_angles[k],
+                jacobians,
+            );
+        }
+
         None
     }
 }
@@ -7,6 +7,7 @@ use crate::solver::{ConstraintSet, GenericNonlinearConstraint, IntegrationParame
 
 /// A joint that allows only two relative rotations between two multibody links.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct UniversalJoint<N: RealField> {
     revo1: RevoluteJoint<N>,
     revo2: RevoluteJoint<N>,
@@ -86,6 +86,11 @@ extern crate ncollide2d as ncollide;
 #[cfg(feature = "dim3")]
 extern crate ncollide3d as ncollide;
 extern crate num_traits as num;
+#[cfg(feature = "serde-serialize")]
+#[macro_use]
+extern crate serde;
+#[cfg(feature = "parallel")]
+extern crate rayon;
 
 //#[cfg(test)]
 //extern crate test;
@@ -203,6 +208,9 @@ macro_rules! user_data_accessors(
         }
 
         /// Sets the user-defined data attached to this object.
+        ///
+        /// Only user-data of primitive types, `String`, or `Vec<u8>` can be serialized: serializing
+        /// this object with user-data of any other type fails with an error.
         #[inline]
         pub fn set_user_data(&mut self, data: Option<Box<dyn Any + Send + Sync>>) -> Option<Box<dyn Any + Send + Sync>> {
             std::mem::replace(&mut self.user_data, data)
@@ -237,10 +245,71 @@ macro_rules! user_data_desc_accessors(
     }
 );
 
+// Implements `Serialize` and `Deserialize` for boxed trait-objects by
+// downcasting them to one of the listed concrete types.
+#[cfg(feature = "serde-serialize")]
+macro_rules! serde_trait_object(
+    ($Trait: ident<$($P: ident: $Bound: path),*>, $Ref: ident, $Owned: ident {
+        $($(#[$attr: meta])* $Variant: ident($T: ty)),* $(,)*
+    }) => {
+        serde_trait_object!($Trait<$($P: $Bound),*> [$($P: $Bound),*], $Ref, $Owned {
+            $($(#[$attr])* $Variant($T)),*
+        });
+    };
+    // The parameters between brackets are the ones actually used by the concrete types.
+    ($Trait: ident<$($P: ident: $Bound: path),*> [$($UP: ident: $UBound: path),*], $Ref: ident, $Owned: ident {
+        $($(#[$attr: meta])* $Variant: ident($T: ty)),* $(,)*
+    }) => {
+        #[derive(Serialize)]
+        enum $Ref<'a, $($UP: $UBound),*> {
+            $($(#[$attr])* $Variant(&'a $T)),*
+        }
+
+        #[derive(Deserialize)]
+        enum $Owned<$($UP: $UBound),*> {
+            $($(#[$attr])* $Variant($T)),*
+        }
+
+        impl<$($P: $Bound),*> serde::Serialize for Box<dyn $Trait<$($P),*>>
+        where
+            $($UP: serde::Serialize),*
+        {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                $(
+                    $(#[$attr])*
+                    {
+                        if let Some(val) = (**self).downcast_ref::<$T>() {
+                            return $Ref::$Variant(val).serialize(serializer);
+                        }
+                    }
+                )*
+
+                Err(serde::ser::Error::custom(concat!(
+                    "Unable to serialize a `",
+                    stringify!($Trait),
+                    "` trait-object: unsupported concrete type."
+                )))
+            }
+        }
+
+        impl<'de, $($P: $Bound),*> serde::Deserialize<'de> for Box<dyn $Trait<$($P),*>>
+        where
+            $($UP: serde::Deserialize<'de>),*
+        {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                match $Owned::deserialize(deserializer)? {
+                    $($(#[$attr])* $Owned::$Variant(val) => Ok(Box::new(val))),*
+                }
+            }
+        }
+    }
+);
+
 const NOT_REGISTERED_ERROR: &'static str =
     "This collider has not been registered into a world (proxy indexes are None).";
 
 pub mod algebra;
+pub mod controller;
 pub mod counters;
 pub mod detection;
 pub mod force_generator;
@@ -11,6 +11,7 @@ use crate::math::Vector;
 /// will average the coefficient of the two surfaces in contact in order
 /// to deduce the restitution/friction coefficient.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct BasicMaterial<N: RealField> {
     /// The ID of this material for automatic lookup.
     pub id: Option<u32>,
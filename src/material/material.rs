@@ -43,6 +43,7 @@ impl<'a, N: RealField> MaterialContext<'a, N> {
 
 /// The way the friction and restitution coefficients of two materials should be combined.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub enum MaterialCombineMode {
     /// Combination by averaging the coefficients from both materials.
     Average,
@@ -123,6 +124,14 @@ pub trait Material<N: RealField>: Downcast + Send + Sync + MaterialClone<N> {
 
 impl_downcast!(Material<N> where N: RealField);
 
+#[cfg(feature = "serde-serialize")]
+use crate::material::BasicMaterial;
+
+#[cfg(feature = "serde-serialize")]
+serde_trait_object!(Material<N: RealField>, MaterialRef, MaterialOwned {
+    BasicMaterial(BasicMaterial<N>),
+});
+
 impl<N: RealField> Clone for Box<dyn Material<N>> {
     fn clone(&self) -> Box<dyn Material<N>> {
         self.clone_box()
@@ -195,6 +204,21 @@ impl<N: RealField> MaterialHandle<N> {
     }
 }
 
+#[cfg(feature = "serde-serialize")]
+impl<N: RealField + serde::Serialize> serde::Serialize for MaterialHandle<N> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (*self.0).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde-serialize")]
+impl<'de, N: RealField + serde::Deserialize<'de>> serde::Deserialize<'de> for MaterialHandle<N> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let material = Box::<dyn Material<N>>::deserialize(deserializer)?;
+        Ok(MaterialHandle(Arc::new(material)))
+    }
+}
+
 impl<N: RealField> AsRef<dyn Material<N>> for MaterialHandle<N> {
     #[inline]
     fn as_ref(&self) -> &dyn Material<N> {
@@ -44,3 +44,48 @@ impl<N: RealField> MaterialsCoefficientsTable<N> {
         self.restitution.get(&SortedPair::new(m1, m2)).cloned()
     }
 }
+
+#[cfg(feature = "serde-serialize")]
+fn sorted_coefficients<N: RealField>(
+    coefficients: &HashMap<SortedPair<u32>, N>,
+) -> Vec<(MaterialId, MaterialId, N)> {
+    // Sort the entries so the serialized table does not depend on the hash-map iteration order.
+    let mut result: Vec<_> = coefficients
+        .iter()
+        .map(|(pair, coeff)| (pair.0, pair.1, *coeff))
+        .collect();
+    result.sort_by_key(|entry| (entry.0, entry.1));
+    result
+}
+
+#[cfg(feature = "serde-serialize")]
+impl<N: RealField + serde::Serialize> serde::Serialize for MaterialsCoefficientsTable<N> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let friction = sorted_coefficients(&self.friction);
+        let restitution = sorted_coefficients(&self.restitution);
+        (friction, restitution).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde-serialize")]
+impl<'de, N: RealField + serde::Deserialize<'de>> serde::Deserialize<'de>
+    for MaterialsCoefficientsTable<N>
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (friction, restitution): (
+            Vec<(MaterialId, MaterialId, N)>,
+            Vec<(MaterialId, MaterialId, N)>,
+        ) = serde::Deserialize::deserialize(deserializer)?;
+        let mut result = MaterialsCoefficientsTable::new();
+
+        for (m1, m2, coeff) in friction {
+            result.set_friction_coefficient(m1, m2, coeff);
+        }
+
+        for (m1, m2, coeff) in restitution {
+            result.set_restitution_coefficient(m1, m2, coeff);
+        }
+
+        Ok(result)
+    }
+}
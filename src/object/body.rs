@@ -11,6 +11,7 @@ use ncollide::shape::DeformationsType;
 use crate::math::{Force, ForceType, Inertia, Isometry, Point, Vector, Velocity};
 
 use crate::solver::{ForceDirection, IntegrationParameters};
+use crate::world::GravityField;
 
 pub enum BodyPartMotion<N: RealField> {
     RigidLinear(ConstantLinearVelocityRigidMotion<N>),
@@ -47,6 +48,7 @@ impl<N: RealField> RigidMotion<N> for BodyPartMotion<N> {
 
 /// The status of a body.
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub enum BodyStatus {
     /// The body is disabled and ignored by the physics engine.
     Disabled,
@@ -62,6 +64,7 @@ pub enum BodyStatus {
 ///
 /// This controls whether a body is sleeping or not.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct ActivationStatus<N: RealField> {
     threshold: Option<N>,
     energy: N,
@@ -157,7 +160,11 @@ pub trait Body<N: RealField>: Downcast + Send + Sync {
     fn update_dynamics(&mut self, dt: N);
 
     /// Update the acceleration of this body given the forces it is subject to and the gravity.
-    fn update_acceleration(&mut self, gravity: &Vector<N>, parameters: &IntegrationParameters<N>);
+    fn update_acceleration(
+        &mut self,
+        gravity: &dyn GravityField<N>,
+        parameters: &IntegrationParameters<N>,
+    );
 
     /// Reset the timestep-specific dynamic information of this body.
     fn clear_forces(&mut self);
@@ -347,6 +354,18 @@ pub trait Body<N: RealField>: Downcast + Send + Sync {
     /// Enable or disable gravity for this body.
     fn enable_gravity(&mut self, enabled: bool);
 
+    /// The factor by which the gravity is multiplied for this body.
+    ///
+    /// Defaults to one for bodies that do not support gravity scaling.
+    fn gravity_scale(&self) -> N {
+        N::one()
+    }
+
+    /// Sets the factor by which the gravity is multiplied for this body.
+    ///
+    /// Does nothing for bodies that do not support gravity scaling.
+    fn set_gravity_scale(&mut self, _scale: N) {}
+
     /// Gets the velocity of the given point of this body.
     fn velocity_at_point(&self, part_id: usize, point: &Point<N>) -> Velocity<N>;
 
@@ -446,6 +465,26 @@ pub trait BodyPart<N: RealField>: Downcast + Send + Sync {
 impl_downcast!(Body<N> where N: RealField);
 impl_downcast!(BodyPart<N> where N: RealField);
 
+#[cfg(all(feature = "serde-serialize", feature = "dim2"))]
+use crate::object::FEMSurface;
+#[cfg(all(feature = "serde-serialize", feature = "dim3"))]
+use crate::object::FEMVolume;
+#[cfg(feature = "serde-serialize")]
+use crate::object::{Ground, MassConstraintSystem, MassSpringSystem, Multibody, RigidBody};
+
+#[cfg(feature = "serde-serialize")]
+serde_trait_object!(Body<N: RealField>, BodyRef, BodyOwned {
+    RigidBody(RigidBody<N>),
+    Multibody(Multibody<N>),
+    Ground(Ground<N>),
+    MassSpringSystem(MassSpringSystem<N>),
+    MassConstraintSystem(MassConstraintSystem<N>),
+    #[cfg(feature = "dim2")]
+    FEMSurface(FEMSurface<N>),
+    #[cfg(feature = "dim3")]
+    FEMVolume(FEMVolume<N>),
+});
+
 bitflags! {
     #[derive(Default)]
     struct BodyUpdateStatusFlags: u8 {
@@ -525,3 +564,20 @@ impl BodyUpdateStatus {
         self.0 = BodyUpdateStatusFlags::empty()
     }
 }
+
+#[cfg(feature = "serde-serialize")]
+impl serde::Serialize for BodyUpdateStatus {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.bits().serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde-serialize")]
+impl<'de> serde::Deserialize<'de> for BodyUpdateStatus {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bits = u8::deserialize(deserializer)?;
+        Ok(BodyUpdateStatus(BodyUpdateStatusFlags::from_bits_truncate(
+            bits,
+        )))
+    }
+}
@@ -44,6 +44,16 @@ pub trait BodySet<N: RealField> {
     /// Mutable iterate through all the bodies on this set, applying the closure `f` on them.
     fn foreach_mut(&mut self, f: &mut dyn FnMut(Self::Handle, &mut dyn Body<N>));
 
+    /// Mutable references to all the bodies of this set at once, if this set can lend them.
+    ///
+    /// With the `parallel` feature enabled, this lets nphysics update the bodies and solve the
+    /// simulation islands on several threads. The default implementation returns `None`, in which
+    /// case the `parallel` feature has no effect for this set: the bodies are updated using
+    /// `foreach_mut`, and the islands are solved one after the other, on the calling thread.
+    fn disjoint_bodies_mut(&mut self) -> Option<Vec<(Self::Handle, &mut dyn Body<N>)>> {
+        None
+    }
+
     /// Gets the handle of one body that has been removed.
     ///
     /// A body set must keep track (using typically a stack or a queue) of every body that has been
@@ -57,6 +67,7 @@ pub trait BodySet<N: RealField> {
 /// The default set containing all the bodies added to the world.
 ///
 /// It is based on an arena using generational indices to avoid the ABA problem.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct DefaultBodySet<N: RealField> {
     bodies: Arena<Box<dyn Body<N>>>,
     removed: Vec<DefaultBodyHandle>,
@@ -169,6 +180,10 @@ impl<N: RealField> BodySet<N> for DefaultBodySet<N> {
         }
     }
 
+    fn disjoint_bodies_mut(&mut self) -> Option<Vec<(Self::Handle, &mut dyn Body<N>)>> {
+        Some(self.iter_mut().collect())
+    }
+
     fn pop_removal_event(&mut self) -> Option<Self::Handle> {
         self.removed.pop()
     }
@@ -181,6 +196,7 @@ pub type DefaultBodyPartHandle = BodyPartHandle<DefaultBodyHandle>;
 
 /// A unique identifier of a body part added to the world.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct BodyPartHandle<Handle: BodyHandle>(pub Handle, pub usize);
 
 /// A abstract body descriptor to be passed to the physics `World` to create a body.
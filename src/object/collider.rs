@@ -1,7 +1,7 @@
 use std::f64;
 use std::sync::Arc;
 
-use na::RealField;
+use na::{RealField, Unit};
 use ncollide::pipeline::{
     BroadPhaseProxyHandle, CollisionGroups, CollisionObject, CollisionObjectGraphIndex,
     CollisionObjectRef, CollisionObjectUpdateFlags, GeometricQueryType,
@@ -17,6 +17,7 @@ use crate::utils::{UserData, UserDataBox};
 
 /// Description of the way a collider is attached to a body.
 #[derive(Clone)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub enum ColliderAnchor<N: RealField, Handle: BodyHandle> {
     /// Attach of a collider with a body part.
     OnBodyPart {
@@ -68,6 +69,10 @@ pub struct ColliderData<N: RealField, Handle: BodyHandle> {
     body_status_dependent_ndofs: usize,
     material: MaterialHandle<N>,
     ccd_enabled: bool,
+    one_way_normal: Option<Unit<Vector<N>>>,
+    // The index this collider had in the interaction graph of its geometrical world when it was
+    // serialized, if it was registered to one.
+    restored_graph_index: Option<usize>,
     user_data: Option<Box<dyn Any + Send + Sync>>,
 }
 
@@ -87,6 +92,8 @@ impl<N: RealField, Handle: BodyHandle> ColliderData<N, Handle> {
             body_status_dependent_ndofs,
             material,
             ccd_enabled: false,
+            one_way_normal: None,
+            restored_graph_index: None,
             user_data: None,
         }
     }
@@ -194,6 +201,9 @@ impl<N: RealField, Handle: BodyHandle> Collider<N, Handle> {
     }
 
     /// Sets the user-data attached to this collider.
+    ///
+    /// Only user-data of primitive types, `String`, or `Vec<u8>` can be serialized: serializing
+    /// this collider with user-data of any other type fails with an error.
     #[inline]
     pub fn set_user_data(
         &mut self,
@@ -291,6 +301,21 @@ impl<N: RealField, Handle: BodyHandle> Collider<N, Handle> {
         self.0.data_mut().ccd_enabled = enabled
     }
 
+    /// The local-space normal of the only side this collider is solid from, if it is a one-way platform.
+    #[inline]
+    pub fn one_way_normal(&self) -> Option<Unit<Vector<N>>> {
+        self.0.data().one_way_normal
+    }
+
+    /// Makes this collider a one-way platform solid only from the side pointed by the given local-space normal.
+    ///
+    /// Colliders coming from the other side pass through it. Set to `None` to make this collider
+    /// solid from every side again.
+    #[inline]
+    pub fn set_one_way_normal(&mut self, normal: Option<Unit<Vector<N>>>) {
+        self.0.data_mut().one_way_normal = normal
+    }
+
     #[inline]
     pub(crate) fn body_status_dependent_ndofs(&self) -> usize {
         self.0.data().body_status_dependent_ndofs
@@ -301,6 +326,16 @@ impl<N: RealField, Handle: BodyHandle> Collider<N, Handle> {
         self.0.data_mut().body_status_dependent_ndofs = ndofs
     }
 
+    #[inline]
+    pub(crate) fn restored_graph_index(&self) -> Option<usize> {
+        self.0.data().restored_graph_index
+    }
+
+    #[inline]
+    pub(crate) fn set_restored_graph_index(&mut self, index: Option<usize>) {
+        self.0.data_mut().restored_graph_index = index
+    }
+
     /*
      * Original methods from the CollisionObject.
      */
@@ -450,6 +485,7 @@ pub struct ColliderDesc<N: RealField> {
     angular_prediction: N,
     is_sensor: bool,
     ccd_enabled: bool,
+    one_way_normal: Option<Unit<Vector<N>>>,
 }
 
 impl<N: RealField> ColliderDesc<N> {
@@ -470,6 +506,7 @@ impl<N: RealField> ColliderDesc<N> {
             angular_prediction,
             is_sensor: false,
             ccd_enabled: false,
+            one_way_normal: None,
         }
     }
 
@@ -497,6 +534,7 @@ impl<N: RealField> ColliderDesc<N> {
     desc_custom_setters!(
         self.translation, set_translation, vector: Vector<N> | { self.position.translation.vector = vector }
         self.material, set_material, material: MaterialHandle<N> | { self.material = Some(material) }
+        self.one_way_normal, set_one_way_normal, normal: Unit<Vector<N>> | { self.one_way_normal = Some(normal) }
     );
 
     desc_setters!(
@@ -531,6 +569,7 @@ impl<N: RealField> ColliderDesc<N> {
         [val] get_angular_prediction -> angular_prediction: N
         [val] is_sensor -> is_sensor: bool
         [val] get_ccd_enabled -> ccd_enabled: bool
+        [val] get_one_way_normal -> one_way_normal: Option<Unit<Vector<N>>>
         [ref] get_position -> position: Isometry<N>
     );
 
@@ -558,6 +597,7 @@ impl<N: RealField> ColliderDesc<N> {
             .unwrap_or_else(|| MaterialHandle::new(BasicMaterial::default()));
         let mut data = ColliderData::new(self.margin, self.density, anchor, 0, material);
         data.ccd_enabled = self.ccd_enabled;
+        data.one_way_normal = self.one_way_normal;
         data.user_data = self.user_data.as_ref().map(|data| data.0.to_any());
         let co = CollisionObject::new(
             None,
@@ -707,3 +747,267 @@ impl<N: RealField> DeformableColliderDesc<N> {
         Collider(co)
     }
 }
+
+#[cfg(feature = "serde-serialize")]
+mod serde_impls {
+    use na::{RealField, Unit};
+    use ncollide::pipeline::{CollisionGroups, CollisionObject, GeometricQueryType};
+    #[cfg(feature = "dim2")]
+    use ncollide::shape::ConvexPolygon;
+    use ncollide::shape::{
+        Ball, Capsule, Compound, Cuboid, HeightField, Plane, Polyline, Segment, Shape, ShapeHandle,
+    };
+    #[cfg(feature = "dim3")]
+    use ncollide::shape::{ConvexHull, TriMesh, Triangle};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::any::Any;
+
+    use super::{Collider, ColliderAnchor, ColliderData};
+    use crate::material::MaterialHandle;
+    use crate::math::{Isometry, Vector};
+    use crate::object::BodyHandle;
+    use crate::utils;
+
+    macro_rules! serde_shapes(
+        ($($(#[$attr: meta])* $Variant: ident),*) => {
+            #[derive(Serialize)]
+            enum ShapeRef<'a, N: RealField> {
+                $($(#[$attr])* $Variant(&'a $Variant<N>),)*
+                Compound(Vec<(Isometry<N>, ShapeRef<'a, N>)>),
+            }
+
+            #[derive(Deserialize)]
+            enum ShapeOwned<N: RealField> {
+                $($(#[$attr])* $Variant($Variant<N>),)*
+                Compound(Vec<(Isometry<N>, ShapeOwned<N>)>),
+            }
+
+            impl<'a, N: RealField> ShapeRef<'a, N> {
+                fn new(shape: &'a dyn Shape<N>) -> Option<Self> {
+                    $(
+                        $(#[$attr])*
+                        {
+                            if let Some(s) = shape.as_shape::<$Variant<N>>() {
+                                return Some(ShapeRef::$Variant(s));
+                            }
+                        }
+                    )*
+
+                    let compound = shape.as_shape::<Compound<N>>()?;
+                    let mut parts = Vec::with_capacity(compound.shapes().len());
+
+                    for (pos, part) in compound.shapes() {
+                        parts.push((*pos, ShapeRef::new(&**part)?));
+                    }
+
+                    Some(ShapeRef::Compound(parts))
+                }
+            }
+
+            impl<N: RealField> ShapeOwned<N> {
+                fn into_shape_handle(self) -> ShapeHandle<N> {
+                    match self {
+                        $($(#[$attr])* ShapeOwned::$Variant(s) => ShapeHandle::new(s),)*
+                        ShapeOwned::Compound(parts) => {
+                            let parts = parts
+                                .into_iter()
+                                .map(|(pos, part)| (pos, part.into_shape_handle()))
+                                .collect();
+                            ShapeHandle::new(Compound::new(parts))
+                        }
+                    }
+                }
+            }
+        }
+    );
+
+    serde_shapes!(
+        Ball,
+        Cuboid,
+        Capsule,
+        Plane,
+        Segment,
+        #[cfg(feature = "dim3")]
+        Triangle,
+        Polyline,
+        HeightField,
+        #[cfg(feature = "dim2")]
+        ConvexPolygon,
+        #[cfg(feature = "dim3")]
+        ConvexHull,
+        #[cfg(feature = "dim3")]
+        TriMesh
+    );
+
+    #[derive(Serialize, Deserialize)]
+    struct CollisionGroupsMasks {
+        membership: u32,
+        whitelist: u32,
+        blacklist: u32,
+        self_interaction: bool,
+    }
+
+    impl CollisionGroupsMasks {
+        fn new(groups: &CollisionGroups) -> Self {
+            let mut result = CollisionGroupsMasks {
+                membership: 0,
+                whitelist: 0,
+                blacklist: 0,
+                self_interaction: groups.can_interact_with_self(),
+            };
+
+            for i in 0..=CollisionGroups::max_group_id() {
+                if groups.is_member_of(i) {
+                    result.membership |= 1 << i;
+                }
+                if groups.is_group_whitelisted(i) {
+                    result.whitelist |= 1 << i;
+                }
+                if groups.is_group_blacklisted(i) {
+                    result.blacklist |= 1 << i;
+                }
+            }
+
+            result
+        }
+
+        fn to_collision_groups(&self) -> CollisionGroups {
+            let mut groups = CollisionGroups::empty()
+                .with_membership_by_mask(self.membership)
+                .with_whitelist_by_mask(self.whitelist)
+                .with_blacklist_by_mask(self.blacklist);
+
+            if self.self_interaction {
+                groups.enable_self_interaction();
+            }
+
+            groups
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    enum QueryType<N: RealField> {
+        Contacts(N, N),
+        Proximity(N),
+    }
+
+    struct UserDataRef<'a>(&'a Option<Box<dyn Any + Send + Sync>>);
+
+    impl<'a> Serialize for UserDataRef<'a> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            utils::serialize_user_data(self.0, serializer)
+        }
+    }
+
+    struct UserDataOwned(Option<Box<dyn Any + Send + Sync>>);
+
+    impl<'de> Deserialize<'de> for UserDataOwned {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            utils::deserialize_user_data(deserializer).map(UserDataOwned)
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct ColliderSnapshot<N: RealField, Handle: BodyHandle, S, M, U> {
+        position: Isometry<N>,
+        predicted_position: Option<Isometry<N>>,
+        shape: S,
+        collision_groups: CollisionGroupsMasks,
+        query_type: QueryType<N>,
+        margin: N,
+        density: N,
+        anchor: ColliderAnchor<N, Handle>,
+        body_status_dependent_ndofs: usize,
+        material: M,
+        ccd_enabled: bool,
+        one_way_normal: Option<Unit<Vector<N>>>,
+        graph_index: Option<usize>,
+        user_data: U,
+    }
+
+    impl<N, Handle> Serialize for Collider<N, Handle>
+    where
+        N: RealField + Serialize,
+        Handle: BodyHandle + Serialize,
+    {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let shape = ShapeRef::new(self.shape()).ok_or_else(|| {
+                serde::ser::Error::custom("Unable to serialize a collider: unsupported shape type.")
+            })?;
+            let query_type = match self.query_type() {
+                GeometricQueryType::Contacts(linear, angular) => {
+                    QueryType::Contacts(linear, angular)
+                }
+                GeometricQueryType::Proximity(linear) => QueryType::Proximity(linear),
+            };
+            let data = self.0.data();
+
+            let snapshot = ColliderSnapshot {
+                position: *self.position(),
+                predicted_position: self.0.predicted_position().cloned(),
+                shape,
+                collision_groups: CollisionGroupsMasks::new(self.collision_groups()),
+                query_type,
+                margin: data.margin,
+                density: data.density,
+                anchor: data.anchor.clone(),
+                body_status_dependent_ndofs: data.body_status_dependent_ndofs,
+                material: &data.material,
+                ccd_enabled: data.ccd_enabled,
+                one_way_normal: data.one_way_normal,
+                graph_index: self.graph_index().map(|i| i.index()),
+                user_data: UserDataRef(&data.user_data),
+            };
+
+            snapshot.serialize(serializer)
+        }
+    }
+
+    impl<'de, N, Handle> Deserialize<'de> for Collider<N, Handle>
+    where
+        N: RealField + Deserialize<'de>,
+        Handle: BodyHandle + Deserialize<'de>,
+    {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let snapshot: ColliderSnapshot<
+                N,
+                Handle,
+                ShapeOwned<N>,
+                MaterialHandle<N>,
+                UserDataOwned,
+            > = ColliderSnapshot::deserialize(deserializer)?;
+            let query_type = match snapshot.query_type {
+                QueryType::Contacts(linear, angular) => {
+                    GeometricQueryType::Contacts(linear, angular)
+                }
+                QueryType::Proximity(linear) => GeometricQueryType::Proximity(linear),
+            };
+
+            let mut data = ColliderData::new(
+                snapshot.margin,
+                snapshot.density,
+                snapshot.anchor,
+                snapshot.body_status_dependent_ndofs,
+                snapshot.material,
+            );
+            data.ccd_enabled = snapshot.ccd_enabled;
+            data.one_way_normal = snapshot.one_way_normal;
+            data.restored_graph_index = snapshot.graph_index;
+            data.user_data = snapshot.user_data.0;
+
+            // The collider is not registered to any geometrical world yet.
+            let mut co = CollisionObject::new(
+                None,
+                None,
+                snapshot.position,
+                snapshot.shape.into_shape_handle(),
+                snapshot.collision_groups.to_collision_groups(),
+                query_type,
+                data,
+            );
+            co.set_predicted_position(snapshot.predicted_position);
+
+            Ok(Collider(co))
+        }
+    }
+}
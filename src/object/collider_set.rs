@@ -139,6 +139,37 @@ impl<N: RealField, Handle: BodyHandle> DefaultColliderSet<N, Handle> {
     }
 }
 
+// Pending removal events are not serialized. Deserialized colliders are all
+// reported as inserted so they get registered to a new geometrical world.
+#[cfg(feature = "serde-serialize")]
+impl<N, Handle> serde::Serialize for DefaultColliderSet<N, Handle>
+where
+    N: RealField + serde::Serialize,
+    Handle: BodyHandle + serde::Serialize,
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.colliders.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde-serialize")]
+impl<'de, N, Handle> serde::Deserialize<'de> for DefaultColliderSet<N, Handle>
+where
+    N: RealField + serde::Deserialize<'de>,
+    Handle: BodyHandle + serde::Deserialize<'de>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let colliders: Arena<Collider<N, Handle>> = serde::Deserialize::deserialize(deserializer)?;
+        let inserted = colliders.iter().map(|(handle, _)| handle).collect();
+
+        Ok(DefaultColliderSet {
+            colliders,
+            removed: Vec::new(),
+            inserted,
+        })
+    }
+}
+
 impl<N: RealField, Handle: BodyHandle> CollisionObjectSet<N> for DefaultColliderSet<N, Handle> {
     type CollisionObject = Collider<N, Handle>;
     type CollisionObjectHandle = DefaultColliderHandle;
@@ -32,6 +32,7 @@ pub(crate) fn elasticity_coefficients<N: RealField>(
 
 /// Indices of the nodes of on element of a body decomposed in finite elements.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub(crate) enum FiniteElementIndices {
     #[cfg(feature = "dim3")]
     /// A tetrahedral element.
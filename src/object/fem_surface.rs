@@ -24,9 +24,11 @@ use crate::object::{
 use crate::solver::{ForceDirection, IntegrationParameters};
 
 use crate::utils::{UserData, UserDataBox};
+use crate::world::GravityField;
 
 /// One element of a deformable surface.
 #[derive(Clone)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct TriangularElement<N: RealField> {
     indices: Point3<usize>,
     com: Point<N>,
@@ -44,6 +46,7 @@ pub struct TriangularElement<N: RealField> {
 ///
 /// The surface is described by a set of triangle elements. This
 /// implements an isoparametric approach where the interpolations are linear.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct FEMSurface<N: RealField> {
     elements: Vec<TriangularElement<N>>,
     kinematic_nodes: DVector<bool>,
@@ -58,6 +61,7 @@ pub struct FEMSurface<N: RealField> {
 
     // Parameters
     gravity_enabled: bool,
+    gravity_scale: N,
     rest_positions: DVector<N>,
     damping_coeffs: (N, N),
     young_modulus: N,
@@ -76,6 +80,13 @@ pub struct FEMSurface<N: RealField> {
     status: BodyStatus,
     update_status: BodyUpdateStatus,
 
+    #[cfg_attr(
+        feature = "serde-serialize",
+        serde(
+            serialize_with = "crate::utils::serialize_user_data",
+            deserialize_with = "crate::utils::deserialize_user_data"
+        )
+    )]
     user_data: Option<Box<dyn Any + Send + Sync>>,
 }
 
@@ -160,6 +171,7 @@ impl<N: RealField> FEMSurface<N> {
             plasticity_max_force: N::zero(),
             plasticity_creep: N::zero(),
             gravity_enabled: true,
+            gravity_scale: N::one(),
             d0,
             d1,
             d2,
@@ -338,7 +350,11 @@ impl<N: RealField> FEMSurface<N> {
         }
     }
 
-    fn assemble_forces(&mut self, gravity: &Vector<N>, parameters: &IntegrationParameters<N>) {
+    fn assemble_forces(
+        &mut self,
+        gravity: &dyn GravityField<N>,
+        parameters: &IntegrationParameters<N>,
+    ) {
         let _1: N = na::one();
         let _2: N = na::convert(2.0);
         let dt = parameters.dt();
@@ -348,15 +364,16 @@ impl<N: RealField> FEMSurface<N> {
         // Gravity
         if self.gravity_enabled {
             for elt in self.elements.iter() {
-                let contribution =
-                    gravity * (elt.density * elt.surface * na::convert::<_, N>(1.0 / 3.0));
+                let node_weight =
+                    elt.density * elt.surface * na::convert::<_, N>(1.0 / 3.0) * self.gravity_scale;
 
                 for k in 0..3 {
                     let ie = elt.indices[k];
 
                     if !self.kinematic_nodes[ie / DIM] {
+                        let node = Point::from(self.positions.fixed_rows::<Dim>(ie).into_owned());
                         let mut forces_part = self.accelerations.fixed_rows_mut::<Dim>(ie);
-                        forces_part += contribution;
+                        forces_part += gravity.gravity_at(&node) * node_weight;
                     }
                 }
             }
@@ -703,6 +720,16 @@ impl<N: RealField> Body<N> for FEMSurface<N> {
         self.gravity_enabled = enabled
     }
 
+    #[inline]
+    fn gravity_scale(&self) -> N {
+        self.gravity_scale
+    }
+
+    #[inline]
+    fn set_gravity_scale(&mut self, scale: N) {
+        self.gravity_scale = scale
+    }
+
     #[inline]
     fn deformed_positions(&self) -> Option<(DeformationsType, &[N])> {
         Some((DeformationsType::Vectors, self.positions.as_slice()))
@@ -757,7 +784,11 @@ impl<N: RealField> Body<N> for FEMSurface<N> {
     }
 
     /// Update the dynamics property of this deformable surface.
-    fn update_acceleration(&mut self, gravity: &Vector<N>, parameters: &IntegrationParameters<N>) {
+    fn update_acceleration(
+        &mut self,
+        gravity: &dyn GravityField<N>,
+        parameters: &IntegrationParameters<N>,
+    ) {
         self.assemble_forces(gravity, parameters);
         self.inv_augmented_mass.solve_mut(&mut self.accelerations);
     }
@@ -1136,6 +1167,7 @@ pub struct FEMSurfaceDesc<'a, N: RealField> {
     kinematic_nodes: Vec<usize>,
     status: BodyStatus,
     gravity_enabled: bool,
+    gravity_scale: N,
 }
 
 impl<'a, N: RealField> FEMSurfaceDesc<'a, N> {
@@ -1143,6 +1175,7 @@ impl<'a, N: RealField> FEMSurfaceDesc<'a, N> {
         FEMSurfaceDesc {
             user_data: None,
             gravity_enabled: true,
+            gravity_scale: N::one(),
             geom,
             scale: Vector::repeat(N::one()),
             position: Isometry::identity(),
@@ -1184,6 +1217,7 @@ impl<'a, N: RealField> FEMSurfaceDesc<'a, N> {
 
     desc_setters!(
         gravity_enabled, enable_gravity, gravity_enabled: bool
+        gravity_scale, set_gravity_scale, gravity_scale: N
         scale, set_scale, scale: Vector<N>
         young_modulus, set_young_modulus, young_modulus: N
         poisson_ratio, set_poisson_ratio, poisson_ratio: N
@@ -1205,6 +1239,7 @@ impl<'a, N: RealField> FEMSurfaceDesc<'a, N> {
 
     desc_getters!(
         [val] is_gravity_enabled -> gravity_enabled: bool
+        [val] get_gravity_scale -> gravity_scale: N
         [val] get_young_modulus -> young_modulus: N
         [val] get_poisson_ratio -> poisson_ratio: N
         [val] get_sleep_threshold -> sleep_threshold: Option<N>
@@ -1244,6 +1279,7 @@ impl<'a, N: RealField> FEMSurfaceDesc<'a, N> {
         vol.set_deactivation_threshold(self.sleep_threshold);
         vol.set_plasticity(self.plasticity.0, self.plasticity.1, self.plasticity.2);
         vol.enable_gravity(self.gravity_enabled);
+        vol.set_gravity_scale(self.gravity_scale);
         vol.set_status(self.status);
         let _ = vol.set_user_data(self.user_data.as_ref().map(|data| data.0.to_any()));
 
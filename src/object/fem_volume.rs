@@ -20,9 +20,11 @@ use crate::object::{
 };
 use crate::solver::{ForceDirection, IntegrationParameters};
 use crate::utils::{UserData, UserDataBox};
+use crate::world::GravityField;
 
 /// One element of a deformable volume.
 #[derive(Clone)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct TetrahedralElement<N: RealField> {
     indices: Point4<usize>,
     com: Point3<N>,
@@ -40,6 +42,7 @@ pub struct TetrahedralElement<N: RealField> {
 ///
 /// The volume is described by a set of tetrahedral elements. This
 /// implements an isoparametric approach where the interpolations are linear.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct FEMVolume<N: RealField> {
     elements: Vec<TetrahedralElement<N>>,
     kinematic_nodes: DVector<bool>,
@@ -55,6 +58,7 @@ pub struct FEMVolume<N: RealField> {
 
     // Parameters
     gravity_enabled: bool,
+    gravity_scale: N,
     rest_positions: DVector<N>,
     damping_coeffs: (N, N),
     young_modulus: N,
@@ -73,6 +77,13 @@ pub struct FEMVolume<N: RealField> {
     status: BodyStatus,
     update_status: BodyUpdateStatus,
 
+    #[cfg_attr(
+        feature = "serde-serialize",
+        serde(
+            serialize_with = "crate::utils::serialize_user_data",
+            deserialize_with = "crate::utils::deserialize_user_data"
+        )
+    )]
     user_data: Option<Box<dyn Any + Send + Sync>>,
 }
 
@@ -173,6 +184,7 @@ impl<N: RealField> FEMVolume<N> {
             status: BodyStatus::Dynamic,
             update_status: BodyUpdateStatus::all(),
             gravity_enabled: true,
+            gravity_scale: N::one(),
             user_data: None,
         }
     }
@@ -360,7 +372,11 @@ impl<N: RealField> FEMVolume<N> {
         //        println!("Augmented mass: {}", self.augmented_mass);
     }
 
-    fn assemble_forces(&mut self, gravity: &Vector3<N>, parameters: &IntegrationParameters<N>) {
+    fn assemble_forces(
+        &mut self,
+        gravity: &dyn GravityField<N>,
+        parameters: &IntegrationParameters<N>,
+    ) {
         let _1: N = na::one();
         let _2: N = na::convert(2.0);
         let _6: N = na::convert(6.0);
@@ -371,15 +387,16 @@ impl<N: RealField> FEMVolume<N> {
         // Gravity
         if self.gravity_enabled {
             for elt in self.elements.iter() {
-                let contribution =
-                    gravity * (elt.density * elt.volume * na::convert::<_, N>(1.0 / 4.0));
+                let node_weight =
+                    elt.density * elt.volume * na::convert::<_, N>(1.0 / 4.0) * self.gravity_scale;
 
                 for k in 0..4 {
                     let ie = elt.indices[k];
 
                     if !self.kinematic_nodes[ie / DIM] {
+                        let node = Point3::from(self.positions.fixed_rows::<U3>(ie).into_owned());
                         let mut forces_part = self.accelerations.fixed_rows_mut::<U3>(ie);
-                        forces_part += contribution;
+                        forces_part += gravity.gravity_at(&node) * node_weight;
                     }
                 }
             }
@@ -770,6 +787,16 @@ impl<N: RealField> Body<N> for FEMVolume<N> {
         self.gravity_enabled = enabled
     }
 
+    #[inline]
+    fn gravity_scale(&self) -> N {
+        self.gravity_scale
+    }
+
+    #[inline]
+    fn set_gravity_scale(&mut self, scale: N) {
+        self.gravity_scale = scale
+    }
+
     #[inline]
     fn deformed_positions(&self) -> Option<(DeformationsType, &[N])> {
         Some((DeformationsType::Vectors, self.positions.as_slice()))
@@ -825,7 +852,11 @@ impl<N: RealField> Body<N> for FEMVolume<N> {
         }
     }
 
-    fn update_acceleration(&mut self, gravity: &Vector3<N>, parameters: &IntegrationParameters<N>) {
+    fn update_acceleration(
+        &mut self,
+        gravity: &dyn GravityField<N>,
+        parameters: &IntegrationParameters<N>,
+    ) {
         self.assemble_forces(gravity, parameters);
         self.inv_augmented_mass.solve_mut(&mut self.accelerations);
     }
@@ -1209,6 +1240,7 @@ enum FEMVolumeDescGeometry<'a, N: RealField> {
 pub struct FEMVolumeDesc<'a, N: RealField> {
     user_data: Option<UserDataBox>,
     gravity_enabled: bool,
+    gravity_scale: N,
     geom: FEMVolumeDescGeometry<'a, N>,
     scale: Vector3<N>,
     position: Isometry3<N>,
@@ -1229,6 +1261,7 @@ impl<'a, N: RealField> FEMVolumeDesc<'a, N> {
         FEMVolumeDesc {
             user_data: None,
             gravity_enabled: true,
+            gravity_scale: N::one(),
             geom,
             scale: Vector3::repeat(N::one()),
             position: Isometry3::identity(),
@@ -1272,6 +1305,7 @@ impl<'a, N: RealField> FEMVolumeDesc<'a, N> {
 
     desc_setters!(
         gravity_enabled, enable_gravity, gravity_enabled: bool
+        gravity_scale, set_gravity_scale, gravity_scale: N
         scale, set_scale, scale: Vector3<N>
         young_modulus, set_young_modulus, young_modulus: N
         poisson_ratio, set_poisson_ratio, poisson_ratio: N
@@ -1293,6 +1327,7 @@ impl<'a, N: RealField> FEMVolumeDesc<'a, N> {
 
     desc_getters!(
         [val] is_gravity_enabled -> gravity_enabled: bool
+        [val] get_gravity_scale -> gravity_scale: N
         [val] get_young_modulus -> young_modulus: N
         [val] get_poisson_ratio -> poisson_ratio: N
         [val] get_sleep_threshold -> sleep_threshold: Option<N>
@@ -1334,6 +1369,7 @@ impl<'a, N: RealField> FEMVolumeDesc<'a, N> {
         vol.set_deactivation_threshold(self.sleep_threshold);
         vol.set_plasticity(self.plasticity.0, self.plasticity.1, self.plasticity.2);
         vol.enable_gravity(self.gravity_enabled);
+        vol.set_gravity_scale(self.gravity_scale);
         vol.set_status(self.status);
         let _ = vol.set_user_data(self.user_data.as_ref().map(|data| data.0.to_any()));
 
@@ -5,6 +5,7 @@ use crate::object::{
     ActivationStatus, Body, BodyPart, BodyPartMotion, BodyStatus, BodyUpdateStatus,
 };
 use crate::solver::{ForceDirection, IntegrationParameters};
+use crate::world::GravityField;
 use ncollide::shape::DeformationsType;
 
 /// A singleton representing the ground.
@@ -12,6 +13,7 @@ use ncollide::shape::DeformationsType;
 /// Most of its methods are useless but provided anyway to be
 /// similar to the other bodies.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct Ground<N: RealField> {
     companion_id: usize,
     activation: ActivationStatus<N>,
@@ -38,6 +40,14 @@ impl<N: RealField> Body<N> for Ground<N> {
     #[inline]
     fn enable_gravity(&mut self, _: bool) {}
 
+    #[inline]
+    fn gravity_scale(&self) -> N {
+        N::zero()
+    }
+
+    #[inline]
+    fn set_gravity_scale(&mut self, _: N) {}
+
     #[inline]
     fn step_started(&mut self) {}
 
@@ -48,7 +58,7 @@ impl<N: RealField> Body<N> for Ground<N> {
     fn update_dynamics(&mut self, _: N) {}
 
     #[inline]
-    fn update_acceleration(&mut self, _: &Vector<N>, _: &IntegrationParameters<N>) {}
+    fn update_acceleration(&mut self, _: &dyn GravityField<N>, _: &IntegrationParameters<N>) {}
 
     #[inline]
     fn clear_forces(&mut self) {}
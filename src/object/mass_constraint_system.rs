@@ -25,15 +25,18 @@ use crate::object::{
 use crate::solver::{ForceDirection, IntegrationParameters};
 
 use crate::utils::{UserData, UserDataBox};
+use crate::world::GravityField;
 
 /// A triangular element of the mass-LengthConstraint surface.
 #[derive(Clone)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct MassConstraintElement<N: RealField> {
     indices: FiniteElementIndices,
     phantom: PhantomData<N>,
 }
 
 #[derive(Clone)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 struct LengthConstraint<N: RealField> {
     nodes: (usize, usize),
     // Should be Unit<Vector<N>>, but can be zero.
@@ -74,6 +77,7 @@ fn key(i: usize, j: usize) -> (usize, usize) {
 }
 
 /// A deformable surface using a mass-LengthConstraint model with triangular elements.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct MassConstraintSystem<N: RealField> {
     constraints: Vec<LengthConstraint<N>>,
     elements: Vec<MassConstraintElement<N>>,
@@ -86,6 +90,7 @@ pub struct MassConstraintSystem<N: RealField> {
 
     companion_id: usize,
     gravity_enabled: bool,
+    gravity_scale: N,
     activation: ActivationStatus<N>,
     status: BodyStatus,
     update_status: BodyUpdateStatus,
@@ -98,6 +103,13 @@ pub struct MassConstraintSystem<N: RealField> {
     plasticity_creep: N,
     plasticity_max_force: N,
 
+    #[cfg_attr(
+        feature = "serde-serialize",
+        serde(
+            serialize_with = "crate::utils::serialize_user_data",
+            deserialize_with = "crate::utils::deserialize_user_data"
+        )
+    )]
     user_data: Option<Box<dyn Any + Send + Sync>>,
 }
 
@@ -155,6 +167,7 @@ impl<N: RealField> MassConstraintSystem<N> {
             node_mass,
             inv_node_mass: N::one() / node_mass,
             gravity_enabled: true,
+            gravity_scale: N::one(),
             warmstart_coeff: na::convert(0.5),
             plasticity_threshold: N::zero(),
             plasticity_creep: N::zero(),
@@ -205,6 +218,7 @@ impl<N: RealField> MassConstraintSystem<N> {
             status: BodyStatus::Dynamic,
             update_status: BodyUpdateStatus::all(),
             gravity_enabled: true,
+            gravity_scale: N::one(),
             mass,
             node_mass,
             inv_node_mass: N::one() / node_mass,
@@ -301,6 +315,11 @@ impl<N: RealField> MassConstraintSystem<N> {
         self.positions.len() / DIM
     }
 
+    /// The indices of the degrees of freedom of the nodes of each element of this mass-constraint system.
+    pub(crate) fn element_indices(&self) -> impl Iterator<Item = FiniteElementIndices> + '_ {
+        self.elements.iter().map(|elt| elt.indices)
+    }
+
     /// The total mass of this body.
     pub fn mass(&self) -> N {
         self.mass
@@ -353,6 +372,16 @@ impl<N: RealField> Body<N> for MassConstraintSystem<N> {
         self.gravity_enabled = enabled
     }
 
+    #[inline]
+    fn gravity_scale(&self) -> N {
+        self.gravity_scale
+    }
+
+    #[inline]
+    fn set_gravity_scale(&mut self, scale: N) {
+        self.gravity_scale = scale
+    }
+
     fn update_kinematics(&mut self) {
         if self.update_status.position_changed() {
             for constraint in &mut self.constraints {
@@ -380,16 +409,19 @@ impl<N: RealField> Body<N> for MassConstraintSystem<N> {
         }
     }
 
-    fn update_acceleration(&mut self, gravity: &Vector<N>, parameters: &IntegrationParameters<N>) {
+    fn update_acceleration(
+        &mut self,
+        gravity: &dyn GravityField<N>,
+        parameters: &IntegrationParameters<N>,
+    ) {
         self.accelerations.copy_from(&self.forces);
 
         if self.gravity_enabled {
-            let gravity_acc = gravity;
-
             for i in 0..self.positions.len() / DIM {
                 if !self.kinematic_nodes[i] {
+                    let node = Point::from(self.positions.fixed_rows::<Dim>(i * DIM).into_owned());
                     let mut acc = self.accelerations.fixed_rows_mut::<Dim>(i * DIM);
-                    acc += gravity_acc
+                    acc += gravity.gravity_at(&node) * self.gravity_scale
                 }
             }
         }
@@ -926,6 +958,7 @@ pub struct MassConstraintSystemDesc<'a, N: RealField> {
     kinematic_nodes: Vec<usize>,
     status: BodyStatus,
     gravity_enabled: bool,
+    gravity_scale: N,
 }
 
 impl<'a, N: RealField> MassConstraintSystemDesc<'a, N> {
@@ -933,6 +966,7 @@ impl<'a, N: RealField> MassConstraintSystemDesc<'a, N> {
         MassConstraintSystemDesc {
             user_data: None,
             gravity_enabled: true,
+            gravity_scale: N::one(),
             geom,
             stiffness: Some(na::convert(1.0e3)),
             sleep_threshold: Some(ActivationStatus::default_threshold()),
@@ -975,6 +1009,7 @@ impl<'a, N: RealField> MassConstraintSystemDesc<'a, N> {
 
     desc_setters!(
             gravity_enabled, enable_gravity, gravity_enabled: bool
+            gravity_scale, set_gravity_scale, gravity_scale: N
             stiffness, set_stiffness, stiffness: Option<N>
             sleep_threshold, set_sleep_threshold, sleep_threshold: Option<N>
     //        damping_ratio, set_damping_ratio, damping_ratio: N
@@ -991,6 +1026,7 @@ impl<'a, N: RealField> MassConstraintSystemDesc<'a, N> {
 
     desc_getters!(
             [val] is_gravity_enabled -> gravity_enabled: bool
+            [val] get_gravity_scale -> gravity_scale: N
             [val] get_stiffness -> stiffness: Option<N>
             [val] get_sleep_threshold -> sleep_threshold: Option<N>
     //        [val] get_damping_ratio -> damping_ratio: N
@@ -1017,6 +1053,7 @@ impl<'a, N: RealField> MassConstraintSystemDesc<'a, N> {
         vol.set_deactivation_threshold(self.sleep_threshold);
         vol.set_plasticity(self.plasticity.0, self.plasticity.1, self.plasticity.2);
         vol.enable_gravity(self.gravity_enabled);
+        vol.set_gravity_scale(self.gravity_scale);
         vol.set_status(self.status);
         let _ = vol.set_user_data(self.user_data.as_ref().map(|data| data.0.to_any()));
 
@@ -27,15 +27,18 @@ use crate::object::{
 use crate::solver::{ForceDirection, IntegrationParameters};
 
 use crate::utils::{UserData, UserDataBox};
+use crate::world::GravityField;
 
 /// An element of the mass-spring system.
 #[derive(Clone)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct MassSpringElement<N: RealField> {
     indices: FiniteElementIndices,
     phantom: PhantomData<N>,
 }
 
 #[derive(Clone)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 struct Spring<N: RealField> {
     nodes: (usize, usize),
     // Should be Unit<Vector<N>>, but can be zero.
@@ -71,6 +74,7 @@ impl<N: RealField> Spring<N> {
 }
 
 /// A deformable surface using a mass-spring model with triangular elements.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct MassSpringSystem<N: RealField> {
     springs: Vec<Spring<N>>,
     elements: Vec<MassSpringElement<N>>,
@@ -86,6 +90,7 @@ pub struct MassSpringSystem<N: RealField> {
 
     companion_id: usize,
     gravity_enabled: bool,
+    gravity_scale: N,
     activation: ActivationStatus<N>,
     status: BodyStatus,
     update_status: BodyUpdateStatus,
@@ -96,6 +101,13 @@ pub struct MassSpringSystem<N: RealField> {
     plasticity_creep: N,
     plasticity_max_force: N,
 
+    #[cfg_attr(
+        feature = "serde-serialize",
+        serde(
+            serialize_with = "crate::utils::serialize_user_data",
+            deserialize_with = "crate::utils::deserialize_user_data"
+        )
+    )]
     user_data: Option<Box<dyn Any + Send + Sync>>,
 }
 
@@ -180,6 +192,7 @@ impl<N: RealField> MassSpringSystem<N> {
             plasticity_creep: N::zero(),
             plasticity_threshold: N::zero(),
             gravity_enabled: true,
+            gravity_scale: N::one(),
             user_data: None,
         }
     }
@@ -235,6 +248,7 @@ impl<N: RealField> MassSpringSystem<N> {
             status: BodyStatus::Dynamic,
             update_status: BodyUpdateStatus::all(),
             gravity_enabled: true,
+            gravity_scale: N::one(),
             mass,
             node_mass,
             plasticity_max_force: N::zero(),
@@ -277,6 +291,11 @@ impl<N: RealField> MassSpringSystem<N> {
         self.positions.len() / DIM
     }
 
+    /// The indices of the degrees of freedom of the nodes of each element of this mass-spring system.
+    pub(crate) fn element_indices(&self) -> impl Iterator<Item = FiniteElementIndices> + '_ {
+        self.elements.iter().map(|elt| elt.indices)
+    }
+
     /// Generate additional springs between nodes that are transitively neighbors.
     ///
     /// Given three nodes `a, b, c`, if a spring exists between `a` and `b`, and between `b` and `c`,
@@ -441,7 +460,11 @@ impl<N: RealField> MassSpringSystem<N> {
         self.inv_augmented_mass = Cholesky::new(self.augmented_mass.clone()).unwrap();
     }
 
-    fn update_forces(&mut self, gravity: &Vector<N>, parameters: &IntegrationParameters<N>) {
+    fn update_forces(
+        &mut self,
+        gravity: &dyn GravityField<N>,
+        parameters: &IntegrationParameters<N>,
+    ) {
         self.accelerations.copy_from(&self.forces);
 
         for spring in &mut self.springs {
@@ -507,14 +530,15 @@ impl<N: RealField> MassSpringSystem<N> {
          * Add forces due to gravity.
          */
         if self.gravity_enabled {
-            let gravity_force = gravity * self.node_mass;
+            let node_weight = self.node_mass * self.gravity_scale;
 
             for i in 0..self.positions.len() / DIM {
                 let idof = i * DIM;
 
                 if !self.kinematic_nodes[i] {
+                    let node = Point::from(self.positions.fixed_rows::<Dim>(idof).into_owned());
                     let mut acc = self.accelerations.fixed_rows_mut::<Dim>(idof);
-                    acc += gravity_force
+                    acc += gravity.gravity_at(&node) * node_weight
                 }
             }
         }
@@ -534,6 +558,16 @@ impl<N: RealField> Body<N> for MassSpringSystem<N> {
         self.gravity_enabled = enabled
     }
 
+    #[inline]
+    fn gravity_scale(&self) -> N {
+        self.gravity_scale
+    }
+
+    #[inline]
+    fn set_gravity_scale(&mut self, scale: N) {
+        self.gravity_scale = scale
+    }
+
     fn update_kinematics(&mut self) {
         if self.update_status.position_changed() {
             for spring in &mut self.springs {
@@ -562,7 +596,11 @@ impl<N: RealField> Body<N> for MassSpringSystem<N> {
         }
     }
 
-    fn update_acceleration(&mut self, gravity: &Vector<N>, parameters: &IntegrationParameters<N>) {
+    fn update_acceleration(
+        &mut self,
+        gravity: &dyn GravityField<N>,
+        parameters: &IntegrationParameters<N>,
+    ) {
         self.update_forces(gravity, parameters);
     }
 
@@ -933,6 +971,7 @@ pub struct MassSpringSystemDesc<'a, N: RealField> {
     kinematic_nodes: Vec<usize>,
     status: BodyStatus,
     gravity_enabled: bool,
+    gravity_scale: N,
 }
 
 impl<'a, N: RealField> MassSpringSystemDesc<'a, N> {
@@ -940,6 +979,7 @@ impl<'a, N: RealField> MassSpringSystemDesc<'a, N> {
         MassSpringSystemDesc {
             user_data: None,
             gravity_enabled: true,
+            gravity_scale: N::one(),
             geom,
             stiffness: na::convert(1.0e3),
             sleep_threshold: Some(ActivationStatus::default_threshold()),
@@ -982,6 +1022,7 @@ impl<'a, N: RealField> MassSpringSystemDesc<'a, N> {
 
     desc_setters!(
         gravity_enabled, enable_gravity, gravity_enabled: bool
+        gravity_scale, set_gravity_scale, gravity_scale: N
         stiffness, set_stiffness, stiffness: N
         sleep_threshold, set_sleep_threshold, sleep_threshold: Option<N>
         damping_ratio, set_damping_ratio, damping_ratio: N
@@ -998,6 +1039,7 @@ impl<'a, N: RealField> MassSpringSystemDesc<'a, N> {
 
     desc_getters!(
         [val] is_gravity_enabled -> gravity_enabled: bool
+        [val] get_gravity_scale -> gravity_scale: N
         [val] get_stiffness -> stiffness: N
         [val] get_sleep_threshold -> sleep_threshold: Option<N>
         [val] get_damping_ratio -> damping_ratio: N
@@ -1035,6 +1077,7 @@ impl<'a, N: RealField> MassSpringSystemDesc<'a, N> {
         vol.set_deactivation_threshold(self.sleep_threshold);
         vol.set_plasticity(self.plasticity.0, self.plasticity.1, self.plasticity.2);
         vol.enable_gravity(self.gravity_enabled);
+        vol.set_gravity_scale(self.gravity_scale);
         vol.set_status(self.status);
         let _ = vol.set_user_data(self.user_data.as_ref().map(|data| data.0.to_any()));
 
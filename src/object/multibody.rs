@@ -1,7 +1,7 @@
 use std::any::Any;
 use std::ops::MulAssign;
 
-use crate::joint::Joint;
+use crate::joint::{FreeJoint, Joint};
 use crate::math::{
     AngularDim, Dim, Force, ForceType, Inertia, Isometry, Jacobian, Point, SpatialMatrix,
     Translation, Vector, Velocity, DIM,
@@ -17,8 +17,10 @@ use na::{self, DMatrix, DVector, DVectorSlice, DVectorSliceMut, Dynamic, MatrixM
 use ncollide::shape::DeformationsType;
 
 use crate::utils::{GeneralizedCross, IndexMut2};
+use crate::world::GravityField;
 
 /// An articulated body simulated using the reduced-coordinates approach.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct Multibody<N: RealField> {
     rbs: MultibodyLinkVec<N>,
     velocities: DVector<N>,
@@ -29,13 +31,25 @@ pub struct Multibody<N: RealField> {
     body_jacobians: Vec<Jacobian<N>>,
     // FIXME: use sparse matrices.
     augmented_mass: DMatrix<N>,
+    // Not serialized because it contains uninitialized data. It is recomputed
+    // from `augmented_mass` by `update_dynamics` after deserialization.
+    #[cfg_attr(feature = "serde-serialize", serde(skip, default = "empty_lu"))]
     inv_augmented_mass: LU<N, Dynamic, Dynamic>,
     status: BodyStatus,
     gravity_enabled: bool,
+    gravity_scale: N,
     update_status: BodyUpdateStatus,
     activation: ActivationStatus<N>,
     ndofs: usize,
     companion_id: usize,
+    inv_dt: N,
+    #[cfg_attr(
+        feature = "serde-serialize",
+        serde(
+            serialize_with = "crate::utils::serialize_user_data",
+            deserialize_with = "crate::utils::deserialize_user_data"
+        )
+    )]
     user_data: Option<Box<dyn Any + Send + Sync>>,
 
     /*
@@ -51,9 +65,15 @@ pub struct Multibody<N: RealField> {
      * FIXME: we should void explicitly generating those constraints by
      * just iterating on all joints at each step of the resolution.
      */
+    #[cfg_attr(feature = "serde-serialize", serde(skip, default = "Option::default"))]
     solver_workspace: Option<SolverWorkspace<N, (), ()>>,
 }
 
+#[cfg(feature = "serde-serialize")]
+fn empty_lu<N: RealField>() -> LU<N, Dynamic, Dynamic> {
+    LU::new(DMatrix::zeros(0, 0))
+}
+
 impl<N: RealField> Multibody<N> {
     /// Creates a new multibody with no link.
     fn new() -> Self {
@@ -70,9 +90,11 @@ impl<N: RealField> Multibody<N> {
             status: BodyStatus::Dynamic,
             update_status: BodyUpdateStatus::all(),
             gravity_enabled: true,
+            gravity_scale: N::one(),
             activation: ActivationStatus::new_active(),
             ndofs: 0,
             companion_id: 0,
+            inv_dt: N::zero(),
             workspace: MultibodyWorkspace::new(),
             coriolis_v: Vec::new(),
             coriolis_w: Vec::new(),
@@ -268,7 +290,7 @@ impl<N: RealField> Multibody<N> {
             .resize_vertically_mut(len + nimpulses, N::zero());
     }
 
-    fn update_acceleration(&mut self, gravity: &Vector<N>) {
+    fn update_acceleration(&mut self, gravity: &dyn GravityField<N>) {
         if self.status != BodyStatus::Dynamic {
             return;
         }
@@ -308,7 +330,8 @@ impl<N: RealField> Multibody<N> {
                 self.workspace.accs[i] = acc;
 
                 let gravity_force = if self.gravity_enabled {
-                    gravity * rb.inertia.mass()
+                    gravity.gravity_at(&rb.center_of_mass())
+                        * (rb.inertia.mass() * self.gravity_scale)
                 } else {
                     Vector::zeros()
                 };
@@ -347,6 +370,13 @@ impl<N: RealField> Multibody<N> {
     /// Computes the constant terms of the dynamics.
     fn update_dynamics(&mut self, dt: N) {
         if !self.update_status.inertia_needs_update() {
+            #[cfg(feature = "serde-serialize")]
+            {
+                if self.inv_augmented_mass.lu_internal().nrows() != self.augmented_mass.nrows() {
+                    self.inv_augmented_mass = LU::new(self.augmented_mass.clone());
+                }
+            }
+
             return;
         }
 
@@ -636,6 +666,149 @@ impl<N: RealField> Multibody<N> {
         )
     }
 
+    /// The generalized force applied by the limits and motors of the joint of the given link during the last timestep.
+    ///
+    /// It has one component per degree of freedom of the joint, e.g., the torque along the rotation axis of a
+    /// revolute joint. This is not the reaction force of the joint: the forces keeping the link attached to its
+    /// parent along the locked degrees of freedom are never computed by the reduced-coordinates approach, so they
+    /// are not included.
+    pub fn joint_limit_and_motor_force(&self, link: &MultibodyLink<N>) -> DVector<N> {
+        self.joint_limit_and_motor_impulse(link) * self.inv_dt
+    }
+
+    /// The generalized impulse applied by the limits and motors of the joint of the given link during the last timestep.
+    fn joint_limit_and_motor_impulse(&self, link: &MultibodyLink<N>) -> DVector<N> {
+        let ndofs = link.dof.ndofs();
+        let mut res = DVector::zeros(ndofs);
+
+        if let Some(workspace) = &self.solver_workspace {
+            let impulses = link.impulse_id..link.impulse_id + link.dof.nimpulses();
+            let jacobians = workspace.jacobians.as_slice();
+            let constraints = &workspace.constraints.velocity;
+            let unilateral = constraints
+                .unilateral_ground
+                .iter()
+                .map(|c| (c.impulse_id, c.j_id, c.impulse));
+            let bilateral = constraints
+                .bilateral_ground
+                .iter()
+                .map(|c| (c.impulse_id, c.j_id, c.impulse));
+
+            for (impulse_id, j_id, impulse) in unilateral.chain(bilateral) {
+                if impulses.contains(&impulse_id) {
+                    let j_id = j_id + link.assembly_id;
+                    let jacobian = DVectorSlice::from_slice(&jacobians[j_id..j_id + ndofs], ndofs);
+                    res.axpy(impulse, &jacobian, N::one());
+                }
+            }
+        }
+
+        res
+    }
+
+    /// Detaches the links which joint limits and motors applied a force greater than their break force during the
+    /// last timestep.
+    ///
+    /// The closure `f` is called with the id of each link that broke and the generalized impulse that broke it.
+    /// Nothing happens if this multibody is asleep because its impulses are not up to date.
+    pub(crate) fn break_overloaded_links(&mut self, mut f: impl FnMut(usize, DVector<N>)) {
+        if !self.is_active() {
+            return;
+        }
+
+        let inv_dt2 = self.inv_dt * self.inv_dt;
+        let mut overloaded = Vec::new();
+
+        // Detaching a link changes the layout of the generalized coordinates, so all the
+        // impulses are read before any link is detached.
+        for link in self.rbs.iter() {
+            if link.broken || link.break_force_squared == N::max_value() {
+                continue;
+            }
+
+            let impulse = self.joint_limit_and_motor_impulse(link);
+
+            if impulse.norm_squared() * inv_dt2 > link.break_force_squared {
+                overloaded.push((link.internal_id, impulse));
+            }
+        }
+
+        for (link_id, impulse) in overloaded {
+            self.detach_link(link_id);
+            f(link_id, impulse)
+        }
+    }
+
+    // Replaces the joint of the given link by a free joint, so it moves with its descendants
+    // independently from its parent. The velocities of all the links are preserved.
+    fn detach_link(&mut self, link_id: usize) {
+        let parent_to_world = if link_id == 0 {
+            Isometry::identity()
+        } else {
+            self.rbs[self.rbs[link_id].parent_internal_id].local_to_world
+        };
+
+        let link = &mut self.rbs[link_id];
+        let joint_velocity = link
+            .velocity_wrt_joint
+            .transformed(&parent_to_world.inverse());
+        let free_joint = FreeJoint::new(link.local_to_parent);
+        let assembly_id = link.assembly_id;
+        let impulse_id = link.impulse_id;
+        let old_ndofs = link.dof.ndofs();
+        let old_nimpulses = link.dof.nimpulses();
+        let new_ndofs = free_joint.ndofs();
+        let new_nimpulses = free_joint.nimpulses();
+
+        link.dof = Box::new(free_joint);
+        link.parent_shift = Vector::zeros();
+        link.body_shift = Vector::zeros();
+        link.broken = true;
+
+        for link in self.rbs.iter_mut().skip(link_id + 1) {
+            link.assembly_id = link.assembly_id + new_ndofs - old_ndofs;
+            link.impulse_id = link.impulse_id + new_nimpulses - old_nimpulses;
+        }
+
+        let splice = |v: &DVector<N>, start: usize, removed: usize, inserted: &[N]| {
+            let mut res = Vec::with_capacity(v.len() + inserted.len() - removed);
+            res.extend_from_slice(&v.as_slice()[..start]);
+            res.extend_from_slice(inserted);
+            res.extend_from_slice(&v.as_slice()[start + removed..]);
+            DVector::from_vec(res)
+        };
+
+        let zeros = vec![N::zero(); new_ndofs];
+        self.velocities = splice(
+            &self.velocities,
+            assembly_id,
+            old_ndofs,
+            joint_velocity.as_slice(),
+        );
+        self.accelerations = splice(&self.accelerations, assembly_id, old_ndofs, &zeros);
+        self.forces = splice(&self.forces, assembly_id, old_ndofs, &zeros);
+        self.damping = splice(&self.damping, assembly_id, old_ndofs, &zeros);
+        self.impulses = splice(&self.impulses, impulse_id, old_nimpulses, &[]);
+        self.ndofs = self.ndofs + new_ndofs - old_ndofs;
+
+        // Force the reallocation of all the buffers depending on the number of degrees of freedom.
+        self.workspace.resize(self.rbs.len(), self.ndofs);
+        self.coriolis_v.clear();
+        self.coriolis_w.clear();
+
+        if let Some(workspace) = &mut self.solver_workspace {
+            workspace.constraints.clear();
+        }
+
+        // The update flags are cleared at the end of the timestep, so the kinematics
+        // and dynamics matching the new joint are computed right away.
+        self.update_status.set_position_changed(true);
+        self.update_status.set_velocity_changed(true);
+        self.update_status.set_damping_changed(true);
+        self.update_kinematics();
+        self.update_dynamics(N::one() / self.inv_dt);
+    }
+
     /// Convert a force applied to the center of mass of the link `rb_id` into generalized force.
     pub fn link_jacobian_mul_force(
         &self,
@@ -729,6 +902,7 @@ impl<N: RealField> Multibody<N> {
 }
 
 /// A temporary workspace for various updates of the multibody.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 struct MultibodyWorkspace<N: RealField> {
     accs: Vec<Velocity<N>>,
     ndofs_vec: DVector<N>,
@@ -752,6 +926,9 @@ impl<N: RealField> MultibodyWorkspace<N> {
 
 struct SolverWorkspace<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle> {
     jacobians: DVector<N>,
+    // Kept separate from `jacobians` so the velocity constraints jacobians are still
+    // available after the position constraints resolution.
+    position_jacobians: DVector<N>,
     constraints: ConstraintSet<N, Handle, CollHandle, usize>,
 }
 
@@ -761,6 +938,7 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
     pub fn new() -> Self {
         SolverWorkspace {
             jacobians: DVector::zeros(0),
+            position_jacobians: DVector::zeros(0),
             constraints: ConstraintSet::new(),
         }
     }
@@ -771,6 +949,10 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
         if self.jacobians.len() != j_len {
             self.jacobians = DVector::zeros(j_len);
         }
+
+        if self.position_jacobians.len() != ndofs * 2 {
+            self.position_jacobians = DVector::zeros(ndofs * 2);
+        }
     }
 }
 
@@ -866,7 +1048,7 @@ impl<N: RealField> Body<N> for Multibody<N> {
         self.update_dynamics(dt)
     }
 
-    fn update_acceleration(&mut self, gravity: &Vector<N>, _: &IntegrationParameters<N>) {
+    fn update_acceleration(&mut self, gravity: &dyn GravityField<N>, _: &IntegrationParameters<N>) {
         self.update_acceleration(gravity)
     }
 
@@ -896,6 +1078,16 @@ impl<N: RealField> Body<N> for Multibody<N> {
         self.gravity_enabled = enabled
     }
 
+    #[inline]
+    fn gravity_scale(&self) -> N {
+        self.gravity_scale
+    }
+
+    #[inline]
+    fn set_gravity_scale(&mut self, scale: N) {
+        self.gravity_scale = scale
+    }
+
     #[inline]
     fn activation_status(&self) -> &ActivationStatus<N> {
         &self.activation
@@ -1037,6 +1229,7 @@ impl<N: RealField> Body<N> for Multibody<N> {
     ) {
         let mut ground_j_id = 0;
         let mut workspace = self.solver_workspace.take().unwrap();
+        self.inv_dt = parameters.inv_dt();
 
         /*
          * Cache impulses from the last timestep for warmstarting.
@@ -1058,12 +1251,13 @@ impl<N: RealField> Body<N> for Multibody<N> {
         let nconstraints = self
             .rbs
             .iter()
+            .filter(|l| !l.broken)
             .map(|l| l.joint().num_velocity_constraints())
             .sum();
 
         workspace.resize(nconstraints, self.ndofs);
 
-        for link in self.rbs.iter() {
+        for link in self.rbs.iter().filter(|l| !l.broken) {
             link.joint().velocity_constraints(
                 parameters,
                 self,
@@ -1113,9 +1307,13 @@ impl<N: RealField> Body<N> for Multibody<N> {
         // FIXME: this `.take()` trick is ugly.
         // We should not pass a reference to the multibody to the link position constraint method.
         let mut workspace = self.solver_workspace.take().unwrap();
-        let jacobians = &mut workspace.jacobians;
+        let jacobians = &mut workspace.position_jacobians;
 
         for i in 0..self.rbs.len() {
+            if self.rbs[i].broken {
+                continue;
+            }
+
             for j in 0..self.rbs[i].joint().num_position_constraints() {
                 let link = &self.rbs[i];
                 // FIXME: should each link directly solve the constraint internally
@@ -1317,6 +1515,8 @@ pub struct MultibodyDesc<N: RealField> {
     local_center_of_mass: Point<N>,
     body_shift: Vector<N>,
     parent_shift: Vector<N>,
+    gravity_scale: N,
+    break_force: N,
 }
 
 impl<N: RealField> MultibodyDesc<N> {
@@ -1331,6 +1531,8 @@ impl<N: RealField> MultibodyDesc<N> {
             local_center_of_mass: Point::origin(),
             body_shift: Vector::zeros(),
             parent_shift: Vector::zeros(),
+            gravity_scale: N::one(),
+            break_force: N::max_value(),
         }
     }
 
@@ -1345,6 +1547,8 @@ impl<N: RealField> MultibodyDesc<N> {
             local_center_of_mass: Point::origin(),
             body_shift: Vector::zeros(),
             parent_shift: Vector::zeros(),
+            gravity_scale: N::one(),
+            break_force: N::max_value(),
         }
     }
 
@@ -1406,6 +1610,8 @@ impl<N: RealField> MultibodyDesc<N> {
             velocity, set_velocity, velocity: Velocity<N>
             local_inertia, set_local_inertia, local_inertia: Inertia<N>
             local_center_of_mass, set_local_center_of_mass, local_center_of_mass: Point<N>
+            gravity_scale, set_gravity_scale, gravity_scale: N
+            break_force, set_break_force, break_force: N
         );
 
     #[cfg(feature = "dim2")]
@@ -1422,6 +1628,8 @@ impl<N: RealField> MultibodyDesc<N> {
     );
 
     desc_getters!(
+        [val] get_gravity_scale -> gravity_scale: N
+        [val] get_break_force -> break_force: N
         [ref] get_parent_shift -> parent_shift: Vector<N>
         [ref] get_body_shift -> body_shift: Vector<N>
         [ref] get_velocity -> velocity: Velocity<N>
@@ -1430,8 +1638,11 @@ impl<N: RealField> MultibodyDesc<N> {
     );
 
     /// Build the multibody described by this factory.
+    ///
+    /// The gravity scale of the multibody is the one of `self`. The gravity scales of its children are ignored.
     pub fn build(&self) -> Multibody<N> {
         let mut multibody = Multibody::new();
+        multibody.gravity_scale = self.gravity_scale;
         let _ = self.do_build_with_parent(&mut multibody, None);
         multibody
     }
@@ -1462,6 +1673,10 @@ impl<N: RealField> MultibodyDesc<N> {
         link.velocity = self.velocity;
         link.name = self.name.clone();
 
+        if self.break_force != N::max_value() {
+            link.set_break_force(self.break_force);
+        }
+
         let me = link.link_id();
 
         for child in &self.children {
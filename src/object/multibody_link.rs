@@ -7,6 +7,7 @@ use crate::math::{Inertia, Isometry, Point, Vector, Velocity};
 use crate::object::BodyPart;
 
 /// One link of a multibody.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct MultibodyLink<N: RealField> {
     pub(crate) name: String,
     // FIXME: make all those private.
@@ -37,6 +38,9 @@ pub struct MultibodyLink<N: RealField> {
 
     pub(crate) local_inertia: Inertia<N>,
     pub(crate) local_com: Point<N>,
+
+    pub(crate) break_force_squared: N,
+    pub(crate) broken: bool,
     // TODO: User-defined data
     // user_data:       T
 }
@@ -84,6 +88,8 @@ impl<N: RealField> MultibodyLink<N> {
             local_com,
             inertia,
             com,
+            break_force_squared: N::max_value(),
+            broken: false,
         }
     }
 
@@ -129,6 +135,21 @@ impl<N: RealField> MultibodyLink<N> {
         self.name = name
     }
 
+    /// The maximum generalized force the limits and motors of this link's joint can apply before breaking.
+    ///
+    /// When it breaks, the joint is replaced by a free joint: the link and its descendants are no longer
+    /// attached to its parent, but remain part of the same multibody.
+    #[inline]
+    pub fn set_break_force(&mut self, break_force: N) {
+        self.break_force_squared = break_force * break_force;
+    }
+
+    /// Returns `true` if this link's joint broke and was replaced by a free joint.
+    #[inline]
+    pub fn is_broken(&self) -> bool {
+        self.broken
+    }
+
     /// The handle of this multibody link.
     #[inline]
     pub fn link_id(&self) -> usize {
@@ -184,6 +205,7 @@ impl<N: RealField> BodyPart<N> for MultibodyLink<N> {
 }
 
 // FIXME: keep this even if we already have the Index2 traits?
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub(crate) struct MultibodyLinkVec<N: RealField>(pub Vec<MultibodyLink<N>>);
 
 impl<N: RealField> MultibodyLinkVec<N> {
@@ -11,6 +11,7 @@ use crate::object::{
 use crate::solver::{ForceDirection, IntegrationParameters};
 
 use crate::utils::{UserData, UserDataBox};
+use crate::world::GravityField;
 use ncollide::interpolation::{
     ConstantLinearVelocityRigidMotion, ConstantVelocityRigidMotion, RigidMotion,
 };
@@ -23,6 +24,7 @@ use crate::utils::GeneralizedCross;
 
 /// A rigid body.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct RigidBody<N: RealField> {
     position0: Isometry<N>,
     position: Isometry<N>,
@@ -41,11 +43,19 @@ pub struct RigidBody<N: RealField> {
     max_angular_velocity: N,
     status: BodyStatus,
     gravity_enabled: bool,
+    gravity_scale: N,
     linear_motion_interpolation_enabled: bool,
     activation: ActivationStatus<N>,
     jacobian_mask: SpatialVector<N>,
     companion_id: usize,
     update_status: BodyUpdateStatus,
+    #[cfg_attr(
+        feature = "serde-serialize",
+        serde(
+            serialize_with = "crate::utils::serialize_user_data",
+            deserialize_with = "crate::utils::deserialize_user_data"
+        )
+    )]
     user_data: Option<Box<dyn Any + Send + Sync>>,
 }
 
@@ -73,6 +83,7 @@ impl<N: RealField> RigidBody<N> {
             max_angular_velocity: N::max_value(),
             status: BodyStatus::Dynamic,
             gravity_enabled: true,
+            gravity_scale: N::one(),
             linear_motion_interpolation_enabled: false,
             activation: ActivationStatus::new_active(),
             jacobian_mask: SpatialVector::repeat(N::one()),
@@ -587,7 +598,7 @@ impl<N: RealField> Body<N> for RigidBody<N> {
         }
     }
 
-    fn update_acceleration(&mut self, gravity: &Vector<N>, _: &IntegrationParameters<N>) {
+    fn update_acceleration(&mut self, gravity: &dyn GravityField<N>, _: &IntegrationParameters<N>) {
         self.acceleration = Velocity::zero();
 
         match self.status {
@@ -606,7 +617,7 @@ impl<N: RealField> Body<N> for RigidBody<N> {
                 }
 
                 if self.inv_augmented_mass.linear != N::zero() && self.gravity_enabled {
-                    self.acceleration.linear = *gravity;
+                    self.acceleration.linear = gravity.gravity_at(&self.com) * self.gravity_scale;
                 }
 
                 self.acceleration += self.inv_augmented_mass * self.external_forces;
@@ -662,6 +673,16 @@ impl<N: RealField> Body<N> for RigidBody<N> {
         self.gravity_enabled = enabled
     }
 
+    #[inline]
+    fn gravity_scale(&self) -> N {
+        self.gravity_scale
+    }
+
+    #[inline]
+    fn set_gravity_scale(&mut self, scale: N) {
+        self.gravity_scale = scale
+    }
+
     #[inline]
     fn velocity_at_point(&self, _: usize, point: &Point<N>) -> Velocity<N> {
         let pos = point - self.com;
@@ -949,6 +970,7 @@ impl<N: RealField> BodyPart<N> for RigidBody<N> {
 pub struct RigidBodyDesc<N: RealField> {
     user_data: Option<UserDataBox>,
     gravity_enabled: bool,
+    gravity_scale: N,
     linear_motion_interpolation_enabled: bool,
     position: Isometry<N>,
     velocity: Velocity<N>,
@@ -973,6 +995,7 @@ impl<'a, N: RealField> RigidBodyDesc<N> {
         RigidBodyDesc {
             user_data: None,
             gravity_enabled: true,
+            gravity_scale: N::one(),
             linear_motion_interpolation_enabled: false,
             position: Isometry::identity(),
             velocity: Velocity::zero(),
@@ -1015,6 +1038,7 @@ impl<'a, N: RealField> RigidBodyDesc<N> {
 
     desc_setters!(
         gravity_enabled, enable_gravity, gravity_enabled: bool
+        gravity_scale, set_gravity_scale, gravity_scale: N
         linear_motion_interpolation_enabled, enable_linear_motion_interpolation, linear_motion_interpolation_enabled: bool
         status, set_status, status: BodyStatus
         position, set_position, position: Isometry<N>
@@ -1050,6 +1074,7 @@ impl<'a, N: RealField> RigidBodyDesc<N> {
 
     desc_getters!(
         [val] is_gravity_enabled -> gravity_enabled: bool
+        [val] get_gravity_scale -> gravity_scale: N
         [val] is_linear_motion_interpolation_enabled -> linear_motion_interpolation_enabled: bool
         [val] get_status -> status: BodyStatus
         [val] get_sleep_threshold -> sleep_threshold: Option<N>
@@ -1074,6 +1099,7 @@ impl<'a, N: RealField> RigidBodyDesc<N> {
         rb.set_deactivation_threshold(self.sleep_threshold);
         rb.set_translations_kinematic(self.kinematic_translations);
         rb.enable_gravity(self.gravity_enabled);
+        rb.set_gravity_scale(self.gravity_scale);
         rb.set_linear_damping(self.linear_damping);
         rb.set_angular_damping(self.angular_damping);
         rb.set_max_linear_velocity(self.max_linear_velocity);
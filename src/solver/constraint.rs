@@ -191,6 +191,10 @@ pub struct BilateralConstraint<N: RealField, Id> {
     pub r: N,
     /// The target velocity change this constraint must apply.
     pub rhs: N,
+    /// The regularization (constraint force mixing) making this constraint soft.
+    ///
+    /// This is zero for rigid constraints.
+    pub(crate) cfm: N,
 
     /// Limits of impulse applicable by this constraint.
     pub limits: ImpulseLimits<N>,
@@ -236,6 +240,7 @@ impl<N: RealField, Id> BilateralConstraint<N, Id> {
             impulse,
             r: geom.r,
             rhs,
+            cfm: N::zero(),
             limits,
             impulse_id,
             assembly_id1,
@@ -248,6 +253,13 @@ impl<N: RealField, Id> BilateralConstraint<N, Id> {
             ndofs2: geom.ndofs2,
         }
     }
+
+    /// Makes this constraint soft using the given constraint force mixing coefficient.
+    #[inline]
+    pub(crate) fn soften(&mut self, cfm: N) {
+        self.r = N::one() / (N::one() / self.r + cfm);
+        self.cfm = cfm;
+    }
 }
 
 /// A bilateral (equality) constraint between a dynamic body and one without any degrees of freedom.
@@ -259,6 +271,10 @@ pub struct BilateralGroundConstraint<N: RealField, Id> {
     pub r: N,
     /// The target velocity change this constraint must apply.
     pub rhs: N,
+    /// The regularization (constraint force mixing) making this constraint soft.
+    ///
+    /// This is zero for rigid constraints.
+    pub(crate) cfm: N,
 
     /// Limits of impulse applicable by this constraint.
     pub limits: ImpulseLimits<N>,
@@ -292,6 +308,7 @@ impl<N: RealField, Id> BilateralGroundConstraint<N, Id> {
                 impulse,
                 r: geom.r,
                 rhs,
+                cfm: N::zero(),
                 limits,
                 impulse_id,
                 assembly_id: assembly_id2,
@@ -304,6 +321,7 @@ impl<N: RealField, Id> BilateralGroundConstraint<N, Id> {
                 impulse,
                 r: geom.r,
                 rhs,
+                cfm: N::zero(),
                 limits,
                 impulse_id,
                 assembly_id: assembly_id1,
@@ -313,4 +331,11 @@ impl<N: RealField, Id> BilateralGroundConstraint<N, Id> {
             }
         }
     }
+
+    /// Makes this constraint soft using the given constraint force mixing coefficient.
+    #[inline]
+    pub(crate) fn soften(&mut self, cfm: N) {
+        self.r = N::one() / (N::one() / self.r + cfm);
+        self.cfm = cfm;
+    }
 }
@@ -4,7 +4,7 @@ use downcast_rs::Downcast;
 use na::{DVector, RealField};
 use ncollide::query::ContactId;
 
-use crate::detection::ColliderContactManifold;
+use crate::detection::{ColliderContactManifold, ContactReport};
 use crate::material::MaterialsCoefficientsTable;
 use crate::object::{BodyHandle, BodySet, ColliderHandle};
 use crate::solver::{ConstraintSet, IntegrationParameters};
@@ -19,6 +19,9 @@ pub trait ContactModel<N: RealField, Handle: BodyHandle, CollHandle: ColliderHan
         manifold: &ColliderContactManifold<N, Handle, CollHandle>,
     ) -> usize;
     /// Generate all constraints for the given contact manifolds.
+    ///
+    /// Each simulation island has its own constraint set, and this may be called for
+    /// several islands before `cache_impulses` is called for each of them.
     fn constraints(
         &mut self,
         parameters: &IntegrationParameters<N>,
@@ -33,7 +36,41 @@ pub trait ContactModel<N: RealField, Handle: BodyHandle, CollHandle: ColliderHan
     );
 
     /// Stores all the impulses found by the solver into a cache for warmstarting.
+    ///
+    /// This is called once per simulation island, after its velocity constraints are solved. The given
+    /// constraint set only contains the constraints generated by this model for this island, so the
+    /// impulses cached for the other islands must not be discarded here.
     fn cache_impulses(&mut self, constraints: &ConstraintSet<N, Handle, CollHandle, ContactId>);
+
+    /// Re-keys the cached impulses after the narrow phase assigned new identifiers to existing contacts.
+    ///
+    /// Each element of `remap` is an `(old, new)` pair of contact identifiers. This happens
+    /// when a deserialized world is stepped for the first time.
+    fn remap_impulses(&mut self, _remap: &[(ContactId, ContactId)]) {}
+
+    /// Discards the contact reports of the previous timestep.
+    ///
+    /// This is called at the beginning of each timestep.
+    fn clear_contact_reports(&mut self) {}
+
+    /// The report of the given contact, if it was solved by this model since the last call to
+    /// `clear_contact_reports`.
+    fn contact_report(&self, _id: ContactId) -> Option<&ContactReport<N>> {
+        None
+    }
 }
 
 impl_downcast!(ContactModel<N, Handle, CollHandle> where N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle);
+
+#[cfg(feature = "serde-serialize")]
+use crate::solver::{SignoriniCoulombPyramidModel, SignoriniModel};
+
+#[cfg(feature = "serde-serialize")]
+serde_trait_object!(
+    ContactModel<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle> [N: RealField],
+    ContactModelRef,
+    ContactModelOwned {
+        SignoriniModel(SignoriniModel<N>),
+        SignoriniCoulombPyramidModel(SignoriniCoulombPyramidModel<N>),
+    }
+);
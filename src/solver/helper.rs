@@ -134,6 +134,153 @@ pub fn constraint_pair_geometry<N: RealField, B: ?Sized + Body<N>, H: BodyHandle
     res
 }
 
+/// A term `coeff * (dir . v)` of a coupled velocity, where `v` is the velocity of a body part at `point`.
+///
+/// If `dir` is a torque, `v` is the angular velocity of the body part and `point` is ignored.
+/// Every input are expressed in world-space.
+#[derive(Copy, Clone, Debug)]
+pub struct CouplingTerm<N: RealField> {
+    /// The point where the velocity of the body part is measured.
+    pub point: Point<N>,
+    /// The direction the velocity of the body part is projected on.
+    pub dir: ForceDirection<N>,
+    /// The coefficient the projected velocity is multiplied by.
+    pub coeff: N,
+}
+
+impl<N: RealField> CouplingTerm<N> {
+    /// Creates the term `coeff * (dir . v)` where `v` is the velocity of a body part at `point`.
+    #[inline]
+    pub fn new(point: Point<N>, dir: ForceDirection<N>, coeff: N) -> Self {
+        CouplingTerm { point, dir, coeff }
+    }
+}
+
+/// Fills all the jacobians (and the jacobians multiplied by the invers augmented mass matricxs) for a
+/// constraint coupling the velocity of two body parts.
+///
+/// The velocity of this constraint is the sum of the terms `terms1` evaluated on the first body part
+/// plus the sum of the terms `terms2` evaluated on the second body part.
+#[inline]
+pub fn coupled_constraint_geometry<N: RealField, B: ?Sized + Body<N>, H: BodyHandle>(
+    body1: &B,
+    part1: &dyn BodyPart<N>,
+    handle1: BodyPartHandle<H>,
+    body2: &B,
+    part2: &dyn BodyPart<N>,
+    handle2: BodyPartHandle<H>,
+    terms1: &[CouplingTerm<N>],
+    terms2: &[CouplingTerm<N>],
+    ground_j_id: &mut usize,
+    j_id: &mut usize,
+    jacobians: &mut [N],
+    ext_vels1: Option<&DVectorSlice<N>>,
+    ext_vels2: Option<&DVectorSlice<N>>,
+    mut out_vel: Option<&mut N>,
+) -> ConstraintGeometry<N> {
+    let mut res = ConstraintGeometry::new();
+
+    res.ndofs1 = body1.status_dependent_ndofs();
+    res.ndofs2 = body2.status_dependent_ndofs();
+
+    let out_j_id;
+    if res.ndofs1 == 0 || res.ndofs2 == 0 {
+        res.j_id1 = *ground_j_id;
+        out_j_id = ground_j_id;
+    } else {
+        res.j_id1 = *j_id;
+        out_j_id = j_id;
+    }
+
+    res.j_id2 = res.j_id1 + res.ndofs1;
+    res.wj_id1 = res.j_id2 + res.ndofs2;
+    res.wj_id2 = res.wj_id1 + res.ndofs1;
+
+    fill_coupled_constraint_geometry(
+        body1,
+        part1,
+        res.ndofs1,
+        terms1,
+        res.j_id1,
+        res.wj_id1,
+        jacobians,
+        ext_vels1,
+        out_vel.as_mut().map(|v| &mut **v),
+    );
+
+    fill_coupled_constraint_geometry(
+        body2, part2, res.ndofs2, terms2, res.j_id2, res.wj_id2, jacobians, ext_vels2, out_vel,
+    );
+
+    let j1 = DVectorSlice::from_slice(&jacobians[res.j_id1..], res.ndofs1);
+    let j2 = DVectorSlice::from_slice(&jacobians[res.j_id2..], res.ndofs2);
+    let invm_j1 = DVectorSlice::from_slice(&jacobians[res.wj_id1..], res.ndofs1);
+    let invm_j2 = DVectorSlice::from_slice(&jacobians[res.wj_id2..], res.ndofs2);
+
+    let mut inv_r = j1.dot(&invm_j1) + j2.dot(&invm_j2);
+
+    if handle1.0 == handle2.0 {
+        inv_r += j2.dot(&invm_j1) + j1.dot(&invm_j2);
+    }
+
+    if !inv_r.is_zero() {
+        res.r = N::one() / inv_r;
+    } else {
+        res.r = N::one()
+    }
+
+    *out_j_id += (res.ndofs1 + res.ndofs2) * 2;
+    res
+}
+
+// Fills the jacobian (and the jacobian multiplied by the inverse augmented mass matrix) of the sum
+// of the given terms evaluated on one body part, and adds the corresponding velocity to `out_vel`.
+fn fill_coupled_constraint_geometry<N: RealField, B: ?Sized + Body<N>>(
+    body: &B,
+    part: &dyn BodyPart<N>,
+    ndofs: usize,
+    terms: &[CouplingTerm<N>],
+    j_id: usize,
+    wj_id: usize,
+    jacobians: &mut [N],
+    ext_vels: Option<&DVectorSlice<N>>,
+    mut out_vel: Option<&mut N>,
+) {
+    let mut workspace = vec![N::zero(); ndofs * 2];
+
+    for i in 0..ndofs {
+        jacobians[j_id + i] = N::zero();
+        jacobians[wj_id + i] = N::zero();
+    }
+
+    for term in terms {
+        let mut inv_r = N::zero();
+        let mut vel = N::zero();
+
+        body.fill_constraint_geometry(
+            part,
+            ndofs,
+            &term.point,
+            &term.dir,
+            0,
+            ndofs,
+            &mut workspace,
+            &mut inv_r,
+            ext_vels,
+            Some(&mut vel),
+        );
+
+        for i in 0..ndofs {
+            jacobians[j_id + i] += workspace[i] * term.coeff;
+            jacobians[wj_id + i] += workspace[ndofs + i] * term.coeff;
+        }
+
+        if let Some(out_vel) = out_vel.as_mut() {
+            **out_vel += vel * term.coeff;
+        }
+    }
+}
+
 /// Test if a constraint between the two given bodies should be a ground
 /// constraint (a constraint between a dynamic body and one without any degree of freedom).
 #[inline]
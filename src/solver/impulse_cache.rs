@@ -4,3 +4,21 @@ use ncollide::query::ContactId;
 
 /// A cache for impulses resulting from contacts and joints.
 pub type ImpulseCache<N> = SecondaryMap<ContactId, N>;
+
+/// Re-keys the impulses of `cache` following the `(old, new)` contact id pairs of `remap`.
+///
+/// Impulses with an identifier not listed in `remap` are discarded.
+pub(crate) fn remap_impulse_cache<N: Clone>(
+    cache: &mut ImpulseCache<N>,
+    remap: &[(ContactId, ContactId)],
+) {
+    let mut remapped = ImpulseCache::new();
+
+    for (old, new) in remap {
+        if let Some(impulse) = cache.get(*old) {
+            let _ = remapped.insert(*new, impulse.clone());
+        }
+    }
+
+    *cache = remapped;
+}
@@ -2,6 +2,7 @@ use na::{self, RealField};
 
 /// Parameters for a time-step of the physics engine.
 #[derive(Clone)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct IntegrationParameters<N: RealField> {
     /// The timestep (default: `1.0 / 60.0`)
     dt: N,
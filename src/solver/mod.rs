@@ -6,7 +6,8 @@ pub use self::constraint::{
 };
 pub use self::constraint_set::{ConstraintSet, LinearConstraints};
 pub use self::contact_model::ContactModel;
-pub use self::helper::ForceDirection;
+pub use self::helper::{CouplingTerm, ForceDirection};
+pub(crate) use self::impulse_cache::remap_impulse_cache;
 pub use self::impulse_cache::ImpulseCache;
 pub use self::integration_parameters::IntegrationParameters;
 pub use self::moreau_jean_solver::MoreauJeanSolver;
@@ -5,21 +5,19 @@ use crate::counters::Counters;
 use crate::detection::ColliderContactManifold;
 use crate::joint::{JointConstraint, JointConstraintSet};
 use crate::material::MaterialsCoefficientsTable;
-use crate::object::{BodyHandle, BodySet, ColliderHandle, ColliderSet};
+use crate::object::{BodyHandle, BodySet, Collider, ColliderHandle, ColliderSet};
 use crate::solver::{
-    ConstraintSet, ContactModel, IntegrationParameters, NonlinearSORProx, SORProx,
+    ConstraintSet, ContactModel, IntegrationParameters, NonlinearConstraintGenerator,
+    NonlinearSORProx, SORProx,
 };
+#[cfg(feature = "parallel")]
+use crate::utils::{par_foreach_island, IslandBodyIndex};
 
 /// Moreau-Jean time-stepping scheme.
 pub struct MoreauJeanSolver<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle> {
-    jacobians: Vec<N>,
-    // FIXME: use a Vec or a DVector?
-    mj_lambda_vel: DVector<N>,
-    ext_vels: DVector<N>,
     contact_model: Box<dyn ContactModel<N, Handle, CollHandle>>,
-    contact_constraints: ConstraintSet<N, Handle, CollHandle, ContactId>,
-    joint_constraints: ConstraintSet<N, Handle, CollHandle, usize>,
-    internal_constraints: Vec<Handle>,
+    // One workspace per simulation island so they can be solved independently.
+    islands: Vec<IslandSolver<N, Handle, CollHandle>>,
 }
 
 impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
@@ -28,21 +26,38 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
     /// Create a new time-stepping scheme with the given contact model.
     pub fn new(contact_model: Box<dyn ContactModel<N, Handle, CollHandle>>) -> Self {
         MoreauJeanSolver {
-            jacobians: Vec::new(),
-            mj_lambda_vel: DVector::zeros(0),
-            ext_vels: DVector::zeros(0),
             contact_model,
-            contact_constraints: ConstraintSet::new(),
-            joint_constraints: ConstraintSet::new(),
-            internal_constraints: Vec::new(),
+            islands: Vec::new(),
         }
     }
 
+    /// The contact model.
+    pub fn contact_model(&self) -> &dyn ContactModel<N, Handle, CollHandle> {
+        &*self.contact_model
+    }
+
     /// Sets the contact model.
     pub fn set_contact_model(&mut self, model: Box<dyn ContactModel<N, Handle, CollHandle>>) {
         self.contact_model = model
     }
 
+    /// Discards the contact reports of the previous timestep.
+    pub(crate) fn clear_contact_reports(&mut self) {
+        self.contact_model.clear_contact_reports()
+    }
+
+    /// Re-keys the impulses cached by the contact model after the narrow phase assigned new contact ids.
+    #[cfg(feature = "serde-serialize")]
+    pub(crate) fn remap_contact_impulses(&mut self, remap: &[(ContactId, ContactId)]) {
+        self.contact_model.remap_impulses(remap)
+    }
+
+    fn reserve_islands(&mut self, nislands: usize) {
+        while self.islands.len() < nislands {
+            self.islands.push(IslandSolver::new())
+        }
+    }
+
     /// Perform one step of the time-stepping scheme.
     pub fn step<
         Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
@@ -59,8 +74,13 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
         parameters: &IntegrationParameters<N>,
         coefficients: &MaterialsCoefficientsTable<N>,
     ) {
+        self.reserve_islands(1);
+        let contact_model = &mut *self.contact_model;
+        let solver = &mut self.islands[0];
+
         counters.assembly_started();
-        self.assemble_system(
+        solver.assemble_system(
+            contact_model,
             counters,
             parameters,
             coefficients,
@@ -71,21 +91,108 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
             island_joints,
         );
         counters.assembly_completed();
-        counters.set_nconstraints(
-            self.contact_constraints.velocity.len() + self.joint_constraints.velocity.len(),
-        );
+        counters.set_nconstraints(solver.nconstraints());
 
         counters.velocity_resolution_started();
-        self.solve_velocity_constraints(parameters, bodies);
-        self.cache_impulses(parameters, bodies, joints, island_joints);
+        solver.solve_velocity_constraints(parameters, bodies);
+        solver.cache_impulses(contact_model, parameters, joints, island_joints);
         counters.velocity_resolution_completed();
 
         counters.velocity_update_started();
-        self.update_velocities_and_integrate(parameters, bodies, island);
+        solver.update_velocities_and_integrate(parameters, bodies, island);
         counters.velocity_update_completed();
 
         counters.position_resolution_started();
-        self.solve_position_constraints(parameters, bodies, colliders, joints, island_joints);
+        let joints = joint_refs(joints, island_joints);
+        let contact_colliders = solver.contact_colliders(colliders);
+        solver.solve_position_constraints(parameters, bodies, &contact_colliders, &joints);
+        counters.position_resolution_completed();
+    }
+
+    /// Perform one step of the time-stepping scheme on several independent islands.
+    ///
+    /// The `i`-th island is made of the bodies `islands[i]`, the contacts `manifolds[i]` and the joints
+    /// `island_joints[i]`. Two islands must not share any dynamic body. With the `parallel` feature enabled,
+    /// the islands are solved on several threads. The result does not depend on the number of threads.
+    pub fn step_islands<
+        Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
+        Constraints: JointConstraintSet<N, Handle>,
+    >(
+        &mut self,
+        counters: &mut Counters,
+        bodies: &mut dyn BodySet<N, Handle = Handle>,
+        colliders: &Colliders,
+        joints: &mut Constraints,
+        manifolds: &[Vec<ColliderContactManifold<N, Handle, CollHandle>>],
+        islands: &[Vec<Handle>],
+        island_joints: &[Vec<Constraints::Handle>],
+        parameters: &IntegrationParameters<N>,
+        coefficients: &MaterialsCoefficientsTable<N>,
+    ) {
+        let nislands = islands.len();
+        self.reserve_islands(nislands);
+        let contact_model = &mut *self.contact_model;
+        let solvers = &mut self.islands[..nislands];
+
+        // The contact model and the joints are shared by all the islands so
+        // everything that modifies them is executed sequentially.
+        counters.assembly_started();
+        let mut nconstraints = 0;
+
+        for (i, solver) in solvers.iter_mut().enumerate() {
+            solver.assemble_system(
+                contact_model,
+                counters,
+                parameters,
+                coefficients,
+                bodies,
+                joints,
+                &manifolds[i],
+                &islands[i],
+                &island_joints[i],
+            );
+            nconstraints += solver.nconstraints();
+        }
+
+        counters.assembly_completed();
+        counters.set_nconstraints(nconstraints);
+
+        // The bodies are split by island once for all the traversals below.
+        #[cfg(feature = "parallel")]
+        let island_bodies = &IslandBodyIndex::new(&*bodies, islands);
+        #[cfg(not(feature = "parallel"))]
+        let island_bodies = islands;
+
+        counters.velocity_resolution_started();
+        foreach_island(solvers, bodies, island_bodies, |_, solver, bodies| {
+            solver.solve_velocity_constraints(parameters, bodies)
+        });
+
+        for (i, solver) in solvers.iter_mut().enumerate() {
+            solver.cache_impulses(contact_model, parameters, joints, &island_joints[i]);
+        }
+        counters.velocity_resolution_completed();
+
+        counters.velocity_update_started();
+        foreach_island(solvers, bodies, island_bodies, |i, solver, bodies| {
+            solver.update_velocities_and_integrate(parameters, bodies, &islands[i])
+        });
+        counters.velocity_update_completed();
+
+        counters.position_resolution_started();
+        let joints = &*joints;
+        let joints: Vec<_> = island_joints
+            .iter()
+            .map(|handles| joint_refs(joints, handles))
+            .collect();
+        let contact_colliders: Vec<_> = solvers
+            .iter()
+            .map(|solver| solver.contact_colliders(colliders))
+            .collect();
+
+        foreach_island(solvers, bodies, island_bodies, |i, solver, bodies| {
+            solver.solve_position_constraints(parameters, bodies, &contact_colliders[i], &joints[i])
+        });
         counters.position_resolution_completed();
     }
 
@@ -107,7 +214,11 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
         parameters: &IntegrationParameters<N>,
         coefficients: &MaterialsCoefficientsTable<N>,
     ) {
-        self.assemble_system(
+        self.reserve_islands(1);
+        let solver = &mut self.islands[0];
+
+        solver.assemble_system(
+            &mut *self.contact_model,
             counters,
             parameters,
             coefficients,
@@ -117,17 +228,91 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
             island,
             island_joints,
         );
-        self.solve_position_constraints(parameters, bodies, colliders, joints, island_joints);
+
+        {
+            let joints = joint_refs(joints, island_joints);
+            let contact_colliders = solver.contact_colliders(colliders);
+            solver.solve_position_constraints(parameters, bodies, &contact_colliders, &joints);
+        }
+
         for ccd_body in ccd_bodies {
             bodies.get_mut(*ccd_body).unwrap().validate_advancement();
         }
 
-        self.solve_velocity_constraints(parameters, bodies);
-        self.update_velocities_and_integrate(parameters, bodies, island);
+        solver.solve_velocity_constraints(parameters, bodies);
+        solver.update_velocities_and_integrate(parameters, bodies, island);
+    }
+}
+
+type ContactColliders<'a, N, Handle> = Vec<(
+    Option<&'a Collider<N, Handle>>,
+    Option<&'a Collider<N, Handle>>,
+)>;
+
+fn joint_refs<'a, N: RealField, Handle: BodyHandle, Constraints: JointConstraintSet<N, Handle>>(
+    joints: &'a Constraints,
+    handles: &[Constraints::Handle],
+) -> Vec<&'a Constraints::JointConstraint> {
+    handles.iter().filter_map(|h| joints.get(*h)).collect()
+}
+
+// Applies `f` to each island solver, on several threads.
+#[cfg(feature = "parallel")]
+fn foreach_island<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>(
+    solvers: &mut [IslandSolver<N, Handle, CollHandle>],
+    bodies: &mut dyn BodySet<N, Handle = Handle>,
+    island_bodies: &IslandBodyIndex<Handle>,
+    f: impl Fn(usize, &mut IslandSolver<N, Handle, CollHandle>, &mut dyn BodySet<N, Handle = Handle>)
+        + Sync,
+) {
+    par_foreach_island(bodies, island_bodies, solvers, f)
+}
+
+// Applies `f` to each island solver.
+#[cfg(not(feature = "parallel"))]
+fn foreach_island<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>(
+    solvers: &mut [IslandSolver<N, Handle, CollHandle>],
+    bodies: &mut dyn BodySet<N, Handle = Handle>,
+    _island_bodies: &[Vec<Handle>],
+    f: impl Fn(usize, &mut IslandSolver<N, Handle, CollHandle>, &mut dyn BodySet<N, Handle = Handle>),
+) {
+    for (i, solver) in solvers.iter_mut().enumerate() {
+        f(i, solver, bodies)
+    }
+}
+
+// The buffers needed to solve one island.
+struct IslandSolver<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle> {
+    jacobians: Vec<N>,
+    // FIXME: use a Vec or a DVector?
+    mj_lambda_vel: DVector<N>,
+    ext_vels: DVector<N>,
+    contact_constraints: ConstraintSet<N, Handle, CollHandle, ContactId>,
+    joint_constraints: ConstraintSet<N, Handle, CollHandle, usize>,
+    internal_constraints: Vec<Handle>,
+}
+
+impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
+    IslandSolver<N, Handle, CollHandle>
+{
+    fn new() -> Self {
+        IslandSolver {
+            jacobians: Vec::new(),
+            mj_lambda_vel: DVector::zeros(0),
+            ext_vels: DVector::zeros(0),
+            contact_constraints: ConstraintSet::new(),
+            joint_constraints: ConstraintSet::new(),
+            internal_constraints: Vec::new(),
+        }
+    }
+
+    fn nconstraints(&self) -> usize {
+        self.contact_constraints.velocity.len() + self.joint_constraints.velocity.len()
     }
 
     fn assemble_system<Constraints: JointConstraintSet<N, Handle>>(
         &mut self,
+        contact_model: &mut dyn ContactModel<N, Handle, CollHandle>,
         counters: &mut Counters,
         parameters: &IntegrationParameters<N>,
         coefficients: &MaterialsCoefficientsTable<N>,
@@ -203,7 +388,7 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
         for m in manifolds {
             let ndofs1 = try_continue!(bodies.get(m.body1())).status_dependent_ndofs();
             let ndofs2 = try_continue!(bodies.get(m.body2())).status_dependent_ndofs();
-            let sz = self.contact_model.num_velocity_constraints(m) * (ndofs1 + ndofs2) * 2;
+            let sz = contact_model.num_velocity_constraints(m) * (ndofs1 + ndofs2) * 2;
 
             if ndofs1 == 0 || ndofs2 == 0 {
                 ground_jacobian_sz += sz;
@@ -238,7 +423,7 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
         }
 
         counters.custom_started();
-        self.contact_model.constraints(
+        contact_model.constraints(
             parameters,
             coefficients,
             bodies,
@@ -275,16 +460,25 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
         );
     }
 
-    fn solve_position_constraints<
-        Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
-        Constraints: JointConstraintSet<N, Handle>,
-    >(
+    // The colliders attached to each position-based contact constraint.
+    fn contact_colliders<'a, Colliders: ColliderSet<N, Handle, Handle = CollHandle>>(
+        &self,
+        colliders: &'a Colliders,
+    ) -> ContactColliders<'a, N, Handle> {
+        self.contact_constraints
+            .position
+            .unilateral
+            .iter()
+            .map(|c| (colliders.get(c.collider1), colliders.get(c.collider2)))
+            .collect()
+    }
+
+    fn solve_position_constraints<J: ?Sized + NonlinearConstraintGenerator<N, Handle>>(
         &mut self,
         parameters: &IntegrationParameters<N>,
         bodies: &mut dyn BodySet<N, Handle = Handle>,
-        colliders: &Colliders,
-        joints: &mut Constraints,
-        island_joints: &[Constraints::Handle],
+        contact_colliders: &[(Option<&Collider<N, Handle>>, Option<&Collider<N, Handle>>)],
+        joints: &[&J],
     ) {
         // XXX: avoid the systematic clone.
         // This is needed for cases where we perform the position resolution
@@ -293,10 +487,9 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
         NonlinearSORProx::solve(
             parameters,
             bodies,
-            colliders,
             &mut self.contact_constraints.position.unilateral,
+            contact_colliders,
             joints,
-            island_joints,
             &self.internal_constraints,
             &mut jacobians,
             parameters.max_position_iterations,
@@ -304,13 +497,13 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
     }
 
     fn cache_impulses<Constraints: JointConstraintSet<N, Handle>>(
-        &mut self,
+        &self,
+        contact_model: &mut dyn ContactModel<N, Handle, CollHandle>,
         parameters: &IntegrationParameters<N>,
-        _bodies: &mut dyn BodySet<N, Handle = Handle>,
         joints: &mut Constraints,
         island_joints: &[Constraints::Handle],
     ) {
-        self.contact_model.cache_impulses(&self.contact_constraints);
+        contact_model.cache_impulses(&self.contact_constraints);
 
         for handle in island_joints {
             if let Some(j) = joints.get_mut(*handle) {
@@ -346,3 +539,29 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
         }
     }
 }
+
+// Only the contact model is serialized: everything else is rebuilt at each step.
+#[cfg(feature = "serde-serialize")]
+impl<N, Handle, CollHandle> serde::Serialize for MoreauJeanSolver<N, Handle, CollHandle>
+where
+    N: RealField + serde::Serialize,
+    Handle: BodyHandle,
+    CollHandle: ColliderHandle,
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.contact_model.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde-serialize")]
+impl<'de, N, Handle, CollHandle> serde::Deserialize<'de> for MoreauJeanSolver<N, Handle, CollHandle>
+where
+    N: RealField + serde::Deserialize<'de>,
+    Handle: BodyHandle,
+    CollHandle: ColliderHandle,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let contact_model = serde::Deserialize::deserialize(deserializer)?;
+        Ok(MoreauJeanSolver::new(contact_model))
+    }
+}
@@ -1,9 +1,8 @@
 use na::{self, Dim, Dynamic, RealField, VectorSliceMutN, U1};
 use std::ops::MulAssign;
 
-use crate::joint::JointConstraintSet;
 use crate::math::Isometry;
-use crate::object::{BodyHandle, BodySet, ColliderAnchor, ColliderSet};
+use crate::object::{BodyHandle, BodySet, Collider, ColliderAnchor, ColliderHandle};
 use crate::solver::{
     ForceDirection, GenericNonlinearConstraint, IntegrationParameters,
     NonlinearConstraintGenerator, NonlinearUnilateralConstraint,
@@ -14,27 +13,26 @@ pub(crate) struct NonlinearSORProx;
 
 impl NonlinearSORProx {
     /// Solve a set of nonlinear position-based constraints.
+    ///
+    /// The `i`-th element of `contact_colliders` contains the colliders involved in the `i`-th contact constraint.
     pub fn solve<
         N: RealField,
         Handle: BodyHandle,
-        Colliders: ColliderSet<N, Handle>,
-        Constraints: JointConstraintSet<N, Handle>,
+        CollHandle: ColliderHandle,
+        J: ?Sized + NonlinearConstraintGenerator<N, Handle>,
     >(
         parameters: &IntegrationParameters<N>,
         bodies: &mut dyn BodySet<N, Handle = Handle>,
-        colliders: &Colliders,
-        contact_constraints: &mut [NonlinearUnilateralConstraint<N, Handle, Colliders::Handle>],
-        joints_constraints: &Constraints,
-        island_joints: &[Constraints::Handle],
+        contact_constraints: &mut [NonlinearUnilateralConstraint<N, Handle, CollHandle>],
+        contact_colliders: &[(Option<&Collider<N, Handle>>, Option<&Collider<N, Handle>>)],
+        joints: &[&J],
         internal_constraints: &[Handle],
         jacobians: &mut [N],
         max_iter: usize,
     ) {
         for _ in 0..max_iter {
-            for handle in island_joints {
-                if let Some(joint) = joints_constraints.get(*handle) {
-                    Self::solve_generator(parameters, bodies, joint, jacobians)
-                }
+            for joint in joints {
+                Self::solve_generator(parameters, bodies, *joint, jacobians)
             }
 
             for constraint in internal_constraints {
@@ -43,12 +41,12 @@ impl NonlinearSORProx {
                 }
             }
 
-            for constraint in contact_constraints.iter_mut() {
+            for (constraint, colliders) in contact_constraints.iter_mut().zip(contact_colliders) {
                 // FIXME: specialize for SPATIAL_DIM.
                 let dim1 = Dynamic::new(constraint.ndofs1);
                 let dim2 = Dynamic::new(constraint.ndofs2);
                 Self::solve_unilateral(
-                    parameters, bodies, colliders, constraint, jacobians, dim1, dim2,
+                    parameters, bodies, *colliders, constraint, jacobians, dim1, dim2,
                 );
             }
         }
@@ -121,14 +119,14 @@ impl NonlinearSORProx {
     fn solve_unilateral<
         N: RealField,
         Handle: BodyHandle,
-        Colliders: ColliderSet<N, Handle>,
+        CollHandle: ColliderHandle,
         D1: Dim,
         D2: Dim,
     >(
         parameters: &IntegrationParameters<N>,
         bodies: &mut dyn BodySet<N, Handle = Handle>,
-        colliders: &Colliders,
-        constraint: &mut NonlinearUnilateralConstraint<N, Handle, Colliders::Handle>,
+        colliders: (Option<&Collider<N, Handle>>, Option<&Collider<N, Handle>>),
+        constraint: &mut NonlinearUnilateralConstraint<N, Handle, CollHandle>,
         jacobians: &mut [N],
         dim1: D1,
         dim2: D2,
@@ -153,23 +151,19 @@ impl NonlinearSORProx {
         }
     }
 
-    fn update_contact_constraint<
-        N: RealField,
-        Handle: BodyHandle,
-        Colliders: ColliderSet<N, Handle>,
-    >(
+    fn update_contact_constraint<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>(
         parameters: &IntegrationParameters<N>,
         bodies: &dyn BodySet<N, Handle = Handle>,
-        colliders: &Colliders,
-        constraint: &mut NonlinearUnilateralConstraint<N, Handle, Colliders::Handle>,
+        colliders: (Option<&Collider<N, Handle>>, Option<&Collider<N, Handle>>),
+        constraint: &mut NonlinearUnilateralConstraint<N, Handle, CollHandle>,
         jacobians: &mut [N],
     ) -> bool {
         let body1 = try_ret!(bodies.get(constraint.body1.0), false);
         let body2 = try_ret!(bodies.get(constraint.body2.0), false);
         let part1 = try_ret!(body1.part(constraint.body1.1), false);
         let part2 = try_ret!(body2.part(constraint.body2.1), false);
-        let collider1 = try_ret!(colliders.get(constraint.collider1), false);
-        let collider2 = try_ret!(colliders.get(constraint.collider2), false);
+        let collider1 = try_ret!(colliders.0, false);
+        let collider2 = try_ret!(colliders.1, false);
 
         let pos1;
         let pos2;
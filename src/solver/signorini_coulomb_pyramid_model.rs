@@ -1,27 +1,27 @@
 use na::{self, DVector, RealField, Unit};
 use ncollide::query::ContactId;
 use slotmap::Key;
-use std::ops::Range;
 
-use crate::detection::ColliderContactManifold;
+use crate::detection::{ColliderContactManifold, ContactReport};
 use crate::material::{Material, MaterialContext, MaterialsCoefficientsTable};
 use crate::math::{Vector, DIM};
 use crate::object::{BodyHandle, BodySet, ColliderHandle};
 use crate::solver::helper;
 use crate::solver::{
-    BilateralConstraint, BilateralGroundConstraint, ConstraintSet, ContactModel, ForceDirection,
-    ImpulseCache, ImpulseLimits, IntegrationParameters, SignoriniModel,
+    remap_impulse_cache, BilateralConstraint, BilateralGroundConstraint, ConstraintSet,
+    ContactModel, ForceDirection, ImpulseCache, ImpulseLimits, IntegrationParameters,
+    SignoriniModel,
 };
 
 /// A contact model generating one non-penetration constraint and two friction constraints per contact.
 ///
 /// This contact model approximates the friction cone at a contact with pyramid.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct SignoriniCoulombPyramidModel<N: RealField> {
     impulses: ImpulseCache<Vector<N>>,
-    vel_ground_rng: Range<usize>,
-    vel_rng: Range<usize>,
-    friction_ground_rng: Range<usize>,
-    friction_rng: Range<usize>,
+    // The contacts solved during the current timestep.
+    #[cfg_attr(feature = "serde-serialize", serde(skip, default = "ImpulseCache::new"))]
+    reports: ImpulseCache<ContactReport<N>>,
 }
 
 impl<N: RealField> SignoriniCoulombPyramidModel<N> {
@@ -29,10 +29,7 @@ impl<N: RealField> SignoriniCoulombPyramidModel<N> {
     pub fn new() -> Self {
         SignoriniCoulombPyramidModel {
             impulses: ImpulseCache::new(),
-            vel_ground_rng: 0..0,
-            vel_rng: 0..0,
-            friction_ground_rng: 0..0,
-            friction_rng: 0..0,
+            reports: ImpulseCache::new(),
         }
     }
 }
@@ -65,11 +62,6 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
         jacobians: &mut [N],
         constraints: &mut ConstraintSet<N, Handle, CollHandle, ContactId>,
     ) {
-        let id_vel_ground = constraints.velocity.unilateral_ground.len();
-        let id_vel = constraints.velocity.unilateral.len();
-        let id_friction_ground = constraints.velocity.bilateral_ground.len();
-        let id_friction = constraints.velocity.bilateral.len();
-
         for manifold in manifolds {
             let body1 = try_continue!(bodies.get(manifold.body1()));
             let body2 = try_continue!(bodies.get(manifold.body2()));
@@ -79,6 +71,9 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
                 let handle2 = manifold.body_part2(c.kinematic.feature2());
                 let part1 = try_continue!(body1.part(handle1.1));
                 let part2 = try_continue!(body2.part(handle2.1));
+                let report =
+                    ContactReport::before_resolution(c, body1, handle1.1, body2, handle2.1);
+                let _ = self.reports.insert(c.id, report);
 
                 let material1 = manifold.collider1.material();
                 let material2 = manifold.collider2.material();
@@ -94,8 +89,9 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
                     c,
                     false,
                 );
-                let props =
+                let mut props =
                     Material::combine(coefficients, material1, context1, material2, context2);
+                manifold.override_material_properties(c, &mut props);
 
                 // if !SignoriniModel::is_constraint_active(c, manifold) {
                 //     continue;
@@ -216,19 +212,13 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
                 );
             }
         }
-
-        self.vel_ground_rng = id_vel_ground..constraints.velocity.unilateral_ground.len();
-        self.vel_rng = id_vel..constraints.velocity.unilateral.len();
-        self.friction_ground_rng = id_friction_ground..constraints.velocity.bilateral_ground.len();
-        self.friction_rng = id_friction..constraints.velocity.bilateral.len();
     }
 
     fn cache_impulses(&mut self, constraints: &ConstraintSet<N, Handle, CollHandle, ContactId>) {
-        let ground_contacts = &constraints.velocity.unilateral_ground[self.vel_ground_rng.clone()];
-        let contacts = &constraints.velocity.unilateral[self.vel_rng.clone()];
-        let ground_friction =
-            &constraints.velocity.bilateral_ground[self.friction_ground_rng.clone()];
-        let friction = &constraints.velocity.bilateral[self.friction_rng.clone()];
+        let ground_contacts = &constraints.velocity.unilateral_ground;
+        let contacts = &constraints.velocity.unilateral;
+        let ground_friction = &constraints.velocity.bilateral_ground;
+        let friction = &constraints.velocity.bilateral;
 
         for c in ground_contacts {
             if !c.impulse_id.is_null() {
@@ -258,5 +248,38 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
                 dim += 1;
             }
         }
+
+        let ids = ground_contacts.iter().map(|c| c.impulse_id);
+        let ids = ids.chain(contacts.iter().map(|c| c.impulse_id));
+
+        for id in ids {
+            if let (Some(report), Some(impulse)) = (self.reports.get_mut(id), self.impulses.get(id))
+            {
+                let mut friction = Vector::zeros();
+                let mut i = 1;
+
+                // The friction constraints apply `-impulse * friction_dir` on the second body part.
+                Vector::orthonormal_subspace_basis(&[report.normal.into_inner()], |friction_dir| {
+                    friction -= friction_dir * impulse[i];
+                    i += 1;
+                    true
+                });
+
+                report.normal_impulse = impulse[0];
+                report.friction_impulse = friction;
+            }
+        }
+    }
+
+    fn remap_impulses(&mut self, remap: &[(ContactId, ContactId)]) {
+        remap_impulse_cache(&mut self.impulses, remap)
+    }
+
+    fn clear_contact_reports(&mut self) {
+        self.reports.clear()
+    }
+
+    fn contact_report(&self, id: ContactId) -> Option<&ContactReport<N>> {
+        self.reports.get(id)
     }
 }
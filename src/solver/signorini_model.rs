@@ -1,15 +1,15 @@
 use na::{self, DVector, RealField};
-use std::ops::Range;
 
-use crate::detection::ColliderContactManifold;
+use crate::detection::{ColliderContactManifold, ContactReport};
 use crate::material::{
     LocalMaterialProperties, Material, MaterialContext, MaterialsCoefficientsTable,
 };
 use crate::object::{Body, BodyHandle, BodyPart, BodyPartHandle, BodySet, ColliderHandle};
 use crate::solver::helper;
 use crate::solver::{
-    ConstraintSet, ContactModel, ForceDirection, ImpulseCache, IntegrationParameters,
-    NonlinearUnilateralConstraint, UnilateralConstraint, UnilateralGroundConstraint,
+    remap_impulse_cache, ConstraintSet, ContactModel, ForceDirection, ImpulseCache,
+    IntegrationParameters, NonlinearUnilateralConstraint, UnilateralConstraint,
+    UnilateralGroundConstraint,
 };
 use ncollide::query::{ContactId, TrackedContact};
 use ncollide::utils::IsometryOps;
@@ -17,10 +17,12 @@ use ncollide::utils::IsometryOps;
 /// A contact model generating one non-penetration constraint per contact.
 ///
 /// This is a frictionless contact model.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct SignoriniModel<N: RealField> {
     impulses: ImpulseCache<N>,
-    vel_ground_rng: Range<usize>,
-    vel_rng: Range<usize>,
+    // The contacts solved during the current timestep.
+    #[cfg_attr(feature = "serde-serialize", serde(skip, default = "ImpulseCache::new"))]
+    reports: ImpulseCache<ContactReport<N>>,
 }
 
 impl<N: RealField> SignoriniModel<N> {
@@ -28,8 +30,7 @@ impl<N: RealField> SignoriniModel<N> {
     pub fn new() -> Self {
         SignoriniModel {
             impulses: ImpulseCache::new(),
-            vel_ground_rng: 0..0,
-            vel_rng: 0..0,
+            reports: ImpulseCache::new(),
         }
     }
 
@@ -204,7 +205,7 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
         &self,
         c: &ColliderContactManifold<N, Handle, CollHandle>,
     ) -> usize {
-        c.manifold.len()
+        c.len()
     }
 
     fn constraints(
@@ -219,9 +220,6 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
         jacobians: &mut [N],
         constraints: &mut ConstraintSet<N, Handle, CollHandle, ContactId>,
     ) {
-        let id_vel_ground = constraints.velocity.unilateral_ground.len();
-        let id_vel = constraints.velocity.unilateral.len();
-
         for manifold in manifolds {
             let body1 = try_ret!(bodies.get(manifold.body1()));
             let body2 = try_ret!(bodies.get(manifold.body2()));
@@ -235,6 +233,9 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
                 let handle2 = manifold.body_part2(c.kinematic.feature2());
                 let part1 = try_ret!(body1.part(handle1.1));
                 let part2 = try_ret!(body2.part(handle2.1));
+                let report =
+                    ContactReport::before_resolution(c, body1, handle1.1, body2, handle2.1);
+                let _ = self.reports.insert(c.id, report);
 
                 let material1 = manifold.collider1.material();
                 let material2 = manifold.collider2.material();
@@ -250,8 +251,9 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
                     c,
                     false,
                 );
-                let props =
+                let mut props =
                     Material::combine(coefficients, material1, context1, material2, context2);
+                manifold.override_material_properties(c, &mut props);
 
                 let _ = Self::build_velocity_constraint(
                     parameters,
@@ -275,21 +277,38 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
                 Self::build_position_constraint(bodies, manifold, c, constraints);
             }
         }
-
-        self.vel_ground_rng = id_vel_ground..constraints.velocity.unilateral_ground.len();
-        self.vel_rng = id_vel..constraints.velocity.unilateral.len();
     }
 
     fn cache_impulses(&mut self, constraints: &ConstraintSet<N, Handle, CollHandle, ContactId>) {
-        let ground_contacts = &constraints.velocity.unilateral_ground[self.vel_ground_rng.clone()];
-        let contacts = &constraints.velocity.unilateral[self.vel_rng.clone()];
+        let ground_contacts = &constraints.velocity.unilateral_ground;
+        let contacts = &constraints.velocity.unilateral;
 
         for c in ground_contacts {
             let _ = self.impulses.insert(c.impulse_id, c.impulse);
+
+            if let Some(report) = self.reports.get_mut(c.impulse_id) {
+                report.normal_impulse = c.impulse;
+            }
         }
 
         for c in contacts {
             let _ = self.impulses.insert(c.impulse_id, c.impulse);
+
+            if let Some(report) = self.reports.get_mut(c.impulse_id) {
+                report.normal_impulse = c.impulse;
+            }
         }
     }
+
+    fn remap_impulses(&mut self, remap: &[(ContactId, ContactId)]) {
+        remap_impulse_cache(&mut self.impulses, remap)
+    }
+
+    fn clear_contact_reports(&mut self) {
+        self.reports.clear()
+    }
+
+    fn contact_report(&self, id: ContactId) -> Option<&ContactReport<N>> {
+        self.reports.get(id)
+    }
 }
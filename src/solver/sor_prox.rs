@@ -279,7 +279,8 @@ impl SORProx {
 
         let dimpulse = jacobian1.dot(&mj_lambda.rows_generic(id1, dim1))
             + jacobian2.dot(&mj_lambda.rows_generic(id2, dim2))
-            + c.rhs;
+            + c.rhs
+            + c.cfm * c.impulse;
 
         let new_impulse = na::clamp(c.impulse - c.r * dimpulse, min_impulse, max_impulse);
         let dlambda = new_impulse - c.impulse;
@@ -331,7 +332,8 @@ impl SORProx {
         let jacobian = VectorSliceN::from_slice_generic(&jacobians[c.j_id..], dim, U1);
         let weighted_jacobian = VectorSliceN::from_slice_generic(&jacobians[c.wj_id..], dim, U1);
 
-        let dimpulse = jacobian.dot(&mj_lambda.rows_generic(c.assembly_id, dim)) + c.rhs;
+        let dimpulse =
+            jacobian.dot(&mj_lambda.rows_generic(c.assembly_id, dim)) + c.rhs + c.cfm * c.impulse;
 
         let new_impulse = na::clamp(c.impulse - c.r * dimpulse, min_impulse, max_impulse);
         let dlambda = new_impulse - c.impulse;
@@ -3,11 +3,17 @@
 pub use self::deterministic_state::DeterministicState;
 pub use self::generalized_cross::GeneralizedCross;
 pub use self::index_mut2::IndexMut2;
+#[cfg(feature = "parallel")]
+pub(crate) use self::parallel::{par_foreach_body_mut, par_foreach_island, IslandBodyIndex};
 pub use self::user_data::UserData;
 pub(crate) use self::user_data::UserDataBox;
+#[cfg(feature = "serde-serialize")]
+pub(crate) use self::user_data::{deserialize_user_data, serialize_user_data};
 
 mod deterministic_state;
 mod generalized_cross;
 mod index_mut2;
+#[cfg(feature = "parallel")]
+mod parallel;
 pub mod union_find;
 mod user_data;
@@ -0,0 +1,221 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+use na::RealField;
+use rayon::prelude::*;
+
+use crate::object::{Body, BodyHandle, BodySet};
+
+/// Applies `f` to every body of the set, on several threads.
+///
+/// The bodies are updated sequentially if the set cannot lend all its bodies at once.
+pub(crate) fn par_foreach_body_mut<N: RealField, Handle: BodyHandle>(
+    bodies: &mut dyn BodySet<N, Handle = Handle>,
+    f: impl Fn(&mut dyn Body<N>) + Sync,
+) {
+    match bodies.disjoint_bodies_mut() {
+        Some(mut disjoint) => disjoint.par_iter_mut().for_each(|(_, b)| f(&mut **b)),
+        None => bodies.foreach_mut(&mut |_, b| f(b)),
+    }
+}
+
+/// Applies `f` to each element of `items` together with a view of the bodies of the
+/// corresponding island of `index`, on several threads.
+///
+/// The islands are processed sequentially, each with the whole body set, if the set cannot lend
+/// all its bodies at once.
+pub(crate) fn par_foreach_island<N: RealField, Handle: BodyHandle, T: Send>(
+    bodies: &mut dyn BodySet<N, Handle = Handle>,
+    index: &IslandBodyIndex<Handle>,
+    items: &mut [T],
+    f: impl Fn(usize, &mut T, &mut dyn BodySet<N, Handle = Handle>) + Sync,
+) {
+    match bodies.disjoint_bodies_mut() {
+        Some(disjoint) => {
+            let mut owned: Vec<Vec<_>> = index
+                .islands
+                .iter()
+                .map(|island| island.iter().map(|_| None).collect())
+                .collect();
+            let mut shared: Vec<_> = index.shared.iter().map(|_| None).collect();
+
+            for (handle, body) in disjoint {
+                match index.ids.get(&handle) {
+                    Some(BodyLocation::Island(i, id)) => owned[*i][*id] = Some(body),
+                    Some(BodyLocation::Shared(id)) => shared[*id] = Some(&*body),
+                    None => {}
+                }
+            }
+
+            let shared = &shared;
+
+            owned
+                .par_iter_mut()
+                .zip(items.par_iter_mut())
+                .enumerate()
+                .for_each(|(i, (owned, item))| {
+                    let mut bodies = IslandBodySet {
+                        island: i,
+                        index,
+                        owned,
+                        shared,
+                    };
+                    f(i, item, &mut bodies)
+                })
+        }
+        None => {
+            for (i, item) in items.iter_mut().enumerate() {
+                f(i, item, bodies)
+            }
+        }
+    }
+}
+
+// Where a body is stored within an `IslandBodyIndex`.
+#[derive(Copy, Clone)]
+enum BodyLocation {
+    // The island owning the body and the index of the body among the bodies of this island.
+    Island(usize, usize),
+    // The index of the body among the bodies that are not part of any island.
+    Shared(usize),
+}
+
+/// The bodies of a set, split by island.
+///
+/// This is computed once per step and used by every call to `par_foreach_island` made while
+/// solving the islands, as long as no body is added to or removed from the set.
+pub(crate) struct IslandBodyIndex<'a, Handle: BodyHandle> {
+    islands: &'a [Vec<Handle>],
+    shared: Vec<Handle>,
+    ids: HashMap<Handle, BodyLocation>,
+}
+
+impl<'a, Handle: BodyHandle> IslandBodyIndex<'a, Handle> {
+    /// Indexes the bodies of the given islands, which must be disjoint.
+    pub(crate) fn new<N: RealField>(
+        bodies: &dyn BodySet<N, Handle = Handle>,
+        islands: &'a [Vec<Handle>],
+    ) -> Self {
+        let mut ids = HashMap::new();
+        let mut shared = Vec::new();
+
+        for (i, island) in islands.iter().enumerate() {
+            for (id, handle) in island.iter().enumerate() {
+                assert!(
+                    ids.insert(*handle, BodyLocation::Island(i, id)).is_none(),
+                    "Internal error: a body cannot belong to two islands."
+                );
+            }
+        }
+
+        bodies.foreach(&mut |handle, _| {
+            if let Entry::Vacant(entry) = ids.entry(handle) {
+                let _ = entry.insert(BodyLocation::Shared(shared.len()));
+                shared.push(handle);
+            }
+        });
+
+        IslandBodyIndex {
+            islands,
+            shared,
+            ids,
+        }
+    }
+}
+
+/// A view of the bodies of one island.
+///
+/// Only the bodies of this island can be modified. The bodies that are not
+/// part of any island can only be read. The bodies of the other islands are
+/// not accessible at all.
+struct IslandBodySet<'a, 'b, N: RealField, Handle: BodyHandle> {
+    island: usize,
+    index: &'b IslandBodyIndex<'b, Handle>,
+    owned: &'b mut [Option<&'a mut dyn Body<N>>],
+    shared: &'b [Option<&'a dyn Body<N>>],
+}
+
+impl<'a, 'b, N: RealField, Handle: BodyHandle> BodySet<N> for IslandBodySet<'a, 'b, N, Handle> {
+    type Handle = Handle;
+
+    fn get(&self, handle: Handle) -> Option<&dyn Body<N>> {
+        match *self.index.ids.get(&handle)? {
+            BodyLocation::Island(i, id) if i == self.island => self.owned[id].as_deref(),
+            BodyLocation::Island(..) => None,
+            BodyLocation::Shared(id) => self.shared[id],
+        }
+    }
+
+    fn get_mut(&mut self, handle: Handle) -> Option<&mut dyn Body<N>> {
+        match *self.index.ids.get(&handle)? {
+            BodyLocation::Island(i, id) if i == self.island => self.owned[id].as_deref_mut(),
+            _ => None,
+        }
+    }
+
+    fn contains(&self, handle: Handle) -> bool {
+        self.get(handle).is_some()
+    }
+
+    fn foreach(&self, f: &mut dyn FnMut(Handle, &dyn Body<N>)) {
+        let handles = &self.index.islands[self.island];
+
+        for (handle, body) in handles.iter().zip(self.owned.iter()) {
+            if let Some(body) = body {
+                f(*handle, &**body)
+            }
+        }
+
+        for (handle, body) in self.index.shared.iter().zip(self.shared.iter()) {
+            if let Some(body) = body {
+                f(*handle, *body)
+            }
+        }
+    }
+
+    fn foreach_mut(&mut self, f: &mut dyn FnMut(Handle, &mut dyn Body<N>)) {
+        let handles = &self.index.islands[self.island];
+
+        for (handle, body) in handles.iter().zip(self.owned.iter_mut()) {
+            if let Some(body) = body {
+                f(*handle, &mut **body)
+            }
+        }
+    }
+
+    fn pop_removal_event(&mut self) -> Option<Handle> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{par_foreach_island, IslandBodyIndex};
+    use crate::object::{DefaultBodySet, Ground, RigidBodyDesc};
+
+    #[test]
+    fn island_body_set_only_exposes_its_island_and_shared_bodies() {
+        let mut bodies = DefaultBodySet::<f64>::new();
+        let ground = bodies.insert(Ground::new());
+        let a = bodies.insert(RigidBodyDesc::new().build());
+        let b = bodies.insert(RigidBodyDesc::new().build());
+        let islands = vec![vec![a], vec![b]];
+        let index = IslandBodyIndex::new(&bodies, &islands);
+        let mut items = [(a, b), (b, a)];
+
+        par_foreach_island(
+            &mut bodies,
+            &index,
+            &mut items,
+            |_, (own, other), bodies| {
+                assert!(bodies.contains(*own) && bodies.get_mut(*own).is_some());
+                assert!(bodies.contains(ground) && bodies.get_mut(ground).is_none());
+                assert!(!bodies.contains(*other) && bodies.get(*other).is_none());
+
+                let mut count = 0;
+                bodies.foreach(&mut |_, _| count += 1);
+                assert_eq!(count, 2);
+            },
+        );
+    }
+}
@@ -2,6 +2,7 @@
 
 /// An element used by the union-find algorithm.
 #[derive(Copy, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct UnionFindSet {
     /// The parent of this union find element.
     parent: usize,
@@ -38,3 +38,72 @@ impl Clone for UserDataBox {
         UserDataBox(self.0.clone_boxed())
     }
 }
+
+// Serializes the user-data attached to an object by downcasting it to one of the listed types.
+#[cfg(feature = "serde-serialize")]
+macro_rules! serde_user_data(
+    ($($Variant: ident($T: ty)),* $(,)*) => {
+        #[derive(Serialize)]
+        enum UserDataRef<'a> {
+            $($Variant(&'a $T)),*
+        }
+
+        #[derive(Deserialize)]
+        enum UserDataOwned {
+            $($Variant($T)),*
+        }
+
+        pub(crate) fn serialize_user_data<S: serde::Serializer>(
+            data: &Option<Box<dyn Any + Send + Sync>>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            use serde::Serialize;
+
+            let data = match data.as_deref() {
+                Some(data) => data,
+                None => return Option::<UserDataRef>::None.serialize(serializer),
+            };
+
+            $(
+                if let Some(val) = data.downcast_ref::<$T>() {
+                    return Some(UserDataRef::$Variant(val)).serialize(serializer);
+                }
+            )*
+
+            Err(serde::ser::Error::custom(
+                "Unable to serialize a user-data: unsupported type.",
+            ))
+        }
+
+        pub(crate) fn deserialize_user_data<'de, D: serde::Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Option<Box<dyn Any + Send + Sync>>, D::Error> {
+            use serde::Deserialize;
+
+            match Option::<UserDataOwned>::deserialize(deserializer)? {
+                $(Some(UserDataOwned::$Variant(val)) => Ok(Some(Box::new(val))),)*
+                None => Ok(None),
+            }
+        }
+    }
+);
+
+#[cfg(feature = "serde-serialize")]
+serde_user_data!(
+    Bool(bool),
+    Char(char),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    Isize(isize),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    Usize(usize),
+    F32(f32),
+    F64(f64),
+    String(String),
+    Bytes(Vec<u8>),
+);
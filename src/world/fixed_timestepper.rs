@@ -0,0 +1,410 @@
+use std::collections::HashMap;
+
+use na::RealField;
+use ncollide::pipeline::BroadPhasePairFilter;
+
+use crate::force_generator::ForceGeneratorSet;
+use crate::joint::JointConstraintSet;
+use crate::math::Isometry;
+use crate::object::{
+    BodyHandle, BodyPartHandle, BodySet, ColliderHandle, ColliderSet, DefaultBodyHandle,
+    DefaultColliderHandle,
+};
+use crate::world::{BroadPhasePairFilterSets, GeometricalWorld, JointBreakEvent, MechanicalWorld};
+
+/// The default fixed timestepper, that can be used with a `DefaultBodyHandle` and `DefaultColliderHandle`.
+pub type DefaultFixedTimestepper<N> = FixedTimestepper<N, DefaultBodyHandle, DefaultColliderHandle>;
+
+/// A driver executing fixed-size time steps of a mechanical world to keep up with the elapsed real time.
+///
+/// The time not simulated yet is accumulated from one call to `self.step` to the next. The positions
+/// of the bodies and colliders before the last time step are saved so that they can be interpolated
+/// with the current positions, resulting in smooth rendering at any frame rate.
+pub struct FixedTimestepper<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle> {
+    accumulator: N,
+    interpolation_factor: N,
+    max_steps: usize,
+    part_positions: HashMap<BodyPartHandle<Handle>, Isometry<N>>,
+    collider_positions: HashMap<CollHandle, Isometry<N>>,
+    deformed_positions: HashMap<Handle, Vec<N>>,
+}
+
+impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
+    FixedTimestepper<N, Handle, CollHandle>
+{
+    /// Creates a new fixed timestepper executing at most 5 time steps per call to `self.step`.
+    pub fn new() -> Self {
+        FixedTimestepper {
+            accumulator: N::zero(),
+            interpolation_factor: N::one(),
+            max_steps: 5,
+            part_positions: HashMap::new(),
+            collider_positions: HashMap::new(),
+            deformed_positions: HashMap::new(),
+        }
+    }
+
+    /// The maximum number of time steps executed by a single call to `self.step`.
+    pub fn max_steps(&self) -> usize {
+        self.max_steps
+    }
+
+    /// Sets the maximum number of time steps executed by a single call to `self.step`.
+    ///
+    /// When more time steps would be needed to catch up with the elapsed time, the remaining time is
+    /// discarded, i.e., the simulation slows down instead of taking longer and longer to compute.
+    pub fn set_max_steps(&mut self, max_steps: usize) {
+        self.max_steps = max_steps
+    }
+
+    /// The elapsed time that has not been simulated yet.
+    pub fn accumulated_time(&self) -> N {
+        self.accumulator
+    }
+
+    /// The factor used to interpolate between the positions before and after the last time step.
+    ///
+    /// This is zero if the positions before the last time step are used, and one if the current positions are used.
+    pub fn interpolation_factor(&self) -> N {
+        self.interpolation_factor
+    }
+
+    /// Executes as many time steps of the mechanical world as needed to simulate the given elapsed time.
+    ///
+    /// The joints that broke during these time steps are appended to `joint_break_events`.
+    /// Returns the number of time steps executed. Nothing is simulated, and the elapsed time is
+    /// discarded, if the timestep of the mechanical world is not positive.
+    pub fn step<Bodies, Colliders, Constraints, Forces>(
+        &mut self,
+        mworld: &mut MechanicalWorld<N, Handle, CollHandle>,
+        gworld: &mut GeometricalWorld<N, Handle, CollHandle>,
+        bodies: &mut Bodies,
+        colliders: &mut Colliders,
+        constraints: &mut Constraints,
+        forces: &mut Forces,
+        elapsed_time: N,
+        joint_break_events: &mut Vec<JointBreakEvent<N, Handle, Constraints::Handle>>,
+    ) -> usize
+    where
+        Bodies: BodySet<N, Handle = Handle>,
+        Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
+        Constraints: JointConstraintSet<N, Handle>,
+        Forces: ForceGeneratorSet<N, Handle>,
+    {
+        self.step_with_filter(
+            mworld,
+            gworld,
+            bodies,
+            colliders,
+            constraints,
+            forces,
+            elapsed_time,
+            joint_break_events,
+            &(),
+        )
+    }
+
+    /// Executes as many time steps of the mechanical world as needed to simulate the given elapsed time.
+    ///
+    /// The joints that broke during these time steps are appended to `joint_break_events`.
+    /// Returns the number of time steps executed. Nothing is simulated, and the elapsed time is
+    /// discarded, if the timestep of the mechanical world is not positive.
+    pub fn step_with_filter<Bodies, Colliders, Constraints, Forces, Filter>(
+        &mut self,
+        mworld: &mut MechanicalWorld<N, Handle, CollHandle>,
+        gworld: &mut GeometricalWorld<N, Handle, CollHandle>,
+        bodies: &mut Bodies,
+        colliders: &mut Colliders,
+        constraints: &mut Constraints,
+        forces: &mut Forces,
+        elapsed_time: N,
+        joint_break_events: &mut Vec<JointBreakEvent<N, Handle, Constraints::Handle>>,
+        filter: &Filter,
+    ) -> usize
+    where
+        Bodies: BodySet<N, Handle = Handle>,
+        Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
+        Constraints: JointConstraintSet<N, Handle>,
+        Forces: ForceGeneratorSet<N, Handle>,
+        Filter: for<'a> BroadPhasePairFilter<N, BroadPhasePairFilterSets<'a, N, Bodies, Colliders>>
+            + ?Sized,
+    {
+        let dt = mworld.timestep();
+
+        if dt <= N::zero() {
+            // Nothing can be simulated, e.g., because the simulation is paused.
+            self.interpolation_factor = N::one();
+            return 0;
+        }
+
+        let mut nsteps = 0;
+        self.accumulator += elapsed_time;
+
+        while self.accumulator >= dt && nsteps < self.max_steps {
+            self.save_positions(bodies, colliders);
+            let events =
+                mworld.step_with_filter(gworld, bodies, colliders, constraints, forces, filter);
+            joint_break_events.extend(events);
+            self.accumulator -= dt;
+            nsteps += 1;
+        }
+
+        if self.accumulator >= dt {
+            // Give up on catching up with the elapsed time.
+            self.accumulator = N::zero();
+        }
+
+        self.interpolation_factor = self.accumulator / dt;
+
+        nsteps
+    }
+
+    fn save_positions<Bodies, Colliders>(&mut self, bodies: &Bodies, colliders: &Colliders)
+    where
+        Bodies: BodySet<N, Handle = Handle>,
+        Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
+    {
+        let part_positions = &mut self.part_positions;
+        let deformed_positions = &mut self.deformed_positions;
+        part_positions.clear();
+        deformed_positions.clear();
+
+        bodies.foreach(&mut |handle, body| {
+            if body.is_static() {
+                return;
+            }
+
+            for i in 0..body.num_parts() {
+                if let Some(part) = body.part(i) {
+                    let _ = part_positions.insert(BodyPartHandle(handle, i), part.position());
+                }
+            }
+
+            if let Some((_, positions)) = body.deformed_positions() {
+                let _ = deformed_positions.insert(handle, positions.to_vec());
+            }
+        });
+
+        let collider_positions = &mut self.collider_positions;
+        collider_positions.clear();
+
+        ColliderSet::foreach(colliders, |handle, collider| {
+            if bodies
+                .get(collider.body())
+                .map(|b| !b.is_static())
+                .unwrap_or(false)
+            {
+                let _ = collider_positions.insert(handle, *collider.position());
+            }
+        });
+    }
+
+    /// The position of the given body part, interpolated between its positions before and after the last time step.
+    pub fn body_part_position<Bodies: BodySet<N, Handle = Handle>>(
+        &self,
+        bodies: &Bodies,
+        handle: BodyPartHandle<Handle>,
+    ) -> Option<Isometry<N>> {
+        let current = bodies.get(handle.0)?.part(handle.1)?.position();
+
+        match self.part_positions.get(&handle) {
+            Some(previous) => Some(interpolate(previous, &current, self.interpolation_factor)),
+            None => Some(current),
+        }
+    }
+
+    /// The position of the given collider, interpolated between its positions before and after the last time step.
+    pub fn collider_position<Colliders: ColliderSet<N, Handle, Handle = CollHandle>>(
+        &self,
+        colliders: &Colliders,
+        handle: CollHandle,
+    ) -> Option<Isometry<N>> {
+        let current = colliders.get(handle)?.position();
+
+        match self.collider_positions.get(&handle) {
+            Some(previous) => Some(interpolate(previous, current, self.interpolation_factor)),
+            None => Some(*current),
+        }
+    }
+
+    /// The deformed positions of the given deformable body, interpolated between their values before and after the last time step.
+    ///
+    /// Returns `None` if the body does not exist or is not deformable.
+    pub fn deformed_positions<Bodies: BodySet<N, Handle = Handle>>(
+        &self,
+        bodies: &Bodies,
+        handle: Handle,
+    ) -> Option<Vec<N>> {
+        let (_, current) = bodies.get(handle)?.deformed_positions()?;
+
+        match self.deformed_positions.get(&handle) {
+            Some(previous) if previous.len() == current.len() => Some(
+                previous
+                    .iter()
+                    .zip(current.iter())
+                    .map(|(p, c)| *p + (*c - *p) * self.interpolation_factor)
+                    .collect(),
+            ),
+            _ => Some(current.to_vec()),
+        }
+    }
+}
+
+impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle> Default
+    for FixedTimestepper<N, Handle, CollHandle>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "dim2")]
+fn interpolate<N: RealField>(start: &Isometry<N>, end: &Isometry<N>, t: N) -> Isometry<N> {
+    let translation = start.translation.vector.lerp(&end.translation.vector, t);
+    let rotation = start.rotation.slerp(&end.rotation, t);
+    Isometry::from_parts(translation.into(), rotation)
+}
+
+#[cfg(feature = "dim3")]
+fn interpolate<N: RealField>(start: &Isometry<N>, end: &Isometry<N>, t: N) -> Isometry<N> {
+    let translation = start.translation.vector.lerp(&end.translation.vector, t);
+    let rotation = start
+        .rotation
+        .try_slerp(&end.rotation, t, N::default_epsilon())
+        .unwrap_or(end.rotation);
+    Isometry::from_parts(translation.into(), rotation)
+}
+
+#[cfg(test)]
+mod test {
+    use super::DefaultFixedTimestepper;
+    use crate::force_generator::DefaultForceGeneratorSet;
+    use crate::joint::DefaultJointConstraintSet;
+    use crate::math::{Vector, Velocity};
+    use crate::object::{
+        BodyPartHandle, DefaultBodyHandle, DefaultBodySet, DefaultColliderSet, RigidBodyDesc,
+    };
+    use crate::world::{DefaultGeometricalWorld, DefaultMechanicalWorld};
+
+    struct Scene {
+        body: DefaultBodyHandle,
+        timestepper: DefaultFixedTimestepper<f64>,
+        mechanical_world: DefaultMechanicalWorld<f64>,
+        geometrical_world: DefaultGeometricalWorld<f64>,
+        bodies: DefaultBodySet<f64>,
+        colliders: DefaultColliderSet<f64>,
+        joint_constraints: DefaultJointConstraintSet<f64>,
+        force_generators: DefaultForceGeneratorSet<f64>,
+    }
+
+    impl Scene {
+        // A single body moving at a unit velocity along the `x` axis, with a timestep of 0.1.
+        fn new() -> Self {
+            let mut bodies = DefaultBodySet::new();
+            let body = RigidBodyDesc::new()
+                .velocity(Velocity::new(Vector::x(), na::zero()))
+                .mass(1.0)
+                .build();
+            let body = bodies.insert(body);
+            let mut mechanical_world = DefaultMechanicalWorld::new(Vector::zeros());
+            mechanical_world.set_timestep(0.1);
+
+            Scene {
+                body,
+                timestepper: DefaultFixedTimestepper::default(),
+                mechanical_world,
+                geometrical_world: DefaultGeometricalWorld::new(),
+                bodies,
+                colliders: DefaultColliderSet::new(),
+                joint_constraints: DefaultJointConstraintSet::new(),
+                force_generators: DefaultForceGeneratorSet::new(),
+            }
+        }
+
+        fn step(&mut self, elapsed_time: f64) -> usize {
+            self.timestepper.step(
+                &mut self.mechanical_world,
+                &mut self.geometrical_world,
+                &mut self.bodies,
+                &mut self.colliders,
+                &mut self.joint_constraints,
+                &mut self.force_generators,
+                elapsed_time,
+                &mut Vec::new(),
+            )
+        }
+
+        // The interpolated position of the body along the `x` axis.
+        fn x(&self) -> f64 {
+            self.timestepper
+                .body_part_position(&self.bodies, BodyPartHandle(self.body, 0))
+                .unwrap()
+                .translation
+                .vector
+                .x
+        }
+    }
+
+    #[test]
+    fn accumulates_elapsed_time() {
+        let mut scene = Scene::new();
+
+        assert_eq!(scene.step(0.04), 0);
+        assert_eq!(scene.step(0.04), 0);
+        assert_eq!(scene.step(0.04), 1);
+        assert_relative_eq!(
+            scene.timestepper.accumulated_time(),
+            0.02,
+            epsilon = 1.0e-10
+        );
+        assert_eq!(scene.step(0.25), 2);
+        assert_relative_eq!(
+            scene.timestepper.accumulated_time(),
+            0.07,
+            epsilon = 1.0e-10
+        );
+    }
+
+    #[test]
+    fn clamps_the_number_of_steps() {
+        let mut scene = Scene::new();
+        scene.timestepper.set_max_steps(3);
+
+        // The time that can't be caught up with is discarded.
+        assert_eq!(scene.step(1.0), 3);
+        assert_eq!(scene.timestepper.accumulated_time(), 0.0);
+        assert_eq!(scene.step(0.05), 0);
+    }
+
+    #[test]
+    fn interpolates_the_positions() {
+        let mut scene = Scene::new();
+
+        // Before and after the first time step, the body is at 0.0 and 0.1.
+        assert_eq!(scene.step(0.125), 1);
+        assert_relative_eq!(
+            scene.timestepper.interpolation_factor(),
+            0.25,
+            epsilon = 1.0e-10
+        );
+        assert_relative_eq!(scene.x(), 0.025, epsilon = 1.0e-10);
+
+        assert_eq!(scene.step(0.05), 0);
+        assert_relative_eq!(
+            scene.timestepper.interpolation_factor(),
+            0.75,
+            epsilon = 1.0e-10
+        );
+        assert_relative_eq!(scene.x(), 0.075, epsilon = 1.0e-10);
+    }
+
+    #[test]
+    fn does_not_step_with_a_zero_timestep() {
+        let mut scene = Scene::new();
+        scene.mechanical_world.set_timestep(0.0);
+
+        assert_eq!(scene.step(1.0), 0);
+        assert_eq!(scene.timestepper.accumulated_time(), 0.0);
+        assert_eq!(scene.timestepper.interpolation_factor(), 1.0);
+    }
+}
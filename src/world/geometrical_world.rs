@@ -1,31 +1,192 @@
-use std::collections::{hash_map, HashMap};
+use std::cell::RefCell;
+use std::cmp::Ordering;
+#[cfg(feature = "serde-serialize")]
+use std::collections::HashSet;
+use std::collections::{hash_map, BinaryHeap, HashMap};
 use std::marker::PhantomData;
 
-use na::RealField;
+use na::{RealField, Unit};
 
-use ncollide::bounding_volume::AABB;
+use ncollide::bounding_volume::{BoundingVolume, AABB};
 use ncollide::pipeline::{
     self, BroadPhase, BroadPhasePairFilter, CollisionGroups, CollisionObjectSet, ContactAlgorithm,
     ContactEvents, DBVTBroadPhase, DefaultContactDispatcher, DefaultProximityDispatcher,
     Interaction, InteractionGraph, NarrowPhase, ProximityDetector, ProximityEvents,
 };
-use ncollide::query::{ContactManifold, Proximity, Ray};
+#[cfg(any(feature = "parallel", feature = "deterministic"))]
+use ncollide::pipeline::{
+    CollisionObjectGraphIndex, CollisionObjectRef, ContactDispatcher, ContactManifoldGenerator,
+};
+use ncollide::query::{
+    self, ContactManifold, DefaultTOIDispatcher, PointProjection, Proximity, Ray, RayCast,
+    RayIntersection, TOIStatus,
+};
+#[cfg(feature = "deterministic")]
+use ncollide::query::{ContactId, TrackedContact};
+#[cfg(any(feature = "parallel", feature = "deterministic"))]
+use ncollide::query::{ContactPrediction, ContactPreprocessor};
+#[cfg(feature = "deterministic")]
+use ncollide::shape::FeatureId;
+#[cfg(feature = "dim2")]
+use ncollide::shape::Polyline;
+use ncollide::shape::Shape;
+#[cfg(feature = "dim3")]
+use ncollide::shape::TriMesh;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 use crate::object::{
-    BodyHandle, BodySet, Collider, ColliderAnchor, ColliderHandle, ColliderSet, DefaultBodyHandle,
-    DefaultBodySet, DefaultColliderHandle, DefaultColliderSet,
+    Body, BodyHandle, BodyPartHandle, BodySet, Collider, ColliderAnchor, ColliderHandle,
+    ColliderSet, DefaultBodyHandle, DefaultBodySet, DefaultColliderHandle, DefaultColliderSet,
 };
 use crate::volumetric::Volumetric;
 
-use crate::math::Point;
+use crate::math::{ForceType, Isometry, Point, Vector};
 
 /// The default geometrical world, that can be used with a `DefaultBodyHandle` and `DefaultColliderHandle`.
 pub type DefaultGeometricalWorld<N> = GeometricalWorld<N, DefaultBodyHandle, DefaultColliderHandle>;
 
+/// The result of the cast of a shape against a collider.
+#[derive(Clone, Debug)]
+pub struct ShapeCastHit<N: RealField> {
+    /// The time at which the cast shape touches the collider.
+    pub toi: N,
+    /// The world-space point of the cast shape touching the collider at the time of impact.
+    pub witness1: Point<N>,
+    /// The world-space point of the collider touching the cast shape at the time of impact.
+    pub witness2: Point<N>,
+    /// The world-space outward normal of the cast shape at the time of impact.
+    pub normal1: Unit<Vector<N>>,
+    /// The world-space outward normal of the collider at the time of impact.
+    pub normal2: Unit<Vector<N>>,
+    /// The way the time-of-impact computation terminated.
+    ///
+    /// If this is `TOIStatus::Penetrating`, the cast shape already intersected the collider
+    /// at its initial position and the witness points and normals are not reliable.
+    pub status: TOIStatus,
+}
+
+// A ray hit ordered by time of impact, used to keep the closest hits in a max-heap.
+struct RayHit<N: RealField, CollHandle> {
+    handle: CollHandle,
+    inter: RayIntersection<N>,
+}
+
+impl<N: RealField, CollHandle> PartialOrd for RayHit<N, CollHandle> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N: RealField, CollHandle> Ord for RayHit<N, CollHandle> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // The times of impact are finite.
+        self.inter
+            .toi
+            .partial_cmp(&other.inter.toi)
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+impl<N: RealField, CollHandle> PartialEq for RayHit<N, CollHandle> {
+    fn eq(&self, other: &Self) -> bool {
+        self.inter.toi == other.inter.toi
+    }
+}
+
+impl<N: RealField, CollHandle> Eq for RayHit<N, CollHandle> {}
+
+/// How the magnitude of a `RadialForce` decreases with the distance to its center.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub enum RadialFalloff {
+    /// The magnitude is the same everywhere within the radius.
+    Constant,
+    /// The magnitude is multiplied by `1 - distance / radius`.
+    Linear,
+    /// The magnitude is multiplied by `(1 - distance / radius)²`.
+    Quadratic,
+}
+
+/// A force, or an impulse, pushing body parts away from a point, e.g., an explosion.
+///
+/// See `GeometricalWorld::apply_radial_force`.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct RadialForce<N: RealField> {
+    /// The world-space point the body parts are pushed away from.
+    pub center: Point<N>,
+    /// The distance beyond which body parts are not affected.
+    ///
+    /// Nothing is affected if this is not positive.
+    pub radius: N,
+    /// The magnitude of the force applied at the center.
+    pub magnitude: N,
+    /// How the magnitude decreases with the distance to the center.
+    pub falloff: RadialFalloff,
+    /// The kind of force applied.
+    pub force_type: ForceType,
+    /// If set, only the points within the cone with this axis and half-angle are affected.
+    pub cone: Option<(Unit<Vector<N>>, N)>,
+    /// If `true`, the points hidden from the center by another collider are not affected.
+    pub occlusion: bool,
+}
+
+impl<N: RealField> RadialForce<N> {
+    /// Creates a radial impulse with a linear falloff, no cone, and no occlusion test.
+    pub fn new(center: Point<N>, radius: N, magnitude: N) -> Self {
+        RadialForce {
+            center,
+            radius,
+            magnitude,
+            falloff: RadialFalloff::Linear,
+            force_type: ForceType::Impulse,
+            cone: None,
+            occlusion: false,
+        }
+    }
+
+    // The magnitude of this force at the given point and its distance to the center, or `None`
+    // if the point is not affected.
+    fn magnitude_at(&self, point: &Point<N>) -> Option<(N, N)> {
+        if self.radius <= N::zero() {
+            return None;
+        }
+
+        let dir = point - self.center;
+        let dist = dir.norm();
+
+        if dist > self.radius {
+            return None;
+        }
+
+        if let Some((axis, half_angle)) = self.cone {
+            if dist > N::default_epsilon() && axis.dot(&dir) < dist * half_angle.cos() {
+                return None;
+            }
+        }
+
+        let ratio = N::one() - dist / self.radius;
+        let magnitude = match self.falloff {
+            RadialFalloff::Constant => self.magnitude,
+            RadialFalloff::Linear => self.magnitude * ratio,
+            RadialFalloff::Quadratic => self.magnitude * ratio * ratio,
+        };
+
+        Some((magnitude, dist))
+    }
+}
+
 /// The world managing all geometric queries.
 ///
 /// This is a wrapper over the `CollisionWorld` structure from `ncollide` to simplify
 /// its use with the [object::Collider] structure.
+///
+/// With the `serde-serialize` feature, the broad phase and narrow phase are not serialized: they are
+/// rebuilt from the colliders during the next step, and the interactions are restored in the same
+/// order and with the same proximities. Because this feature enables `deterministic`, the contacts
+/// are recomputed from scratch at each step, so a deserialized world steps exactly like the
+/// original one.
 pub struct GeometricalWorld<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle> {
     /// The broad phase used by this collision world.
     pub(crate) broad_phase: Box<dyn BroadPhase<N, AABB<N>, CollHandle>>,
@@ -34,6 +195,27 @@ pub struct GeometricalWorld<N: RealField, Handle: BodyHandle, CollHandle: Collid
     /// The graph of interactions detected so far.
     pub(crate) interactions: InteractionGraph<N, CollHandle>,
     pub(crate) body_colliders: HashMap<Handle, Vec<CollHandle>>,
+    // Used to compute the contact manifolds outside of the narrow phase.
+    #[cfg(any(feature = "parallel", feature = "deterministic"))]
+    pub(crate) contact_dispatcher: Box<dyn ContactDispatcher<N>>,
+    // Interactions that existed when this world was serialized. They are recreated once the
+    // deserialized colliders are registered, and used to re-key the solver's impulse cache once
+    // the narrow phase has assigned new contact ids.
+    #[cfg(feature = "serde-serialize")]
+    restored_interactions: Option<Vec<SerializedInteraction<CollHandle>>>,
+}
+
+// An interaction, with its colliders in the same order as in the interaction graph.
+#[cfg(feature = "serde-serialize")]
+#[derive(Serialize, Deserialize)]
+enum SerializedInteraction<CollHandle> {
+    // The features and ids of the contacts.
+    Contact(
+        CollHandle,
+        CollHandle,
+        Vec<(FeatureId, FeatureId, ContactId)>,
+    ),
+    Proximity(CollHandle, CollHandle, Proximity),
 }
 
 impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
@@ -49,6 +231,10 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
             narrow_phase,
             interactions: InteractionGraph::new(),
             body_colliders: HashMap::new(),
+            #[cfg(any(feature = "parallel", feature = "deterministic"))]
+            contact_dispatcher: Box::new(DefaultContactDispatcher::new()),
+            #[cfg(feature = "serde-serialize")]
+            restored_interactions: None,
         }
     }
 
@@ -104,8 +290,27 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
         bodies: &mut dyn BodySet<N, Handle = Handle>,
         colliders: &mut Colliders,
     ) {
+        let mut inserted = Vec::new();
+
         while let Some(handle) = colliders.pop_insertion_event() {
+            inserted.push(handle);
+        }
+
+        // Colliders restored by a deserialization are registered in the order they had in the
+        // interaction graph, so they keep the same graph indices.
+        #[cfg(feature = "serde-serialize")]
+        inserted.sort_by_key(|handle| {
+            colliders
+                .get(*handle)
+                .and_then(|c| c.restored_graph_index())
+                .unwrap_or(usize::max_value())
+        });
+
+        for handle in inserted {
             if let Some(collider) = colliders.get_mut(handle) {
+                // The mass properties of restored colliders are already part of their parent body.
+                let add_mass = collider.restored_graph_index().is_none();
+                collider.set_restored_graph_index(None);
                 self.register_collider(handle, collider);
 
                 match collider.anchor() {
@@ -118,7 +323,7 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
                             .expect("Invalid parent body part handle.");
 
                         // Update the parent body's inertia.
-                        if !collider.density().is_zero() {
+                        if add_mass && !collider.density().is_zero() {
                             let (com, inertia) = collider.shape().transformed_mass_properties(
                                 collider.density(),
                                 position_wrt_body_part,
@@ -140,6 +345,9 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
                 }
             }
         }
+
+        #[cfg(feature = "serde-serialize")]
+        self.restore_interactions(colliders);
     }
 
     fn handle_removals<Colliders: ColliderSet<N, Handle, Handle = CollHandle>>(
@@ -297,7 +505,6 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
             user_filter,
             _pd: PhantomData,
         };
-
         pipeline::perform_broad_phase(
             &BroadPhasePairFilterSets {
                 bodies,
@@ -308,7 +515,53 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
             &mut self.narrow_phase,
             &mut self.interactions,
             Some(&pair_filter),
-        )
+        );
+
+        #[cfg(feature = "deterministic")]
+        self.orient_interactions(colliders);
+    }
+
+    // The order of the two colliders of an interaction determines the direction of its contact
+    // normals. This gives the interactions created by the broad phase, which reports the collider
+    // pairs in an arbitrary order, the order of the graph indices of their colliders.
+    //
+    // The interactions with contacts, or with a proximity other than `Disjoint`, keep their order
+    // to preserve their state. All the others are recreated if needed.
+    #[cfg(feature = "deterministic")]
+    fn orient_interactions<Colliders>(&mut self, colliders: &Colliders)
+    where
+        Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
+    {
+        let graph_index = |h| colliders.get(h).and_then(|c| c.graph_index());
+        let mut misoriented: Vec<_> = self
+            .interactions
+            .interaction_pairs(false)
+            .filter(|(h1, h2, interaction)| {
+                let is_empty = match interaction {
+                    Interaction::Contact(_, manifold) => manifold.len() == 0,
+                    Interaction::Proximity(_, proximity) => *proximity == Proximity::Disjoint,
+                };
+                is_empty && graph_index(*h2) < graph_index(*h1)
+            })
+            .map(|(h1, h2, _)| (h1, h2))
+            .collect();
+
+        // The interactions of a deserialized world keep the order of colliders they had when it
+        // was serialized.
+        #[cfg(feature = "serde-serialize")]
+        {
+            if let Some(restored) = &self.restored_interactions {
+                let restored: HashSet<_> = restored.iter().map(|i| i.colliders()).collect();
+                misoriented.retain(|pair| !restored.contains(pair));
+            }
+        }
+
+        for (h1, h2) in misoriented {
+            self.narrow_phase
+                .handle_interaction(&mut self.interactions, colliders, h1, h2, false);
+            self.narrow_phase
+                .handle_interaction(&mut self.interactions, colliders, h2, h1, true);
+        }
     }
 
     /// Executes the narrow phase of the collision detection pipeline.
@@ -316,9 +569,103 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
     where
         Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
     {
+        #[cfg(any(feature = "parallel", feature = "deterministic"))]
+        {
+            let detectors = self.update_contacts(colliders);
+            pipeline::perform_narrow_phase(
+                colliders,
+                &mut self.narrow_phase,
+                &mut self.interactions,
+            );
+
+            for (id1, id2, detector) in detectors {
+                if let Some((_, _, Interaction::Contact(algo, _))) =
+                    self.interactions.interaction_pair_mut(id1, id2)
+                {
+                    *algo = detector
+                }
+            }
+        }
+
+        #[cfg(not(any(feature = "parallel", feature = "deterministic")))]
         pipeline::perform_narrow_phase(colliders, &mut self.narrow_phase, &mut self.interactions)
     }
 
+    // Computes, with `compute_contacts`, the contact manifolds the narrow phase is about to update.
+    // This is done on several threads with the `parallel` feature.
+    //
+    // The contact detector of each of those pairs is temporarily replaced by one that outputs the
+    // precomputed manifold. This way, the narrow phase still assigns the contact ids and emits the
+    // contact events sequentially, in a deterministic order. Returns the original contact detectors.
+    #[cfg(any(feature = "parallel", feature = "deterministic"))]
+    fn update_contacts<Colliders>(
+        &mut self,
+        colliders: &Colliders,
+    ) -> Vec<(
+        CollisionObjectGraphIndex,
+        CollisionObjectGraphIndex,
+        ContactAlgorithm<N>,
+    )>
+    where
+        Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
+    {
+        let pairs: Vec<_> = self
+            .interactions
+            .contact_pairs(false)
+            .filter_map(|(h1, h2, _, _)| {
+                let c1 = colliders.get(h1)?;
+                let c2 = colliders.get(h2)?;
+                let needs_update = c1.update_flags().needs_narrow_phase_update()
+                    || c2.update_flags().needs_narrow_phase_update();
+                let prediction = c1
+                    .query_type()
+                    .contact_queries_to_prediction(c2.query_type())?;
+
+                if needs_update {
+                    Some((c1, c2, prediction))
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        let mut jobs = Vec::with_capacity(pairs.len());
+
+        for (c1, c2, prediction) in pairs {
+            let id1 = c1.graph_index().expect(crate::NOT_REGISTERED_ERROR);
+            let id2 = c2.graph_index().expect(crate::NOT_REGISTERED_ERROR);
+
+            if let Some((_, _, Interaction::Contact(algo, manifold))) =
+                self.interactions.interaction_pair_mut(id1, id2)
+            {
+                let detector = std::mem::replace(algo, Box::new(PrecomputedContacts::new()));
+                jobs.push((id1, id2, c1, c2, prediction, detector, manifold.clone()));
+            }
+        }
+
+        let dispatcher = &*self.contact_dispatcher;
+        #[cfg(feature = "parallel")]
+        let jobs_iter = jobs.par_iter_mut();
+        #[cfg(not(feature = "parallel"))]
+        let jobs_iter = jobs.iter_mut();
+
+        jobs_iter.for_each(|(_, _, c1, c2, prediction, detector, manifold)| {
+            compute_contacts(dispatcher, c1, c2, prediction, detector, manifold)
+        });
+
+        jobs.into_iter()
+            .map(|(id1, id2, _, _, _, detector, manifold)| {
+                if let Some((_, _, Interaction::Contact(algo, _))) =
+                    self.interactions.interaction_pair_mut(id1, id2)
+                {
+                    *algo = Box::new(PrecomputedContacts { manifold })
+                }
+
+                (id1, id2, detector)
+            })
+            .collect()
+    }
+
     /// The broad-phase used by this geometrical world.
     pub fn broad_phase(&self) -> &dyn BroadPhase<N, AABB<N>, CollHandle> {
         &*self.broad_phase
@@ -340,6 +687,108 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
         pipeline::interferences_with_ray(&colliders, &*self.broad_phase, ray, max_toi, groups)
     }
 
+    /// Computes the collider hit first by the given ray.
+    ///
+    /// If `max_toi` is `None`, the ray is not bounded. If `solid` is `false`, a ray starting inside
+    /// of a collider hits its boundary instead of hitting it at time zero. Only the colliders that
+    /// can interact with `groups` and for which `filter` returns `true` are taken into account.
+    pub fn first_interference_with_ray<'a, Colliders, Filter>(
+        &self,
+        colliders: &'a Colliders,
+        ray: &Ray<N>,
+        max_toi: Option<N>,
+        solid: bool,
+        groups: &CollisionGroups,
+        filter: Filter,
+    ) -> Option<(CollHandle, &'a Collider<N, Handle>, RayIntersection<N>)>
+    where
+        Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
+        Filter: Fn(CollHandle, &Collider<N, Handle>) -> bool,
+    {
+        self.first_interferences_with_ray(colliders, ray, max_toi, solid, groups, filter, 1)
+            .pop()
+    }
+
+    /// Computes the `max_hits` colliders hit first by the given ray, sorted by time of impact.
+    ///
+    /// See `self.first_interference_with_ray` for details about the other arguments.
+    pub fn first_interferences_with_ray<'a, Colliders, Filter>(
+        &self,
+        colliders: &'a Colliders,
+        ray: &Ray<N>,
+        max_toi: Option<N>,
+        solid: bool,
+        groups: &CollisionGroups,
+        filter: Filter,
+        max_hits: usize,
+    ) -> Vec<(CollHandle, &'a Collider<N, Handle>, RayIntersection<N>)>
+    where
+        Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
+        Filter: Fn(CollHandle, &Collider<N, Handle>) -> bool,
+    {
+        if max_hits == 0 {
+            return Vec::new();
+        }
+
+        let max_toi = max_toi.unwrap_or_else(N::max_value);
+        // The closest hits found so far, the farthest one being on top.
+        let hits = RefCell::new(BinaryHeap::with_capacity(max_hits));
+
+        // Once `max_hits` hits are found, the farthest one is returned to the broad phase
+        // so it prunes all the nodes that are farther away.
+        let cost_fn = |handle: CollHandle, ray: &Ray<N>, max_toi: N| {
+            let mut hits = hits.borrow_mut();
+            let is_full = hits.len() == max_hits;
+            let bound = if is_full {
+                hits.peek()
+                    .map_or(max_toi, |h: &RayHit<N, CollHandle>| h.inter.toi)
+            } else {
+                max_toi
+            };
+
+            let collider = colliders.get(handle)?;
+
+            if !groups.can_interact_with_groups(collider.collision_groups())
+                || !filter(handle, collider)
+            {
+                return None;
+            }
+
+            let inter =
+                collider
+                    .shape()
+                    .toi_and_normal_with_ray(collider.position(), ray, bound, solid)?;
+
+            if !inter.toi.is_finite() || (is_full && inter.toi >= bound) {
+                return None;
+            }
+
+            if is_full {
+                let _ = hits.pop();
+            }
+
+            hits.push(RayHit { handle, inter });
+
+            if hits.len() == max_hits {
+                hits.peek().map(|h| (h.handle, h.inter))
+            } else {
+                None
+            }
+        };
+
+        // The broad phase may store its proxies in several trees, and the hits
+        // of all of them are gathered by `cost_fn`. So its result is ignored.
+        let _ = self
+            .broad_phase
+            .first_interference_with_ray(ray, max_toi, &cost_fn);
+
+        hits.into_inner()
+            .into_sorted_vec()
+            .into_iter()
+            .filter_map(|h| Some((h.handle, colliders.get(h.handle)?, h.inter)))
+            .collect()
+    }
+
     /// Computes the interferences between every rigid bodies of a given broad phase, and a point.
     #[inline]
     pub fn interferences_with_point<
@@ -370,6 +819,332 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
         pipeline::interferences_with_aabb(&colliders, &*self.broad_phase, aabb, groups)
     }
 
+    /// Casts a shape along a linear motion and returns every collider it hits, sorted by time of impact.
+    ///
+    /// The shape starts at `position` and moves with the constant `velocity` until the time `max_toi`,
+    /// which must be finite. Only the colliders that can interact with `groups` and for which `filter`
+    /// returns `true` are taken into account. The filter is also given the body each collider is
+    /// attached to, e.g., to check its status. Colliders whose body is not in `bodies` are skipped.
+    ///
+    /// The colliders whose shape is not supported by the time-of-impact dispatcher against `shape`
+    /// are skipped, as well as the hits with a non-finite time of impact.
+    pub fn cast_shape<'a, Bodies, Colliders, Filter>(
+        &self,
+        bodies: &Bodies,
+        colliders: &'a Colliders,
+        shape: &dyn Shape<N>,
+        position: &Isometry<N>,
+        velocity: &Vector<N>,
+        max_toi: N,
+        groups: &CollisionGroups,
+        filter: Filter,
+    ) -> Vec<(CollHandle, &'a Collider<N, Handle>, ShapeCastHit<N>)>
+    where
+        Bodies: BodySet<N, Handle = Handle>,
+        Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
+        Filter: Fn(CollHandle, &Collider<N, Handle>, &dyn Body<N>) -> bool,
+    {
+        let mut end = *position;
+        end.translation.vector += velocity * max_toi;
+        let aabb = shape.aabb(position).merged(&shape.aabb(&end));
+        let mut handles = Vec::new();
+        self.broad_phase
+            .interferences_with_bounding_volume(&aabb, &mut handles);
+
+        let mut hits: Vec<_> = handles
+            .into_iter()
+            .filter_map(|handle| {
+                let collider = colliders.get(*handle)?;
+                let body = bodies.get(collider.body())?;
+
+                if !groups.can_interact_with_groups(collider.collision_groups())
+                    || !filter(*handle, collider, body)
+                {
+                    return None;
+                }
+
+                let toi = match query::time_of_impact(
+                    &DefaultTOIDispatcher,
+                    position,
+                    velocity,
+                    shape,
+                    collider.position(),
+                    &Vector::zeros(),
+                    collider.shape(),
+                    max_toi,
+                    N::zero(),
+                ) {
+                    Ok(Some(toi)) => toi,
+                    // No hit along the motion.
+                    Ok(None) => return None,
+                    // This pair of shapes is not supported: skip the collider.
+                    Err(_) => return None,
+                };
+
+                if !toi.toi.is_finite() {
+                    return None;
+                }
+
+                let mut position_at_toi = *position;
+                position_at_toi.translation.vector += velocity * toi.toi;
+                let hit = ShapeCastHit {
+                    toi: toi.toi,
+                    witness1: position_at_toi * toi.witness1,
+                    witness2: collider.position() * toi.witness2,
+                    normal1: position_at_toi * toi.normal1,
+                    normal2: collider.position() * toi.normal2,
+                    status: toi.status,
+                };
+
+                Some((*handle, collider, hit))
+            })
+            .collect();
+
+        // All the times of impact are finite here so this comparison never fails.
+        hits.sort_by(|a, b| a.2.toi.partial_cmp(&b.2.toi).unwrap_or(Ordering::Equal));
+        hits
+    }
+
+    /// Computes all the colliders intersecting the given shape.
+    ///
+    /// Only the colliders that can interact with `groups` and for which `filter` returns `true` are
+    /// taken into account. The filter is also given the body each collider is attached to.
+    pub fn intersections_with_shape<'a, Bodies, Colliders, Filter>(
+        &'a self,
+        bodies: &'a Bodies,
+        colliders: &'a Colliders,
+        shape: &'a dyn Shape<N>,
+        position: &'a Isometry<N>,
+        groups: &'a CollisionGroups,
+        filter: Filter,
+    ) -> impl Iterator<Item = (CollHandle, &'a Collider<N, Handle>)> + 'a
+    where
+        Bodies: BodySet<N, Handle = Handle>,
+        Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
+        Filter: Fn(CollHandle, &Collider<N, Handle>, &dyn Body<N>) -> bool + 'a,
+    {
+        let aabb = shape.aabb(position);
+        let mut handles = Vec::new();
+        self.broad_phase
+            .interferences_with_bounding_volume(&aabb, &mut handles);
+
+        handles.into_iter().filter_map(move |handle| {
+            let collider = colliders.get(*handle)?;
+            let body = bodies.get(collider.body())?;
+
+            if groups.can_interact_with_groups(collider.collision_groups())
+                && filter(*handle, collider, body)
+                && query::proximity(
+                    position,
+                    shape,
+                    collider.position(),
+                    collider.shape(),
+                    N::zero(),
+                ) == Proximity::Intersecting
+            {
+                Some((*handle, collider))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Computes the collider closest to the given point, and the projection of the point on it.
+    ///
+    /// Colliders further than `max_dist` from the point are ignored. If `solid` is `false`, points
+    /// inside of a collider are projected on its boundary. Only the colliders that can interact with
+    /// `groups` and for which `filter` returns `true` are taken into account. The filter is also given
+    /// the body each collider is attached to.
+    pub fn project_point<'a, Bodies, Colliders, Filter>(
+        &self,
+        bodies: &Bodies,
+        colliders: &'a Colliders,
+        point: &Point<N>,
+        max_dist: N,
+        solid: bool,
+        groups: &CollisionGroups,
+        filter: Filter,
+    ) -> Option<(CollHandle, &'a Collider<N, Handle>, PointProjection<N>)>
+    where
+        Bodies: BodySet<N, Handle = Handle>,
+        Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
+        Filter: Fn(CollHandle, &Collider<N, Handle>, &dyn Body<N>) -> bool,
+    {
+        let aabb = AABB::new(*point, *point).loosened(max_dist);
+        let mut handles = Vec::new();
+        self.broad_phase
+            .interferences_with_bounding_volume(&aabb, &mut handles);
+
+        let mut closest = None;
+        let mut closest_dist = max_dist;
+
+        for handle in handles {
+            let collider = try_continue!(colliders.get(*handle));
+            let body = try_continue!(bodies.get(collider.body()));
+
+            if !groups.can_interact_with_groups(collider.collision_groups())
+                || !filter(*handle, collider, body)
+            {
+                continue;
+            }
+
+            let shape = try_continue!(collider.shape().as_point_query());
+            let proj = shape.project_point(collider.position(), point, solid);
+            let dist = na::distance(point, &proj.point);
+
+            if dist <= closest_dist {
+                closest_dist = dist;
+                closest = Some((*handle, collider, proj));
+            }
+        }
+
+        closest
+    }
+
+    /// Applies a radial force to every dynamic body part within its radius, and wakes them up.
+    ///
+    /// Rigid bodies and multibody links are pushed at the point of their colliders closest to the
+    /// center. Deformable bodies are pushed at each affected vertex of their colliders: the force
+    /// computed for each vertex is divided by the number of affected vertices of the collider, so
+    /// a deformable body is not pushed harder than a rigid one because of its resolution. Only the
+    /// non-sensor colliders that can interact with `groups` and for which `filter` returns `true`
+    /// are affected, and can hide other colliders from the center when `force.occlusion` is `true`.
+    pub fn apply_radial_force<Bodies, Colliders, Filter>(
+        &self,
+        bodies: &mut Bodies,
+        colliders: &Colliders,
+        force: &RadialForce<N>,
+        groups: &CollisionGroups,
+        filter: Filter,
+    ) where
+        Bodies: BodySet<N, Handle = Handle>,
+        Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
+        Filter: Fn(CollHandle, &Collider<N, Handle>) -> bool,
+    {
+        let filter = |handle: CollHandle, collider: &Collider<N, Handle>| {
+            !collider.is_sensor() && filter(handle, collider)
+        };
+        let is_visible = |body: Handle, point: &Point<N>, dist: N| {
+            if !force.occlusion || dist <= N::default_epsilon() {
+                return true;
+            }
+
+            let ray = Ray::new(force.center, (point - force.center) / dist);
+            let occluder = self.first_interference_with_ray(
+                colliders,
+                &ray,
+                Some(dist),
+                true,
+                groups,
+                |handle, collider| collider.body() != body && filter(handle, collider),
+            );
+
+            occluder.is_none()
+        };
+
+        let aabb = AABB::from_half_extents(force.center, Vector::repeat(force.radius));
+        // The body part, application point, and value of each force to apply.
+        let mut targets: Vec<(BodyPartHandle<Handle>, Point<N>, Vector<N>)> = Vec::new();
+        // The rigid targets, with their distance to the center.
+        let mut rigid_targets: Vec<(BodyPartHandle<Handle>, Point<N>, Vector<N>, N)> = Vec::new();
+
+        for (handle, collider) in self.interferences_with_aabb(colliders, &aabb, groups) {
+            if !filter(handle, collider) {
+                continue;
+            }
+
+            match collider.anchor() {
+                ColliderAnchor::OnBodyPart { body_part, .. } => {
+                    let shape = try_continue!(collider.shape().as_point_query());
+                    let point = shape
+                        .project_point(collider.position(), &force.center, true)
+                        .point;
+                    let (magnitude, dist) = try_continue!(force.magnitude_at(&point));
+                    // A collider containing the center is pushed away from it through its center of mass.
+                    let center_of_mass = collider.position() * collider.shape().center_of_mass();
+                    let dir =
+                        Unit::try_new(point - force.center, N::default_epsilon()).or_else(|| {
+                            Unit::try_new(center_of_mass - force.center, N::default_epsilon())
+                        });
+                    let value = *try_continue!(dir) * magnitude;
+
+                    match rigid_targets.iter_mut().find(|t| t.0 == *body_part) {
+                        Some(target) if target.3 <= dist => {}
+                        Some(target) => *target = (*body_part, point, value, dist),
+                        None => rigid_targets.push((*body_part, point, value, dist)),
+                    }
+                }
+                ColliderAnchor::OnDeformableBody { body, .. } => {
+                    let position = collider.position();
+
+                    #[cfg(feature = "dim2")]
+                    let faces = collider.shape().as_shape::<Polyline<N>>().map(|polyline| {
+                        let faces: Vec<_> = polyline
+                            .edges()
+                            .iter()
+                            .map(|e| e.indices.coords.as_slice().to_vec())
+                            .collect();
+                        (polyline.points(), faces)
+                    });
+                    #[cfg(feature = "dim3")]
+                    let faces = collider.shape().as_shape::<TriMesh<N>>().map(|mesh| {
+                        let faces: Vec<_> = mesh
+                            .faces()
+                            .iter()
+                            .map(|f| f.indices.coords.as_slice().to_vec())
+                            .collect();
+                        (mesh.points(), faces)
+                    });
+
+                    let (points, faces) = try_continue!(faces);
+                    let mut visited = vec![false; points.len()];
+                    let first_target = targets.len();
+
+                    for (i, face) in faces.iter().enumerate() {
+                        for vid in face {
+                            if visited[*vid] {
+                                continue;
+                            }
+
+                            visited[*vid] = true;
+                            let point = position * points[*vid];
+                            let (magnitude, dist) = try_continue!(force.magnitude_at(&point));
+
+                            if dist > N::default_epsilon() && is_visible(*body, &point, dist) {
+                                let value = (point - force.center) * (magnitude / dist);
+                                targets.push((collider.body_part(i), point, value));
+                            }
+                        }
+                    }
+
+                    // Split the force between all the affected vertices.
+                    let vertex_targets = &mut targets[first_target..];
+                    let share = N::one() / na::convert(vertex_targets.len() as f64);
+
+                    for target in vertex_targets {
+                        target.2 *= share;
+                    }
+                }
+            }
+        }
+
+        for (part, point, value, dist) in rigid_targets {
+            if is_visible(part.0, &point, dist) {
+                targets.push((part, point, value));
+            }
+        }
+
+        for (part, point, value) in targets {
+            let body = try_continue!(bodies.get_mut(part.0));
+
+            if !body.is_dynamic() {
+                continue;
+            }
+
+            body.apply_force_at_point(part.1, &value, &point, force.force_type, true);
+        }
+    }
+
     /// The contact events pool.
     pub fn contact_events(&self) -> &ContactEvents<CollHandle> {
         self.narrow_phase.contact_events()
@@ -863,6 +1638,133 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
     }
 }
 
+#[cfg(feature = "serde-serialize")]
+impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
+    GeometricalWorld<N, Handle, CollHandle>
+{
+    // Recreates the interactions of a deserialized world in the order they had when it was
+    // serialized, with the same order of colliders and the same proximities. Their contacts are
+    // computed by the next narrow phase.
+    fn restore_interactions<Colliders: ColliderSet<N, Handle, Handle = CollHandle>>(
+        &mut self,
+        colliders: &Colliders,
+    ) {
+        let restored_interactions = try_ret!(self.restored_interactions.as_ref());
+
+        for interaction in restored_interactions {
+            let (h1, h2) = interaction.colliders();
+
+            if colliders.get(h1).is_none() || colliders.get(h2).is_none() {
+                continue;
+            }
+
+            self.narrow_phase
+                .handle_interaction(&mut self.interactions, colliders, h1, h2, true);
+
+            if let SerializedInteraction::Proximity(_, _, proximity) = interaction {
+                let id1 = colliders.get(h1).and_then(|c| c.graph_index());
+                let id2 = colliders.get(h2).and_then(|c| c.graph_index());
+
+                if let (Some(id1), Some(id2)) = (id1, id2) {
+                    if let Some((_, _, _, prox)) = self.interactions.proximity_pair_mut(id1, id2) {
+                        *prox = *proximity
+                    }
+                }
+            }
+        }
+    }
+
+    /// Pairs each contact id from before the serialization to the id the narrow phase
+    /// assigned to the same contact after deserialization.
+    ///
+    /// Returns `None` if this world was not deserialized, or if this was already
+    /// called since its deserialization.
+    pub(crate) fn take_restored_contact_ids(&mut self) -> Option<Vec<(ContactId, ContactId)>> {
+        let restored_interactions = self.restored_interactions.take()?;
+
+        let mut manifolds = HashMap::new();
+
+        for (h1, h2, _, manifold) in self.interactions.contact_pairs(false) {
+            let _ = manifolds.insert((h1, h2), manifold);
+        }
+
+        let mut result = Vec::new();
+
+        for interaction in restored_interactions {
+            if let SerializedInteraction::Contact(h1, h2, contacts) = interaction {
+                if let Some(manifold) = manifolds.get(&(h1, h2)) {
+                    // The contacts are matched the same way `compute_contacts` matches the
+                    // contacts from two successive steps.
+                    let ids = match_contacts(&contacts, manifold.contacts());
+
+                    for (contact, id) in manifold.contacts().zip(ids) {
+                        result.extend(id.map(|id| (id, contact.id)));
+                    }
+                }
+            }
+        }
+
+        Some(result)
+    }
+}
+
+#[cfg(feature = "serde-serialize")]
+impl<CollHandle: Copy> SerializedInteraction<CollHandle> {
+    fn colliders(&self) -> (CollHandle, CollHandle) {
+        match self {
+            SerializedInteraction::Contact(h1, h2, _) => (*h1, *h2),
+            SerializedInteraction::Proximity(h1, h2, _) => (*h1, *h2),
+        }
+    }
+}
+
+// The broad phase, narrow phase, and interaction graph are not serialized. A deserialized
+// geometrical world is empty and gets populated again by the `maintain` method from the
+// colliders reported as inserted by the collider set. Only the collider pairs of the existing
+// interactions, their proximity, and the features and identifiers of their contacts are saved.
+// This is enough to recreate the same interactions, and to keep the warmstart impulses cached by
+// the solver usable.
+#[cfg(feature = "serde-serialize")]
+impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle + serde::Serialize>
+    serde::Serialize for GeometricalWorld<N, Handle, CollHandle>
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let interactions: Vec<_> = self
+            .interactions
+            .interaction_pairs(false)
+            .map(|(h1, h2, interaction)| match interaction {
+                Interaction::Contact(_, manifold) => {
+                    let contacts = manifold
+                        .contacts()
+                        .map(|c| (c.kinematic.feature1(), c.kinematic.feature2(), c.id))
+                        .collect();
+                    SerializedInteraction::Contact(h1, h2, contacts)
+                }
+                Interaction::Proximity(_, proximity) => {
+                    SerializedInteraction::Proximity(h1, h2, *proximity)
+                }
+            })
+            .collect();
+
+        interactions.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde-serialize")]
+impl<
+        'de,
+        N: RealField,
+        Handle: BodyHandle,
+        CollHandle: ColliderHandle + serde::Deserialize<'de>,
+    > serde::Deserialize<'de> for GeometricalWorld<N, Handle, CollHandle>
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut result = GeometricalWorld::new();
+        result.restored_interactions = Some(serde::Deserialize::deserialize(deserializer)?);
+        Ok(result)
+    }
+}
+
 /// The default set of bodies and colliders used within broad phase collision filtering.
 pub type DefaultBroadPhasePairFilterSets<'a, N> =
     BroadPhasePairFilterSets<'a, N, DefaultBodySet<N>, DefaultColliderSet<N>>;
@@ -969,3 +1871,137 @@ where
             && self.user_filter.is_pair_valid(h1, h2, set)
     }
 }
+
+// Replaces a contact manifold by the contacts between two colliders at their current positions.
+//
+// With the `deterministic` feature, the contacts are computed from scratch, by a new contact
+// detector, and do not depend on any result from the previous updates, e.g., the separating
+// direction found by GJK. Only their ids are taken from the contacts of the previous manifold on
+// the same features. This way, the contacts of a deserialized world are the same as the ones of
+// the original world.
+#[cfg(any(feature = "parallel", feature = "deterministic"))]
+fn compute_contacts<N: RealField, Handle: BodyHandle>(
+    dispatcher: &dyn ContactDispatcher<N>,
+    c1: &Collider<N, Handle>,
+    c2: &Collider<N, Handle>,
+    prediction: &ContactPrediction<N>,
+    detector: &mut ContactAlgorithm<N>,
+    manifold: &mut ContactManifold<N>,
+) {
+    #[cfg(feature = "deterministic")]
+    let previous: Vec<_> = manifold
+        .contacts()
+        .map(|c| (c.kinematic.feature1(), c.kinematic.feature2(), c.id))
+        .collect();
+
+    #[cfg(feature = "deterministic")]
+    {
+        if let Some(new_detector) = dispatcher.get_contact_algorithm(c1.shape(), c2.shape()) {
+            *detector = new_detector;
+        }
+
+        *manifold = detector.init_manifold();
+    }
+
+    manifold.save_cache_and_clear();
+    let _ = detector.generate_contacts(
+        dispatcher,
+        c1.position(),
+        c1.shape(),
+        None,
+        c2.position(),
+        c2.shape(),
+        None,
+        prediction,
+        manifold,
+    );
+
+    #[cfg(feature = "deterministic")]
+    {
+        let ids = match_contacts(&previous, manifold.contacts());
+
+        for (contact, id) in manifold.contacts_mut().zip(ids) {
+            if let Some(id) = id {
+                contact.id = id;
+            }
+        }
+    }
+}
+
+// Updates the contact manifold between two colliders like `NarrowPhase::update_contact`, but
+// computes the contacts with `compute_contacts`.
+#[cfg(feature = "deterministic")]
+pub(crate) fn update_contact<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>(
+    narrow_phase: &mut NarrowPhase<N, CollHandle>,
+    dispatcher: &dyn ContactDispatcher<N>,
+    (ch1, c1): (CollHandle, &Collider<N, Handle>),
+    (ch2, c2): (CollHandle, &Collider<N, Handle>),
+    detector: &mut ContactAlgorithm<N>,
+    manifold: &mut ContactManifold<N>,
+) {
+    if let Some(prediction) = c1
+        .query_type()
+        .contact_queries_to_prediction(c2.query_type())
+    {
+        let mut new_manifold = manifold.clone();
+        compute_contacts(dispatcher, c1, c2, &prediction, detector, &mut new_manifold);
+        let mut precomputed = PrecomputedContacts {
+            manifold: new_manifold,
+        };
+        narrow_phase.update_contact(c1, c2, ch1, ch2, &mut precomputed, manifold);
+    }
+}
+
+// For each contact, finds the id of the first of the `previous` contacts on the same features, and
+// not matched yet.
+#[cfg(feature = "deterministic")]
+fn match_contacts<'a, N: RealField>(
+    previous: &[(FeatureId, FeatureId, ContactId)],
+    contacts: impl Iterator<Item = &'a TrackedContact<N>>,
+) -> Vec<Option<ContactId>> {
+    let mut matched = vec![false; previous.len()];
+
+    contacts
+        .map(|contact| {
+            let features = (contact.kinematic.feature1(), contact.kinematic.feature2());
+            let i = (0..previous.len())
+                .find(|i| !matched[*i] && (previous[*i].0, previous[*i].1) == features)?;
+            matched[i] = true;
+            Some(previous[i].2)
+        })
+        .collect()
+}
+
+// A contact manifold generator that outputs a contact manifold computed beforehand.
+#[cfg(any(feature = "parallel", feature = "deterministic"))]
+struct PrecomputedContacts<N: RealField> {
+    manifold: ContactManifold<N>,
+}
+
+#[cfg(any(feature = "parallel", feature = "deterministic"))]
+impl<N: RealField> PrecomputedContacts<N> {
+    fn new() -> Self {
+        PrecomputedContacts {
+            manifold: ContactManifold::new(),
+        }
+    }
+}
+
+#[cfg(any(feature = "parallel", feature = "deterministic"))]
+impl<N: RealField> ContactManifoldGenerator<N> for PrecomputedContacts<N> {
+    fn generate_contacts(
+        &mut self,
+        _: &dyn ContactDispatcher<N>,
+        _: &Isometry<N>,
+        _: &dyn Shape<N>,
+        _: Option<&dyn ContactPreprocessor<N>>,
+        _: &Isometry<N>,
+        _: &dyn Shape<N>,
+        _: Option<&dyn ContactPreprocessor<N>>,
+        _: &ContactPrediction<N>,
+        manifold: &mut ContactManifold<N>,
+    ) -> bool {
+        std::mem::swap(manifold, &mut self.manifold);
+        true
+    }
+}
@@ -0,0 +1,197 @@
+#![allow(missing_docs)] // for downcast.
+
+use downcast_rs::Downcast;
+use na::{RealField, Unit};
+
+use ncollide::bounding_volume::AABB;
+
+use crate::math::{Point, Vector};
+
+/// A field giving the gravitational acceleration at each point of space.
+///
+/// The gravity fields of a mechanical world are evaluated by each body while computing its
+/// acceleration, at the center of mass of each rigid body and multibody link, and at each node of
+/// deformable bodies. The result is then multiplied by the gravity scale of the body.
+pub trait GravityField<N: RealField>: Downcast + Send + Sync {
+    /// The gravitational acceleration at the given world-space point.
+    fn gravity_at(&self, point: &Point<N>) -> Vector<N>;
+}
+
+impl_downcast!(GravityField<N> where N: RealField);
+
+#[cfg(feature = "serde-serialize")]
+serde_trait_object!(GravityField<N: RealField>, GravityFieldRef, GravityFieldOwned {
+    Uniform(Vector<N>),
+    PointGravity(PointGravity<N>),
+    DirectionalGravityZone(DirectionalGravityZone<N>),
+    CylinderGravity(CylinderGravity<N>),
+});
+
+/// A vector is a uniform gravity field.
+impl<N: RealField> GravityField<N> for Vector<N> {
+    #[inline]
+    fn gravity_at(&self, _: &Point<N>) -> Vector<N> {
+        *self
+    }
+}
+
+/// A gravity field attracting everything toward a point, e.g., a planet.
+///
+/// The magnitude of the acceleration is inversely proportional to the squared distance to the center.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct PointGravity<N: RealField> {
+    /// The world-space point everything is attracted toward.
+    pub center: Point<N>,
+    /// The magnitude of the acceleration at a unit distance from the center.
+    ///
+    /// For a planet, this is its mass multiplied by the gravitational constant.
+    pub strength: N,
+    /// The distance below which the magnitude of the acceleration stops increasing.
+    ///
+    /// For a planet, this is usually its radius.
+    pub min_distance: N,
+    /// The distance beyond which the acceleration is zero.
+    pub max_distance: N,
+}
+
+impl<N: RealField> PointGravity<N> {
+    /// Creates a point attractor with the given strength, a minimum distance of 1.0, and no maximum distance.
+    pub fn new(center: Point<N>, strength: N) -> Self {
+        PointGravity {
+            center,
+            strength,
+            min_distance: N::one(),
+            max_distance: N::max_value(),
+        }
+    }
+}
+
+impl<N: RealField> GravityField<N> for PointGravity<N> {
+    fn gravity_at(&self, point: &Point<N>) -> Vector<N> {
+        let dir = self.center - point;
+        let dist = dir.norm();
+
+        if dist > self.max_distance || dist <= N::default_epsilon() {
+            return Vector::zeros();
+        }
+
+        let clamped = dist.max(self.min_distance);
+        dir * (self.strength / (clamped * clamped * dist))
+    }
+}
+
+/// A uniform gravity field restricted to an axis-aligned box.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct DirectionalGravityZone<N: RealField> {
+    /// The world-space box outside of which the acceleration is zero.
+    pub zone: AABB<N>,
+    /// The acceleration inside of the zone.
+    pub gravity: Vector<N>,
+}
+
+impl<N: RealField> DirectionalGravityZone<N> {
+    /// Creates a gravity field with the acceleration `gravity` inside of the box `zone`.
+    pub fn new(zone: AABB<N>, gravity: Vector<N>) -> Self {
+        DirectionalGravityZone { zone, gravity }
+    }
+}
+
+impl<N: RealField> GravityField<N> for DirectionalGravityZone<N> {
+    fn gravity_at(&self, point: &Point<N>) -> Vector<N> {
+        if self.zone.contains_local_point(point) {
+            self.gravity
+        } else {
+            Vector::zeros()
+        }
+    }
+}
+
+/// A gravity field attracting everything toward an axis, within a cylinder around this axis.
+///
+/// The acceleration is orthogonal to the axis and its magnitude is constant inside of the cylinder.
+/// A negative strength pushes everything away from the axis, like in a rotating space station.
+/// In 2D, the cylinder is a strip around its axis.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct CylinderGravity<N: RealField> {
+    /// A world-space point of the axis, at the middle of the cylinder.
+    pub origin: Point<N>,
+    /// The world-space direction of the axis.
+    pub axis: Unit<Vector<N>>,
+    /// The radius of the cylinder.
+    pub radius: N,
+    /// Half the length of the cylinder along its axis.
+    pub half_height: N,
+    /// The magnitude of the acceleration toward the axis.
+    pub strength: N,
+}
+
+impl<N: RealField> CylinderGravity<N> {
+    /// Creates a gravity field attracting everything toward the given axis, within an infinitely long cylinder.
+    pub fn new(origin: Point<N>, axis: Unit<Vector<N>>, radius: N, strength: N) -> Self {
+        CylinderGravity {
+            origin,
+            axis,
+            radius,
+            half_height: N::max_value(),
+            strength,
+        }
+    }
+}
+
+impl<N: RealField> GravityField<N> for CylinderGravity<N> {
+    fn gravity_at(&self, point: &Point<N>) -> Vector<N> {
+        let dpt = point - self.origin;
+        let height = dpt.dot(&self.axis);
+
+        if height.abs() > self.half_height {
+            return Vector::zeros();
+        }
+
+        let radial = *self.axis * height - dpt;
+        let dist = radial.norm();
+
+        if dist > self.radius || dist <= N::default_epsilon() {
+            return Vector::zeros();
+        }
+
+        radial * (self.strength / dist)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{CylinderGravity, GravityField, PointGravity};
+    use crate::math::{Point, Vector};
+
+    #[test]
+    fn point_gravity_decreases_with_the_squared_distance() {
+        let mut field = PointGravity::new(Point::origin(), 8.0);
+        field.max_distance = 10.0;
+
+        let point = Point::from(Vector::x() * 2.0);
+        assert_relative_eq!(field.gravity_at(&point), Vector::x() * -2.0);
+        // Clamped below the minimum distance.
+        let point = Point::from(Vector::x() * 0.5);
+        assert_relative_eq!(field.gravity_at(&point), Vector::x() * -8.0);
+        // Zero beyond the maximum distance.
+        let point = Point::from(Vector::x() * 11.0);
+        assert_relative_eq!(field.gravity_at(&point), Vector::zeros());
+    }
+
+    #[test]
+    fn cylinder_gravity_points_toward_its_axis() {
+        let mut field = CylinderGravity::new(Point::origin(), Vector::y_axis(), 5.0, 3.0);
+        field.half_height = 10.0;
+
+        let point = Point::from(Vector::x() * 2.0 + Vector::y() * 4.0);
+        assert_relative_eq!(field.gravity_at(&point), Vector::x() * -3.0);
+        // Zero outside of the cylinder.
+        let point = Point::from(Vector::x() * 6.0);
+        assert_relative_eq!(field.gravity_at(&point), Vector::zeros());
+        let point = Point::from(Vector::x() * 2.0 + Vector::y() * 11.0);
+        assert_relative_eq!(field.gravity_at(&point), Vector::zeros());
+    }
+}
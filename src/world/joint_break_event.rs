@@ -0,0 +1,28 @@
+use na::{DVector, RealField};
+
+use crate::math::Force;
+use crate::object::{BodyHandle, BodyPartHandle};
+
+/// An event generated by the mechanical world when a joint breaks during a timestep.
+#[derive(Clone, Debug)]
+pub enum JointBreakEvent<N: RealField, Handle: BodyHandle, JointHandle> {
+    /// A joint constraint broke because the force or torque it applied exceeded its limits.
+    Constraint {
+        /// The handle of the joint constraint that broke.
+        handle: JointHandle,
+        /// The two body parts the joint constraint was attached to.
+        anchors: (BodyPartHandle<Handle>, BodyPartHandle<Handle>),
+        /// The impulse applied by the joint constraint on its second body part during the timestep it broke.
+        ///
+        /// It is expressed in world-space, at the anchor of the second body part.
+        impulse: Force<N>,
+    },
+    /// The joint of a multibody link broke because the force applied by its limits and motors
+    /// exceeded the break force of the link. The link is now attached to its parent by a free joint.
+    MultibodyLink {
+        /// The multibody link which joint broke.
+        link: BodyPartHandle<Handle>,
+        /// The generalized impulse applied by the limits and motors of the joint during the timestep it broke.
+        impulse: DVector<N>,
+    },
+}
@@ -4,21 +4,28 @@ use na::{self, RealField};
 use ncollide;
 use ncollide::interpolation::{RigidMotion, RigidMotionComposition};
 use ncollide::narrow_phase::Interaction;
-use ncollide::pipeline::BroadPhasePairFilter;
+use ncollide::pipeline::{BroadPhasePairFilter, CollisionObjectGraphIndex};
 use ncollide::query::{self, Proximity, TOIStatus};
 
 use crate::counters::Counters;
-use crate::detection::{ActivationManager, ColliderContactManifold};
+use crate::detection::{
+    self, ActivationEvent, ActivationManager, ColliderContactManifold, ContactModifier,
+    ContactReport, OneWayPlatforms, WakeUpCause,
+};
 use crate::force_generator::{ForceGenerator, ForceGeneratorSet};
 use crate::joint::{JointConstraint, JointConstraintSet};
 use crate::material::MaterialsCoefficientsTable;
-use crate::math::Vector;
+use crate::math::{Point, Vector};
 use crate::object::{
-    Body, BodyHandle, BodyPartMotion, BodySet, BodyStatus, Collider, ColliderHandle, ColliderSet,
-    DefaultBodyHandle, DefaultColliderHandle,
+    Body, BodyHandle, BodyPartHandle, BodyPartMotion, BodySet, BodyStatus, Collider,
+    ColliderHandle, ColliderSet, DefaultBodyHandle, DefaultColliderHandle, Multibody,
 };
 use crate::solver::{IntegrationParameters, MoreauJeanSolver, SignoriniCoulombPyramidModel};
-use crate::world::{BroadPhasePairFilterSets, GeometricalWorld};
+#[cfg(feature = "parallel")]
+use crate::utils::par_foreach_body_mut;
+#[cfg(feature = "deterministic")]
+use crate::world::geometrical_world;
+use crate::world::{BroadPhasePairFilterSets, GeometricalWorld, GravityField, JointBreakEvent};
 
 /// The default mechanical world, that can be used with a `DefaultBodyHandle` and `DefaultColliderHandle`.
 pub type DefaultMechanicalWorld<N> = MechanicalWorld<N, DefaultBodyHandle, DefaultColliderHandle>;
@@ -41,6 +48,24 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
     }
 }
 
+// The sum of the uniform gravity and of the gravity fields of a mechanical world.
+//
+// The fields are moved out of the mechanical world while the body accelerations are
+// computed, because a gravity field can't contain references.
+struct WorldGravity<N: RealField> {
+    gravity: Vector<N>,
+    fields: Vec<Box<dyn GravityField<N>>>,
+}
+
+impl<N: RealField> GravityField<N> for WorldGravity<N> {
+    fn gravity_at(&self, point: &Point<N>) -> Vector<N> {
+        self.fields
+            .iter()
+            .fold(self.gravity, |acc, field| acc + field.gravity_at(point))
+    }
+}
+
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 struct SubstepState<N: RealField, Handle: BodyHandle> {
     active: bool,
     dt: N,
@@ -52,8 +77,10 @@ struct SubstepState<N: RealField, Handle: BodyHandle> {
 }
 
 /// The physics world.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct MechanicalWorld<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle> {
     /// Performance counters used for debugging and benchmarking nphysics.
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
     pub counters: Counters,
     /// The constraints solver.
     pub solver: MoreauJeanSolver<N, Handle, CollHandle>,
@@ -63,7 +90,19 @@ pub struct MechanicalWorld<N: RealField, Handle: BodyHandle, CollHandle: Collide
     pub material_coefficients: MaterialsCoefficientsTable<N>,
     /// The acting on this mechanical world.
     pub gravity: Vector<N>,
+    /// Additional gravity fields acting on this mechanical world, added to `self.gravity`.
+    ///
+    /// Only the gravity fields provided by nphysics can be serialized.
+    pub gravity_fields: Vec<Box<dyn GravityField<N>>>,
+    /// User-defined hook executed on each contact manifold before the generation of contact constraints.
+    ///
+    /// It is not serialized and has to be set again after the deserialization of this mechanical world.
+    #[cfg_attr(feature = "serde-serialize", serde(skip, default = "Option::default"))]
+    pub contact_modifier: Option<Box<dyn ContactModifier<N, Handle, CollHandle>>>,
+    /// Whether joint constraints are automatically removed from their set during the timestep they break.
+    pub remove_broken_joint_constraints: bool,
     activation_manager: ActivationManager<N, Handle>,
+    one_way_platforms: OneWayPlatforms<CollHandle>,
     substep: SubstepState<N, Handle>,
 }
 
@@ -94,6 +133,10 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
             activation_manager,
             material_coefficients,
             gravity,
+            gravity_fields: Vec::new(),
+            contact_modifier: None,
+            remove_broken_joint_constraints: false,
+            one_way_platforms: OneWayPlatforms::new(),
             integration_parameters,
             substep,
         }
@@ -109,6 +152,27 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
         self.integration_parameters.set_dt(dt);
     }
 
+    /// The bodies that fell asleep or woke up during the last timestep.
+    ///
+    /// Bodies put to sleep or woken up by the user before the last timestep are reported too.
+    pub fn activation_events(&self) -> &[ActivationEvent<Handle>] {
+        self.activation_manager.events()
+    }
+
+    /// Reports the impulses applied at each contact of the given manifold during the last step.
+    ///
+    /// Contacts that were not solved during the last step, e.g., because their bodies are asleep,
+    /// are not reported.
+    pub fn contact_reports<'a>(
+        &'a self,
+        manifold: &'a ColliderContactManifold<'a, N, Handle, CollHandle>,
+    ) -> impl Iterator<Item = ContactReport<N>> + 'a {
+        let contact_model = self.solver.contact_model();
+        manifold
+            .contacts()
+            .filter_map(move |c| contact_model.contact_report(c.id).cloned())
+    }
+
     /// Maintain the internal structures of the mechanical world by handling insersion and removal
     /// events from every sets this mechanical world interacts with.
     pub fn maintain<Colliders, Constraints>(
@@ -155,30 +219,35 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
 
         while let Some((_, body1, body2)) = constraints.pop_removal_event() {
             // Wake-up the bodies this was attached to.
-            if let Some(body1) = bodies.get_mut(body1.0) {
-                body1.activate()
-            }
-
-            if let Some(body2) = bodies.get_mut(body2.0) {
-                body2.activate()
-            }
+            self.activate_joint_body(bodies, body1.0);
+            self.activate_joint_body(bodies, body2.0);
         }
 
         while let Some((_, body1, body2)) = constraints.pop_insertion_event() {
             // Wake-up the bodies this was attached to.
-            if let Some(body1) = bodies.get_mut(body1.0) {
-                body1.activate()
-            }
-
-            if let Some(body2) = bodies.get_mut(body2.0) {
-                body2.activate()
-            }
+            self.activate_joint_body(bodies, body1.0);
+            self.activate_joint_body(bodies, body2.0);
         }
 
         gworld.maintain(bodies, colliders);
     }
 
+    // Wakes up a body because one of its joints has been inserted or removed.
+    fn activate_joint_body(
+        &mut self,
+        bodies: &mut dyn BodySet<N, Handle = Handle>,
+        handle: Handle,
+    ) {
+        if let Some(body) = bodies.get_mut(handle) {
+            body.activate();
+            self.activation_manager
+                .set_wake_up_cause(handle, WakeUpCause::Joint);
+        }
+    }
+
     /// Execute one time step of the physics simulation.
+    ///
+    /// Returns the joints that broke during this time step.
     pub fn step<Bodies, Colliders, Constraints, Forces>(
         &mut self,
         gworld: &mut GeometricalWorld<N, Handle, CollHandle>,
@@ -186,7 +255,8 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
         colliders: &mut Colliders,
         constraints: &mut Constraints,
         forces: &mut Forces,
-    ) where
+    ) -> Vec<JointBreakEvent<N, Handle, Constraints::Handle>>
+    where
         Bodies: BodySet<N, Handle = Handle>,
         Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
         Constraints: JointConstraintSet<N, Handle>,
@@ -196,6 +266,8 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
     }
 
     /// Execute one time step of the physics simulation.
+    ///
+    /// Returns the joints that broke during this time step.
     pub fn step_with_filter<Bodies, Colliders, Constraints, Forces, Filter>(
         &mut self,
         gworld: &mut GeometricalWorld<N, Handle, CollHandle>,
@@ -204,7 +276,8 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
         constraints: &mut Constraints,
         forces: &mut Forces,
         filter: &Filter,
-    ) where
+    ) -> Vec<JointBreakEvent<N, Handle, Constraints::Handle>>
+    where
         Bodies: BodySet<N, Handle = Handle>,
         Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
         Constraints: JointConstraintSet<N, Handle>,
@@ -212,8 +285,11 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
         Filter: for<'a> BroadPhasePairFilter<N, BroadPhasePairFilterSets<'a, N, Bodies, Colliders>>
             + ?Sized,
     {
+        let mut joint_break_events = Vec::new();
+
         if !self.substep.active {
             self.counters.step_started();
+            self.solver.clear_contact_reports();
 
             /*
              *
@@ -227,20 +303,24 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
              * Update body dynamics and accelerations.
              *
              */
-            bodies.foreach_mut(&mut |_, b: &mut dyn Body<N>| {
+            let dt = self.integration_parameters.dt();
+            foreach_body_mut(bodies, |b| {
                 b.step_started();
                 b.update_kinematics();
-                b.update_dynamics(self.integration_parameters.dt());
+                b.update_dynamics(dt);
             });
 
-            // FIXME: how to make force generators work
-            // with the external body set?
             let parameters = &self.integration_parameters;
             forces.foreach_mut(|_, f| f.apply(parameters, bodies));
 
-            bodies.foreach_mut(&mut |_, b: &mut dyn Body<N>| {
-                b.update_acceleration(&self.gravity, parameters);
+            let gravity = WorldGravity {
+                gravity: self.gravity,
+                fields: std::mem::take(&mut self.gravity_fields),
+            };
+            foreach_body_mut(bodies, |b| {
+                b.update_acceleration(&gravity, parameters);
             });
+            self.gravity_fields = gravity.fields;
 
             /*
              *
@@ -252,6 +332,13 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
             gworld.perform_broad_phase(bodies, colliders, filter);
             gworld.perform_narrow_phase(colliders);
 
+            #[cfg(feature = "serde-serialize")]
+            {
+                if let Some(remap) = gworld.take_restored_contact_ids() {
+                    self.solver.remap_contact_impulses(&remap);
+                }
+            }
+
             colliders.foreach_mut(|_, c| c.clear_update_flags());
 
             /*
@@ -260,21 +347,23 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
              * islands.
              *
              */
-            // FIXME: for now, no island is built.
             self.counters.island_construction_started();
-            let mut active_bodies = Vec::new();
-            self.activation_manager.update(
-                bodies,
-                colliders,
-                gworld,
-                constraints,
-                &mut active_bodies,
-            );
+            self.activation_manager
+                .update(bodies, colliders, gworld, constraints);
 
-            let mut active_joints = Vec::new();
+            let nislands = self.activation_manager.islands().len();
+            let activation_manager = &self.activation_manager;
+            let mut island_joints: Vec<_> = (0..nislands).map(|_| Vec::new()).collect();
             constraints.foreach(|h, j| {
                 if !j.is_broken() && j.is_active(bodies) {
-                    active_joints.push(h)
+                    let (b1, b2) = j.anchors();
+                    let island = activation_manager
+                        .body_island(bodies, b1.0)
+                        .or_else(|| activation_manager.body_island(bodies, b2.0));
+
+                    if let Some(island) = island {
+                        island_joints[island].push(h)
+                    }
                 }
             });
             self.counters.island_construction_completed();
@@ -284,10 +373,14 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
              * Collect contact manifolds.
              *
              */
-            let mut contact_manifolds = Vec::new(); // FIXME: avoid allocations.
+            // FIXME: avoid allocations.
+            let mut contact_manifolds: Vec<_> = (0..nislands).map(|_| Vec::new()).collect();
+            self.one_way_platforms.begin();
             for (h1, c1, h2, c2, _, manifold) in gworld.contact_pairs(colliders, false) {
                 let b1 = try_continue!(bodies.get(c1.body()));
                 let b2 = try_continue!(bodies.get(c2.body()));
+                let mut manifold = ColliderContactManifold::new(h1, c1, h2, c2, manifold);
+                self.one_way_platforms.filter_contacts(&mut manifold);
 
                 if manifold.len() > 0
                     && b1.status() != BodyStatus::Disabled
@@ -295,13 +388,35 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
                     && ((b1.status_dependent_ndofs() != 0 && b1.is_active())
                         || (b2.status_dependent_ndofs() != 0 && b2.is_active()))
                 {
-                    contact_manifolds.push(ColliderContactManifold::new(h1, c1, h2, c2, manifold));
+                    let island = activation_manager
+                        .body_island(bodies, c1.body())
+                        .or_else(|| activation_manager.body_island(bodies, c2.body()));
+
+                    if let Some(island) = island {
+                        if let Some(modifier) = &mut self.contact_modifier {
+                            detection::modify_manifold_contacts(
+                                &mut **modifier,
+                                &*bodies,
+                                &mut manifold,
+                            );
+                        }
+
+                        contact_manifolds[island].push(manifold);
+                    }
+                }
+            }
+            self.one_way_platforms.end();
+
+            #[cfg(feature = "deterministic")]
+            {
+                for manifolds in &mut contact_manifolds {
+                    sort_contact_manifolds(manifolds)
                 }
             }
 
             /*
              *
-             * Solve the system and integrate.
+             * Solve the system of each island and integrate.
              *
              */
             bodies.foreach_mut(&mut |_, b: &mut dyn Body<N>| {
@@ -313,14 +428,15 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
             });
 
             self.counters.solver_started();
-            self.solver.step(
+            self.counters.set_nislands(nislands);
+            self.solver.step_islands(
                 &mut self.counters,
                 bodies,
                 colliders,
                 constraints,
-                &contact_manifolds[..],
-                &active_bodies[..],
-                &active_joints[..],
+                &contact_manifolds,
+                self.activation_manager.islands(),
+                &island_joints,
                 parameters,
                 &self.material_coefficients,
             );
@@ -340,10 +456,17 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
              */
             // FIXME: objects involved in a non-linear position stabilization already
             // updated their kinematics.
-            bodies.foreach_mut(&mut |_, b: &mut dyn Body<N>| {
+            foreach_body_mut(bodies, |b| {
                 b.update_kinematics();
                 b.update_dynamics(parameters.dt());
             });
+
+            /*
+             *
+             * Collect the joints that broke.
+             *
+             */
+            joint_break_events = self.handle_broken_joints(bodies, constraints, &island_joints);
         }
 
         /*
@@ -393,6 +516,57 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
 
             colliders.foreach_mut(|_, c| c.clear_update_flags())
         }
+
+        joint_break_events
+    }
+
+    // Generates an event for each joint constraint and multibody link that broke during the
+    // last resolution, and removes the broken joint constraints if requested.
+    fn handle_broken_joints<Constraints>(
+        &mut self,
+        bodies: &mut dyn BodySet<N, Handle = Handle>,
+        constraints: &mut Constraints,
+        island_joints: &[Vec<Constraints::Handle>],
+    ) -> Vec<JointBreakEvent<N, Handle, Constraints::Handle>>
+    where
+        Constraints: JointConstraintSet<N, Handle>,
+    {
+        let dt = self.integration_parameters.dt();
+        let mut events = Vec::new();
+        let mut broken = Vec::new();
+
+        for handle in island_joints.iter().flat_map(|joints| joints.iter()) {
+            if let Some(joint) = constraints.get(*handle) {
+                if joint.is_broken() {
+                    broken.push((*handle, joint.anchors(), joint.reaction_force() * dt));
+                }
+            }
+        }
+
+        for (handle, anchors, impulse) in broken {
+            if self.remove_broken_joint_constraints {
+                constraints.remove(handle);
+            }
+
+            events.push(JointBreakEvent::Constraint {
+                handle,
+                anchors,
+                impulse,
+            });
+        }
+
+        bodies.foreach_mut(&mut |handle, body: &mut dyn Body<N>| {
+            if let Some(multibody) = body.downcast_mut::<Multibody<N>>() {
+                multibody.break_overloaded_links(|link_id, impulse| {
+                    events.push(JointBreakEvent::MultibodyLink {
+                        link: BodyPartHandle(handle, link_id),
+                        impulse,
+                    })
+                });
+            }
+        });
+
+        events
     }
 
     // Outputs a sorted list of TOI event (in ascending order) for the given time interval,
@@ -671,10 +845,23 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
                     }
 
                     let graph_id = colliders.get(c).unwrap().graph_index().unwrap();
+                    let interactions = gworld.interactions.interactions_with_mut(graph_id);
+
+                    // The traversal order determines which bodies are added to the island, so it
+                    // must not depend on the order the interactions were created.
+                    #[cfg(feature = "deterministic")]
+                    let interactions = {
+                        let mut interactions: Vec<_> = interactions.collect();
+                        interactions.sort_by_key(|(ch1, ch2, ..)| {
+                            (
+                                colliders.get(*ch1).and_then(|c| c.graph_index()),
+                                colliders.get(*ch2).and_then(|c| c.graph_index()),
+                            )
+                        });
+                        interactions
+                    };
 
-                    for (ch1, ch2, eid, inter) in
-                        gworld.interactions.interactions_with_mut(graph_id)
-                    {
+                    for (ch1, ch2, eid, inter) in interactions {
                         if (ch1 == c && visited.contains(&ch2))
                             || (ch2 == c && visited.contains(&ch1))
                         {
@@ -691,7 +878,10 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
                                             b.clear_forces();
                                             b.update_kinematics();
                                             b.update_dynamics(parameters.dt());
-                                            b.update_acceleration(&Vector::zeros(), &parameters);
+                                            b.update_acceleration(
+                                                &Vector::<N>::zeros(),
+                                                &parameters,
+                                            );
 
                                             let curr_body_time = self
                                                 .substep
@@ -746,6 +936,16 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
                                     let (c1, c2) = colliders.get_pair(ch1, ch2);
                                     let (c1, c2) = (c1.unwrap(), c2.unwrap());
 
+                                    #[cfg(feature = "deterministic")]
+                                    geometrical_world::update_contact(
+                                        &mut gworld.narrow_phase,
+                                        &*gworld.contact_dispatcher,
+                                        (ch1, c1),
+                                        (ch2, c2),
+                                        alg,
+                                        manifold,
+                                    );
+                                    #[cfg(not(feature = "deterministic"))]
                                     gworld
                                         .narrow_phase
                                         .update_contact(c1, c2, ch1, ch2, &mut **alg, manifold);
@@ -847,13 +1047,27 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
                         Interaction::Contact(_, manifold) => {
                             let c1 = colliders.get(ch1).unwrap();
                             let c2 = colliders.get(ch2).unwrap();
-                            contact_manifolds
-                                .push(ColliderContactManifold::new(ch1, c1, ch2, c2, manifold));
+                            let mut manifold =
+                                ColliderContactManifold::new(ch1, c1, ch2, c2, manifold);
+                            self.one_way_platforms.filter_contacts(&mut manifold);
+
+                            if let Some(modifier) = &mut self.contact_modifier {
+                                detection::modify_manifold_contacts(
+                                    &mut **modifier,
+                                    &*bodies,
+                                    &mut manifold,
+                                );
+                            }
+
+                            contact_manifolds.push(manifold);
                         }
                         Interaction::Proximity(..) => {}
                     }
                 }
 
+                #[cfg(feature = "deterministic")]
+                sort_contact_manifolds(&mut contact_manifolds);
+
                 self.counters.ccd.narrow_phase_time.pause();
 
                 // Solve the system and integrate.
@@ -908,8 +1122,36 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
     }
 }
 
+// Applies `f` to every body, on several threads if the `parallel` feature is enabled.
+#[cfg(feature = "parallel")]
+fn foreach_body_mut<N: RealField, Handle: BodyHandle>(
+    bodies: &mut dyn BodySet<N, Handle = Handle>,
+    f: impl Fn(&mut dyn Body<N>) + Sync,
+) {
+    par_foreach_body_mut(bodies, f)
+}
+
+#[cfg(not(feature = "parallel"))]
+fn foreach_body_mut<N: RealField, Handle: BodyHandle>(
+    bodies: &mut dyn BodySet<N, Handle = Handle>,
+    f: impl Fn(&mut dyn Body<N>),
+) {
+    bodies.foreach_mut(&mut |_, b| f(b))
+}
+
+// Sorts contact manifolds by collider so that their order does not depend on
+// the order the broad phase reported the collider pairs.
+#[cfg(feature = "deterministic")]
+fn sort_contact_manifolds<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>(
+    manifolds: &mut [ColliderContactManifold<N, Handle, CollHandle>],
+) {
+    manifolds.sort_by_key(|m| (m.collider1.graph_index(), m.collider2.graph_index()))
+}
+
 struct TOIEntry<N: RealField, Handle, CollHandle> {
     toi: N,
+    // Used to process simultaneous impacts in a stable order.
+    graph_ids: (CollisionObjectGraphIndex, CollisionObjectGraphIndex),
     c1: CollHandle,
     b1: Handle,
     c2: CollHandle,
@@ -921,6 +1163,7 @@ struct TOIEntry<N: RealField, Handle, CollHandle> {
 impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle> TOIEntry<N, Handle, CollHandle> {
     fn new(
         toi: N,
+        graph_ids: (CollisionObjectGraphIndex, CollisionObjectGraphIndex),
         c1: CollHandle,
         b1: Handle,
         c2: CollHandle,
@@ -930,6 +1173,7 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle> TOIEntry<N, H
     ) -> Self {
         Self {
             toi,
+            graph_ids,
             c1,
             b1,
             c2,
@@ -1016,8 +1260,13 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle> TOIEntry<N, H
 
         if params.ccd_on_penetration_enabled || toi.status != TOIStatus::Penetrating {
             let toi = start_time + toi.toi;
+            let graph_ids = (
+                c1.graph_index().expect(crate::NOT_REGISTERED_ERROR),
+                c2.graph_index().expect(crate::NOT_REGISTERED_ERROR),
+            );
             Some(Self::new(
                 toi,
+                graph_ids,
                 ch1,
                 c1.body(),
                 ch2,
@@ -1033,7 +1282,12 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle> TOIEntry<N, H
 
 impl<N: RealField, CollHandle, BodyHandle> PartialOrd for TOIEntry<N, CollHandle, BodyHandle> {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        (-self.toi).partial_cmp(&(-other.toi))
+        // NOTE: the smallest TOI must be popped first from the binary heap.
+        Some(
+            (-self.toi)
+                .partial_cmp(&(-other.toi))?
+                .then_with(|| other.graph_ids.cmp(&self.graph_ids)),
+        )
     }
 }
 
@@ -1045,8 +1299,880 @@ impl<N: RealField, CollHandle, BodyHandle> Ord for TOIEntry<N, CollHandle, BodyH
 
 impl<N: RealField, CollHandle, BodyHandle> PartialEq for TOIEntry<N, CollHandle, BodyHandle> {
     fn eq(&self, other: &Self) -> bool {
-        self.toi == other.toi
+        self.toi == other.toi && self.graph_ids == other.graph_ids
     }
 }
 
 impl<N: RealField, CollHandle, BodyHandle> Eq for TOIEntry<N, CollHandle, BodyHandle> {}
+
+#[cfg(test)]
+mod test {
+    use crate::detection::{ActivationEvent, WakeUpCause};
+    use crate::force_generator::DefaultForceGeneratorSet;
+    #[cfg(all(feature = "serde-serialize", feature = "dim3"))]
+    use crate::joint::BallConstraint;
+    #[cfg(all(feature = "serde-serialize", feature = "dim2"))]
+    use crate::joint::RevoluteConstraint as BallConstraint;
+    use crate::joint::{
+        DefaultJointConstraintSet, DistanceConstraint, FixedConstraint, GearConstraint,
+        JointCompliance, PrismaticConstraint, PulleyConstraint, RackAndPinionConstraint,
+        RevoluteConstraint,
+    };
+    #[cfg(feature = "dim3")]
+    use crate::math::AngularVector;
+    use crate::math::{Point, Rotation, Vector, Velocity};
+    use crate::object::{
+        BodyPartHandle, ColliderDesc, DefaultBodyHandle, DefaultBodySet, DefaultColliderSet,
+        Ground, RigidBodyDesc,
+    };
+    use crate::world::{
+        DefaultGeometricalWorld, DefaultMechanicalWorld, DirectionalGravityZone, JointBreakEvent,
+    };
+    use ncollide::bounding_volume::AABB;
+    use ncollide::pipeline::CollisionGroups;
+    use ncollide::query::Ray;
+    #[cfg(feature = "serde-serialize")]
+    use ncollide::shape::Capsule;
+    #[cfg(all(feature = "serde-serialize", feature = "dim3"))]
+    use ncollide::shape::ConvexHull;
+    #[cfg(all(feature = "serde-serialize", feature = "dim2"))]
+    use ncollide::shape::ConvexPolygon;
+    use ncollide::shape::{Ball, Cuboid, ShapeHandle};
+
+    #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+    struct Scene {
+        ground: DefaultBodyHandle,
+        mechanical_world: DefaultMechanicalWorld<f64>,
+        geometrical_world: DefaultGeometricalWorld<f64>,
+        bodies: DefaultBodySet<f64>,
+        colliders: DefaultColliderSet<f64>,
+        joint_constraints: DefaultJointConstraintSet<f64>,
+        force_generators: DefaultForceGeneratorSet<f64>,
+    }
+
+    impl Scene {
+        // The bodies of the stacks are given the shapes of `shapes`, in turn.
+        fn new(shapes: &[ShapeHandle<f64>]) -> Self {
+            let mut bodies = DefaultBodySet::new();
+            let mut colliders = DefaultColliderSet::new();
+
+            let ground = bodies.insert(Ground::new());
+            let ground_shape = ShapeHandle::new(Cuboid::new(Vector::repeat(20.0)));
+            let _ = colliders.insert(
+                ColliderDesc::new(ground_shape)
+                    .translation(Vector::y() * -20.0)
+                    .build(BodyPartHandle(ground, 0)),
+            );
+
+            // Several stacks, each one forming its own island.
+            for i in 0..4 {
+                for j in 0..5 {
+                    let shift = Vector::x() * (i as f64 * 3.0 + j as f64 * 0.05);
+                    let body = RigidBodyDesc::new()
+                        .translation(shift + Vector::y() * (0.5 + j as f64 * 1.1))
+                        .build();
+                    let handle = bodies.insert(body);
+                    let shape = shapes[(i + j) % shapes.len()].clone();
+                    let _ = colliders.insert(
+                        ColliderDesc::new(shape)
+                            .density(1.0)
+                            .build(BodyPartHandle(handle, 0)),
+                    );
+                }
+            }
+
+            // A fast projectile hitting one of the stacks.
+            let projectile = RigidBodyDesc::new()
+                .translation(Vector::x() * -10.0 + Vector::y() * 1.0)
+                .velocity(Velocity::new(Vector::x() * 100.0, na::zero()))
+                .build();
+            let projectile = bodies.insert(projectile);
+            let _ = colliders.insert(
+                ColliderDesc::new(ShapeHandle::new(Ball::new(0.2)))
+                    .density(10.0)
+                    .ccd_enabled(true)
+                    .build(BodyPartHandle(projectile, 0)),
+            );
+
+            Scene {
+                ground,
+                mechanical_world: DefaultMechanicalWorld::new(Vector::y() * -9.81),
+                geometrical_world: DefaultGeometricalWorld::new(),
+                bodies,
+                colliders,
+                joint_constraints: DefaultJointConstraintSet::new(),
+                force_generators: DefaultForceGeneratorSet::new(),
+            }
+        }
+
+        fn step(&mut self, nsteps: usize) {
+            for _ in 0..nsteps {
+                let _ = self.mechanical_world.step(
+                    &mut self.geometrical_world,
+                    &mut self.bodies,
+                    &mut self.colliders,
+                    &mut self.joint_constraints,
+                    &mut self.force_generators,
+                );
+            }
+        }
+
+        // Hashes the positions and velocities of all the bodies.
+        //
+        // This uses FNV-1a instead of the standard `DefaultHasher` whose algorithm may change
+        // between Rust releases, so the hash can be compared to a hard-coded value.
+        #[cfg(feature = "deterministic")]
+        fn state_hash(&self) -> u64 {
+            let mut hash = 0xcbf2_9ce4_8422_2325u64;
+            let mut write = |x: f64| {
+                for byte in x.to_bits().to_le_bytes().iter() {
+                    hash ^= u64::from(*byte);
+                    hash = hash.wrapping_mul(0x0100_0000_01b3);
+                }
+            };
+
+            for (_, body) in self.bodies.iter() {
+                let position = body.part(0).unwrap().position();
+
+                for x in position.to_homogeneous().iter() {
+                    write(*x);
+                }
+
+                for v in body.generalized_velocity().iter() {
+                    write(*v);
+                }
+            }
+
+            hash
+        }
+    }
+
+    // The exact results are only reproducible with the `deterministic` feature.
+    #[cfg(feature = "deterministic")]
+    fn state_hash_after(nsteps: usize) -> u64 {
+        // Alternating boxes and balls.
+        let cuboid = ShapeHandle::new(Cuboid::new(Vector::repeat(0.5)));
+        let ball = ShapeHandle::new(Ball::new(0.5));
+        let mut scene = Scene::new(&[cuboid, ball]);
+        scene.step(nsteps);
+        scene.state_hash()
+    }
+
+    // The state reached by the reference scene on `x86_64-unknown-linux-gnu`. It must only change
+    // along with the simulation itself. Other targets may have other floating-point transcendental
+    // functions, so their results are only compared between runs.
+    #[cfg(all(feature = "deterministic", feature = "dim2"))]
+    const GOLDEN_STATE_HASH: u64 = 10952051307531299712;
+    #[cfg(all(feature = "deterministic", feature = "dim3"))]
+    const GOLDEN_STATE_HASH: u64 = 11302150070548938571;
+
+    #[cfg(feature = "deterministic")]
+    #[test]
+    fn state_hash_is_reproducible() {
+        let hash = state_hash_after(100);
+
+        if cfg!(all(
+            target_arch = "x86_64",
+            target_os = "linux",
+            target_env = "gnu"
+        )) {
+            assert_eq!(hash, GOLDEN_STATE_HASH);
+        }
+
+        assert_eq!(hash, state_hash_after(100));
+
+        // The hash maps created on another thread use different random keys.
+        let other_thread = std::thread::spawn(|| state_hash_after(100));
+        assert_eq!(hash, other_thread.join().unwrap());
+    }
+
+    #[test]
+    fn step_reports_broken_joints() {
+        let cuboid = ShapeHandle::new(Cuboid::new(Vector::repeat(0.5)));
+        let mut scene = Scene::new(std::slice::from_ref(&cuboid));
+
+        // A body hanging from the ground, away from the stacks, by a joint too weak to hold it.
+        let body = RigidBodyDesc::new()
+            .translation(Vector::x() * -15.0 + Vector::y() * 5.0)
+            .build();
+        let body = scene.bodies.insert(body);
+        let _ = scene.colliders.insert(
+            ColliderDesc::new(cuboid)
+                .density(1.0)
+                .build(BodyPartHandle(body, 0)),
+        );
+        let mut joint = FixedConstraint::new(
+            BodyPartHandle(scene.ground, 0),
+            BodyPartHandle(body, 0),
+            Point::from(Vector::x() * -15.0 + Vector::y() * 5.0),
+            Rotation::identity(),
+            Point::origin(),
+            Rotation::identity(),
+        );
+        joint.set_break_force(1.0e-3);
+        let joint = scene.joint_constraints.insert(joint);
+
+        let events = scene.mechanical_world.step(
+            &mut scene.geometrical_world,
+            &mut scene.bodies,
+            &mut scene.colliders,
+            &mut scene.joint_constraints,
+            &mut scene.force_generators,
+        );
+
+        match &events[..] {
+            [JointBreakEvent::Constraint {
+                handle, anchors, ..
+            }] => {
+                assert_eq!(*handle, joint);
+                assert_eq!(anchors.1, BodyPartHandle(body, 0));
+            }
+            _ => panic!("Unexpected joint break events: {:?}", events),
+        }
+    }
+
+    #[test]
+    fn ray_hits_are_sorted_and_filtered() {
+        let mut scene = Scene::new(&[ShapeHandle::new(Ball::new(0.5))]);
+        // Three balls attached to the ground in a row, away from the stacks and the projectile.
+        let balls: Vec<_> = [-25.0, -20.0, -15.0]
+            .iter()
+            .map(|x| {
+                scene.colliders.insert(
+                    ColliderDesc::new(ShapeHandle::new(Ball::new(0.5)))
+                        .translation(Vector::x() * *x + Vector::y() * 10.0)
+                        .build(BodyPartHandle(scene.ground, 0)),
+                )
+            })
+            .collect();
+        // Updates the broad phase.
+        scene.step(1);
+
+        let ray = Ray::new(
+            Point::from(Vector::x() * -30.0 + Vector::y() * 10.0),
+            Vector::x(),
+        );
+        let groups = CollisionGroups::new();
+        let cast = |max_toi, max_hits, excluded| {
+            scene
+                .geometrical_world
+                .first_interferences_with_ray(
+                    &scene.colliders,
+                    &ray,
+                    max_toi,
+                    true,
+                    &groups,
+                    |handle, _| Some(handle) != excluded,
+                    max_hits,
+                )
+                .into_iter()
+                .map(|(handle, _, inter)| (handle, inter.toi))
+                .collect::<Vec<_>>()
+        };
+
+        let hits = cast(None, 10, None);
+        let handles: Vec<_> = hits.iter().map(|hit| hit.0).collect();
+        assert_eq!(handles, balls);
+        assert_relative_eq!(hits[0].1, 4.5, epsilon = 1.0e-6);
+        assert_relative_eq!(hits[1].1, 9.5, epsilon = 1.0e-6);
+        assert_relative_eq!(hits[2].1, 14.5, epsilon = 1.0e-6);
+
+        // Only the closest hits are kept.
+        let handles: Vec<_> = cast(None, 2, None).iter().map(|hit| hit.0).collect();
+        assert_eq!(handles, &balls[..2]);
+
+        // The hits beyond `max_toi` are ignored.
+        let handles: Vec<_> = cast(Some(10.0), 10, None).iter().map(|hit| hit.0).collect();
+        assert_eq!(handles, &balls[..2]);
+
+        // The colliders rejected by the filter are ignored.
+        let handles: Vec<_> = cast(None, 2, Some(balls[1]))
+            .iter()
+            .map(|hit| hit.0)
+            .collect();
+        assert_eq!(handles, [balls[0], balls[2]]);
+
+        let first = scene.geometrical_world.first_interference_with_ray(
+            &scene.colliders,
+            &ray,
+            None,
+            true,
+            &groups,
+            |_, _| true,
+        );
+        assert_eq!(first.map(|hit| hit.0), Some(balls[0]));
+    }
+
+    #[test]
+    fn gravity_fields_are_scaled_per_body() {
+        let mut scene = Scene::new(&[ShapeHandle::new(Ball::new(0.5))]);
+        // A zone pushing along `x` around the first body only.
+        let zone = AABB::new(
+            Point::from(Vector::x() * -16.0 + Vector::y() * 9.0),
+            Point::from(Vector::x() * -14.0 + Vector::y() * 11.0),
+        );
+        let field = DirectionalGravityZone::new(zone, Vector::x() * 5.0);
+        scene.mechanical_world.gravity_fields.push(Box::new(field));
+
+        // Bodies in the air, away from the stacks and the projectile.
+        let bodies: Vec<_> = [(-15.0, 1.0), (-20.0, 2.0), (-25.0, 0.0)]
+            .iter()
+            .map(|(x, scale)| {
+                let body = RigidBodyDesc::new()
+                    .translation(Vector::x() * *x + Vector::y() * 10.0)
+                    .gravity_scale(*scale)
+                    .mass(1.0)
+                    .build();
+                scene.bodies.insert(body)
+            })
+            .collect();
+
+        scene.step(1);
+
+        let dt = scene.mechanical_world.integration_parameters.dt();
+        let velocity = |body| scene.bodies.rigid_body(body).unwrap().velocity().linear;
+        let gravity = Vector::y() * -9.81;
+        assert_relative_eq!(
+            velocity(bodies[0]),
+            (gravity + Vector::x() * 5.0) * dt,
+            epsilon = 1.0e-6
+        );
+        assert_relative_eq!(velocity(bodies[1]), gravity * 2.0 * dt, epsilon = 1.0e-6);
+        assert_relative_eq!(velocity(bodies[2]), Vector::zeros(), epsilon = 1.0e-6);
+    }
+
+    // A rod attached to the ground by a revolute joint at `anchor`, around the `z` axis in 3D.
+    //
+    // The rod is aligned with the `x` axis and centered at `anchor + offset`. The joint is not
+    // inserted into the scene.
+    fn revolute_pendulum(
+        scene: &mut Scene,
+        anchor: Point<f64>,
+        offset: Vector<f64>,
+    ) -> (
+        DefaultBodyHandle,
+        RevoluteConstraint<f64, DefaultBodyHandle>,
+    ) {
+        let rod = Vector::repeat(0.1) + Vector::x() * 0.9;
+        let body = RigidBodyDesc::new()
+            .translation(anchor.coords + offset)
+            .build();
+        let body = scene.bodies.insert(body);
+        let _ = scene.colliders.insert(
+            ColliderDesc::new(ShapeHandle::new(Cuboid::new(rod)))
+                .density(1.0)
+                .build(BodyPartHandle(body, 0)),
+        );
+
+        #[cfg(feature = "dim2")]
+        let joint = RevoluteConstraint::new(
+            BodyPartHandle(scene.ground, 0),
+            BodyPartHandle(body, 0),
+            anchor,
+            Point::from(-offset),
+        );
+        #[cfg(feature = "dim3")]
+        let joint = RevoluteConstraint::new(
+            BodyPartHandle(scene.ground, 0),
+            BodyPartHandle(body, 0),
+            anchor,
+            Vector::z_axis(),
+            Point::from(-offset),
+            Vector::z_axis(),
+        );
+
+        (body, joint)
+    }
+
+    // The angle and angular velocity of `body` around the `z` axis in 3D.
+    fn angle_and_angular_velocity(scene: &Scene, body: DefaultBodyHandle) -> (f64, f64) {
+        let body = scene.bodies.rigid_body(body).unwrap();
+
+        #[cfg(feature = "dim2")]
+        return (body.position().rotation.angle(), body.velocity().angular);
+        #[cfg(feature = "dim3")]
+        return (
+            body.position().rotation.scaled_axis().z,
+            body.velocity().angular.z,
+        );
+    }
+
+    #[test]
+    fn revolute_joint_stops_at_its_limit() {
+        let mut scene = Scene::new(&[ShapeHandle::new(Ball::new(0.5))]);
+        // A horizontal pendulum, away from the stacks, falling clockwise.
+        let anchor = Point::from(Vector::x() * -15.0 + Vector::y() * 5.0);
+        let (body, mut joint) = revolute_pendulum(&mut scene, anchor, Vector::x());
+        joint.enable_min_angle(-0.5);
+        let _ = scene.joint_constraints.insert(joint);
+
+        // The limit is only enforced once reached, so it may be crossed by one step of motion.
+        for _ in 0..200 {
+            scene.step(1);
+            let (angle, _) = angle_and_angular_velocity(&scene, body);
+            assert!(angle > -0.55, "The limit was crossed: {}", angle);
+        }
+
+        // The pendulum rests against its limit.
+        let (angle, angvel) = angle_and_angular_velocity(&scene, body);
+        assert_relative_eq!(angle, -0.5, epsilon = 1.0e-2);
+        assert_relative_eq!(angvel, 0.0, epsilon = 1.0e-2);
+    }
+
+    #[test]
+    fn revolute_motor_reaches_its_target_velocity() {
+        let mut scene = Scene::new(&[ShapeHandle::new(Ball::new(0.5))]);
+        // Attached at its center of mass so gravity doesn't affect its rotation.
+        let anchor = Point::from(Vector::x() * -15.0 + Vector::y() * 5.0);
+        let (body, mut joint) = revolute_pendulum(&mut scene, anchor, Vector::zeros());
+        joint.enable_angular_motor();
+        joint.set_desired_angular_motor_velocity(2.0);
+        let _ = scene.joint_constraints.insert(joint);
+
+        scene.step(10);
+
+        let (_, angvel) = angle_and_angular_velocity(&scene, body);
+        assert_relative_eq!(angvel, 2.0, epsilon = 1.0e-3);
+    }
+
+    // A ball hanging from the ground at `anchor` by a distance constraint built by `constraint`,
+    // starting `distance` below the anchor.
+    fn hanging_ball(
+        scene: &mut Scene,
+        anchor: Point<f64>,
+        distance: f64,
+        constraint: impl FnOnce(
+            BodyPartHandle<DefaultBodyHandle>,
+            BodyPartHandle<DefaultBodyHandle>,
+        ) -> DistanceConstraint<f64, DefaultBodyHandle>,
+    ) -> DefaultBodyHandle {
+        let body = RigidBodyDesc::new()
+            .translation(anchor.coords - Vector::y() * distance)
+            .build();
+        let body = scene.bodies.insert(body);
+        let _ = scene.colliders.insert(
+            ColliderDesc::new(ShapeHandle::new(Ball::new(0.2)))
+                .density(1.0)
+                .build(BodyPartHandle(body, 0)),
+        );
+        let joint = constraint(BodyPartHandle(scene.ground, 0), BodyPartHandle(body, 0));
+        let _ = scene.joint_constraints.insert(joint);
+        body
+    }
+
+    #[test]
+    fn rope_only_pulls_when_taut() {
+        let mut scene = Scene::new(&[ShapeHandle::new(Ball::new(0.5))]);
+        // Away from the stacks, with a slack rope.
+        let anchor = Point::from(Vector::x() * -15.0 + Vector::y() * 5.0);
+        let body = hanging_ball(&mut scene, anchor, 1.0, |b1, b2| {
+            DistanceConstraint::new_rope(b1, b2, anchor, Point::origin(), 2.0)
+        });
+        let distance = |scene: &Scene| {
+            let body = scene.bodies.rigid_body(body).unwrap();
+            na::distance(&anchor, &Point::from(body.position().translation.vector))
+        };
+
+        // Free fall until the rope is taut.
+        scene.step(10);
+        let velocity = scene.bodies.rigid_body(body).unwrap().velocity().linear;
+        let dt = scene.mechanical_world.integration_parameters.dt();
+        assert_relative_eq!(velocity, Vector::y() * -9.81 * dt * 10.0, epsilon = 1.0e-6);
+
+        for _ in 0..200 {
+            scene.step(1);
+            assert!(
+                distance(&scene) < 2.05,
+                "The rope stretched: {}",
+                distance(&scene)
+            );
+        }
+
+        assert_relative_eq!(distance(&scene), 2.0, epsilon = 1.0e-2);
+    }
+
+    #[test]
+    fn rod_keeps_its_length() {
+        let mut scene = Scene::new(&[ShapeHandle::new(Ball::new(0.5))]);
+        // Away from the stacks, swinging.
+        let anchor = Point::from(Vector::x() * -15.0 + Vector::y() * 5.0);
+        let body = hanging_ball(&mut scene, anchor, 2.0, |b1, b2| {
+            DistanceConstraint::new_rod(b1, b2, anchor, Point::origin(), 2.0)
+        });
+        scene
+            .bodies
+            .rigid_body_mut(body)
+            .unwrap()
+            .set_linear_velocity(Vector::x() * 3.0 + Vector::y() * 3.0);
+
+        for _ in 0..200 {
+            scene.step(1);
+            let body = scene.bodies.rigid_body(body).unwrap();
+            let distance = na::distance(&anchor, &Point::from(body.position().translation.vector));
+            assert_relative_eq!(distance, 2.0, epsilon = 1.0e-2);
+        }
+    }
+
+    #[test]
+    fn gear_couples_angular_velocities() {
+        let mut scene = Scene::new(&[ShapeHandle::new(Ball::new(0.5))]);
+        // Two rods attached at their centers of mass, away from the stacks, the first one driven by a motor.
+        let anchor1 = Point::from(Vector::x() * -15.0 + Vector::y() * 5.0);
+        let anchor2 = Point::from(Vector::x() * -20.0 + Vector::y() * 5.0);
+        let (body1, mut joint1) = revolute_pendulum(&mut scene, anchor1, Vector::zeros());
+        let (body2, joint2) = revolute_pendulum(&mut scene, anchor2, Vector::zeros());
+        joint1.enable_angular_motor();
+        joint1.set_desired_angular_motor_velocity(2.0);
+        let _ = scene.joint_constraints.insert(joint1);
+        let _ = scene.joint_constraints.insert(joint2);
+
+        #[cfg(feature = "dim2")]
+        let gear = GearConstraint::new(BodyPartHandle(body1, 0), BodyPartHandle(body2, 0), -0.5);
+        #[cfg(feature = "dim3")]
+        let gear = GearConstraint::new(
+            BodyPartHandle(body1, 0),
+            BodyPartHandle(body2, 0),
+            AngularVector::z_axis(),
+            AngularVector::z_axis(),
+            -0.5,
+        );
+        let _ = scene.joint_constraints.insert(gear);
+
+        scene.step(30);
+
+        let (_, angvel1) = angle_and_angular_velocity(&scene, body1);
+        let (_, angvel2) = angle_and_angular_velocity(&scene, body2);
+        assert_relative_eq!(angvel1, 2.0, epsilon = 1.0e-2);
+        assert_relative_eq!(angvel2, -1.0, epsilon = 1.0e-2);
+    }
+
+    #[test]
+    fn rack_and_pinion_couples_rotation_and_translation() {
+        let mut scene = Scene::new(&[ShapeHandle::new(Ball::new(0.5))]);
+        // A pinion driven by a motor, above a rack sliding along `x`, away from the stacks.
+        let anchor = Point::from(Vector::x() * -15.0 + Vector::y() * 5.0);
+        let (pinion, mut joint) = revolute_pendulum(&mut scene, anchor, Vector::zeros());
+        joint.enable_angular_motor();
+        joint.set_desired_angular_motor_velocity(2.0);
+        let _ = scene.joint_constraints.insert(joint);
+
+        let rack_anchor = Point::from(Vector::x() * -15.0 + Vector::y() * 4.0);
+        let rack = RigidBodyDesc::new().translation(rack_anchor.coords).build();
+        let rack = scene.bodies.insert(rack);
+        let _ = scene.colliders.insert(
+            ColliderDesc::new(ShapeHandle::new(Cuboid::new(Vector::repeat(0.2))))
+                .density(1.0)
+                .build(BodyPartHandle(rack, 0)),
+        );
+        let slider = PrismaticConstraint::new(
+            BodyPartHandle(scene.ground, 0),
+            BodyPartHandle(rack, 0),
+            rack_anchor,
+            Vector::x_axis(),
+            Point::origin(),
+        );
+        let _ = scene.joint_constraints.insert(slider);
+
+        #[cfg(feature = "dim2")]
+        let coupling = RackAndPinionConstraint::new(
+            BodyPartHandle(pinion, 0),
+            BodyPartHandle(rack, 0),
+            Vector::x_axis(),
+            0.5,
+        );
+        #[cfg(feature = "dim3")]
+        let coupling = RackAndPinionConstraint::new(
+            BodyPartHandle(pinion, 0),
+            BodyPartHandle(rack, 0),
+            AngularVector::z_axis(),
+            Vector::x_axis(),
+            0.5,
+        );
+        let _ = scene.joint_constraints.insert(coupling);
+
+        scene.step(30);
+
+        let (_, angvel) = angle_and_angular_velocity(&scene, pinion);
+        let velocity = scene.bodies.rigid_body(rack).unwrap().velocity().linear;
+        assert_relative_eq!(angvel, 2.0, epsilon = 1.0e-2);
+        assert_relative_eq!(velocity, Vector::x(), epsilon = 1.0e-2);
+    }
+
+    #[test]
+    fn pulley_keeps_the_total_length_of_its_rope() {
+        let mut scene = Scene::new(&[ShapeHandle::new(Ball::new(0.5))]);
+        // Two balls of different masses hanging 1.0 below their ground anchors, away from the stacks.
+        let ground_anchors = [
+            Point::from(Vector::x() * -15.0 + Vector::y() * 5.0),
+            Point::from(Vector::x() * -18.0 + Vector::y() * 5.0),
+        ];
+        let bodies: Vec<_> = ground_anchors
+            .iter()
+            .zip([0.2, 0.3].iter())
+            .map(|(anchor, radius)| {
+                let body = RigidBodyDesc::new()
+                    .translation(anchor.coords - Vector::y())
+                    .build();
+                let body = scene.bodies.insert(body);
+                let _ = scene.colliders.insert(
+                    ColliderDesc::new(ShapeHandle::new(Ball::new(*radius)))
+                        .density(1.0)
+                        .build(BodyPartHandle(body, 0)),
+                );
+                body
+            })
+            .collect();
+        let pulley = PulleyConstraint::new(
+            BodyPartHandle(bodies[0], 0),
+            BodyPartHandle(bodies[1], 0),
+            Point::origin(),
+            Point::origin(),
+            ground_anchors[0],
+            ground_anchors[1],
+            1.0,
+            2.0,
+        );
+        let _ = scene.joint_constraints.insert(pulley);
+        let length = |scene: &Scene, i: usize| {
+            let body = scene.bodies.rigid_body(bodies[i]).unwrap();
+            na::distance(
+                &ground_anchors[i],
+                &Point::from(body.position().translation.vector),
+            )
+        };
+
+        for _ in 0..30 {
+            scene.step(1);
+            assert_relative_eq!(length(&scene, 0) + length(&scene, 1), 2.0, epsilon = 1.0e-2);
+        }
+
+        // The heavier ball goes down.
+        assert!(
+            length(&scene, 1) > 1.1,
+            "The pulley did not move: {}",
+            length(&scene, 1)
+        );
+    }
+
+    #[test]
+    fn compliant_joint_sags_like_a_spring() {
+        let mut scene = Scene::new(&[ShapeHandle::new(Ball::new(0.5))]);
+        // Attached at its center of mass, away from the stacks.
+        let anchor = Point::from(Vector::x() * -15.0 + Vector::y() * 5.0);
+        let (body, mut joint) = revolute_pendulum(&mut scene, anchor, Vector::zeros());
+        joint.set_linear_compliance(JointCompliance::Frequency {
+            frequency: 2.0,
+            damping_ratio: 1.0,
+        });
+        let _ = scene.joint_constraints.insert(joint);
+
+        let sag = |scene: &Scene| {
+            let body = scene.bodies.rigid_body(body).unwrap();
+            anchor.y - body.position().translation.y
+        };
+        scene.step(300);
+        let rest_sag = sag(&scene);
+
+        // The static deflection of a spring with the same natural frequency. The joint sags less
+        // because the position correction is softened, but not disabled.
+        let omega = std::f64::consts::PI * 4.0;
+        let deflection = 9.81 / (omega * omega);
+        assert!(
+            rest_sag > deflection * 0.1 && rest_sag < deflection,
+            "Unexpected sag: {}",
+            rest_sag
+        );
+
+        // The joint is at rest.
+        scene.step(60);
+        assert_relative_eq!(sag(&scene), rest_sag, epsilon = 1.0e-6);
+    }
+
+    // The activation events of the given body reported by the last step.
+    fn activation_events_of(
+        scene: &Scene,
+        body: DefaultBodyHandle,
+    ) -> Vec<ActivationEvent<DefaultBodyHandle>> {
+        scene
+            .mechanical_world
+            .activation_events()
+            .iter()
+            .filter(|event| match event {
+                ActivationEvent::FellAsleep(handle) | ActivationEvent::WokeUp(handle, _) => {
+                    *handle == body
+                }
+            })
+            .cloned()
+            .collect()
+    }
+
+    #[test]
+    fn activation_events_report_sleep_and_wake_ups() {
+        let mut scene = Scene::new(&[ShapeHandle::new(Ball::new(0.5))]);
+        let cuboid = ShapeHandle::new(Cuboid::new(Vector::repeat(0.5)));
+        // Away from the stacks, and from the projectile.
+        let body = RigidBodyDesc::new()
+            .translation(Vector::x() * -15.0 + Vector::y() * 0.5)
+            .build();
+        let body = scene.bodies.insert(body);
+        let _ = scene.colliders.insert(
+            ColliderDesc::new(cuboid.clone())
+                .density(1.0)
+                .build(BodyPartHandle(body, 0)),
+        );
+
+        let fall_asleep = |scene: &mut Scene| {
+            scene.step(1);
+
+            while scene.bodies.get(body).unwrap().is_active() {
+                assert!(activation_events_of(scene, body).is_empty());
+                scene.step(1);
+            }
+
+            assert_eq!(
+                activation_events_of(scene, body),
+                [ActivationEvent::FellAsleep(body)]
+            );
+        };
+
+        fall_asleep(&mut scene);
+        scene.bodies.get_mut(body).unwrap().activate();
+        scene.step(1);
+        assert_eq!(
+            activation_events_of(&scene, body),
+            [ActivationEvent::WokeUp(body, WakeUpCause::User)]
+        );
+
+        fall_asleep(&mut scene);
+        let joint = FixedConstraint::new(
+            BodyPartHandle(scene.ground, 0),
+            BodyPartHandle(body, 0),
+            Point::from(Vector::x() * -15.0 + Vector::y() * 0.5),
+            Rotation::identity(),
+            Point::origin(),
+            Rotation::identity(),
+        );
+        let joint = scene.joint_constraints.insert(joint);
+        scene.step(1);
+        assert_eq!(
+            activation_events_of(&scene, body),
+            [ActivationEvent::WokeUp(body, WakeUpCause::Joint)]
+        );
+        let _ = scene.joint_constraints.remove(joint);
+
+        // A box falling on the sleeping one.
+        fall_asleep(&mut scene);
+        let falling = RigidBodyDesc::new()
+            .translation(Vector::x() * -15.0 + Vector::y() * 2.0)
+            .build();
+        let falling = scene.bodies.insert(falling);
+        let _ = scene.colliders.insert(
+            ColliderDesc::new(cuboid)
+                .density(1.0)
+                .build(BodyPartHandle(falling, 0)),
+        );
+
+        while !scene.bodies.get(body).unwrap().is_active() {
+            scene.step(1);
+        }
+
+        assert_eq!(
+            activation_events_of(&scene, body),
+            [ActivationEvent::WokeUp(body, WakeUpCause::Contact)]
+        );
+    }
+
+    #[cfg(feature = "serde-serialize")]
+    #[test]
+    fn deserialized_world_steps_identically() {
+        let cuboid = ShapeHandle::new(Cuboid::new(Vector::repeat(0.5)));
+        let capsule = ShapeHandle::new(Capsule::new(0.3, 0.5));
+        let points = [
+            Point::origin(),
+            Point::from(Vector::x()),
+            Point::from(Vector::y() * 0.8),
+            Point::from(Vector::repeat(0.9)),
+            Point::from(-Vector::repeat(0.1)),
+        ];
+        #[cfg(feature = "dim2")]
+        let hull = ShapeHandle::new(ConvexPolygon::try_from_points(&points).unwrap());
+        #[cfg(feature = "dim3")]
+        let hull = ShapeHandle::new(ConvexHull::try_from_points(&points).unwrap());
+        let mut scene = Scene::new(&[cuboid.clone(), capsule, hull]);
+
+        // A pendulum attached to the ground, swinging down onto the first stack.
+        let pendulum = RigidBodyDesc::new()
+            .translation(Vector::x() * -2.0 + Vector::y() * 5.0)
+            .user_data(String::from("pendulum"))
+            .build();
+        let pendulum = scene.bodies.insert(pendulum);
+        let pendulum_collider = scene.colliders.insert(
+            ColliderDesc::new(cuboid)
+                .density(1.0)
+                .user_data(42u32)
+                .build(BodyPartHandle(pendulum, 0)),
+        );
+        let joint = BallConstraint::new(
+            BodyPartHandle(scene.ground, 0),
+            BodyPartHandle(pendulum, 0),
+            Point::from(Vector::y() * 5.0),
+            Point::from(Vector::x() * 2.0),
+        );
+        let _ = scene.joint_constraints.insert(joint);
+
+        // Serialize while the pendulum is pushing the first stack.
+        scene.step(50);
+
+        let data = bincode::serialize(&scene).unwrap();
+        let mut restored: Scene = bincode::deserialize(&data).unwrap();
+
+        let body = restored.bodies.rigid_body(pendulum).unwrap();
+        let collider = restored.colliders.get(pendulum_collider).unwrap();
+        assert_eq!(
+            body.user_data().unwrap().downcast_ref::<String>().unwrap(),
+            "pendulum"
+        );
+        assert_eq!(
+            collider.user_data().unwrap().downcast_ref::<u32>(),
+            Some(&42)
+        );
+
+        for _ in 0..100 {
+            scene.step(1);
+            restored.step(1);
+            assert_eq!(scene.state_hash(), restored.state_hash());
+        }
+    }
+
+    #[cfg(feature = "serde-serialize")]
+    #[test]
+    fn deserialized_world_reports_wake_ups() {
+        let mut scene = Scene::new(&[ShapeHandle::new(Ball::new(0.5))]);
+        // Away from the stacks, and from the projectile.
+        let body = RigidBodyDesc::new()
+            .translation(Vector::x() * -15.0 + Vector::y() * 0.5)
+            .build();
+        let body = scene.bodies.insert(body);
+        let _ = scene.colliders.insert(
+            ColliderDesc::new(ShapeHandle::new(Cuboid::new(Vector::repeat(0.5))))
+                .density(1.0)
+                .build(BodyPartHandle(body, 0)),
+        );
+
+        while scene.bodies.get(body).unwrap().is_active() {
+            scene.step(1);
+        }
+
+        let data = bincode::serialize(&scene).unwrap();
+        let restored: Scene = bincode::deserialize(&data).unwrap();
+
+        for scene in &mut [scene, restored] {
+            scene.bodies.get_mut(body).unwrap().activate();
+            scene.step(1);
+            assert_eq!(
+                scene.mechanical_world.activation_events(),
+                &[ActivationEvent::WokeUp(body, WakeUpCause::User)]
+            );
+        }
+    }
+}
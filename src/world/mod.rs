@@ -1,10 +1,47 @@
 //! The physics world.
+//!
+//! # Determinism
+//!
+//! Stepping the same world, built by inserting the same objects in the same order, gives the same
+//! results each time. The hash maps and hash sets used while stepping, for example by the CCD and
+//! the island traversal, only serve lookups: the results never depend on their iteration order.
+//! Enabling the `parallel` feature does not change the results, whatever the number of threads.
+//!
+//! The `deterministic` feature strengthens this guarantee, which is needed when worlds with the same
+//! state but a different history must step identically, e.g., a world and its deserialized copy:
+//!
+//! * The contacts are recomputed from scratch at each step, instead of reusing some results of the
+//!   previous step, so they only depend on the current positions of the colliders.
+//! * The two colliders of a new interaction are ordered by their graph index, which determines the
+//!   direction of its contact normals, instead of the order the broad phase reports them.
+//! * The contact manifolds of each island are sorted by collider before being given to the solver,
+//!   so the order of the contact constraints no longer depends on the order the broad phase reports
+//!   collider pairs.
+//! * The joint constraints are solved in the order they are iterated by the joint constraint set.
+//! * CCD times of impact occurring at the same date are processed in the order of their collider pairs.
+//! * The bodies moved by CCD are gathered by traversing the collider pairs in the same order.
+//!
+//! Results are then identical across runs of the same build on the same machine. Custom body,
+//! collider and joint sets must iterate through their elements in a deterministic order.
+//!
+//! Results are not guaranteed to be identical across build targets, compiler versions, or even
+//! machines: the floating-point transcendental functions are provided by the platform, which may
+//! select their implementation depending on the CPU. Lockstep networking between different machines
+//! thus requires checking that they compute the same results, e.g., by comparing state hashes.
 
+pub use self::fixed_timestepper::{DefaultFixedTimestepper, FixedTimestepper};
 pub use self::geometrical_world::{
     BroadPhasePairFilterSets, DefaultBroadPhasePairFilterSets, DefaultGeometricalWorld,
-    GeometricalWorld,
+    GeometricalWorld, RadialFalloff, RadialForce, ShapeCastHit,
 };
+pub use self::gravity_field::{
+    CylinderGravity, DirectionalGravityZone, GravityField, PointGravity,
+};
+pub use self::joint_break_event::JointBreakEvent;
 pub use self::mechanical_world::{DefaultMechanicalWorld, MechanicalWorld};
 
+mod fixed_timestepper;
 mod geometrical_world;
+mod gravity_field;
+mod joint_break_event;
 mod mechanical_world;